@@ -23,12 +23,14 @@ pub use strong::{
 #[derive(Clone, Debug)]
 pub struct ChecksumConfig {
     strong: StrongHash,
+    file_strong: StrongHash,
     seed: u32,
 }
 
 #[derive(Clone, Debug)]
 pub struct ChecksumConfigBuilder {
     strong: StrongHash,
+    file_strong: Option<StrongHash>,
     seed: u32,
 }
 
@@ -36,6 +38,7 @@ impl Default for ChecksumConfigBuilder {
     fn default() -> Self {
         Self {
             strong: StrongHash::Md4,
+            file_strong: None,
             seed: 0,
         }
     }
@@ -51,6 +54,12 @@ impl ChecksumConfigBuilder {
         self
     }
 
+    /// Selects the whole-file hash independently of the block hash.
+    pub fn file_strong(mut self, alg: StrongHash) -> Self {
+        self.file_strong = Some(alg);
+        self
+    }
+
     pub fn seed(mut self, seed: u32) -> Self {
         self.seed = seed;
         self
@@ -66,6 +75,7 @@ impl ChecksumConfigBuilder {
     pub fn build(self) -> ChecksumConfig {
         ChecksumConfig {
             strong: self.strong,
+            file_strong: self.file_strong.unwrap_or(self.strong),
             seed: self.seed,
         }
     }
@@ -88,4 +98,8 @@ impl ChecksumConfig {
     pub fn strong_hasher(&self) -> Box<dyn StrongChecksum> {
         strong::select_strong_checksum(self.strong, self.seed)
     }
+
+    pub fn file_hasher(&self) -> Box<dyn StrongChecksum> {
+        strong::select_strong_checksum(self.file_strong, self.seed)
+    }
 }
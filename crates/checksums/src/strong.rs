@@ -3,6 +3,7 @@
 use md4::{Digest, Md4};
 use md5::Md5;
 use sha1::Sha1;
+use xxhash_rust::xxh3::{Xxh3, xxh3_64_with_seed};
 use xxhash_rust::xxh64::{Xxh64, xxh64};
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -11,6 +12,7 @@ pub enum StrongHash {
     Md5,
     Sha1,
     XxHash,
+    Xxh3,
 }
 
 pub trait StrongChecksum: Send {
@@ -70,6 +72,18 @@ impl StrongChecksum for XxHashChecksum {
     }
 }
 
+struct Xxh3Checksum(Xxh3);
+
+impl StrongChecksum for Xxh3Checksum {
+    fn update(&mut self, data: &[u8]) {
+        self.0.update(data);
+    }
+
+    fn finalize(self: Box<Self>) -> Vec<u8> {
+        self.0.digest().to_le_bytes().to_vec()
+    }
+}
+
 pub fn select_strong_checksum(alg: StrongHash, seed: u32) -> Box<dyn StrongChecksum> {
     match alg {
         StrongHash::Md4 => Box::new(Md4Checksum {
@@ -87,6 +101,7 @@ pub fn select_strong_checksum(alg: StrongHash, seed: u32) -> Box<dyn StrongCheck
             Box::new(Sha1Checksum(h))
         }
         StrongHash::XxHash => Box::new(XxHashChecksum(Xxh64::new(seed as u64))),
+        StrongHash::Xxh3 => Box::new(Xxh3Checksum(Xxh3::with_seed(seed as u64))),
     }
 }
 
@@ -112,11 +127,13 @@ pub fn strong_digest(data: &[u8], alg: StrongHash, seed: u32) -> Vec<u8> {
             hasher.finalize().to_vec()
         }
         StrongHash::XxHash => xxh64(data, seed as u64).to_le_bytes().to_vec(),
+        StrongHash::Xxh3 => xxh3_64_with_seed(data, seed as u64).to_le_bytes().to_vec(),
     }
 }
 
 pub fn available_strong_hashes() -> &'static [StrongHash] {
     &[
+        StrongHash::Xxh3,
         StrongHash::XxHash,
         StrongHash::Md5,
         StrongHash::Md4,
@@ -148,6 +165,9 @@ mod tests {
 
         let digest_xxhash = strong_digest(b"hello world", StrongHash::XxHash, 0);
         assert_eq!(hex::encode(digest_xxhash), "68691eb23467ab45");
+
+        let digest_xxh3 = strong_digest(b"hello world", StrongHash::Xxh3, 0);
+        assert_eq!(hex::encode(digest_xxh3), "8b98e640eab147d4");
     }
 
     #[test]
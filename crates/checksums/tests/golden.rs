@@ -68,6 +68,40 @@ fn builder_strong_digests() {
     assert_eq!(hex::encode(cs_xxh.strong), "68691eb23467ab45");
 }
 
+#[test]
+fn block_and_file_hashes_are_independent() {
+    let cfg = ChecksumConfigBuilder::new()
+        .strong(StrongHash::Xxh3)
+        .file_strong(StrongHash::Md5)
+        .build();
+    let data = b"hello world";
+
+    assert_eq!(hex::encode(cfg.checksum(data).strong), "8b98e640eab147d4");
+    assert_eq!(
+        hex::encode(cfg.strong_hasher().finalize()),
+        "c294d3380580062d"
+    );
+    let mut file = cfg.file_hasher();
+    file.update(data);
+    assert_eq!(
+        hex::encode(file.finalize()),
+        "be4b47980f89d075f8f7e7a9fab84e29"
+    );
+}
+
+#[test]
+fn file_hash_defaults_to_block_hash() {
+    let cfg = ChecksumConfigBuilder::new()
+        .strong(StrongHash::Sha1)
+        .build();
+    let mut file = cfg.file_hasher();
+    file.update(b"hello world");
+    assert_eq!(
+        file.finalize(),
+        strong_digest(b"hello world", StrongHash::Sha1, 0)
+    );
+}
+
 #[test]
 fn negotiation_picks_common_algorithm() {
     let remote = vec![StrongHash::Md4, StrongHash::XxHash];
@@ -38,12 +38,22 @@ const XXHASH_VECTORS: &[(&[u8], &str)] = &[
     ),
 ];
 
+const XXH3_VECTORS: &[(&[u8], &str)] = &[
+    (b"", "c294d3380580062d"),
+    (b"hello world", "8b98e640eab147d4"),
+    (
+        b"The quick brown fox jumps over the lazy dog",
+        "65b38f41a5197dce",
+    ),
+];
+
 fn vectors_for(alg: StrongHash) -> &'static [(&'static [u8], &'static str)] {
     match alg {
         StrongHash::Md4 => MD4_VECTORS,
         StrongHash::Md5 => MD5_VECTORS,
         StrongHash::Sha1 => SHA1_VECTORS,
         StrongHash::XxHash => XXHASH_VECTORS,
+        StrongHash::Xxh3 => XXH3_VECTORS,
     }
 }
 
@@ -55,6 +65,7 @@ proptest! {
         Just(StrongHash::Md5),
         Just(StrongHash::Sha1),
         Just(StrongHash::XxHash),
+        Just(StrongHash::Xxh3),
     ], idx in 0usize..3) {
         let vectors = vectors_for(alg);
         let (data, expected) = vectors[idx];
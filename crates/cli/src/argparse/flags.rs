@@ -5,12 +5,13 @@ use std::{ffi::OsString, path::PathBuf};
 
 use crate::daemon::DaemonOpts;
 use crate::utils::{
-    RshCommand, parse_duration, parse_minutes, parse_nonzero_duration, parse_rsh, parse_size,
-    parse_stop_at,
+    RshCommand, parse_age, parse_duration, parse_iconv_spec, parse_minutes, parse_nonzero_duration,
+    parse_protocol, parse_remote_env, parse_rsh, parse_size, parse_stop_at, parse_unicode_form,
 };
 use clap::{ArgAction, Parser, ValueEnum};
 use logging::{DebugFlag, InfoFlag, StderrMode};
-use oc_rsync_core::message::SUPPORTED_PROTOCOLS;
+use oc_rsync_core::config::MAX_BLOCK_SIZE;
+use oc_rsync_core::message::{SUPPORTED_PROTOCOLS, UnicodeForm};
 
 fn parse_lowercase(value: &str) -> Result<String, String> {
     Ok(value.to_ascii_lowercase())
@@ -20,10 +21,19 @@ fn parse_rsh_arg(value: &str) -> Result<RshCommand, String> {
     parse_rsh(Some(value.to_string())).map_err(|e| e.to_string())
 }
 
-fn parse_nonzero_size(value: &str) -> Result<usize, String> {
+fn parse_umask(value: &str) -> Result<u32, String> {
+    match u32::from_str_radix(value, 8) {
+        Ok(mask) if mask <= 0o7777 => Ok(mask),
+        _ => Err(format!("invalid umask {value}")),
+    }
+}
+
+fn parse_block_size(value: &str) -> Result<usize, String> {
     let size = parse_size::<usize>(value)?;
     if size == 0 {
         Err("value must be greater than 0".into())
+    } else if size > MAX_BLOCK_SIZE {
+        Err(format!("too large (max: {MAX_BLOCK_SIZE})"))
     } else {
         Ok(size)
     }
@@ -37,6 +47,13 @@ pub enum OutBuf {
     B,
 }
 
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, ValueEnum)]
+pub enum BatchFormat {
+    #[default]
+    Native,
+    Rsync,
+}
+
 #[allow(non_snake_case)]
 #[derive(Parser, Debug, Clone)]
 pub struct ClientOpts {
@@ -101,6 +118,12 @@ pub struct ClientOpts {
         allow_hyphen_values = true
     )]
     pub out_format: Option<String>,
+    #[arg(
+        long = "out-format-nul",
+        help_heading = "Output",
+        help = "terminate each output record with NUL, leaving names unescaped"
+    )]
+    pub out_format_nul: bool,
     #[arg(
         long,
         value_name = "FLAGS",
@@ -210,6 +233,19 @@ pub struct ClientOpts {
         help_heading = "Misc"
     )]
     pub max_alloc: Option<usize>,
+    #[arg(
+        long = "max-blocks",
+        value_name = "NUM",
+        help_heading = "Misc",
+        help = "cap the number of checksum blocks per file, growing the block size to fit"
+    )]
+    pub max_blocks: Option<usize>,
+    #[arg(
+        long = "cdc",
+        help_heading = "Misc",
+        help = "match content-defined chunks instead of fixed-size blocks"
+    )]
+    pub cdc: bool,
     #[arg(
         long = "max-size",
         value_name = "SIZE",
@@ -224,6 +260,36 @@ pub struct ClientOpts {
         help_heading = "Misc"
     )]
     pub min_size: Option<u64>,
+    #[arg(
+        long = "exclude-older-than",
+        value_name = "AGE",
+        value_parser = parse_age,
+        help_heading = "Misc",
+        help = "skip files last modified more than AGE ago (suffixes s, m, h, d)"
+    )]
+    pub exclude_older_than: Option<Duration>,
+    #[arg(
+        long = "exclude-newer-than",
+        value_name = "AGE",
+        value_parser = parse_age,
+        help_heading = "Misc",
+        help = "skip files last modified less than AGE ago (suffixes s, m, h, d)"
+    )]
+    pub exclude_newer_than: Option<Duration>,
+    #[arg(
+        long = "max-name-len",
+        value_name = "BYTES",
+        help_heading = "Misc",
+        help = "skip entries with a destination path component longer than BYTES"
+    )]
+    pub max_name_len: Option<usize>,
+    #[arg(
+        long = "max-path-len",
+        value_name = "BYTES",
+        help_heading = "Misc",
+        help = "skip entries whose destination path is longer than BYTES"
+    )]
+    pub max_path_len: Option<usize>,
     #[arg(
         long,
         help_heading = "Misc",
@@ -238,6 +304,13 @@ pub struct ClientOpts {
     pub suffix: Option<String>,
     #[arg(short = 'c', long, help_heading = "Attributes")]
     pub checksum: bool,
+    #[arg(
+        long = "checksum-cache",
+        help_heading = "Attributes",
+        requires = "checksum",
+        help = "keep --checksum sums in a per-directory cache, reused while size and mtime match"
+    )]
+    pub checksum_cache: bool,
     #[arg(
         long = "checksum-choice",
         value_name = "STR",
@@ -271,6 +344,14 @@ pub struct ClientOpts {
     pub executability: bool,
     #[arg(long = "chmod", value_name = "CHMOD", help_heading = "Attributes")]
     pub chmod: Vec<String>,
+    #[arg(
+        long = "umask",
+        value_name = "OCTAL",
+        value_parser = parse_umask,
+        help_heading = "Attributes",
+        help = "mask applied to newly created files and directories"
+    )]
+    pub umask: Option<u32>,
     #[arg(long = "chown", value_name = "USER:GROUP", help_heading = "Attributes")]
     pub chown: Option<String>,
     #[arg(
@@ -308,6 +389,12 @@ pub struct ClientOpts {
     pub no_times: bool,
     #[arg(short = 'U', long, help_heading = "Attributes")]
     pub atimes: bool,
+    #[arg(
+        long = "compare-atimes",
+        help_heading = "Attributes",
+        help = "with --atimes, also re-sync files whose access time differs"
+    )]
+    pub compare_atimes: bool,
     #[arg(short = 'N', long, help_heading = "Attributes")]
     pub crtimes: bool,
     #[arg(short = 'O', long, help_heading = "Attributes")]
@@ -444,8 +531,17 @@ pub struct ClientOpts {
         overrides_with_all = ["devices", "specials", "devices_specials"]
     )]
     pub no_D: bool,
-    #[arg(short = 'X', long, help_heading = "Attributes")]
-    pub xattrs: bool,
+    #[arg(
+        short = 'X',
+        long,
+        help_heading = "Attributes",
+        value_name = "PATTERN",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "",
+        action = ArgAction::Append
+    )]
+    pub xattrs: Vec<String>,
     #[arg(
         short = 'A',
         long,
@@ -457,8 +553,19 @@ pub struct ClientOpts {
     pub no_acls: bool,
     #[arg(long = "fake-super", help_heading = "Attributes")]
     pub fake_super: bool,
-    #[arg(long = "super", help_heading = "Attributes")]
+    #[arg(
+        long = "super",
+        help_heading = "Attributes",
+        overrides_with = "no_super"
+    )]
     pub super_user: bool,
+    #[arg(
+        long = "no-super",
+        help_heading = "Attributes",
+        overrides_with = "super_user",
+        hide = true
+    )]
+    pub no_super: bool,
     #[arg(short = 'z', long, help_heading = "Compression")]
     pub compress: bool,
     #[arg(
@@ -473,7 +580,8 @@ pub struct ClientOpts {
         value_name = "NUM",
         help_heading = "Compression",
         visible_alias = "zl",
-        value_parser = clap::value_parser!(i32).range(0..=9)
+        allow_negative_numbers = true,
+        value_parser = clap::value_parser!(i32).range(-7..=22)
     )]
     pub compress_level: Option<i32>,
     #[arg(
@@ -484,6 +592,13 @@ pub struct ClientOpts {
         value_parser = parse_lowercase
     )]
     pub skip_compress: Vec<String>,
+    #[arg(
+        long = "compress-map",
+        value_name = "CODEC:EXTS",
+        help_heading = "Compression",
+        help = "compress files with extensions EXTS using CODEC (or none)"
+    )]
+    pub compress_map: Vec<String>,
 
     #[arg(long, help_heading = "Misc")]
     pub partial: bool,
@@ -502,6 +617,12 @@ pub struct ClientOpts {
     pub blocking_io: bool,
     #[arg(long = "open-noatime", help_heading = "Misc")]
     pub open_noatime: bool,
+    #[arg(
+        long = "direct-io",
+        help_heading = "Misc",
+        help = "read files with O_DIRECT, bypassing the page cache where supported"
+    )]
+    pub direct_io: bool,
     #[arg(
         long = "outbuf",
         value_name = "MODE",
@@ -511,6 +632,32 @@ pub struct ClientOpts {
     pub outbuf: Option<OutBuf>,
     #[arg(long, help_heading = "Misc")]
     pub fsync: bool,
+    #[arg(
+        long = "verify-after-write",
+        help_heading = "Misc",
+        help = "check each written file against the checksum of the data sent"
+    )]
+    pub verify_after_write: bool,
+    #[arg(
+        long = "post-verify",
+        help_heading = "Misc",
+        help = "re-checksum every transferred file against the data sent once the transfer ends"
+    )]
+    pub post_verify: bool,
+    #[arg(
+        long = "fs-op-rate",
+        value_name = "OPS",
+        value_parser = clap::value_parser!(u32).range(1..),
+        help_heading = "Misc",
+        help = "limit receiver metadata operations (chmod, utimes, chown) to OPS per second"
+    )]
+    pub fs_op_rate: Option<u32>,
+    #[arg(
+        long = "metadata-only",
+        help_heading = "Misc",
+        help = "update metadata of existing destination files without transferring content"
+    )]
+    pub metadata_only: bool,
     #[arg(short = 'y', long = "fuzzy", help_heading = "Misc")]
     pub fuzzy: bool,
     #[arg(short = 'P', help_heading = "Misc")]
@@ -535,6 +682,22 @@ pub struct ClientOpts {
         help_heading = "Misc"
     )]
     pub connect_timeout: Option<Duration>,
+    #[arg(
+        long = "greeting-timeout",
+        value_name = "SECONDS",
+        value_parser = parse_nonzero_duration,
+        help_heading = "Misc",
+        help = "give up if a daemon sends no version greeting within SECONDS"
+    )]
+    pub greeting_timeout: Option<Duration>,
+    #[arg(
+        long = "daemon-retries",
+        value_name = "NUM",
+        default_value_t = 0,
+        help_heading = "Misc",
+        help = "reconnect up to NUM times when a daemon handshake fails transiently"
+    )]
+    pub daemon_retries: u32,
     #[arg(long = "modify-window", value_name = "SECONDS", value_parser = parse_duration, help_heading = "Misc")]
     pub modify_window: Option<Duration>,
     #[arg(
@@ -555,7 +718,7 @@ pub struct ClientOpts {
     #[arg(
         long = "protocol",
         value_name = "VER",
-        value_parser = clap::value_parser!(u32),
+        value_parser = parse_protocol,
         help_heading = "Misc",
         help = "force an older protocol version"
     )]
@@ -581,7 +744,7 @@ pub struct ClientOpts {
         long = "block-size",
         value_name = "SIZE",
         help_heading = "Misc",
-        value_parser = parse_nonzero_size,
+        value_parser = parse_block_size,
     )]
     pub block_size: Option<usize>,
     #[arg(
@@ -625,6 +788,13 @@ pub struct ClientOpts {
     pub early_input: Option<PathBuf>,
     #[arg(short = 'e', long, value_name = "COMMAND", value_parser = parse_rsh_arg)]
     pub rsh: Option<RshCommand>,
+    #[arg(
+        long = "remote-env",
+        value_name = "NAME[=VALUE]",
+        value_parser = parse_remote_env,
+        help = "set NAME (to VALUE, or its local value) in the remote shell's environment"
+    )]
+    pub remote_env: Vec<(String, String)>,
     #[arg(
         short = 'M',
         long = "remote-option",
@@ -660,10 +830,19 @@ pub struct ClientOpts {
     #[arg(
         long = "iconv",
         value_name = "CONVERT_SPEC",
+        value_parser = parse_iconv_spec,
         help_heading = "Misc",
         help = "request charset conversion of filenames"
     )]
     pub iconv: Option<String>,
+    #[arg(
+        long = "normalize-names",
+        value_name = "FORM",
+        value_parser = parse_unicode_form,
+        help_heading = "Misc",
+        help = "normalize UTF-8 filenames to FORM (nfc or nfd)"
+    )]
+    pub normalize_names: Option<UnicodeForm>,
     #[arg(
         long = "write-batch",
         value_name = "FILE",
@@ -688,6 +867,15 @@ pub struct ClientOpts {
         conflicts_with_all = ["write_batch", "only_write_batch"]
     )]
     pub read_batch: Option<PathBuf>,
+    #[arg(
+        long = "batch-format",
+        value_name = "FORMAT",
+        value_enum,
+        default_value_t = BatchFormat::Native,
+        help_heading = "Misc",
+        help = "format of the batch file written by --write-batch"
+    )]
+    pub batch_format: BatchFormat,
     #[arg(long = "copy-devices", help_heading = "Misc")]
     pub copy_devices: bool,
     #[arg(
@@ -136,6 +136,25 @@ pub(crate) fn build_matcher(opts: &ClientOpts, matches: &ArgMatches) -> Result<M
                 .map_err(|e| EngineError::Other(format!("{:?}", e)))?,
         );
     }
+    let xattr_patterns: Vec<&str> = opts
+        .xattrs
+        .iter()
+        .flat_map(|v| v.split(','))
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .collect();
+    if !xattr_patterns.is_empty() {
+        let sep = if opts.from0 { '\0' } else { '\n' };
+        let mut spec = String::new();
+        for pat in xattr_patterns {
+            spec.push_str(&format!("+x {pat}{sep}"));
+        }
+        spec.push_str(&format!("-x *{sep}"));
+        add_rules(
+            usize::MAX,
+            parse_filters(&spec, opts.from0).map_err(|e| EngineError::Other(format!("{:?}", e)))?,
+        );
+    }
     if opts.cvs_exclude {
         let mut cvs_rules =
             default_cvs_rules().map_err(|e| EngineError::Other(format!("{:?}", e)))?;
@@ -1,6 +1,6 @@
 // crates/cli/src/client/exec.rs
 
-use std::collections::HashSet;
+use std::collections::HashMap;
 use std::env;
 use std::ffi::OsStr;
 use std::fs;
@@ -12,9 +12,10 @@ use std::time::Duration;
 use clap::ArgMatches;
 use logging::{InfoFlag, parse_escapes};
 use oc_rsync_core::{
-    compress::{Codec, available_codecs},
-    config::{DeleteMode, SyncOptions},
+    compress::{Codec, SkipCompress, available_codecs},
+    config::{BatchFormat, DeleteMode, SyncOptions},
     fs::{IdKind, parse_chmod, parse_chown},
+    message::ExitCode,
     transfer::{Result, Stats, StrongHash},
 };
 use transport::{AddressFamily, parse_sockopts};
@@ -24,12 +25,20 @@ use users::get_user_by_uid;
 use crate::exec::{check_privileges, execute_transfer};
 use crate::{
     EngineError, RemoteSpec,
-    options::ClientOpts,
+    options::{self, ClientOpts},
     utils::{parse_iconv, parse_name_map, parse_remote_specs, parse_rsh, parse_rsync_path},
 };
 
 use super::args::build_matcher;
 
+pub(crate) fn whole_file(opts: &ClientOpts, src: &RemoteSpec, dst: &RemoteSpec) -> bool {
+    if opts.no_whole_file {
+        return false;
+    }
+    let local = matches!((src, dst), (RemoteSpec::Local(_), RemoteSpec::Local(_)));
+    opts.whole_file || (local && !opts.append && !opts.append_verify)
+}
+
 pub(crate) fn run_single(
     mut opts: ClientOpts,
     matches: &ArgMatches,
@@ -98,8 +107,17 @@ pub(crate) fn run_single(
     if let Some(spec) = &opts.iconv {
         remote_opts.push(format!("--iconv={spec}"));
     }
-    if opts.xattrs {
+    for spec in &opts.compress_map {
+        remote_opts.push(format!("--compress-map={spec}"));
+    }
+    if let Some(form) = opts.normalize_names {
+        remote_opts.push(format!("--normalize-names={}", form.as_str()));
+    }
+    if !opts.xattrs.is_empty() {
         remote_opts.push("--xattrs".into());
+        for pat in opts.xattrs.iter().filter(|p| !p.is_empty()) {
+            remote_opts.push(format!("--xattrs={pat}"));
+        }
     }
     if acls {
         remote_opts.push("--acls".into());
@@ -139,13 +157,19 @@ pub(crate) fn run_single(
             }
         }
     }
+    if let Some(spec) = &opts.chown
+        && matches!(dst, RemoteSpec::Remote { .. })
+    {
+        remote_opts.push(format!("--chown={spec}"));
+    }
 
     let known_hosts = opts.known_hosts.clone();
     let strict_host_key_checking = !opts.no_host_key_checking;
-    let rsh_cmd = match opts.rsh.clone() {
+    let mut rsh_cmd = match opts.rsh.clone() {
         Some(cmd) => cmd,
         None => parse_rsh(env::var("RSYNC_RSH").ok().or_else(|| env::var("RSH").ok()))?,
     };
+    rsh_cmd.env.extend(opts.remote_env.iter().cloned());
     let rsync_path_cmd = parse_rsync_path(opts.rsync_path.clone())?;
     let mut rsync_env: Vec<(String, String)> = env::vars()
         .filter(|(k, _)| k.starts_with("RSYNC_"))
@@ -177,15 +201,24 @@ pub(crate) fn run_single(
     let remote_bin_vec = rsync_path_cmd.as_ref().map(|c| c.cmd.clone());
     let remote_env_vec = rsync_path_cmd.as_ref().map(|c| c.env.clone());
 
+    let parse_choice = |name: &str| match name {
+        "md4" => Ok(StrongHash::Md4),
+        "md5" => Ok(StrongHash::Md5),
+        "sha1" => Ok(StrongHash::Sha1),
+        "xxh64" | "xxhash" => Ok(StrongHash::XxHash),
+        "xxh3" => Ok(StrongHash::Xxh3),
+        other => Err(EngineError::Other(format!("unknown checksum {other}"))),
+    };
+    let mut file_strong = None;
     let strong = if let Some(choice) = opts.checksum_choice.as_deref() {
-        match choice {
-            "md4" => StrongHash::Md4,
-            "md5" => StrongHash::Md5,
-            "sha1" => StrongHash::Sha1,
-            other => {
-                return Err(EngineError::Other(format!("unknown checksum {other}")));
-            }
+        let (block, file) = match choice.split_once(',') {
+            Some((block, file)) => (block, Some(file)),
+            None => (choice, None),
+        };
+        if let Some(file) = file {
+            file_strong = Some(parse_choice(file)?);
         }
+        parse_choice(block)?
     } else if let Ok(list) = env::var("RSYNC_CHECKSUM_LIST") {
         let mut chosen = StrongHash::Md4;
         for name in list.split(',') {
@@ -225,8 +258,10 @@ pub(crate) fn run_single(
             RemoteSpec::Remote { path, .. } => path.path.push(rel),
         }
     }
+    let src_missing =
+        matches!(src, RemoteSpec::Local(_)) && fs::symlink_metadata(src_path).is_err();
     match &mut dst {
-        RemoteSpec::Local(p) if !p.path.is_dir() => {
+        RemoteSpec::Local(p) if !p.path.is_dir() && !src_missing => {
             if let Some(parent) = p.path.parent() {
                 p.path = parent.to_path_buf();
             }
@@ -243,6 +278,8 @@ pub(crate) fn run_single(
                     "zlib" => Codec::Zlib,
                     "zlibx" => Codec::ZlibX,
                     "zstd" => Codec::Zstd,
+                    "bzip2" => Codec::Bzip2,
+                    "lz4" => Codec::Lz4,
                     other => {
                         return Err(EngineError::Other(format!("unknown codec {other}")));
                     }
@@ -258,6 +295,61 @@ pub(crate) fn run_single(
         }
         None => None,
     };
+    if let Some(level) = opts.compress_level {
+        let codec = match &compress_choice {
+            Some(list) => list.first().copied(),
+            None => [Codec::Zstd, Codec::ZlibX, Codec::Zlib]
+                .into_iter()
+                .find(|c| available_codecs().contains(c)),
+        };
+        if let Some(codec) = codec
+            && !codec.level_range().contains(&level)
+        {
+            let name = match codec {
+                Codec::Zlib => "zlib",
+                Codec::ZlibX => "zlibx",
+                Codec::Zstd => "zstd",
+                Codec::Bzip2 => "bzip2",
+                Codec::Lz4 => "lz4",
+            };
+            let range = codec.level_range();
+            return Err(EngineError::Exit(
+                ExitCode::SyntaxOrUsage,
+                format!(
+                    "--compress-level={level} is out of range for {name} ({}-{})",
+                    range.start(),
+                    range.end()
+                ),
+            ));
+        }
+    }
+    let mut compress_map = HashMap::new();
+    for spec in &opts.compress_map {
+        let (name, exts) = spec
+            .split_once(':')
+            .ok_or_else(|| EngineError::Other(format!("invalid --compress-map {spec}")))?;
+        let codec = match name {
+            "zlib" => Some(Codec::Zlib),
+            "zlibx" => Some(Codec::ZlibX),
+            "zstd" | "zst" => Some(Codec::Zstd),
+            "bzip2" | "bz2" => Some(Codec::Bzip2),
+            "lz4" => Some(Codec::Lz4),
+            "none" => None,
+            other => {
+                return Err(EngineError::Other(format!("unknown codec {other}")));
+            }
+        };
+        if let Some(c) = codec
+            && !available_codecs().contains(&c)
+        {
+            return Err(EngineError::Other(format!(
+                "codec {name} not supported by this build"
+            )));
+        }
+        for ext in exts.split(',').filter(|e| !e.is_empty()) {
+            compress_map.insert(ext.trim_start_matches('.').to_ascii_lowercase(), codec);
+        }
+    }
     let compress = if opts.compress_choice.as_deref() == Some("none") {
         false
     } else {
@@ -280,10 +372,9 @@ pub(crate) fn run_single(
     for spec in &opts.chmod {
         chmod_rules.extend(parse_chmod(spec).map_err(EngineError::Other)?);
     }
-    let chown_ids = if let Some(ref spec) = opts.chown {
-        Some(parse_chown(spec).map_err(EngineError::Other)?)
-    } else {
-        None
+    let chown_ids = match (&opts.chown, &dst) {
+        (Some(spec), RemoteSpec::Local(_)) => Some(parse_chown(spec).map_err(EngineError::Other)?),
+        _ => None,
     };
     let copy_as = if let Some(ref spec) = opts.copy_as {
         let (uid_opt, gid_opt) = parse_chown(spec).map_err(EngineError::Other)?;
@@ -313,6 +404,11 @@ pub(crate) fn run_single(
             (None, None) => (None, false),
             _ => unreachable!(),
         };
+    #[cfg(unix)]
+    let privileged = oc_rsync_core::fs::has_chown_privilege();
+    #[cfg(not(unix))]
+    let privileged = false;
+    let fake_super = opts.fake_super && !(opts.super_user && privileged);
     let mut sync_opts = SyncOptions {
         delete: delete_mode,
         delete_excluded: opts.delete_excluded,
@@ -323,10 +419,18 @@ pub(crate) fn run_single(
         force: opts.force,
         max_delete: opts.max_delete,
         max_alloc: opts.max_alloc.unwrap_or(1usize << 30),
+        max_blocks: opts.max_blocks.unwrap_or(0),
+        cdc: opts.cdc,
         max_size: opts.max_size,
         min_size: opts.min_size,
+        exclude_older_than: opts.exclude_older_than,
+        exclude_newer_than: opts.exclude_newer_than,
+        age_reference: None,
+        max_name_len: opts.max_name_len,
+        max_path_len: opts.max_path_len,
         preallocate: opts.preallocate,
         checksum: opts.checksum,
+        checksum_cache: opts.checksum_cache,
         compress,
         dirs_only: opts.dirs,
         no_implied_dirs: opts.no_implied_dirs,
@@ -344,12 +448,15 @@ pub(crate) fn run_single(
             opts.perms || opts.archive || acls
         },
         executability: opts.executability,
+        keep_dest_perms: false,
+        umask: opts.umask,
         times: if opts.no_times {
             false
         } else {
             opts.times || opts.archive
         },
         atimes: opts.atimes,
+        compare_atimes: opts.compare_atimes,
         crtimes: opts.crtimes,
         omit_dir_times: opts.omit_dir_times,
         omit_link_times: opts.omit_link_times,
@@ -387,19 +494,17 @@ pub(crate) fn run_single(
         } else {
             opts.specials || opts.archive || opts.devices_specials
         },
-        xattrs: opts.xattrs || (opts.fake_super && !opts.super_user),
+        xattrs: !opts.xattrs.is_empty() || fake_super,
         acls,
         sparse: opts.sparse,
         strong,
+        file_strong,
         checksum_seed: opts.checksum_seed.unwrap_or_default(),
         compress_level: opts.compress_level,
         compress_choice,
-        whole_file: if opts.no_whole_file {
-            false
-        } else {
-            opts.whole_file
-        },
-        skip_compress: opts.skip_compress.iter().cloned().collect::<HashSet<_>>(),
+        whole_file: whole_file(&opts, &src, &dst),
+        skip_compress: SkipCompress::new(&opts.skip_compress),
+        compress_map,
         partial: opts.partial
             || opts.partial_progress
             || opts.partial_dir.is_some()
@@ -420,6 +525,7 @@ pub(crate) fn run_single(
         bwlimit: opts.bwlimit,
         stop_after: opts.stop_after,
         stop_at: opts.stop_at,
+        greeting_timeout: opts.greeting_timeout,
         block_size,
         link_dest: opts.link_dest.clone(),
         copy_dest: opts.copy_dest.clone(),
@@ -442,22 +548,34 @@ pub(crate) fn run_single(
         copy_as,
         uid_map,
         gid_map,
+        path_transform: None,
+        normalize_names: opts.normalize_names,
         eight_bit_output: opts.eight_bit_output,
         blocking_io: opts.blocking_io,
         open_noatime: opts.open_noatime,
+        direct_io: opts.direct_io,
         early_input: opts.early_input.clone(),
+        protocol: opts.protocol,
         secluded_args: opts.secluded_args,
         sockopts: opts.sockopts.clone(),
         remote_options: remote_opts.clone(),
         write_batch,
         only_write_batch,
         read_batch: opts.read_batch.clone(),
+        batch_format: match opts.batch_format {
+            options::BatchFormat::Native => BatchFormat::Native,
+            options::BatchFormat::Rsync => BatchFormat::Rsync,
+        },
         copy_devices: opts.copy_devices,
         write_devices: opts.write_devices,
         fsync: opts.fsync,
+        verify_after_write: opts.verify_after_write,
+        post_verify: opts.post_verify,
+        fs_op_rate: opts.fs_op_rate,
+        metadata_only: opts.metadata_only,
         fuzzy: opts.fuzzy,
         super_user: opts.super_user,
-        fake_super: opts.fake_super && !opts.super_user,
+        fake_super: opts.fake_super,
         quiet: opts.quiet,
     };
     let stats = execute_transfer(
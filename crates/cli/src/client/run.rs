@@ -1,7 +1,8 @@
 // crates/cli/src/client/run.rs
 
 use clap::ArgMatches;
-use oc_rsync_core::transfer::{Result, Stats};
+use oc_rsync_core::message::ExitCode;
+use oc_rsync_core::transfer::{EngineError, Result, Stats};
 
 use crate::{
     daemon::run_daemon,
@@ -42,15 +43,37 @@ pub(crate) fn run_client(opts: ClientOpts, matches: &ArgMatches) -> Result<()> {
         total.bytes_transferred += stats.bytes_transferred;
         total.literal_data += stats.literal_data;
         total.matched_data += stats.matched_data;
+        total.pre_compression_bytes += stats.pre_compression_bytes;
+        total.post_compression_bytes += stats.post_compression_bytes;
+        total.compress_calls += stats.compress_calls;
         total.file_list_size += stats.file_list_size;
         total.file_list_gen_time += stats.file_list_gen_time;
         total.file_list_transfer_time += stats.file_list_transfer_time;
         total.bytes_sent += stats.bytes_sent;
         total.bytes_received += stats.bytes_received;
+        total.had_partial_errors |= stats.had_partial_errors;
+        total.file_errors += stats.file_errors;
+        total.deletes_skipped += stats.deletes_skipped;
     }
     if opts.stats && !opts.quiet {
         print::print_stats(&total, &opts);
     }
+    if total.had_partial_errors || total.file_errors > 0 {
+        return Err(EngineError::Exit(
+            ExitCode::Partial,
+            "rsync error: some files/attrs were not transferred (see previous errors) (code 23)"
+                .into(),
+        ));
+    }
+    if total.deletes_skipped > 0 {
+        return Err(EngineError::Exit(
+            ExitCode::DelLimit,
+            format!(
+                "Deletions stopped due to --max-delete limit ({} skipped)\nrsync error: the --max-delete limit stopped deletions (code 25)",
+                total.deletes_skipped
+            ),
+        ));
+    }
     Ok(())
 }
 
@@ -188,4 +211,25 @@ mod tests {
             _ => panic!("expected remote spec"),
         }
     }
+
+    #[test]
+    fn whole_file_defaults_on_for_local_transfers_only() {
+        use super::super::exec::whole_file;
+        use crate::options::ClientOpts;
+        let parse = |args: &[&str]| {
+            let matches = cli_command().try_get_matches_from(args).unwrap();
+            ClientOpts::from_arg_matches(&matches).unwrap()
+        };
+        let local = parse_remote_spec(OsStr::new("/tmp/src")).unwrap();
+        let remote = parse_remote_spec(OsStr::new("host:/tmp/dst")).unwrap();
+        let opts = parse(&["prog", "src", "dst"]);
+        assert!(whole_file(&opts, &local, &local));
+        assert!(!whole_file(&opts, &local, &remote));
+        assert!(!whole_file(&opts, &remote, &local));
+
+        let opts = parse(&["prog", "--no-whole-file", "src", "dst"]);
+        assert!(!whole_file(&opts, &local, &local));
+        let opts = parse(&["prog", "--whole-file", "src", "dst"]);
+        assert!(whole_file(&opts, &local, &remote));
+    }
 }
@@ -12,14 +12,18 @@ use std::time::{Duration, Instant};
 
 use crate::utils::{parse_bool, parse_dparam};
 use clap::{ArgMatches, Args};
+use daemon::auth::{EARLY_INPUT_PREFIX, MAX_EARLY_INPUT};
 use daemon::{self, Module, parse_config_file, parse_module};
 use logging::parse_escapes;
-use oc_rsync_core::message::{CharsetConv, ExitCode, negotiate_version};
+use oc_rsync_core::message::{CharsetConv, ExitCode, negotiate_forced_version, negotiate_version};
 use oc_rsync_core::{
     config::SyncOptions,
     transfer::{EngineError, Result},
 };
-use transport::{AddressFamily, SockOpt, TcpTransport, Transport, parse_sockopts};
+use transport::{
+    AddressFamily, DEFAULT_GREETING_TIMEOUT, SockOpt, TcpTransport, Transport, parse_greeting,
+    parse_sockopts,
+};
 
 #[derive(Args, Debug, Clone)]
 pub struct DaemonOpts {
@@ -47,6 +51,59 @@ pub struct DaemonOpts {
     pub state_dir: Option<PathBuf>,
     #[arg(long = "dparam", value_name = "NAME=VALUE", value_parser = parse_dparam)]
     pub dparam: Vec<(String, String)>,
+    #[arg(
+        long = "bwlimit-pacing",
+        help = "enforce --bwlimit with the kernel's SO_MAX_PACING_RATE where available"
+    )]
+    pub bwlimit_pacing: bool,
+}
+
+const DAEMON_RETRY_DELAY: Duration = Duration::from_millis(500);
+
+#[derive(Debug)]
+struct DaemonBusy(String);
+
+impl std::fmt::Display for DaemonBusy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for DaemonBusy {}
+
+fn is_transient_handshake_error(e: &EngineError) -> bool {
+    match e {
+        EngineError::Io(err) if err.get_ref().is_some_and(|inner| inner.is::<DaemonBusy>()) => true,
+        EngineError::Io(err) => matches!(
+            err.kind(),
+            io::ErrorKind::TimedOut
+                | io::ErrorKind::WouldBlock
+                | io::ErrorKind::ConnectionRefused
+                | io::ErrorKind::ConnectionAborted
+                | io::ErrorKind::ConnectionReset
+                | io::ErrorKind::NotConnected
+                | io::ErrorKind::UnexpectedEof
+        ),
+        EngineError::Exit(code, _) => matches!(code, ExitCode::Timeout),
+        _ => false,
+    }
+}
+
+pub fn reconnect_daemon_session<F>(retries: u32, mut connect: F) -> Result<TcpTransport>
+where
+    F: FnMut() -> Result<TcpTransport>,
+{
+    let mut attempt = 0;
+    loop {
+        match connect() {
+            Err(e) if attempt < retries && is_transient_handshake_error(&e) => {
+                attempt += 1;
+                tracing::warn!("daemon handshake failed ({e}); reconnect {attempt}/{retries}");
+                std::thread::sleep(DAEMON_RETRY_DELAY * attempt);
+            }
+            res => return res,
+        }
+    }
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -89,16 +146,35 @@ pub fn spawn_daemon_session(
         .map_err(EngineError::from)?;
     t.set_write_timeout(handshake_timeout)
         .map_err(EngineError::from)?;
+    t.send(&version.to_be_bytes()).map_err(EngineError::from)?;
+    let greeting_timeout = opts.greeting_timeout.unwrap_or(DEFAULT_GREETING_TIMEOUT);
+    let greeting_timeout = handshake_timeout.map_or(greeting_timeout, |d| d.min(greeting_timeout));
+    let peer = t
+        .read_greeting(greeting_timeout)
+        .map_err(EngineError::from)?;
+    match opts.protocol {
+        Some(forced) => negotiate_forced_version(forced, peer),
+        None => negotiate_version(version, peer),
+    }
+    .map_err(|e| EngineError::Other(e.to_string()))?;
+
     if let Some(p) = early_input {
-        if let Ok(data) = fs::read(p) {
-            t.send(&data).map_err(EngineError::from)?;
+        let data = fs::read(p).map_err(|e| {
+            EngineError::Other(format!(
+                "failed to read early-input file {}: {e}",
+                p.display()
+            ))
+        })?;
+        if data.len() > MAX_EARLY_INPUT {
+            return Err(EngineError::Other(format!(
+                "early-input file {} is larger than {MAX_EARLY_INPUT} bytes",
+                p.display()
+            )));
         }
+        let mut msg = format!("{EARLY_INPUT_PREFIX}{}\n", data.len()).into_bytes();
+        msg.extend_from_slice(&data);
+        t.send(&msg).map_err(EngineError::from)?;
     }
-    t.send(&version.to_be_bytes()).map_err(EngineError::from)?;
-    let mut buf = [0u8; 4];
-    t.receive(&mut buf).map_err(EngineError::from)?;
-    let peer = u32::from_be_bytes(buf);
-    negotiate_version(version, peer).map_err(|e| EngineError::Other(e.to_string()))?;
 
     let token = password_file
         .and_then(|p| fs::read_to_string(p).ok())
@@ -109,6 +185,7 @@ pub fn spawn_daemon_session(
     let mut line = Vec::new();
     let mut b = [0u8; 1];
     let mut seen_ok = false;
+    let mut in_motd = false;
     loop {
         let n = t.receive(&mut b).map_err(EngineError::from)?;
         if n == 0 {
@@ -130,15 +207,23 @@ pub fn spawn_daemon_session(
                 if msg == "timeout waiting for daemon connection" {
                     return Err(EngineError::Exit(ExitCode::Timeout, msg));
                 }
+                if msg.starts_with("max connections") {
+                    return Err(io::Error::other(DaemonBusy(msg)).into());
+                }
                 return Err(EngineError::Other(msg));
             }
-            if !no_motd {
-                if let Some(msg) = s.strip_prefix("@RSYNCD: ") {
-                    print!("{msg}");
-                } else {
-                    print!("{s}");
+            if !in_motd && let Some(version) = parse_greeting(&s) {
+                tracing::debug!("daemon greeting: protocol {version}");
+            } else {
+                in_motd = true;
+                if !no_motd {
+                    if let Some(msg) = s.strip_prefix("@RSYNCD: ") {
+                        print!("{msg}");
+                    } else {
+                        print!("{s}");
+                    }
+                    let _ = io::stdout().flush();
                 }
-                let _ = io::stdout().flush();
             }
             line.clear();
         }
@@ -206,7 +291,7 @@ pub(crate) fn run_daemon(opts: DaemonOpts, matches: &ArgMatches) -> Result<()> {
     let mut state_dir = opts.state_dir.clone();
     let mut port = matches.get_one::<u16>("port").copied().unwrap_or(873);
     let mut address = opts.address;
-    let timeout = matches.get_one::<Duration>("timeout").copied();
+    let mut timeout = matches.get_one::<Duration>("timeout").copied();
     let bwlimit = matches.get_one::<u64>("bwlimit").copied();
     let numeric_ids_flag = matches.get_flag("numeric_ids");
     let mut list = true;
@@ -239,6 +324,9 @@ pub(crate) fn run_daemon(opts: DaemonOpts, matches: &ArgMatches) -> Result<()> {
         if let Some(a) = cfg.address {
             address = Some(a);
         }
+        if timeout.is_none() {
+            timeout = cfg.timeout;
+        }
         if !cfg.hosts_allow.is_empty() {
             hosts_allow = cfg.hosts_allow;
         }
@@ -351,7 +439,7 @@ pub(crate) fn run_daemon(opts: DaemonOpts, matches: &ArgMatches) -> Result<()> {
         None
     };
 
-    let handler: Arc<daemon::Handler> = Arc::new(|_, _| Ok(()));
+    let handler: Arc<daemon::Handler> = Arc::new(|_, _, _| Ok(()));
     let quiet = matches.get_flag("quiet");
 
     daemon::run_daemon(
@@ -370,6 +458,7 @@ pub(crate) fn run_daemon(opts: DaemonOpts, matches: &ArgMatches) -> Result<()> {
         state_dir,
         timeout,
         bwlimit,
+        opts.bwlimit_pacing,
         max_conn,
         refuse,
         list,
@@ -6,7 +6,7 @@ use std::path::Path;
 use crate::EngineError;
 use crate::options::ClientOpts;
 use crate::utils::RshCommand;
-use crate::{PathSpec, RemoteSpec};
+use crate::{PathSpec, RemoteSpec, reconnect_daemon_session, spawn_daemon_session};
 
 use oc_rsync_core::{
     compress::available_codecs,
@@ -15,10 +15,20 @@ use oc_rsync_core::{
     message::{CAP_ACLS, CAP_CODECS, CAP_XATTRS, CharsetConv},
     transfer::{Result, Stats, sync},
 };
-use transport::{AddressFamily, SshStdioTransport};
+use transport::{AddressFamily, RemoteBinaryError, SshStdioTransport, daemon_remote_opts};
 
 mod remote_remote;
 
+fn remote_connect_error(e: std::io::Error) -> EngineError {
+    match e
+        .get_ref()
+        .and_then(|inner| inner.downcast_ref::<RemoteBinaryError>())
+    {
+        Some(err) => EngineError::Other(format!("remote rsync not found or incompatible: {err}")),
+        None => EngineError::from(e),
+    }
+}
+
 fn build_rsync_url(host: &str, port: Option<u16>, module: &str, path: &PathSpec) -> OsString {
     let host = if host.contains(':') {
         format!("[{host}]")
@@ -43,6 +53,41 @@ fn build_rsync_url(host: &str, port: Option<u16>, module: &str, path: &PathSpec)
     }
 }
 
+/// Opens the daemon session for a local<->daemon transfer, retrying transient
+/// handshake failures up to `--daemon-retries` times.
+#[allow(clippy::too_many_arguments)]
+fn connect_daemon(
+    host: &str,
+    port: Option<u16>,
+    module: &str,
+    path: &PathSpec,
+    opts: &ClientOpts,
+    addr_family: Option<AddressFamily>,
+    iconv: Option<&CharsetConv>,
+    sync_opts: &SyncOptions,
+) -> Result<()> {
+    let mut daemon_opts = sync_opts.clone();
+    daemon_opts.remote_options = daemon_remote_opts(&sync_opts.remote_options, &path.path);
+    reconnect_daemon_session(opts.daemon_retries, || {
+        spawn_daemon_session(
+            host,
+            module,
+            port.or(opts.port),
+            opts.password_file.as_deref(),
+            opts.no_motd,
+            opts.timeout,
+            opts.connect_timeout,
+            addr_family,
+            &opts.sockopts,
+            &daemon_opts,
+            opts.protocol.unwrap_or(31),
+            opts.early_input.as_deref(),
+            iconv,
+        )
+    })?;
+    Ok(())
+}
+
 #[allow(clippy::too_many_arguments)]
 pub(crate) fn execute_transfer(
     src: RemoteSpec,
@@ -77,6 +122,16 @@ pub(crate) fn execute_transfer(
             },
             RemoteSpec::Local(dst),
         ) => {
+            connect_daemon(
+                &host,
+                port,
+                &module,
+                &src,
+                opts,
+                addr_family,
+                iconv,
+                sync_opts,
+            )?;
             let remote_src = build_rsync_url(&host, port, &module, &src);
             sync(
                 Path::new(&remote_src),
@@ -118,7 +173,7 @@ pub(crate) fn execute_transfer(
                 caps_send,
                 None,
             )
-            .map_err(EngineError::from)?;
+            .map_err(remote_connect_error)?;
             if sync_opts.xattrs && caps & CAP_XATTRS == 0 {
                 sync_opts.xattrs = false;
             }
@@ -145,6 +200,16 @@ pub(crate) fn execute_transfer(
                 module: Some(module),
             },
         ) => {
+            connect_daemon(
+                &host,
+                port,
+                &module,
+                &dst,
+                opts,
+                addr_family,
+                iconv,
+                sync_opts,
+            )?;
             let remote_dst = build_rsync_url(&host, port, &module, &dst);
             sync(
                 &src.path,
@@ -186,7 +251,7 @@ pub(crate) fn execute_transfer(
                 caps_send,
                 None,
             )
-            .map_err(EngineError::from)?;
+            .map_err(remote_connect_error)?;
             if sync_opts.xattrs && caps & CAP_XATTRS == 0 {
                 sync_opts.xattrs = false;
             }
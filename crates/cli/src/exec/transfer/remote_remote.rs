@@ -5,7 +5,7 @@ use std::path::Path;
 use crate::options::ClientOpts;
 use crate::session::check_session_errors;
 use crate::utils::RshCommand;
-use crate::{EngineError, PathSpec, spawn_daemon_session};
+use crate::{EngineError, PathSpec, reconnect_daemon_session, spawn_daemon_session};
 use oc_rsync_core::{
     config::SyncOptions,
     message::CharsetConv,
@@ -87,36 +87,40 @@ pub(super) fn remote_to_remote(
             src_opts.remote_options = daemon_remote_opts(&sync_opts.remote_options, &src_path.path);
             let mut dst_opts = sync_opts.clone();
             dst_opts.remote_options = daemon_remote_opts(&sync_opts.remote_options, &dst_path.path);
-            let mut src_session = spawn_daemon_session(
-                &src_host,
-                &sm,
-                src_port.or(opts.port),
-                opts.password_file.as_deref(),
-                opts.no_motd,
-                opts.timeout,
-                opts.connect_timeout,
-                addr_family,
-                &opts.sockopts,
-                &src_opts,
-                opts.protocol.unwrap_or(31),
-                opts.early_input.as_deref(),
-                iconv,
-            )?;
-            let mut dst_session = spawn_daemon_session(
-                &dst_host,
-                &dm,
-                dst_port.or(opts.port),
-                opts.password_file.as_deref(),
-                opts.no_motd,
-                opts.timeout,
-                opts.connect_timeout,
-                addr_family,
-                &opts.sockopts,
-                &dst_opts,
-                opts.protocol.unwrap_or(31),
-                opts.early_input.as_deref(),
-                iconv,
-            )?;
+            let mut src_session = reconnect_daemon_session(opts.daemon_retries, || {
+                spawn_daemon_session(
+                    &src_host,
+                    &sm,
+                    src_port.or(opts.port),
+                    opts.password_file.as_deref(),
+                    opts.no_motd,
+                    opts.timeout,
+                    opts.connect_timeout,
+                    addr_family,
+                    &opts.sockopts,
+                    &src_opts,
+                    opts.protocol.unwrap_or(31),
+                    opts.early_input.as_deref(),
+                    iconv,
+                )
+            })?;
+            let mut dst_session = reconnect_daemon_session(opts.daemon_retries, || {
+                spawn_daemon_session(
+                    &dst_host,
+                    &dm,
+                    dst_port.or(opts.port),
+                    opts.password_file.as_deref(),
+                    opts.no_motd,
+                    opts.timeout,
+                    opts.connect_timeout,
+                    addr_family,
+                    &opts.sockopts,
+                    &dst_opts,
+                    opts.protocol.unwrap_or(31),
+                    opts.early_input.as_deref(),
+                    iconv,
+                )
+            })?;
             if let Some(limit) = opts.bwlimit {
                 let mut dst_session = RateLimitedTransport::new(dst_session, limit);
                 let stats = pipe_sessions(&mut src_session, &mut dst_session)?;
@@ -145,21 +149,23 @@ pub(super) fn remote_to_remote(
             .map_err(EngineError::from)?;
             let mut src_opts = sync_opts.clone();
             src_opts.remote_options = daemon_remote_opts(&sync_opts.remote_options, &src_path.path);
-            let mut src_session = spawn_daemon_session(
-                &src_host,
-                &sm,
-                src_port.or(opts.port),
-                opts.password_file.as_deref(),
-                opts.no_motd,
-                opts.timeout,
-                opts.connect_timeout,
-                addr_family,
-                &opts.sockopts,
-                &src_opts,
-                opts.protocol.unwrap_or(31),
-                opts.early_input.as_deref(),
-                iconv,
-            )?;
+            let mut src_session = reconnect_daemon_session(opts.daemon_retries, || {
+                spawn_daemon_session(
+                    &src_host,
+                    &sm,
+                    src_port.or(opts.port),
+                    opts.password_file.as_deref(),
+                    opts.no_motd,
+                    opts.timeout,
+                    opts.connect_timeout,
+                    addr_family,
+                    &opts.sockopts,
+                    &src_opts,
+                    opts.protocol.unwrap_or(31),
+                    opts.early_input.as_deref(),
+                    iconv,
+                )
+            })?;
             if let Some(limit) = opts.bwlimit {
                 let mut dst_session = RateLimitedTransport::new(dst_session, limit);
                 let stats = pipe_sessions(&mut src_session, &mut dst_session)?;
@@ -175,21 +181,23 @@ pub(super) fn remote_to_remote(
         (None, Some(dm)) => {
             let mut dst_opts = sync_opts.clone();
             dst_opts.remote_options = daemon_remote_opts(&sync_opts.remote_options, &dst_path.path);
-            let mut dst_session = spawn_daemon_session(
-                &dst_host,
-                &dm,
-                dst_port.or(opts.port),
-                opts.password_file.as_deref(),
-                opts.no_motd,
-                opts.timeout,
-                opts.connect_timeout,
-                addr_family,
-                &opts.sockopts,
-                &dst_opts,
-                opts.protocol.unwrap_or(31),
-                opts.early_input.as_deref(),
-                iconv,
-            )?;
+            let mut dst_session = reconnect_daemon_session(opts.daemon_retries, || {
+                spawn_daemon_session(
+                    &dst_host,
+                    &dm,
+                    dst_port.or(opts.port),
+                    opts.password_file.as_deref(),
+                    opts.no_motd,
+                    opts.timeout,
+                    opts.connect_timeout,
+                    addr_family,
+                    &opts.sockopts,
+                    &dst_opts,
+                    opts.protocol.unwrap_or(31),
+                    opts.early_input.as_deref(),
+                    iconv,
+                )
+            })?;
             let mut src_session = SshStdioTransport::spawn_with_rsh(
                 &src_host,
                 &src_path.path,
@@ -24,7 +24,7 @@ pub mod options {
 }
 
 pub use client::run;
-pub use daemon::spawn_daemon_session;
+pub use daemon::{reconnect_daemon_session, spawn_daemon_session};
 pub use formatter::{ARG_ORDER, dump_help_body, render_help};
 pub use oc_rsync_core::transfer::EngineError;
 pub use oc_rsync_core::{PathSpec, RemoteSpec, is_remote_spec, parse_remote_spec};
@@ -33,4 +33,7 @@ pub use options::{
     exit_code_from_error_kind, validate_paths,
 };
 pub use print::handle_clap_error;
-pub use utils::{parse_iconv, parse_logging_flags, parse_rsh, print_version_if_requested};
+pub use utils::{
+    parse_iconv, parse_logging_flags, parse_rsh, print_iconv_charsets_if_requested,
+    print_version_if_requested,
+};
@@ -2,7 +2,7 @@
 use crate::branding;
 use crate::formatter::render_help;
 use crate::options::{ClientOpts, exit_code_from_error_kind};
-use logging::{InfoFlag, human_bytes, progress_formatter, rate_formatter};
+use logging::{InfoFlag, human_bytes, progress_formatter, rate_formatter, timestring};
 use oc_rsync_core::message::ExitCode;
 use oc_rsync_core::transfer::Stats;
 
@@ -18,7 +18,12 @@ pub fn handle_clap_error(cmd: &clap::Command, e: clap::Error) -> ! {
             let first = msg.lines().next().unwrap_or("");
             if first.contains("--block-size") || first.contains("'-B") {
                 let val = first.split('\'').nth(1).unwrap_or("");
-                msg = format!("--block-size={val} is invalid");
+                msg = match first.split_once("': ") {
+                    Some((_, reason)) if reason.starts_with("too large") => {
+                        format!("--block-size={val} is {reason}")
+                    }
+                    _ => format!("--block-size={val} is invalid"),
+                };
             } else if let Some(rest) = first.strip_prefix("error: invalid value '") {
                 if let Some((val, rest)) = rest.split_once('\'') {
                     if let Some(rest) = rest.strip_prefix(" for '") {
@@ -29,7 +34,10 @@ pub fn handle_clap_error(cmd: &clap::Command, e: clap::Error) -> ! {
                             } else {
                                 "invalid value"
                             };
-                            msg = format!("{opt_name}={val}: {kind}");
+                            msg = match rest.split_once("': ") {
+                                Some((_, reason)) if opt_name == "--iconv" => reason.to_string(),
+                                _ => format!("{opt_name}={val}: {kind}"),
+                            };
                         }
                     }
                 }
@@ -98,6 +106,14 @@ pub(crate) fn print_stats(stats: &Stats, opts: &ClientOpts) {
     );
     println!("Literal data: {}", fmt_bytes(stats.literal_data));
     println!("Matched data: {}", fmt_bytes(stats.matched_data));
+    if opts.info.contains(&InfoFlag::Stats3) && stats.pre_compression_bytes > 0 {
+        println!(
+            "Compressed literal data: {} from {} (ratio {:.2})",
+            fmt_bytes(stats.post_compression_bytes),
+            fmt_bytes(stats.pre_compression_bytes),
+            stats.compression_ratio()
+        );
+    }
     println!("File list size: {}", fmt_count(stats.file_list_size));
     println!(
         "File list generation time: {:.3} seconds",
@@ -116,6 +132,11 @@ pub(crate) fn print_stats(stats: &Stats, opts: &ClientOpts) {
         progress_formatter(stats.bytes_received, opts.human_readable)
     );
     let elapsed = stats.elapsed().as_secs_f64();
+    if opts.info.contains(&InfoFlag::Stats3) {
+        println!("Transfer start time: {}", timestring(stats.started_at));
+        println!("Transfer end time: {}", timestring(stats.end_time()));
+        println!("Total elapsed time: {elapsed:.3} seconds");
+    }
     let rate = if elapsed > 0.0 {
         let total = stats.bytes_sent + stats.bytes_received;
         rate_formatter(total as f64 / elapsed)
@@ -145,6 +166,8 @@ pub(crate) fn print_stats(stats: &Stats, opts: &ClientOpts) {
         target: InfoFlag::Stats.target(),
         files_transferred = stats.files_transferred,
         files_deleted = stats.files_deleted,
-        bytes = stats.bytes_transferred
+        bytes = stats.bytes_transferred,
+        "{}",
+        stats.machine_summary()
     );
 }
@@ -8,14 +8,13 @@ use std::{ffi::OsStr, io, path::PathBuf};
 
 use crate::EngineError;
 use clap::ArgMatches;
-use encoding_rs::Encoding;
 use logging::{DebugFlag, InfoFlag, StderrMode, SubscriberConfig};
 use oc_rsync_core::{
     RemoteSpec,
     config::IdMapper,
     filter::{ParseError, Rule, parse_with_options},
     fs::{IdKind, parse_id_map},
-    message::CharsetConv,
+    message::{CharsetConv, SUPPORTED_PROTOCOLS, UnicodeForm},
     parse_remote_spec,
     transfer::Result,
 };
@@ -48,6 +47,19 @@ where
     }
 }
 
+pub fn print_iconv_charsets_if_requested<I>(args: I) -> bool
+where
+    I: IntoIterator<Item = OsString>,
+{
+    if !args.into_iter().any(|arg| arg == "--iconv=?") {
+        return false;
+    }
+    for name in CharsetConv::supported_charsets() {
+        println!("{name}");
+    }
+    true
+}
+
 pub(crate) fn parse_filters(s: &str, from0: bool) -> std::result::Result<Vec<Rule>, ParseError> {
     let mut v = HashSet::new();
     parse_with_options(s, from0, &mut v, 0, None)
@@ -66,6 +78,28 @@ pub(crate) fn parse_nonzero_duration(s: &str) -> std::result::Result<Duration, S
     }
 }
 
+pub(crate) fn parse_age(s: &str) -> std::result::Result<Duration, String> {
+    let (num, unit) = match s.char_indices().last() {
+        Some((i, c)) if c.is_ascii_alphabetic() => (&s[..i], c.to_ascii_lowercase()),
+        _ => (s, 's'),
+    };
+    let scale = match unit {
+        's' => 1,
+        'm' => 60,
+        'h' => 60 * 60,
+        'd' => 24 * 60 * 60,
+        _ => {
+            return Err(format!(
+                "invalid age suffix '{unit}' (expected s, m, h or d)"
+            ));
+        }
+    };
+    let n: u64 = num.parse().map_err(|_| format!("invalid age: {s}"))?;
+    n.checked_mul(scale)
+        .map(Duration::from_secs)
+        .ok_or_else(|| format!("age too large: {s}"))
+}
+
 pub(crate) fn parse_minutes(s: &str) -> std::result::Result<Duration, String> {
     parse_nonzero_duration(s).map(|d| d * 60)
 }
@@ -79,6 +113,15 @@ pub(crate) fn parse_stop_at(s: &str) -> std::result::Result<SystemTime, String>
 
 const SIZE_SUFFIXES: &[(char, u32)] = &[('k', 10), ('m', 20), ('g', 30), ('t', 40), ('p', 50)];
 
+pub(crate) fn parse_protocol(s: &str) -> std::result::Result<u32, String> {
+    let ver = s.parse::<u32>().map_err(|e| e.to_string())?;
+    if SUPPORTED_PROTOCOLS.contains(&ver) {
+        Ok(ver)
+    } else {
+        Err(format!("unsupported protocol version {ver}"))
+    }
+}
+
 pub(crate) fn parse_suffixed<T>(s: &str, shifts: &[(char, u32)]) -> std::result::Result<T, String>
 where
     T: TryFrom<u64>,
@@ -126,6 +169,16 @@ pub(crate) fn parse_dparam(s: &str) -> std::result::Result<(String, String), Str
     Ok((name, value))
 }
 
+pub(crate) fn parse_remote_env(s: &str) -> std::result::Result<(String, String), String> {
+    match s.split_once('=') {
+        Some(("", _)) => Err("missing variable name".into()),
+        Some((name, value)) => Ok((name.to_string(), value.to_string())),
+        None => std::env::var(s)
+            .map(|value| (s.to_string(), value))
+            .map_err(|_| format!("{s} is not set in the local environment")),
+    }
+}
+
 pub(crate) fn parse_bool(s: &str) -> std::result::Result<bool, String> {
     if ["1", "true", "yes"]
         .iter()
@@ -183,6 +236,7 @@ pub(crate) fn init_logging(matches: &ArgMatches, log_file_fmt: Option<String>) -
         .journald(journald)
         .colored(true)
         .timestamps(false)
+        .nul_terminated(matches.get_flag("out_format_nul"))
         .build();
     logging::init(cfg)
 }
@@ -216,8 +270,8 @@ pub fn parse_iconv(spec: &str) -> std::result::Result<CharsetConv, String> {
         remote_label.to_string()
     };
 
-    let local_enc = Encoding::for_label(local_label.as_bytes());
-    let remote_enc = Encoding::for_label(remote_label.as_bytes());
+    let local_enc = CharsetConv::lookup(&local_label);
+    let remote_enc = CharsetConv::lookup(&remote_label);
 
     let local_enc = local_enc
         .ok_or_else(|| format!("iconv_open(\"{local_label}\", \"{remote_label}\") failed"))?;
@@ -226,6 +280,17 @@ pub fn parse_iconv(spec: &str) -> std::result::Result<CharsetConv, String> {
 
     Ok(CharsetConv::new(remote_enc, local_enc))
 }
+
+pub fn parse_unicode_form(spec: &str) -> std::result::Result<UnicodeForm, String> {
+    UnicodeForm::parse(spec).ok_or_else(|| format!("invalid normalization form: {spec}"))
+}
+
+pub fn parse_iconv_spec(spec: &str) -> std::result::Result<String, String> {
+    if spec != "?" {
+        parse_iconv(spec)?;
+    }
+    Ok(spec.to_string())
+}
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct RshCommand {
     pub env: Vec<(String, String)>,
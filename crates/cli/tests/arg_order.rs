@@ -30,6 +30,28 @@ const SKIP_ARGS: &[&str] = &[
     "journald",
     "no_acls",
     "super_user",
+    "normalize_names",
+    "compress_map",
+    "metadata_only",
+    "direct_io",
+    "verify_after_write",
+    "post_verify",
+    "out_format_nul",
+    "checksum_cache",
+    "daemon_retries",
+    "remote_env",
+    "batch_format",
+    "max_blocks",
+    "compare_atimes",
+    "bwlimit_pacing",
+    "cdc",
+    "umask",
+    "max_name_len",
+    "max_path_len",
+    "fs_op_rate",
+    "greeting_timeout",
+    "exclude_older_than",
+    "exclude_newer_than",
 ];
 
 #[test]
@@ -20,12 +20,25 @@ fn compress_level_accepts_range() {
 fn compress_level_rejects_out_of_range() {
     let cmd = cli_command();
     let err = cmd
-        .try_get_matches_from(["prog", "--compress-level", "10", "src", "dst"])
+        .try_get_matches_from(["prog", "--compress-level", "23", "src", "dst"])
         .unwrap_err();
     assert_eq!(err.kind(), ErrorKind::ValueValidation);
     let cmd = cli_command();
     let err = cmd
-        .try_get_matches_from(["prog", "--compress-level=-1", "src", "dst"])
+        .try_get_matches_from(["prog", "--compress-level=-8", "src", "dst"])
         .unwrap_err();
     assert_eq!(err.kind(), ErrorKind::ValueValidation);
 }
+
+#[test]
+fn empty_skip_compress_is_accepted() {
+    let cmd = cli_command();
+    let matches = cmd
+        .try_get_matches_from(["prog", "--skip-compress=", "src", "dst"])
+        .expect("empty skip-compress");
+    let skip: Vec<&String> = matches
+        .get_many::<String>("skip_compress")
+        .unwrap()
+        .collect();
+    assert_eq!(skip, [""]);
+}
@@ -0,0 +1,45 @@
+// crates/compress/src/bzip2.rs
+use std::io::{self, Read, Write};
+
+use crate::{Compressor, Decompressor};
+
+#[cfg(feature = "bzip2")]
+#[derive(Clone, Copy)]
+pub struct Bzip2 {
+    level: i32,
+}
+
+#[cfg(feature = "bzip2")]
+impl Bzip2 {
+    pub fn new(level: i32) -> Self {
+        let level = level.clamp(1, 9);
+        Self { level }
+    }
+}
+
+#[cfg(feature = "bzip2")]
+impl Default for Bzip2 {
+    fn default() -> Self {
+        Self { level: 9 }
+    }
+}
+
+#[cfg(feature = "bzip2")]
+impl Compressor for Bzip2 {
+    fn compress(&self, input: &mut dyn Read, output: &mut dyn Write) -> io::Result<()> {
+        let mut encoder =
+            bzip2::write::BzEncoder::new(output, bzip2::Compression::new(self.level as u32));
+        io::copy(input, &mut encoder)?;
+        encoder.finish()?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "bzip2")]
+impl Decompressor for Bzip2 {
+    fn decompress(&self, input: &mut dyn Read, output: &mut dyn Write) -> io::Result<()> {
+        let mut decoder = bzip2::read::BzDecoder::new(input);
+        io::copy(&mut decoder, output)?;
+        Ok(())
+    }
+}
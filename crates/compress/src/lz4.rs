@@ -0,0 +1,27 @@
+// crates/compress/src/lz4.rs
+use std::io::{self, Read, Write};
+
+use crate::{Compressor, Decompressor};
+
+#[cfg(feature = "lz4")]
+#[derive(Clone, Copy, Default)]
+pub struct Lz4;
+
+#[cfg(feature = "lz4")]
+impl Compressor for Lz4 {
+    fn compress(&self, input: &mut dyn Read, output: &mut dyn Write) -> io::Result<()> {
+        let mut encoder = lz4_flex::frame::FrameEncoder::new(output);
+        io::copy(input, &mut encoder)?;
+        encoder.finish().map_err(io::Error::other)?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "lz4")]
+impl Decompressor for Lz4 {
+    fn decompress(&self, input: &mut dyn Read, output: &mut dyn Write) -> io::Result<()> {
+        let mut decoder = lz4_flex::frame::FrameDecoder::new(input);
+        io::copy(&mut decoder, output)?;
+        Ok(())
+    }
+}
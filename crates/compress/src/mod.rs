@@ -2,26 +2,39 @@
 #![doc = include_str!("../../../docs/crates/compress/lib.md")]
 #![forbid(unsafe_code)]
 
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::io::{self, Read, Write};
+use std::ops::RangeInclusive;
 use std::path::Path;
 use std::sync::LazyLock;
 
+use globset::{GlobBuilder, GlobMatcher};
+
+#[cfg(feature = "bzip2")]
+pub mod bzip2;
+#[cfg(feature = "lz4")]
+pub mod lz4;
 #[cfg(feature = "zlib")]
 pub mod zlib;
 #[cfg(feature = "zstd")]
 pub mod zstd;
 
+#[cfg(feature = "bzip2")]
+pub use self::bzip2::Bzip2;
+#[cfg(feature = "lz4")]
+pub use lz4::Lz4;
 #[cfg(feature = "zlib")]
 pub use zlib::{Zlib, ZlibX};
 #[cfg(feature = "zstd")]
-pub use zstd::Zstd;
+pub use zstd::{ZSTD_LDM_WINDOW_LOG, Zstd, ZstdStream, ZstdStreamDecoder};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Codec {
     Zlib,
     ZlibX,
     Zstd,
+    Bzip2,
+    Lz4,
 }
 
 impl Codec {
@@ -30,6 +43,8 @@ impl Codec {
             Codec::Zlib => 1,
             Codec::ZlibX => 2,
             Codec::Zstd => 4,
+            Codec::Bzip2 => 8,
+            Codec::Lz4 => 16,
         }
     }
 
@@ -38,32 +53,78 @@ impl Codec {
             1 => Ok(Codec::Zlib),
             2 => Ok(Codec::ZlibX),
             4 => Ok(Codec::Zstd),
+            #[cfg(feature = "bzip2")]
+            8 => Ok(Codec::Bzip2),
+            #[cfg(feature = "lz4")]
+            16 => Ok(Codec::Lz4),
             other => Err(io::Error::new(
                 io::ErrorKind::InvalidData,
                 format!("unknown codec {other}"),
             )),
         }
     }
+
+    pub fn level_range(self) -> RangeInclusive<i32> {
+        match self {
+            Codec::Zlib | Codec::ZlibX => 0..=9,
+            Codec::Zstd => -7..=22,
+            Codec::Bzip2 => 1..=9,
+            Codec::Lz4 => 0..=0,
+        }
+    }
+
+    /// Whether this build was compiled with support for the codec.
+    pub fn is_available(self) -> bool {
+        match self {
+            Codec::Zlib | Codec::ZlibX => cfg!(feature = "zlib"),
+            Codec::Zstd => cfg!(feature = "zstd"),
+            Codec::Bzip2 => cfg!(feature = "bzip2"),
+            Codec::Lz4 => cfg!(feature = "lz4"),
+        }
+    }
 }
 
+/// Local codec preference, most preferred first. [`available_codecs`]
+/// advertises codecs in this order and [`negotiate_codec`] picks the first
+/// local entry the peer also supports, so zstd wins over lz4 when both
+/// sides have it.
+pub const CODEC_PREFERENCE: &[Codec] = &[
+    Codec::Zstd,
+    Codec::Lz4,
+    Codec::ZlibX,
+    Codec::Zlib,
+    Codec::Bzip2,
+];
+
 pub fn available_codecs() -> Vec<Codec> {
-    let codecs = [
-        #[cfg(feature = "zstd")]
-        Codec::Zstd,
-        #[cfg(feature = "zlib")]
-        Codec::ZlibX,
-        #[cfg(feature = "zlib")]
-        Codec::Zlib,
-    ];
-    codecs.into_iter().collect()
+    CODEC_PREFERENCE
+        .iter()
+        .copied()
+        .filter(|c| c.is_available())
+        .collect()
 }
 
 pub trait Compressor {
     fn compress(&self, input: &mut dyn Read, output: &mut dyn Write) -> io::Result<()>;
+
+    /// Compresses `input` straight into a new `Vec`, reading the slice in
+    /// place rather than copying it into an intermediate buffer.
+    fn compress_to_vec(&self, mut input: &[u8]) -> io::Result<Vec<u8>> {
+        let mut out = Vec::new();
+        self.compress(&mut input, &mut out)?;
+        Ok(out)
+    }
 }
 
 pub trait Decompressor {
     fn decompress(&self, input: &mut dyn Read, output: &mut dyn Write) -> io::Result<()>;
+
+    /// Decompresses `input` straight into a new `Vec`.
+    fn decompress_to_vec(&self, mut input: &[u8]) -> io::Result<Vec<u8>> {
+        let mut out = Vec::new();
+        self.decompress(&mut input, &mut out)?;
+        Ok(out)
+    }
 }
 
 pub fn compressor(codec: Codec) -> io::Result<Box<dyn Compressor>> {
@@ -74,7 +135,37 @@ pub fn compressor(codec: Codec) -> io::Result<Box<dyn Compressor>> {
         Codec::ZlibX => Ok(Box::new(ZlibX::default())),
         #[cfg(feature = "zstd")]
         Codec::Zstd => Ok(Box::new(Zstd::default())),
-        #[cfg(not(all(feature = "zlib", feature = "zstd")))]
+        #[cfg(feature = "bzip2")]
+        Codec::Bzip2 => Ok(Box::new(Bzip2::default())),
+        #[cfg(feature = "lz4")]
+        Codec::Lz4 => Ok(Box::new(Lz4)),
+        #[cfg(not(all(feature = "zlib", feature = "zstd", feature = "bzip2", feature = "lz4")))]
+        _ => Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "codec not available",
+        )),
+    }
+}
+
+/// Like [`compressor`], but compresses at `level` instead of the codec's
+/// default. Levels outside [`Codec::level_range`] are clamped to it. For
+/// zstd, negative levels select its fast modes, trading ratio for speed,
+/// and `0` means the library default.
+pub fn compressor_with_level(codec: Codec, level: i32) -> io::Result<Box<dyn Compressor>> {
+    let range = codec.level_range();
+    let level = level.clamp(*range.start(), *range.end());
+    match codec {
+        #[cfg(feature = "zlib")]
+        Codec::Zlib => Ok(Box::new(Zlib::new(level))),
+        #[cfg(feature = "zlib")]
+        Codec::ZlibX => Ok(Box::new(ZlibX::new(level))),
+        #[cfg(feature = "zstd")]
+        Codec::Zstd => Ok(Box::new(Zstd::new(level))),
+        #[cfg(feature = "bzip2")]
+        Codec::Bzip2 => Ok(Box::new(Bzip2::new(level))),
+        #[cfg(feature = "lz4")]
+        Codec::Lz4 => Ok(Box::new(Lz4)),
+        #[cfg(not(all(feature = "zlib", feature = "zstd", feature = "bzip2", feature = "lz4")))]
         _ => Err(io::Error::new(
             io::ErrorKind::Unsupported,
             "codec not available",
@@ -90,7 +181,11 @@ pub fn decompressor(codec: Codec) -> io::Result<Box<dyn Decompressor>> {
         Codec::ZlibX => Ok(Box::new(Zlib::default())),
         #[cfg(feature = "zstd")]
         Codec::Zstd => Ok(Box::new(Zstd::default())),
-        #[cfg(not(all(feature = "zlib", feature = "zstd")))]
+        #[cfg(feature = "bzip2")]
+        Codec::Bzip2 => Ok(Box::new(Bzip2::default())),
+        #[cfg(feature = "lz4")]
+        Codec::Lz4 => Ok(Box::new(Lz4)),
+        #[cfg(not(all(feature = "zlib", feature = "zstd", feature = "bzip2", feature = "lz4")))]
         _ => Err(io::Error::new(
             io::ErrorKind::Unsupported,
             "codec not available",
@@ -98,6 +193,8 @@ pub fn decompressor(codec: Codec) -> io::Result<Box<dyn Decompressor>> {
     }
 }
 
+/// Picks the first codec in `local` that `remote` also supports, so the
+/// local preference order decides between codecs both peers share.
 pub fn negotiate_codec(local: &[Codec], remote: &[Codec]) -> Option<Codec> {
     local.iter().copied().find(|c| remote.contains(c))
 }
@@ -121,18 +218,105 @@ pub const DEFAULT_SKIP_COMPRESS: &[&str] = &[
     "webp", "xz", "z", "zip", "zst",
 ];
 
+pub const NO_DEFAULT_SKIP: &str = "";
+
 static DEFAULT_SKIP_COMPRESS_SET: LazyLock<HashSet<&'static str>> =
     LazyLock::new(|| DEFAULT_SKIP_COMPRESS.iter().copied().collect());
 
-pub fn should_compress(path: &Path, skip: &HashSet<String>) -> bool {
-    let ext = match path.extension().and_then(|e| e.to_str()) {
-        Some(ext) => ext.to_ascii_lowercase(),
-        None => return true,
-    };
+/// A compiled `--skip-compress` list.
+///
+/// Bare entries are extensions. Entries containing a dot cover the whole
+/// name, so `tar.gz` is a suffix and `core.*` or `*.iso` are name patterns;
+/// wildcard entries without a dot, like rsync's `mp[34]`, match the
+/// extension. Entries are expected in lowercase and are matched against the
+/// lowercased file name. An empty list selects [`DEFAULT_SKIP_COMPRESS`].
+#[derive(Debug, Clone, Default)]
+pub struct SkipCompress {
+    custom: bool,
+    exts: HashSet<String>,
+    suffixes: Vec<String>,
+    name_globs: Vec<GlobMatcher>,
+    ext_globs: Vec<GlobMatcher>,
+}
 
-    if skip.is_empty() {
-        return !DEFAULT_SKIP_COMPRESS_SET.contains(ext.as_str());
+impl SkipCompress {
+    pub fn new<I, S>(entries: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut skip = SkipCompress::default();
+        for entry in entries {
+            let pat = entry.as_ref();
+            skip.custom = true;
+            if pat.is_empty() {
+                continue;
+            }
+            if !pat.contains(['*', '?', '[']) {
+                if pat.contains('.') {
+                    skip.suffixes.push(pat.to_string());
+                } else {
+                    skip.exts.insert(pat.to_string());
+                }
+            } else if let Ok(glob) = GlobBuilder::new(pat)
+                .literal_separator(true)
+                .backslash_escape(true)
+                .build()
+            {
+                if pat.contains('.') {
+                    skip.name_globs.push(glob.compile_matcher());
+                } else {
+                    skip.ext_globs.push(glob.compile_matcher());
+                }
+            }
+        }
+        skip
+    }
+}
+
+pub fn should_compress(path: &Path, skip: &SkipCompress) -> bool {
+    let ext = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .filter(|e| !e.is_empty())
+        .map(str::to_ascii_lowercase);
+
+    if !skip.custom {
+        return !ext.is_some_and(|ext| DEFAULT_SKIP_COMPRESS_SET.contains(ext.as_str()));
     }
 
-    !skip.contains(ext.as_str())
+    if let Some(ext) = ext.as_deref()
+        && (skip.exts.contains(ext) || skip.ext_globs.iter().any(|g| g.is_match(ext)))
+    {
+        return false;
+    }
+    let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+        return true;
+    };
+    let name = name.to_ascii_lowercase();
+    let suffix_match = skip.suffixes.iter().any(|pat| {
+        name.strip_suffix(pat.as_str())
+            .is_some_and(|stem| stem.ends_with('.'))
+    });
+    !suffix_match && !skip.name_globs.iter().any(|g| g.is_match(&name))
+}
+
+/// Codec used for the data of `path`. An entry in `map` for its extension
+/// wins when it names one of the `negotiated` codecs or `None` (stored
+/// uncompressed); otherwise `default` is used unless `skip` matches.
+pub fn codec_for_path(
+    path: &Path,
+    default: Option<Codec>,
+    negotiated: &[Codec],
+    skip: &SkipCompress,
+    map: &HashMap<String, Option<Codec>>,
+) -> Option<Codec> {
+    let default = default?;
+    if let Some(ext) = path.extension().and_then(|e| e.to_str())
+        && let Some(codec) = map.get(&ext.to_ascii_lowercase())
+        && codec.is_none_or(|c| negotiated.contains(&c))
+    {
+        return *codec;
+    }
+    should_compress(path, skip).then_some(default)
 }
@@ -3,6 +3,11 @@ use std::io::{self, Read, Write};
 
 use crate::{Compressor, Decompressor};
 
+/// Window size (as a power of two) used for long-distance matching; also
+/// the largest window the decompressors accept.
+#[cfg(feature = "zstd")]
+pub const ZSTD_LDM_WINDOW_LOG: u32 = 27;
+
 #[cfg(feature = "zstd")]
 #[derive(Clone, Copy, Default)]
 pub struct Zstd {
@@ -26,10 +31,63 @@ impl Compressor for Zstd {
     }
 }
 
+/// Compresses a sequence of chunks as one zstd frame. Each chunk is flushed
+/// on its own so [`ZstdStreamDecoder`] can decode it as soon as it arrives,
+/// while later chunks still match against earlier ones.
+#[cfg(feature = "zstd")]
+pub struct ZstdStream {
+    encoder: zstd::stream::write::Encoder<'static, Vec<u8>>,
+}
+
+#[cfg(feature = "zstd")]
+impl ZstdStream {
+    /// Starts a frame at `level`. With `long_distance` set, long-distance
+    /// matching with a [`ZSTD_LDM_WINDOW_LOG`] window finds repeats far
+    /// apart in large inputs.
+    pub fn new(level: i32, long_distance: bool) -> io::Result<Self> {
+        let mut encoder = zstd::stream::write::Encoder::new(Vec::new(), level)?;
+        if long_distance {
+            encoder.long_distance_matching(true)?;
+            encoder.window_log(ZSTD_LDM_WINDOW_LOG)?;
+        }
+        Ok(Self { encoder })
+    }
+
+    /// Compresses `chunk` and returns the bytes produced for it.
+    pub fn compress_chunk(&mut self, chunk: &[u8]) -> io::Result<Vec<u8>> {
+        self.encoder.write_all(chunk)?;
+        self.encoder.flush()?;
+        Ok(std::mem::take(self.encoder.get_mut()))
+    }
+}
+
+/// Decodes the chunks produced by a [`ZstdStream`], in order.
+#[cfg(feature = "zstd")]
+pub struct ZstdStreamDecoder {
+    decoder: zstd::stream::write::Decoder<'static, Vec<u8>>,
+}
+
+#[cfg(feature = "zstd")]
+impl ZstdStreamDecoder {
+    pub fn new() -> io::Result<Self> {
+        let mut decoder = zstd::stream::write::Decoder::new(Vec::new())?;
+        decoder.window_log_max(ZSTD_LDM_WINDOW_LOG)?;
+        Ok(Self { decoder })
+    }
+
+    /// Decodes the next chunk of the frame.
+    pub fn decompress_chunk(&mut self, chunk: &[u8]) -> io::Result<Vec<u8>> {
+        self.decoder.write_all(chunk)?;
+        self.decoder.flush()?;
+        Ok(std::mem::take(self.decoder.get_mut()))
+    }
+}
+
 #[cfg(feature = "zstd")]
 impl Decompressor for Zstd {
     fn decompress(&self, input: &mut dyn Read, output: &mut dyn Write) -> io::Result<()> {
         let mut decoder = zstd::stream::write::Decoder::new(output)?;
+        decoder.window_log_max(ZSTD_LDM_WINDOW_LOG)?;
         io::copy(input, &mut decoder)?;
         decoder.flush()?;
         Ok(())
@@ -1,14 +1,15 @@
 // crates/compress/tests/codecs.rs
 use compress::{
-    Codec, available_codecs, compressor, decode_codecs, decompressor, encode_codecs,
-    negotiate_codec, should_compress,
+    CODEC_PREFERENCE, Codec, NO_DEFAULT_SKIP, SkipCompress, available_codecs, codec_for_path,
+    compressor, compressor_with_level, decode_codecs, decompressor, encode_codecs, negotiate_codec,
+    should_compress,
 };
 
-use std::collections::HashSet;
+use std::collections::HashMap;
 use std::io;
 use std::path::Path;
 
-#[cfg(any(feature = "zlib", feature = "zstd"))]
+#[cfg(any(feature = "zlib", feature = "zstd", feature = "bzip2", feature = "lz4"))]
 const DATA: &[u8] = b"The quick brown fox jumps over the lazy dog";
 
 #[cfg(feature = "zlib")]
@@ -59,6 +60,151 @@ fn zstd_roundtrip() {
     assert_eq!(DATA, decompressed.as_slice());
 }
 
+#[cfg(feature = "bzip2")]
+#[test]
+fn bzip2_roundtrip() {
+    let comp = compressor(Codec::Bzip2).expect("compressor");
+    let mut compressed = Vec::new();
+    let mut src = DATA;
+    comp.compress(&mut src, &mut compressed).expect("compress");
+    assert!(compressed.starts_with(b"BZh"));
+    let decomp = decompressor(Codec::Bzip2).expect("decompressor");
+    let mut decompressed = Vec::new();
+    let mut c_slice = compressed.as_slice();
+    decomp
+        .decompress(&mut c_slice, &mut decompressed)
+        .expect("decompress");
+    assert_eq!(DATA, decompressed.as_slice());
+    assert_eq!(decode_codecs(&[8]).unwrap(), vec![Codec::Bzip2]);
+}
+
+#[cfg(not(feature = "bzip2"))]
+#[test]
+fn bzip2_byte_rejected_without_feature() {
+    let err = decode_codecs(&[Codec::Bzip2.to_byte()]).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    assert!(!available_codecs().contains(&Codec::Bzip2));
+    let err = compressor(Codec::Bzip2).err().expect("unavailable");
+    assert_eq!(err.kind(), io::ErrorKind::Unsupported);
+}
+
+#[test]
+fn negotiate_codec_skips_bzip2_unless_advertised() {
+    let local = [Codec::Bzip2, Codec::Zlib];
+    assert_eq!(negotiate_codec(&local, &[Codec::Zlib]), Some(Codec::Zlib));
+    assert_eq!(
+        negotiate_codec(&local, &[Codec::Zstd, Codec::Bzip2]),
+        Some(Codec::Bzip2)
+    );
+    assert_eq!(Codec::Bzip2.to_byte(), 8);
+}
+
+#[cfg(feature = "lz4")]
+#[test]
+fn lz4_roundtrip() {
+    let comp = compressor(Codec::Lz4).expect("compressor");
+    let mut compressed = Vec::new();
+    let mut src = DATA;
+    comp.compress(&mut src, &mut compressed).expect("compress");
+    assert!(compressed.starts_with(&[0x04, 0x22, 0x4d, 0x18]));
+    let decomp = decompressor(Codec::Lz4).expect("decompressor");
+    let mut decompressed = Vec::new();
+    let mut c_slice = compressed.as_slice();
+    decomp
+        .decompress(&mut c_slice, &mut decompressed)
+        .expect("decompress");
+    assert_eq!(DATA, decompressed.as_slice());
+    assert_eq!(decode_codecs(&[16]).unwrap(), vec![Codec::Lz4]);
+}
+
+#[cfg(not(feature = "lz4"))]
+#[test]
+fn lz4_byte_rejected_without_feature() {
+    let err = decode_codecs(&[Codec::Lz4.to_byte()]).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    assert!(!available_codecs().contains(&Codec::Lz4));
+    let err = compressor(Codec::Lz4).err().expect("unavailable");
+    assert_eq!(err.kind(), io::ErrorKind::Unsupported);
+}
+
+#[test]
+fn codec_preference_puts_zstd_before_lz4() {
+    assert_eq!(
+        CODEC_PREFERENCE,
+        &[
+            Codec::Zstd,
+            Codec::Lz4,
+            Codec::ZlibX,
+            Codec::Zlib,
+            Codec::Bzip2
+        ]
+    );
+    assert_eq!(Codec::Lz4.to_byte(), 16);
+}
+
+#[test]
+fn negotiate_codec_follows_local_preference() {
+    let remote = [Codec::Lz4, Codec::Zlib, Codec::Zstd];
+    assert_eq!(
+        negotiate_codec(CODEC_PREFERENCE, &remote),
+        Some(Codec::Zstd)
+    );
+    assert_eq!(
+        negotiate_codec(&[Codec::Lz4, Codec::Zstd], &remote),
+        Some(Codec::Lz4)
+    );
+    assert_eq!(
+        negotiate_codec(CODEC_PREFERENCE, &[Codec::Zlib, Codec::Lz4]),
+        Some(Codec::Lz4)
+    );
+}
+
+#[cfg(feature = "zstd")]
+#[test]
+fn compressor_with_level_clamps_zstd_levels() {
+    for level in [-100, -7, 0, 22, 100] {
+        let comp = compressor_with_level(Codec::Zstd, level).expect("compressor");
+        let compressed = comp.compress_to_vec(DATA).expect("compress");
+        let decompressed = decompressor(Codec::Zstd)
+            .expect("decompressor")
+            .decompress_to_vec(&compressed)
+            .expect("decompress");
+        assert_eq!(DATA, decompressed.as_slice());
+    }
+}
+
+#[cfg(feature = "zlib")]
+#[test]
+fn compressor_with_level_applies_zlib_level() {
+    let data = DATA.repeat(64);
+    let compress_at = |level| {
+        compressor_with_level(Codec::Zlib, level)
+            .expect("compressor")
+            .compress_to_vec(&data)
+            .expect("compress")
+    };
+    assert!(compress_at(0).len() > data.len());
+    assert!(compress_at(9).len() < data.len() / 4);
+    assert_eq!(compress_at(-5), compress_at(0));
+}
+
+#[test]
+fn to_vec_helpers_roundtrip_every_codec() {
+    let data = DATA.repeat(32);
+    for codec in available_codecs() {
+        let compressed = compressor(codec)
+            .expect("compressor")
+            .compress_to_vec(&data)
+            .expect("compress");
+        assert!(compressed.len() < data.len(), "{codec:?}");
+        let decompressed = decompressor(codec)
+            .expect("decompressor")
+            .decompress_to_vec(&compressed)
+            .expect("decompress");
+        assert_eq!(decompressed, data, "{codec:?}");
+    }
+}
+
 #[test]
 fn negotiate_codec_returns_common_codec() {
     let local = [Codec::Zstd, Codec::Zlib];
@@ -107,32 +253,77 @@ fn encode_decode_roundtrip_and_invalid_bytes() {
 
 #[test]
 fn should_compress_respects_default_list() {
-    let skip = HashSet::new();
+    let skip = SkipCompress::default();
     assert!(should_compress(Path::new("file.txt"), &skip));
     assert!(!should_compress(Path::new("archive.gz"), &skip));
     assert!(!should_compress(Path::new("IMAGE.JpG"), &skip));
     assert!(should_compress(Path::new("archivegz"), &skip));
 }
 
+#[test]
+fn no_default_skip_compresses_everything() {
+    let skip = SkipCompress::new([NO_DEFAULT_SKIP.to_string()]);
+    assert!(should_compress(Path::new("archive.gz"), &skip));
+    assert!(should_compress(Path::new("IMAGE.JpG"), &skip));
+    assert!(should_compress(Path::new("trailing."), &skip));
+    assert_eq!(
+        codec_for_path(
+            Path::new("archive.gz"),
+            Some(Codec::Zlib),
+            &[Codec::Zlib],
+            &skip,
+            &HashMap::new()
+        ),
+        Some(Codec::Zlib)
+    );
+    let skip = SkipCompress::new([NO_DEFAULT_SKIP.to_string(), "zip".to_string()]);
+    assert!(should_compress(Path::new("archive.gz"), &skip));
+    assert!(!should_compress(Path::new("archive.zip"), &skip));
+}
+
+#[test]
+fn should_compress_matches_glob_patterns() {
+    let skip = SkipCompress::new(["core.*", "*.iso", "*.tar.*", "mp[34]", "tar.bz2"]);
+    assert!(!should_compress(Path::new("dir/core.1234"), &skip));
+    assert!(!should_compress(Path::new("CORE.1"), &skip));
+    assert!(!should_compress(Path::new("disk.ISO"), &skip));
+    assert!(!should_compress(Path::new("archive.tar.gz"), &skip));
+    assert!(!should_compress(Path::new("song.MP3"), &skip));
+    assert!(!should_compress(Path::new("backup.tar.bz2"), &skip));
+    assert!(should_compress(Path::new("core"), &skip));
+    assert!(should_compress(Path::new("notes.txt"), &skip));
+    assert!(should_compress(Path::new("archive.tar"), &skip));
+    assert!(should_compress(Path::new("iso"), &skip));
+    assert!(should_compress(Path::new("mp3"), &skip));
+    assert!(should_compress(Path::new("tar.bz2x"), &skip));
+}
+
+#[test]
+fn glob_patterns_and_names_without_extension() {
+    let skip = SkipCompress::new(["core.*", "mp[34]"]);
+    assert!(should_compress(Path::new("core"), &skip));
+    assert!(should_compress(Path::new("README"), &skip));
+    assert!(should_compress(Path::new("trailing."), &skip));
+    assert!(!should_compress(Path::new("core."), &skip));
+}
+
 #[test]
 fn should_compress_handles_mixed_case_patterns() {
-    let skip = ["tXt".to_ascii_lowercase()]
-        .into_iter()
-        .collect::<HashSet<_>>();
+    let skip = SkipCompress::new(["tXt".to_ascii_lowercase()]);
     assert!(!should_compress(Path::new("file.TXT"), &skip));
     assert!(should_compress(Path::new("archive.gz"), &skip));
 }
 
 #[test]
 fn should_compress_requires_dot_with_custom_patterns() {
-    let skip = ["gz".to_string()].into_iter().collect::<HashSet<_>>();
+    let skip = SkipCompress::new(["gz".to_string()]);
     assert!(!should_compress(Path::new("archive.gz"), &skip));
     assert!(should_compress(Path::new("archivegz"), &skip));
 }
 
 #[test]
 fn should_compress_requires_lowercase_patterns() {
-    let skip = ["GZ".to_string()].into_iter().collect::<HashSet<_>>();
+    let skip = SkipCompress::new(["GZ".to_string()]);
     assert!(should_compress(Path::new("archive.gz"), &skip));
 }
 
@@ -141,10 +332,52 @@ fn available_codecs_matches_features() {
     let mut expected = Vec::new();
     #[cfg(feature = "zstd")]
     expected.push(Codec::Zstd);
+    #[cfg(feature = "lz4")]
+    expected.push(Codec::Lz4);
     #[cfg(feature = "zlib")]
     {
         expected.push(Codec::ZlibX);
         expected.push(Codec::Zlib);
     }
+    #[cfg(feature = "bzip2")]
+    expected.push(Codec::Bzip2);
     assert_eq!(available_codecs(), expected);
 }
+
+#[test]
+fn codec_for_path_applies_extension_map() {
+    let skip = SkipCompress::default();
+    let map: HashMap<String, Option<Codec>> = [
+        ("txt".to_string(), Some(Codec::Zstd)),
+        ("log".to_string(), Some(Codec::Zstd)),
+        ("jpg".to_string(), None),
+    ]
+    .into_iter()
+    .collect();
+    let default = Some(Codec::Zlib);
+    let negotiated = [Codec::Zlib, Codec::Zstd];
+    assert_eq!(
+        codec_for_path(Path::new("notes.TXT"), default, &negotiated, &skip, &map),
+        Some(Codec::Zstd)
+    );
+    assert_eq!(
+        codec_for_path(Path::new("photo.jpg"), default, &negotiated, &skip, &map),
+        None
+    );
+    assert_eq!(
+        codec_for_path(Path::new("data.csv"), default, &negotiated, &skip, &map),
+        Some(Codec::Zlib)
+    );
+    assert_eq!(
+        codec_for_path(Path::new("archive.gz"), default, &negotiated, &skip, &map),
+        None
+    );
+    assert_eq!(
+        codec_for_path(Path::new("notes.txt"), default, &[Codec::Zlib], &skip, &map),
+        Some(Codec::Zlib)
+    );
+    assert_eq!(
+        codec_for_path(Path::new("notes.txt"), None, &negotiated, &skip, &map),
+        None
+    );
+}
@@ -0,0 +1,24 @@
+// crates/core/src/capabilities.rs
+use checksums::{StrongHash, available_strong_hashes};
+use compress::{Codec, available_codecs};
+use meta::META_OPTS;
+use protocol::SUPPORTED_PROTOCOLS;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Capabilities {
+    pub codecs: Vec<Codec>,
+    pub strong_hashes: Vec<StrongHash>,
+    pub xattrs: bool,
+    pub acls: bool,
+    pub protocols: Vec<u32>,
+}
+
+pub fn capabilities() -> Capabilities {
+    Capabilities {
+        codecs: available_codecs(),
+        strong_hashes: available_strong_hashes().to_vec(),
+        xattrs: META_OPTS.xattrs,
+        acls: META_OPTS.acl,
+        protocols: SUPPORTED_PROTOCOLS.to_vec(),
+    }
+}
@@ -3,6 +3,10 @@
 #![forbid(unsafe_code)]
 #![deny(rust_2018_idioms, warnings)]
 
+mod capabilities;
+
+pub use capabilities::{Capabilities, capabilities};
+
 pub mod fs {
     pub use meta::*;
 }
@@ -20,7 +24,9 @@ pub mod hardlink {
 }
 
 pub mod config {
-    pub use engine::{DeleteMode, IdMapper, SyncOptions};
+    pub use engine::{
+        BatchFormat, DeleteMode, IdMapper, MAX_BLOCK_SIZE, PathTransform, SyncOptions,
+    };
 }
 
 pub mod transfer {
@@ -29,7 +35,10 @@ pub mod transfer {
 
 pub mod message {
     pub use protocol::types::*;
-    pub use protocol::{CAP_ACLS, CAP_CODECS, CAP_XATTRS, SUPPORTED_PROTOCOLS, negotiate_version};
+    pub use protocol::{
+        CAP_ACLS, CAP_CODECS, CAP_XATTRS, SUPPORTED_PROTOCOLS, negotiate_forced_version,
+        negotiate_version,
+    };
 }
 
 pub mod compress {
@@ -0,0 +1,23 @@
+// crates/core/tests/capabilities.rs
+use oc_rsync_core::checksums::available_strong_hashes;
+use oc_rsync_core::compress::available_codecs;
+use oc_rsync_core::message::SUPPORTED_PROTOCOLS;
+use oc_rsync_core::metadata::META_OPTS;
+use oc_rsync_core::{Capabilities, capabilities};
+
+#[test]
+fn capabilities_reflect_enabled_features() {
+    let caps = capabilities();
+    assert_eq!(
+        caps,
+        Capabilities {
+            codecs: available_codecs(),
+            strong_hashes: available_strong_hashes().to_vec(),
+            xattrs: META_OPTS.xattrs,
+            acls: META_OPTS.acl,
+            protocols: SUPPORTED_PROTOCOLS.to_vec(),
+        }
+    );
+    assert_eq!(caps.acls, cfg!(feature = "acl"));
+    assert!(!caps.strong_hashes.is_empty());
+}
@@ -40,17 +40,37 @@ pub fn parse_auth_token(token: &str, contents: &str) -> Option<Vec<String>> {
     None
 }
 
-pub fn authenticate_token(token: &str, path: &Path) -> io::Result<Vec<String>> {
+pub fn check_secrets_file(path: &Path, strict_modes: bool) -> io::Result<()> {
     #[cfg(unix)]
     {
         let mode = fs::metadata(path)?.permissions().mode();
-        if mode & 0o077 != 0 {
+        if strict_modes && mode & 0o077 != 0 {
             return Err(io::Error::new(
                 io::ErrorKind::PermissionDenied,
-                "auth file permissions are too open",
+                format!(
+                    "secrets file {} must not be accessible by group or other (mode {:o}); \
+                     set \"strict modes = false\" to override",
+                    path.display(),
+                    mode & 0o7777
+                ),
             ));
         }
     }
+    #[cfg(not(unix))]
+    let _ = (path, strict_modes);
+    Ok(())
+}
+
+pub fn authenticate_token(token: &str, path: &Path) -> io::Result<Vec<String>> {
+    authenticate_token_with(token, path, true)
+}
+
+pub fn authenticate_token_with(
+    token: &str,
+    path: &Path,
+    strict_modes: bool,
+) -> io::Result<Vec<String>> {
+    check_secrets_file(path, strict_modes)?;
     let contents = fs::read_to_string(path)?;
     if let Some(allowed) = parse_auth_token(token, &contents) {
         Ok(allowed)
@@ -62,52 +82,103 @@ pub fn authenticate_token(token: &str, path: &Path) -> io::Result<Vec<String>> {
     }
 }
 
+pub const EARLY_INPUT_PREFIX: &str = "#early_input=";
+pub const MAX_EARLY_INPUT: usize = 5120;
+
+pub type EarlyAuth = (Option<String>, Vec<String>, bool, Option<Vec<u8>>);
+
 pub fn authenticate(
     t: &mut dyn Transport,
     path: Option<&Path>,
     password: Option<&str>,
 ) -> io::Result<(Option<String>, Vec<String>, bool)> {
-    let mut no_motd = false;
-    const MAX_TOKEN: usize = 256;
-    let mut token = Vec::new();
+    let (token, allowed, no_motd, _) = authenticate_with_early_input(t, path, password)?;
+    Ok((token, allowed, no_motd))
+}
+
+fn read_token_line(
+    t: &mut dyn Transport,
+    pending: &mut Vec<u8>,
+    max: usize,
+) -> io::Result<Vec<u8>> {
     let mut buf = [0u8; 64];
     loop {
+        if let Some(pos) = pending.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = pending.drain(..=pos).take(pos).collect();
+            if line.len() > max + 1 {
+                return Err(io::Error::new(io::ErrorKind::InvalidData, "token too long"));
+            }
+            return Ok(line);
+        }
+        if pending.len() > max + 1 {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "token too long"));
+        }
         let n = t.receive(&mut buf)?;
         if n == 0 {
-            if token.is_empty() {
+            if pending.iter().all(|&b| b == 0) {
                 return Err(io::Error::new(
                     io::ErrorKind::PermissionDenied,
                     "missing token",
                 ));
-            } else {
-                return Err(io::Error::new(
-                    io::ErrorKind::InvalidData,
-                    "missing terminator",
-                ));
-            }
-        }
-        let mut start = 0;
-        if token.is_empty() && buf[0] == 0 {
-            no_motd = true;
-            start = 1;
-            if start >= n {
-                continue;
             }
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "missing terminator",
+            ));
         }
-        if let Some(pos) = buf[start..n].iter().position(|&b| b == b'\n') {
-            token.extend_from_slice(&buf[start..start + pos]);
-            if token.len() > MAX_TOKEN {
-                return Err(io::Error::new(io::ErrorKind::InvalidData, "token too long"));
-            }
-            break;
-        } else {
-            token.extend_from_slice(&buf[start..n]);
-            if token.len() > MAX_TOKEN {
-                return Err(io::Error::new(io::ErrorKind::InvalidData, "token too long"));
-            }
+        pending.extend_from_slice(&buf[..n]);
+    }
+}
+
+fn read_early_input(
+    t: &mut dyn Transport,
+    pending: &mut Vec<u8>,
+    len: &str,
+) -> io::Result<Vec<u8>> {
+    let len: usize = len
+        .trim()
+        .parse()
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "invalid early input length"))?;
+    if len > MAX_EARLY_INPUT {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "early input too long",
+        ));
+    }
+    let mut buf = [0u8; 64];
+    while pending.len() < len {
+        let n = t.receive(&mut buf)?;
+        if n == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "truncated early input",
+            ));
         }
+        pending.extend_from_slice(&buf[..n]);
     }
-    let token_str = String::from_utf8_lossy(&token).trim().to_string();
+    Ok(pending.drain(..len).collect())
+}
+
+pub fn authenticate_with_early_input(
+    t: &mut dyn Transport,
+    path: Option<&Path>,
+    password: Option<&str>,
+) -> io::Result<EarlyAuth> {
+    const MAX_TOKEN: usize = 256;
+    let mut pending = Vec::new();
+    let mut early_input = None;
+    let mut line = read_token_line(t, &mut pending, MAX_TOKEN)?;
+    if let Some(len) = line.strip_prefix(EARLY_INPUT_PREFIX.as_bytes()) {
+        let len = String::from_utf8_lossy(len).into_owned();
+        early_input = Some(read_early_input(t, &mut pending, &len)?);
+        line = read_token_line(t, &mut pending, MAX_TOKEN)?;
+    }
+    let no_motd = line.first() == Some(&0);
+    let token = if no_motd { &line[1..] } else { &line[..] };
+    if token.len() > MAX_TOKEN {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "token too long"));
+    }
+    let token_str = String::from_utf8_lossy(token).trim().to_string();
 
     if let Some(auth_path) = path {
         if !auth_path.exists() {
@@ -119,8 +190,8 @@ pub fn authenticate(
                 "missing token",
             ));
         }
-        let allowed = authenticate_token(&token_str, auth_path)?;
-        Ok((Some(token_str), allowed, no_motd))
+        let allowed = authenticate_token_with(&token_str, auth_path, false)?;
+        Ok((Some(token_str), allowed, no_motd, early_input))
     } else if let Some(pw) = password {
         if token_str.is_empty() {
             return Err(io::Error::new(
@@ -134,11 +205,11 @@ pub fn authenticate(
                 "unauthorized",
             ));
         }
-        Ok((Some(token_str), Vec::new(), no_motd))
+        Ok((Some(token_str), Vec::new(), no_motd, early_input))
     } else if token_str.is_empty() {
-        Ok((None, Vec::new(), no_motd))
+        Ok((None, Vec::new(), no_motd, early_input))
     } else {
-        Ok((Some(token_str), Vec::new(), no_motd))
+        Ok((Some(token_str), Vec::new(), no_motd, early_input))
     }
 }
 
@@ -191,6 +262,20 @@ mod tests {
         assert_eq!(allowed, vec!["user".to_string()]);
     }
 
+    #[test]
+    fn authenticate_reads_early_input_before_token() {
+        let reader = ChunkReader {
+            data: b"#early_input=5\nhello\0tok\n".to_vec(),
+            pos: 0,
+            chunk: 3,
+        };
+        let mut t = LocalPipeTransport::new(reader, io::sink());
+        let (tok, _, no_motd, early) = authenticate_with_early_input(&mut t, None, None).unwrap();
+        assert_eq!(tok.as_deref(), Some("tok"));
+        assert!(no_motd);
+        assert_eq!(early.as_deref(), Some(&b"hello"[..]));
+    }
+
     #[test]
     fn authenticate_rejects_long_token() {
         let dir = tempdir().unwrap();
@@ -220,6 +305,29 @@ mod tests {
         assert!(!no_motd);
     }
 
+    #[cfg(unix)]
+    #[test]
+    fn secrets_file_must_not_be_group_or_world_readable() {
+        let dir = tempdir().unwrap();
+        let auth_path = dir.path().join("auth");
+        fs::write(&auth_path, "secret user\n").unwrap();
+
+        fs::set_permissions(&auth_path, fs::Permissions::from_mode(0o644)).unwrap();
+        let err = authenticate_token("secret", &auth_path).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::PermissionDenied);
+        assert!(err.to_string().contains("mode 644"), "{err}");
+        assert_eq!(
+            authenticate_token_with("secret", &auth_path, false).unwrap(),
+            vec!["user".to_string()]
+        );
+
+        fs::set_permissions(&auth_path, fs::Permissions::from_mode(0o600)).unwrap();
+        assert_eq!(
+            authenticate_token("secret", &auth_path).unwrap(),
+            vec!["user".to_string()]
+        );
+    }
+
     #[test]
     fn authenticate_wrong_token() {
         let dir = tempdir().unwrap();
@@ -1,10 +1,13 @@
 // crates/daemon/src/config/model.rs
 
+use std::collections::HashSet;
+use std::io;
 use std::net::IpAddr;
-use std::path::PathBuf;
+use std::path::{Component, Path, PathBuf};
 use std::sync::{Arc, atomic::AtomicUsize};
 use std::time::Duration;
 
+use oc_rsync_core::filter::{Matcher, parse, parse_list_file};
 use transport::AddressFamily;
 
 #[derive(Debug)]
@@ -18,6 +21,7 @@ pub struct Module {
     pub secrets_file: Option<PathBuf>,
     pub timeout: Option<Duration>,
     pub use_chroot: bool,
+    pub strict_modes: bool,
     pub numeric_ids: bool,
     pub uid: Option<u32>,
     pub gid: Option<u32>,
@@ -26,6 +30,12 @@ pub struct Module {
     pub list: bool,
     pub max_connections: Option<u32>,
     pub refuse_options: Vec<String>,
+    pub pre_xfer_exec: Option<String>,
+    pub filter: Vec<String>,
+    pub include: Vec<String>,
+    pub exclude: Vec<String>,
+    pub include_from: Option<PathBuf>,
+    pub exclude_from: Option<PathBuf>,
     pub connections: Arc<AtomicUsize>,
 }
 
@@ -41,6 +51,7 @@ impl Clone for Module {
             secrets_file: self.secrets_file.clone(),
             timeout: self.timeout,
             use_chroot: self.use_chroot,
+            strict_modes: self.strict_modes,
             numeric_ids: self.numeric_ids,
             uid: self.uid,
             gid: self.gid,
@@ -49,6 +60,12 @@ impl Clone for Module {
             list: self.list,
             max_connections: self.max_connections,
             refuse_options: self.refuse_options.clone(),
+            pre_xfer_exec: self.pre_xfer_exec.clone(),
+            filter: self.filter.clone(),
+            include: self.include.clone(),
+            exclude: self.exclude.clone(),
+            include_from: self.include_from.clone(),
+            exclude_from: self.exclude_from.clone(),
             connections: Arc::clone(&self.connections),
         }
     }
@@ -66,6 +83,7 @@ impl Default for Module {
             secrets_file: None,
             timeout: None,
             use_chroot: true,
+            strict_modes: true,
             numeric_ids: false,
             uid: None,
             gid: None,
@@ -74,6 +92,12 @@ impl Default for Module {
             list: true,
             max_connections: None,
             refuse_options: Vec::new(),
+            pre_xfer_exec: None,
+            filter: Vec::new(),
+            include: Vec::new(),
+            exclude: Vec::new(),
+            include_from: None,
+            exclude_from: None,
             connections: Arc::new(AtomicUsize::new(0)),
         }
     }
@@ -129,6 +153,11 @@ impl ModuleBuilder {
         self
     }
 
+    pub fn strict_modes(mut self, strict: bool) -> Self {
+        self.inner.strict_modes = strict;
+        self
+    }
+
     pub fn numeric_ids(mut self, numeric: bool) -> Self {
         self.inner.numeric_ids = numeric;
         self
@@ -169,6 +198,36 @@ impl ModuleBuilder {
         self
     }
 
+    pub fn pre_xfer_exec(mut self, cmd: impl Into<String>) -> Self {
+        self.inner.pre_xfer_exec = Some(cmd.into());
+        self
+    }
+
+    pub fn filter(mut self, rules: Vec<String>) -> Self {
+        self.inner.filter = rules;
+        self
+    }
+
+    pub fn include(mut self, patterns: Vec<String>) -> Self {
+        self.inner.include = patterns;
+        self
+    }
+
+    pub fn exclude(mut self, patterns: Vec<String>) -> Self {
+        self.inner.exclude = patterns;
+        self
+    }
+
+    pub fn include_from(mut self, path: impl Into<PathBuf>) -> Self {
+        self.inner.include_from = Some(path.into());
+        self
+    }
+
+    pub fn exclude_from(mut self, path: impl Into<PathBuf>) -> Self {
+        self.inner.exclude_from = Some(path.into());
+        self
+    }
+
     pub fn build(self) -> Module {
         self.inner
     }
@@ -178,6 +237,68 @@ impl Module {
     pub fn builder(name: impl Into<String>, path: impl Into<PathBuf>) -> ModuleBuilder {
         ModuleBuilder::new(name, path)
     }
+
+    pub fn matcher(&self) -> io::Result<Matcher> {
+        let mut text = String::new();
+        for rule in &self.filter {
+            text.push_str(rule);
+            text.push('\n');
+        }
+        let from_file = |path: &Path, sign: char, text: &mut String| -> io::Result<()> {
+            let pats = parse_list_file(path, false).map_err(|e| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("{}: {e:?}", path.display()),
+                )
+            })?;
+            for pat in pats.iter().filter(|p| !p.is_empty()) {
+                text.push_str(&format!("{sign} {pat}\n"));
+            }
+            Ok(())
+        };
+        if let Some(path) = &self.include_from {
+            from_file(path, '+', &mut text)?;
+        }
+        for pat in &self.include {
+            text.push_str(&format!("+ {pat}\n"));
+        }
+        if let Some(path) = &self.exclude_from {
+            from_file(path, '-', &mut text)?;
+        }
+        for pat in &self.exclude {
+            text.push_str(&format!("- {pat}\n"));
+        }
+        let rules = parse(&text, &mut HashSet::new(), 0).map_err(|e| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("module {}: invalid filter rules: {e:?}", self.name),
+            )
+        })?;
+        Ok(Matcher::new(rules))
+    }
+
+    pub fn excluded_path<'a>(&self, args: &'a [String]) -> io::Result<Option<&'a str>> {
+        let matcher = self.matcher()?;
+        for arg in args.iter().filter(|a| !a.starts_with('-')) {
+            let path = match arg.strip_prefix(self.name.as_str()) {
+                Some(rest) if rest.is_empty() || rest.starts_with('/') => rest,
+                _ => arg,
+            };
+            let mut rel = PathBuf::new();
+            for comp in Path::new(path).components() {
+                if let Component::Normal(c) = comp {
+                    rel.push(c);
+                    let included = matcher.is_included(&rel).map_err(|e| {
+                        io::Error::new(io::ErrorKind::InvalidData, format!("{e:?}"))
+                    })?;
+                    if !included {
+                        return Ok(Some(arg));
+                    }
+                }
+            }
+        }
+        Ok(None)
+    }
 }
 
 #[derive(Debug, Default, Clone)]
@@ -201,6 +322,7 @@ pub struct DaemonConfig {
     pub secrets_file: Option<PathBuf>,
     pub timeout: Option<Duration>,
     pub use_chroot: Option<bool>,
+    pub strict_modes: Option<bool>,
     pub numeric_ids: Option<bool>,
     pub uid: Option<u32>,
     pub gid: Option<u32>,
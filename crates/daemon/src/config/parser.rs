@@ -18,6 +18,39 @@ fn parse_list(val: &str) -> Vec<String> {
         .collect()
 }
 
+fn parse_words(val: &str) -> Vec<String> {
+    val.split_whitespace().map(|s| s.to_string()).collect()
+}
+
+fn parse_filter_rules(val: &str) -> Vec<String> {
+    const KEYWORDS: &[&str] = &[
+        "include",
+        "exclude",
+        "protect",
+        "risk",
+        "hide",
+        "show",
+        "merge",
+        "dir-merge",
+    ];
+    let mut rules = Vec::new();
+    let mut words = val.split_whitespace();
+    while let Some(word) = words.next() {
+        let name = word.split(',').next().unwrap_or(word);
+        let mut chars = word.chars();
+        let short = chars.next().is_some_and(|c| "+-PRHS.:".contains(c))
+            && chars.all(|c| "/!Csrpxnwe+-,".contains(c));
+        if short || KEYWORDS.contains(&name) {
+            if let Some(pat) = words.next() {
+                rules.push(format!("{word} {pat}"));
+                continue;
+            }
+        }
+        rules.push(word.to_string());
+    }
+    rules
+}
+
 pub fn parse_module(s: &str) -> std::result::Result<Module, String> {
     let mut chars = s.chars().peekable();
     let mut name = String::new();
@@ -115,6 +148,10 @@ pub fn parse_module(s: &str) -> std::result::Result<Module, String> {
                 module.use_chroot =
                     parse_bool(val).map_err(|e| format!("{key}={val} at position {pos}: {e}"))?;
             }
+            "strict_modes" => {
+                module.strict_modes =
+                    parse_bool(val).map_err(|e| format!("{key}={val} at position {pos}: {e}"))?;
+            }
             "numeric_ids" => {
                 module.numeric_ids =
                     parse_bool(val).map_err(|e| format!("{key}={val} at position {pos}: {e}"))?;
@@ -148,6 +185,11 @@ pub fn parse_module(s: &str) -> std::result::Result<Module, String> {
                 module.max_connections = Some(max);
             }
             "refuse_options" => module.refuse_options = parse_list(val),
+            "filter" => module.filter = parse_filter_rules(val),
+            "include" => module.include = parse_words(val),
+            "exclude" => module.exclude = parse_words(val),
+            "include_from" => module.include_from = Some(PathBuf::from(val)),
+            "exclude_from" => module.exclude_from = Some(PathBuf::from(val)),
             _ => {
                 return Err(format!("unknown option {key}={val} at position {pos}"));
             }
@@ -254,6 +296,8 @@ pub fn parse_config(contents: &str) -> io::Result<DaemonConfig> {
                 name,
                 path: PathBuf::new(),
                 use_chroot: cfg.use_chroot.unwrap_or(true),
+                strict_modes: cfg.strict_modes.unwrap_or(true),
+                write_only: cfg.write_only.unwrap_or(false),
                 ..Module::default()
             });
             continue;
@@ -321,6 +365,7 @@ pub fn parse_config(contents: &str) -> io::Result<DaemonConfig> {
                 };
             }
             (false, "use chroot") => cfg.use_chroot = Some(parse_bool(&val)?),
+            (false, "strict modes") => cfg.strict_modes = Some(parse_bool(&val)?),
             (false, "numeric ids") => cfg.numeric_ids = Some(parse_bool(&val)?),
             (false, "uid") => cfg.uid = Some(parse_uid(&val)?),
             (false, "gid") => cfg.gid = Some(parse_gid(&val)?),
@@ -381,6 +426,11 @@ pub fn parse_config(contents: &str) -> io::Result<DaemonConfig> {
                     m.use_chroot = parse_bool(&val)?;
                 }
             }
+            (true, "strict modes") => {
+                if let Some(ref mut m) = current {
+                    m.strict_modes = parse_bool(&val)?;
+                }
+            }
             (true, "numeric ids") => {
                 if let Some(ref mut m) = current {
                     m.numeric_ids = parse_bool(&val)?;
@@ -424,6 +474,36 @@ pub fn parse_config(contents: &str) -> io::Result<DaemonConfig> {
                     m.refuse_options = parse_list(&val);
                 }
             }
+            (true, "pre xfer exec") => {
+                if let Some(ref mut m) = current {
+                    m.pre_xfer_exec = Some(val.clone());
+                }
+            }
+            (true, "filter") => {
+                if let Some(ref mut m) = current {
+                    m.filter = parse_filter_rules(&val);
+                }
+            }
+            (true, "include") => {
+                if let Some(ref mut m) = current {
+                    m.include = parse_words(&val);
+                }
+            }
+            (true, "exclude") => {
+                if let Some(ref mut m) = current {
+                    m.exclude = parse_words(&val);
+                }
+            }
+            (true, "include from") => {
+                if let Some(ref mut m) = current {
+                    m.include_from = Some(PathBuf::from(&val));
+                }
+            }
+            (true, "exclude from") => {
+                if let Some(ref mut m) = current {
+                    m.exclude_from = Some(PathBuf::from(&val));
+                }
+            }
             _ => {
                 return Err(io::Error::new(
                     io::ErrorKind::InvalidData,
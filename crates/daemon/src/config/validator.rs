@@ -87,5 +87,6 @@ pub fn validate_module(module: &Module) -> io::Result<()> {
             format!("module {} missing path", module.name),
         ));
     }
+    module.matcher()?;
     Ok(())
 }
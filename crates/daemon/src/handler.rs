@@ -0,0 +1,65 @@
+// crates/daemon/src/handler.rs
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::path::Path;
+use std::sync::atomic::Ordering;
+
+use oc_rsync_core::filter::Matcher;
+use transport::Transport;
+
+use crate::config::Module;
+use crate::service::{PrivilegeContext, chroot_and_drop_privileges};
+
+pub type Handler = dyn Fn(&mut dyn Transport, &[String], &Matcher) -> io::Result<()> + Send + Sync;
+
+pub(crate) fn finish_session(transport: &mut dyn Transport) {
+    let _ = transport.send(b"@RSYNCD: EXIT\n");
+    let _ = transport.send(&[]);
+    let _ = transport.close();
+}
+
+pub(crate) fn deny_module(transport: &mut dyn Transport, module: &Module, msg: &str) {
+    let _ = transport.send(format!("@ERROR: {msg}").as_bytes());
+    if module.max_connections.is_some() {
+        module.connections.fetch_sub(1, Ordering::SeqCst);
+    }
+    finish_session(transport);
+}
+
+pub(crate) fn check_module_filter(
+    transport: &mut dyn Transport,
+    module: &Module,
+    opts: &[String],
+) -> io::Result<()> {
+    if let Some(path) = module.excluded_path(opts)? {
+        deny_module(transport, module, "access denied");
+        return Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            format!("{path}: excluded by module filter"),
+        ));
+    }
+    Ok(())
+}
+
+pub fn serve_module(
+    _t: &mut dyn Transport,
+    module: &Module,
+    peer: &str,
+    log_file: Option<&Path>,
+    log_format: Option<&str>,
+    uid: u32,
+    gid: u32,
+) -> io::Result<(Option<File>, PrivilegeContext, Matcher)> {
+    let log = if let Some(path) = log_file {
+        let fmt = log_format.unwrap_or("%h %m");
+        let line = fmt.replace("%h", peer).replace("%m", &module.name);
+        let mut f = OpenOptions::new().create(true).append(true).open(path)?;
+        writeln!(f, "{}", line)?;
+        Some(f)
+    } else {
+        None
+    };
+    let matcher = module.matcher()?;
+    let ctx = chroot_and_drop_privileges(&module.path, uid, gid, module.use_chroot)?;
+    Ok((log, ctx, matcher))
+}
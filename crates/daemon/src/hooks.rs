@@ -0,0 +1,50 @@
+// crates/daemon/src/hooks.rs
+use std::io::{self, Write};
+use std::process::{Command, Stdio};
+
+use crate::config::Module;
+
+pub fn run_pre_xfer_exec(
+    module: &Module,
+    peer: &str,
+    args: &[String],
+    early_input: Option<&[u8]>,
+) -> io::Result<()> {
+    let Some(cmd) = module.pre_xfer_exec.as_deref() else {
+        return Ok(());
+    };
+    let mut command = Command::new("sh");
+    command
+        .arg("-c")
+        .arg(cmd)
+        .env("RSYNC_MODULE_NAME", &module.name)
+        .env("RSYNC_MODULE_PATH", &module.path)
+        .env("RSYNC_HOST_ADDR", peer)
+        .env("RSYNC_HOST_NAME", peer)
+        .env("RSYNC_PID", std::process::id().to_string())
+        .env("RSYNC_REQUEST", format!("{}/", module.name))
+        .env("RSYNC_ARG0", "rsyncd")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null());
+    for (i, arg) in args.iter().enumerate() {
+        command.env(format!("RSYNC_ARG{}", i + 1), arg);
+    }
+    let mut child = command.spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        if let Some(data) = early_input {
+            match stdin.write_all(data) {
+                Err(e) if e.kind() != io::ErrorKind::BrokenPipe => return Err(e),
+                _ => {}
+            }
+        }
+    }
+    let status = child.wait()?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            format!("pre-xfer exec returned failure ({status})"),
+        ))
+    }
+}
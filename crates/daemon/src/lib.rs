@@ -6,16 +6,23 @@
 
 pub mod auth;
 pub mod config;
+pub mod handler;
+pub mod hooks;
 pub mod service;
 
-pub use auth::{authenticate, authenticate_token, parse_auth_token};
+pub use auth::{
+    authenticate, authenticate_token, authenticate_token_with, authenticate_with_early_input,
+    check_secrets_file, parse_auth_token,
+};
 pub use config::{
     load_config, parse_config, parse_config_file, parse_daemon_args, parse_module, DaemonArgs,
     DaemonConfig, Module, ModuleBuilder,
 };
+pub use hooks::run_pre_xfer_exec;
+pub use handler::{serve_module, Handler};
 pub use service::{
     chroot_and_drop_privileges, drop_privileges, handle_connection, host_allowed, init_logging,
-    run_daemon, serve_module, Handler, PrivilegeContext,
+    run_daemon, PrivilegeContext,
 };
 
 pub use oc_rsync_core::metadata::{MetaOpts, META_OPTS};
@@ -11,20 +11,17 @@ use std::time::{Duration, Instant};
 use nix::unistd::daemon;
 
 use ipnet::IpNet;
-use logging::{DebugFlag, InfoFlag, LogFormat, StderrMode, SubscriberConfig};
+use logging::{parse_escapes, DebugFlag, InfoFlag, LogFormat, StderrMode, SubscriberConfig};
 use protocol::{negotiate_version, SUPPORTED_PROTOCOLS};
 #[cfg(unix)]
 use sd_notify::{self, NotifyState};
-use transport::{AddressFamily, RateLimitedTransport, TcpTransport, Transport};
+use transport::{AddressFamily, TcpTransport, Transport};
 
-use crate::auth::{authenticate, authenticate_token};
-use crate::config::Module;
-
-fn finish_session(transport: &mut dyn Transport) {
-    let _ = transport.send(b"@RSYNCD: EXIT\n");
-    let _ = transport.send(&[]);
-    let _ = transport.close();
-}
+use crate::auth::{authenticate_token_with, authenticate_with_early_input, check_secrets_file};
+use crate::config::validator::validate_daemon_args;
+use crate::config::{DaemonArgs, Module};
+use crate::handler::{check_module_filter, deny_module, finish_session, serve_module, Handler};
+use crate::hooks::run_pre_xfer_exec;
 
 #[cfg(unix)]
 pub struct PrivilegeContext {
@@ -175,8 +172,6 @@ pub fn drop_privileges(_uid: u32, _gid: u32) -> io::Result<()> {
     Ok(())
 }
 
-pub type Handler = dyn Fn(&mut dyn Transport, &[String]) -> io::Result<()> + Send + Sync;
-
 fn host_matches(ip: &IpAddr, pat: &str) -> bool {
     if pat == "*" {
         return true;
@@ -251,7 +246,8 @@ pub fn handle_connection(
         let latest = SUPPORTED_PROTOCOLS[0];
         transport.send(&latest.to_be_bytes())?;
         negotiate_version(latest, peer_ver).map_err(|e| io::Error::other(e.to_string()))?;
-        let (token, global_allowed, no_motd) = authenticate(transport, secrets, password)?;
+        let (token, global_allowed, no_motd, early_input) =
+            authenticate_with_early_input(transport, secrets, password)?;
         if !no_motd {
             if let Some(mpath) = motd {
                 if let Ok(content) = fs::read_to_string(mpath) {
@@ -292,7 +288,7 @@ pub fn handle_connection(
             }
             let allowed = if let Some(path) = module.secrets_file.as_deref() {
                 match token.as_deref() {
-                    Some(tok) => authenticate_token(tok, path)?,
+                    Some(tok) => authenticate_token_with(tok, path, module.strict_modes)?,
                     None => {
                         let _ = transport.send(b"@ERROR: access denied");
                         return Err(io::Error::new(
@@ -302,6 +298,9 @@ pub fn handle_connection(
                     }
                 }
             } else {
+                if let Some(path) = secrets {
+                    check_secrets_file(path, module.strict_modes)?;
+                }
                 global_allowed.clone()
             };
             if !allowed.is_empty() && !allowed.iter().any(|m| m == &name) {
@@ -363,7 +362,7 @@ pub fn handle_connection(
                     log_file = Some(PathBuf::from(v));
                     consumed = true;
                 } else if let Some(v) = opt.strip_prefix("--log-file-format=") {
-                    log_format = Some(v.to_string());
+                    log_format = Some(parse_escapes(v));
                     consumed = true;
                 }
                 if (opt == "--numeric-ids" && !module.numeric_ids)
@@ -382,31 +381,31 @@ pub fn handle_connection(
                 }
             }
             if module.read_only && saw_server && !is_sender {
-                let _ = transport.send(b"@ERROR: read only");
-                if module.max_connections.is_some() {
-                    module.connections.fetch_sub(1, Ordering::SeqCst);
-                }
-                finish_session(transport);
-                return Err(io::Error::new(io::ErrorKind::PermissionDenied, "read only"));
+                deny_module(transport, module, "module is read only");
+                return Err(io::Error::new(
+                    io::ErrorKind::PermissionDenied,
+                    "module is read only",
+                ));
             }
             if module.write_only && saw_server && is_sender {
-                let _ = transport.send(b"@ERROR: write only");
-                if module.max_connections.is_some() {
-                    module.connections.fetch_sub(1, Ordering::SeqCst);
-                }
-                finish_session(transport);
+                deny_module(transport, module, "module is write only");
                 return Err(io::Error::new(
                     io::ErrorKind::PermissionDenied,
-                    "write only",
+                    "module is write only",
                 ));
             }
+            check_module_filter(transport, module, &opts)?;
             if let Some(dur) = module.timeout {
                 transport.set_read_timeout(Some(dur))?;
                 transport.set_write_timeout(Some(dur))?;
             }
+            if let Err(e) = run_pre_xfer_exec(module, peer, &opts, early_input.as_deref()) {
+                deny_module(transport, module, "pre-xfer exec returned failure");
+                return Err(e);
+            }
             let m_uid = module.uid.unwrap_or(uid);
             let m_gid = module.gid.unwrap_or(gid);
-            let (mut log, _guard) = serve_module(
+            let (mut log, _guard, matcher) = serve_module(
                 transport,
                 module,
                 peer,
@@ -418,7 +417,7 @@ pub fn handle_connection(
             if module.max_connections.is_some() {
                 module.connections.fetch_sub(1, Ordering::SeqCst);
             }
-            let res = handler(transport, &opts);
+            let res = handler(transport, &opts, &matcher);
             let log_flush_res = if let Some(f) = log.as_mut() {
                 f.flush()
             } else {
@@ -438,28 +437,6 @@ pub fn handle_connection(
     res
 }
 
-pub fn serve_module(
-    _t: &mut dyn Transport,
-    module: &Module,
-    peer: &str,
-    log_file: Option<&Path>,
-    log_format: Option<&str>,
-    uid: u32,
-    gid: u32,
-) -> io::Result<(Option<File>, PrivilegeContext)> {
-    let log = if let Some(path) = log_file {
-        let fmt = log_format.unwrap_or("%h %m");
-        let line = fmt.replace("%h", peer).replace("%m", &module.name);
-        let mut f = OpenOptions::new().create(true).append(true).open(path)?;
-        writeln!(f, "{}", line)?;
-        Some(f)
-    } else {
-        None
-    };
-    let ctx = chroot_and_drop_privileges(&module.path, uid, gid, module.use_chroot)?;
-    Ok((log, ctx))
-}
-
 #[allow(clippy::too_many_arguments)]
 pub fn run_daemon(
     modules: HashMap<String, Module>,
@@ -477,6 +454,7 @@ pub fn run_daemon(
     state_dir: Option<PathBuf>,
     timeout: Option<Duration>,
     bwlimit: Option<u64>,
+    bwlimit_pacing: bool,
     _max_connections: Option<usize>,
     refuse_options: Vec<String>,
     list: bool,
@@ -528,24 +506,11 @@ pub fn run_daemon(
         quiet,
     )?;
 
-    if let Some(addr) = address {
-        if let Some(AddressFamily::V4) = family {
-            if addr.is_ipv6() {
-                return Err(io::Error::new(
-                    io::ErrorKind::InvalidInput,
-                    "IPv6 address provided with --ipv4",
-                ));
-            }
-        }
-        if let Some(AddressFamily::V6) = family {
-            if addr.is_ipv4() {
-                return Err(io::Error::new(
-                    io::ErrorKind::InvalidInput,
-                    "IPv4 address provided with --ipv6",
-                ));
-            }
-        }
-    }
+    validate_daemon_args(&DaemonArgs {
+        address,
+        port,
+        family,
+    })?;
 
     let (listener, port) = TcpTransport::listen(address, port, family)?;
     let _ = writeln!(io::stdout(), "{port}");
@@ -557,30 +522,41 @@ pub fn run_daemon(
         let (stream, addr) = TcpTransport::accept(&listener, &hosts_allow, &hosts_deny)?;
         let stream = TcpTransport::from_stream(stream);
         let mut transport: Box<dyn Transport> = if let Some(limit) = bwlimit {
-            Box::new(RateLimitedTransport::new(stream, limit))
+            stream.with_bwlimit(limit, bwlimit_pacing)
         } else {
             Box::new(stream)
         };
-        if let Some(dur) = timeout {
-            transport.set_read_timeout(Some(dur))?;
-            transport.set_write_timeout(Some(dur))?;
+        let peer = transport.peer_addr().unwrap_or(addr).ip().to_string();
+        let res = match timeout {
+            Some(dur) => transport
+                .set_read_timeout(Some(dur))
+                .and_then(|()| transport.set_write_timeout(Some(dur))),
+            None => Ok(()),
+        }
+        .and_then(|()| {
+            handle_connection(
+                transport.as_mut(),
+                &modules,
+                secrets.as_deref(),
+                password.as_deref(),
+                log_file.as_deref(),
+                log_format.as_deref(),
+                motd.as_deref(),
+                list,
+                &refuse_options,
+                &peer,
+                uid,
+                gid,
+                &handler,
+                timeout,
+            )
+        });
+        match res {
+            Ok(()) => {}
+            Err(e) if e.kind() == io::ErrorKind::TimedOut => {
+                tracing::warn!("timeout on connection from {peer}, disconnecting idle client");
+            }
+            Err(e) => tracing::error!("connection from {peer} failed: {e}"),
         }
-        let peer = addr.to_string();
-        handle_connection(
-            transport.as_mut(),
-            &modules,
-            secrets.as_deref(),
-            password.as_deref(),
-            log_file.as_deref(),
-            log_format.as_deref(),
-            motd.as_deref(),
-            list,
-            &refuse_options,
-            &peer,
-            uid,
-            gid,
-            &handler,
-            timeout,
-        )?;
     }
 }
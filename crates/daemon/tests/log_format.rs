@@ -0,0 +1,68 @@
+// crates/daemon/tests/log_format.rs
+use daemon::{Handler, Module, handle_connection};
+use nix::unistd::{getegid, geteuid};
+use protocol::SUPPORTED_PROTOCOLS;
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, Cursor, Read};
+use std::sync::Arc;
+use tempfile::tempdir;
+use transport::LocalPipeTransport;
+
+struct MultiReader {
+    parts: Vec<Vec<u8>>,
+    idx: usize,
+}
+
+impl Read for MultiReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let Some(part) = self.parts.get(self.idx) else {
+            return Ok(0);
+        };
+        let len = part.len().min(buf.len());
+        buf[..len].copy_from_slice(&part[..len]);
+        self.idx += 1;
+        Ok(len)
+    }
+}
+
+#[test]
+fn forwarded_log_file_format_is_honored() {
+    let dir = tempdir().unwrap();
+    let module = Module::builder("data", dir.path())
+        .use_chroot(false)
+        .build();
+    let log = dir.path().join("rsyncd.log");
+    let mut modules = HashMap::new();
+    modules.insert(module.name.clone(), module);
+    let handler: Arc<Handler> = Arc::new(|_, _, _| Ok(()));
+    let parts = vec![
+        SUPPORTED_PROTOCOLS[0].to_be_bytes().to_vec(),
+        b"\n".to_vec(),
+        b"data\n".to_vec(),
+        b"--log-file-format=remote\\t%m@%h\n".to_vec(),
+        b"--server\n".to_vec(),
+        b"--sender\n".to_vec(),
+        b"\n".to_vec(),
+    ];
+    let mut t = LocalPipeTransport::new(MultiReader { parts, idx: 0 }, Cursor::new(Vec::new()));
+    handle_connection(
+        &mut t,
+        &modules,
+        None,
+        None,
+        Some(&log),
+        None,
+        None,
+        true,
+        &[],
+        "127.0.0.1",
+        geteuid().as_raw(),
+        getegid().as_raw(),
+        &handler,
+        None,
+    )
+    .unwrap();
+    let contents = fs::read_to_string(&log).unwrap();
+    assert_eq!(contents, "remote\tdata@127.0.0.1\n");
+}
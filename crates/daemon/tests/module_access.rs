@@ -0,0 +1,152 @@
+// crates/daemon/tests/module_access.rs
+use daemon::{Handler, Module, handle_connection};
+use nix::unistd::{getegid, geteuid};
+use protocol::SUPPORTED_PROTOCOLS;
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, Cursor, Read};
+use std::path::Path;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use tempfile::tempdir;
+use transport::LocalPipeTransport;
+
+struct MultiReader {
+    parts: Vec<Vec<u8>>,
+    idx: usize,
+}
+
+impl Read for MultiReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let Some(part) = self.parts.get(self.idx) else {
+            return Ok(0);
+        };
+        let len = part.len().min(buf.len());
+        buf[..len].copy_from_slice(&part[..len]);
+        self.idx += 1;
+        Ok(len)
+    }
+}
+
+fn connect(module: &Module, args: &[&str]) -> (io::Result<()>, String, usize) {
+    connect_with(module, None, "", args)
+}
+
+fn connect_with(
+    module: &Module,
+    secrets: Option<&Path>,
+    token: &str,
+    args: &[&str],
+) -> (io::Result<()>, String, usize) {
+    let mut parts = vec![
+        SUPPORTED_PROTOCOLS[0].to_be_bytes().to_vec(),
+        format!("{token}\n").into_bytes(),
+        format!("{}\n", module.name).into_bytes(),
+    ];
+    for arg in args {
+        parts.push(format!("{arg}\n").into_bytes());
+    }
+    parts.push(b"\n".to_vec());
+    let mut modules = HashMap::new();
+    modules.insert(module.name.clone(), module.clone());
+    let calls = Arc::new(AtomicUsize::new(0));
+    let counter = Arc::clone(&calls);
+    let handler: Arc<Handler> = Arc::new(move |_, _, _| {
+        counter.fetch_add(1, Ordering::SeqCst);
+        Ok(())
+    });
+    let reader = MultiReader { parts, idx: 0 };
+    let mut t = LocalPipeTransport::new(reader, Cursor::new(Vec::new()));
+    let res = handle_connection(
+        &mut t,
+        &modules,
+        secrets,
+        None,
+        None,
+        None,
+        None,
+        true,
+        &[],
+        "127.0.0.1",
+        geteuid().as_raw(),
+        getegid().as_raw(),
+        &handler,
+        None,
+    );
+    let (_, writer) = t.into_inner();
+    let out = String::from_utf8_lossy(&writer.into_inner()).into_owned();
+    (res, out, calls.load(Ordering::SeqCst))
+}
+
+const DOWNLOAD: &[&str] = &["--server", "--sender", ".", "data/"];
+const UPLOAD: &[&str] = &["--server", ".", "data/"];
+
+#[test]
+fn write_only_module_refuses_downloads() {
+    let dir = tempdir().unwrap();
+    let module = Module::builder("data", dir.path())
+        .read_only(false)
+        .write_only(true)
+        .use_chroot(false)
+        .max_connections(1)
+        .build();
+
+    let (res, out, calls) = connect(&module, DOWNLOAD);
+    assert_eq!(res.unwrap_err().kind(), io::ErrorKind::PermissionDenied);
+    assert!(out.contains("@ERROR: module is write only"), "{out}");
+    assert_eq!(calls, 0);
+    assert_eq!(module.connections.load(Ordering::SeqCst), 0);
+
+    let (res, out, calls) = connect(&module, UPLOAD);
+    res.unwrap();
+    assert!(!out.contains("@ERROR"), "{out}");
+    assert_eq!(calls, 1);
+}
+
+#[test]
+fn read_only_module_refuses_uploads() {
+    let dir = tempdir().unwrap();
+    let module = Module::builder("data", dir.path())
+        .read_only(true)
+        .use_chroot(false)
+        .max_connections(1)
+        .build();
+
+    let (res, out, calls) = connect(&module, UPLOAD);
+    assert_eq!(res.unwrap_err().kind(), io::ErrorKind::PermissionDenied);
+    assert!(out.contains("@ERROR: module is read only"), "{out}");
+    assert_eq!(calls, 0);
+    assert_eq!(module.connections.load(Ordering::SeqCst), 0);
+
+    let (res, out, calls) = connect(&module, DOWNLOAD);
+    res.unwrap();
+    assert!(!out.contains("@ERROR"), "{out}");
+    assert_eq!(calls, 1);
+}
+
+#[cfg(unix)]
+#[test]
+fn global_secrets_file_honours_module_strict_modes() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = tempdir().unwrap();
+    let secrets = dir.path().join("secrets");
+    fs::write(&secrets, "secret data\n").unwrap();
+    fs::set_permissions(&secrets, fs::Permissions::from_mode(0o644)).unwrap();
+
+    let strict = Module::builder("data", dir.path())
+        .use_chroot(false)
+        .build();
+    let (res, _, calls) = connect_with(&strict, Some(&secrets), "secret", DOWNLOAD);
+    assert_eq!(res.unwrap_err().kind(), io::ErrorKind::PermissionDenied);
+    assert_eq!(calls, 0);
+
+    let relaxed = Module::builder("data", dir.path())
+        .use_chroot(false)
+        .strict_modes(false)
+        .build();
+    let (res, out, calls) = connect_with(&relaxed, Some(&secrets), "secret", DOWNLOAD);
+    res.unwrap();
+    assert!(!out.contains("@ERROR"), "{out}");
+    assert_eq!(calls, 1);
+}
@@ -0,0 +1,168 @@
+// crates/daemon/tests/module_filters.rs
+use daemon::{Handler, Module, handle_connection, parse_config};
+use nix::unistd::{getegid, geteuid};
+use oc_rsync_core::filter::Matcher;
+use protocol::SUPPORTED_PROTOCOLS;
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, Cursor, Read};
+use std::path::Path;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use tempfile::tempdir;
+use transport::{LocalPipeTransport, Transport};
+
+struct MultiReader {
+    parts: Vec<Vec<u8>>,
+    idx: usize,
+}
+
+impl Read for MultiReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let Some(part) = self.parts.get(self.idx) else {
+            return Ok(0);
+        };
+        let len = part.len().min(buf.len());
+        buf[..len].copy_from_slice(&part[..len]);
+        self.idx += 1;
+        Ok(len)
+    }
+}
+
+fn request(module: &Module, args: &[&str], handler: &Arc<Handler>) -> (io::Result<()>, Vec<u8>) {
+    let mut parts = vec![
+        SUPPORTED_PROTOCOLS[0].to_be_bytes().to_vec(),
+        b"\n".to_vec(),
+        format!("{}\n", module.name).into_bytes(),
+    ];
+    for arg in args {
+        parts.push(format!("{arg}\n").into_bytes());
+    }
+    parts.push(b"\n".to_vec());
+    let mut modules = HashMap::new();
+    modules.insert(module.name.clone(), module.clone());
+    let reader = MultiReader { parts, idx: 0 };
+    let mut t = LocalPipeTransport::new(reader, Cursor::new(Vec::new()));
+    let res = handle_connection(
+        &mut t,
+        &modules,
+        None,
+        None,
+        None,
+        None,
+        None,
+        true,
+        &[],
+        "127.0.0.1",
+        geteuid().as_raw(),
+        getegid().as_raw(),
+        handler,
+        None,
+    );
+    let (_, writer) = t.into_inner();
+    (res, writer.into_inner())
+}
+
+#[test]
+fn module_exclude_denies_excluded_paths() {
+    let dir = tempdir().unwrap();
+    fs::create_dir(dir.path().join("secret")).unwrap();
+    fs::write(dir.path().join("secret/key"), b"k").unwrap();
+    fs::write(dir.path().join("public"), b"p").unwrap();
+    let cfg = format!(
+        "[data]\npath = {}\nuse chroot = no\nexclude = secret *.bak\n",
+        dir.path().display()
+    );
+    let module = parse_config(&cfg).unwrap().modules.remove(0);
+    assert_eq!(module.exclude, vec!["secret", "*.bak"]);
+
+    let calls = Arc::new(AtomicUsize::new(0));
+    let counter = Arc::clone(&calls);
+    let handler: Arc<Handler> = Arc::new(move |_, _, _| {
+        counter.fetch_add(1, Ordering::SeqCst);
+        Ok(())
+    });
+
+    for path in ["data/secret/key", "data/secret", "old.bak"] {
+        let (res, out) = request(&module, &["--server", "--sender", ".", path], &handler);
+        assert_eq!(res.unwrap_err().kind(), io::ErrorKind::PermissionDenied);
+        assert!(String::from_utf8_lossy(&out).contains("@ERROR: access denied"));
+    }
+    assert_eq!(calls.load(Ordering::SeqCst), 0);
+
+    let (res, _) = request(
+        &module,
+        &["--server", "--sender", ".", "data/public"],
+        &handler,
+    );
+    res.unwrap();
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn module_filter_rules_override_excludes() {
+    let dir = tempdir().unwrap();
+    let cfg = format!(
+        "[data]\npath = {}\nfilter = + keep.log - *.log\n",
+        dir.path().display()
+    );
+    let module = parse_config(&cfg).unwrap().modules.remove(0);
+    assert_eq!(module.filter, vec!["+ keep.log", "- *.log"]);
+    let module = Module {
+        exclude: vec!["keep.log".into()],
+        ..module
+    };
+    let matcher = module.matcher().unwrap();
+    assert!(matcher.is_included("keep.log").unwrap());
+    assert!(!matcher.is_included("debug.log").unwrap());
+    let args = vec!["data/keep.log".to_string(), "data/debug.log".to_string()];
+    assert_eq!(module.excluded_path(&args).unwrap(), Some("data/debug.log"));
+}
+
+fn send_tree(t: &mut dyn Transport, root: &Path, rel: &Path, matcher: &Matcher) -> io::Result<()> {
+    let mut entries: Vec<_> = fs::read_dir(root.join(rel))?.collect::<Result<_, _>>()?;
+    entries.sort_by_key(|e| e.file_name());
+    for entry in entries {
+        let rel = rel.join(entry.file_name());
+        if !matcher
+            .is_included(&rel)
+            .map_err(|e| io::Error::other(format!("{e:?}")))?
+        {
+            continue;
+        }
+        if entry.file_type()?.is_dir() {
+            send_tree(t, root, &rel, matcher)?;
+        } else {
+            t.send(format!("{}\n", rel.display()).as_bytes())?;
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn module_root_request_skips_excluded_entries() {
+    let dir = tempdir().unwrap();
+    fs::create_dir(dir.path().join("secret")).unwrap();
+    fs::write(dir.path().join("secret/key"), b"k").unwrap();
+    fs::create_dir(dir.path().join("docs")).unwrap();
+    fs::write(dir.path().join("docs/secret"), b"s").unwrap();
+    fs::write(dir.path().join("docs/readme"), b"r").unwrap();
+    fs::write(dir.path().join("old.bak"), b"b").unwrap();
+    fs::write(dir.path().join("public"), b"p").unwrap();
+    let cfg = format!(
+        "[data]\npath = {}\nuse chroot = no\nexclude = secret *.bak\n",
+        dir.path().display()
+    );
+    let module = parse_config(&cfg).unwrap().modules.remove(0);
+
+    let root = dir.path().to_path_buf();
+    let handler: Arc<Handler> =
+        Arc::new(move |t, _, matcher| send_tree(t, &root, Path::new(""), matcher));
+    let (res, out) = request(&module, &["--server", "--sender", ".", "data/"], &handler);
+    res.unwrap();
+    let out = String::from_utf8_lossy(&out);
+    assert!(out.contains("public\n"), "{out}");
+    assert!(out.contains("docs/readme\n"), "{out}");
+    assert!(!out.contains("secret"), "{out}");
+    assert!(!out.contains("old.bak"), "{out}");
+}
@@ -26,7 +26,7 @@ fn handle_connection_empty_module_name_lists_modules() {
             ..Module::default()
         },
     );
-    let handler: Arc<Handler> = Arc::new(|_, _| Ok(()));
+    let handler: Arc<Handler> = Arc::new(|_, _, _| Ok(()));
 
     handle_connection(
         &mut transport,
@@ -27,7 +27,7 @@ fn handle_sequential_chrooted_connections() {
     };
     let mut modules = HashMap::new();
     modules.insert(module.name.clone(), module);
-    let handler: Arc<Handler> = Arc::new(|_, _| Ok(()));
+    let handler: Arc<Handler> = Arc::new(|_, _, _| Ok(()));
     let cwd = std::env::current_dir().unwrap();
     struct MultiReader {
         parts: Vec<Vec<u8>>,
@@ -1,9 +1,20 @@
 // crates/engine/src/batch.rs
 
+#[cfg(unix)]
+use std::ffi::OsStr;
 use std::fs;
+use std::io::Write;
+#[cfg(unix)]
+use std::os::unix::ffi::OsStrExt;
+#[cfg(unix)]
+use std::os::unix::fs::MetadataExt;
 use std::path::{Path, PathBuf};
 
-use crate::{EngineError, Result};
+use filters::Matcher;
+use md5::{Digest, Md5};
+use protocol::{NDX_DONE, NdxCodec};
+
+use crate::{EngineError, Result, SyncOptions, io_context};
 
 fn unescape_rsync(path: &str) -> String {
     let mut bytes = Vec::with_capacity(path.len());
@@ -82,15 +93,352 @@ pub fn decode_batch(bytes: &[u8]) -> Result<Batch> {
     })
 }
 
-pub(crate) fn parse_batch_file(batch_path: &Path) -> Result<Vec<PathBuf>> {
+fn escape_rsync(path: &Path) -> String {
+    let mut out = String::new();
+    for b in name_bytes(path) {
+        if b.is_ascii_graphic() && !matches!(b, b'\\' | b'=' | b'#') {
+            out.push(b as char);
+        } else {
+            out.push_str(&format!("\\{b:03o}"));
+        }
+    }
+    out
+}
+
+/// A file recorded in a native batch, with the source checksum it had when
+/// the batch was written. Hand-written batches may omit the checksum.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct BatchFile {
+    pub(crate) path: PathBuf,
+    pub(crate) sum: Option<Vec<u8>>,
+}
+
+pub(crate) fn write_batch_entry(out: &mut impl Write, rel: &Path, sum: &[u8]) -> Result<()> {
+    writeln!(out, "{}", escape_rsync(rel))?;
+    writeln!(out, "sum={}", hex::encode(sum))?;
+    Ok(())
+}
+
+/// Reads the files of a native batch along with the number of files its
+/// `files_transferred=` trailers say were written, if it has any.
+pub(crate) fn parse_batch_file(batch_path: &Path) -> Result<(Vec<BatchFile>, Option<usize>)> {
     let content = fs::read_to_string(batch_path).map_err(|e| EngineError::Other(e.to_string()))?;
-    let mut paths = Vec::new();
+    let mut files: Vec<BatchFile> = Vec::new();
+    let mut listed: Option<usize> = None;
     for line in content.lines() {
         let trimmed = line.trim();
+        if let Some(rest) = trimmed.strip_prefix("files_transferred=") {
+            let count = rest
+                .split_whitespace()
+                .next()
+                .and_then(|n| n.parse::<usize>().ok())
+                .ok_or_else(|| EngineError::Other(format!("invalid batch trailer: {trimmed}")))?;
+            *listed.get_or_insert(0) += count;
+            continue;
+        }
+        if let Some(sum) = trimmed.strip_prefix("sum=") {
+            let sum = hex::decode(sum)
+                .map_err(|_| EngineError::Other(format!("invalid batch checksum: {sum}")))?;
+            if let Some(file) = files.last_mut() {
+                file.sum = Some(sum);
+            }
+            continue;
+        }
         if trimmed.is_empty() || trimmed.starts_with('#') || trimmed.contains('=') {
             continue;
         }
-        paths.push(PathBuf::from(unescape_rsync(trimmed)));
+        files.push(BatchFile {
+            path: PathBuf::from(unescape_rsync(trimmed)),
+            sum: None,
+        });
+    }
+    Ok((files, listed))
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BatchFormat {
+    #[default]
+    Native,
+    Rsync,
+}
+
+pub const RSYNC_BATCH_PROTOCOL: i32 = 30;
+
+pub const BATCH_RECURSE: i32 = 1 << 0;
+pub const BATCH_XFER_DIRS: i32 = 1 << 7;
+pub const BATCH_INPLACE: i32 = 1 << 12;
+
+const XMIT_TOP_DIR: u16 = 1 << 0;
+const XMIT_EXTENDED_FLAGS: u16 = 1 << 2;
+const XMIT_SAME_NAME: u16 = 1 << 5;
+const XMIT_LONG_NAME: u16 = 1 << 6;
+
+const ITEM_IS_NEW: u16 = 1 << 13;
+const ITEM_TRANSFER: u16 = 1 << 15;
+
+const CHUNK_SIZE: usize = 32 * 1024;
+
+const S_IFMT: u32 = 0o170000;
+const S_IFDIR: u32 = 0o040000;
+const S_IFREG: u32 = 0o100000;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RsyncBatchEntry {
+    pub name: Vec<u8>,
+    pub mode: u32,
+    pub size: u64,
+    pub mtime: i64,
+}
+
+impl RsyncBatchEntry {
+    fn is_dir(&self) -> bool {
+        self.mode & S_IFMT == S_IFDIR
+    }
+
+    fn is_file(&self) -> bool {
+        self.mode & S_IFMT == S_IFREG
+    }
+
+    fn sort_key(&self) -> Vec<u8> {
+        let mut key = self.name.clone();
+        if self.is_dir() {
+            key.push(b'/');
+        }
+        key
+    }
+}
+
+fn write_int(out: &mut Vec<u8>, x: i32) {
+    out.extend(x.to_le_bytes());
+}
+
+fn write_varint(out: &mut Vec<u8>, x: i32) {
+    let mut b = [0u8; 5];
+    b[1..].copy_from_slice(&x.to_le_bytes());
+    let mut cnt = 4;
+    while cnt > 1 && b[cnt] == 0 {
+        cnt -= 1;
+    }
+    let bit = 1u8 << (8 - cnt);
+    if b[cnt] >= bit {
+        cnt += 1;
+        b[0] = !(bit - 1);
+    } else if cnt > 1 {
+        b[0] = b[cnt] | !(bit * 2 - 1);
+    } else {
+        b[0] = b[cnt];
+    }
+    out.extend(&b[..cnt]);
+}
+
+fn write_varlong(out: &mut Vec<u8>, x: i64, min_bytes: usize) {
+    let mut b = [0u8; 9];
+    b[1..].copy_from_slice(&x.to_le_bytes());
+    let mut cnt = 8;
+    while cnt > min_bytes && b[cnt] == 0 {
+        cnt -= 1;
+    }
+    let bit = 1u8 << (7 + min_bytes - cnt);
+    if b[cnt] >= bit {
+        cnt += 1;
+        b[0] = !(bit - 1);
+    } else if cnt > min_bytes {
+        b[0] = b[cnt] | !(bit * 2 - 1);
+    } else {
+        b[0] = b[cnt];
+    }
+    out.extend(&b[..cnt]);
+}
+
+pub fn encode_rsync_header(stream_flags: i32, checksum_seed: i32) -> Vec<u8> {
+    let mut out = Vec::new();
+    write_int(&mut out, stream_flags);
+    write_int(&mut out, RSYNC_BATCH_PROTOCOL);
+    write_varint(&mut out, 0);
+    write_int(&mut out, checksum_seed);
+    out
+}
+
+pub fn encode_rsync_flist(entries: &[RsyncBatchEntry]) -> Vec<u8> {
+    let mut out = Vec::new();
+    let mut prev_name: &[u8] = &[];
+    for (idx, entry) in entries.iter().enumerate() {
+        let mut xflags = 0u16;
+        if idx == 0 && entry.is_dir() {
+            xflags |= XMIT_TOP_DIR;
+        }
+        let l1 = entry
+            .name
+            .iter()
+            .zip(prev_name)
+            .take_while(|(a, b)| a == b)
+            .count()
+            .min(255);
+        let l2 = entry.name.len() - l1;
+        if l1 > 0 {
+            xflags |= XMIT_SAME_NAME;
+        }
+        if l2 > 255 {
+            xflags |= XMIT_LONG_NAME;
+        }
+        if xflags == 0 && !entry.is_dir() {
+            xflags |= XMIT_TOP_DIR;
+        }
+        if xflags & 0xFF00 != 0 || xflags == 0 {
+            xflags |= XMIT_EXTENDED_FLAGS;
+            out.extend(xflags.to_le_bytes());
+        } else {
+            out.push(xflags as u8);
+        }
+        if xflags & XMIT_SAME_NAME != 0 {
+            out.push(l1 as u8);
+        }
+        if xflags & XMIT_LONG_NAME != 0 {
+            write_varint(&mut out, l2 as i32);
+        } else {
+            out.push(l2 as u8);
+        }
+        out.extend(&entry.name[l1..]);
+        write_varlong(&mut out, entry.size as i64, 3);
+        write_varlong(&mut out, entry.mtime, 4);
+        write_int(&mut out, entry.mode as i32);
+        prev_name = &entry.name;
+    }
+    out.push(0);
+    out
+}
+
+#[cfg(unix)]
+fn name_bytes(path: &Path) -> Vec<u8> {
+    path.as_os_str().as_bytes().to_vec()
+}
+
+#[cfg(not(unix))]
+fn name_bytes(path: &Path) -> Vec<u8> {
+    path.to_string_lossy().replace('\\', "/").into_bytes()
+}
+
+#[cfg(unix)]
+fn name_path(name: &[u8]) -> PathBuf {
+    PathBuf::from(OsStr::from_bytes(name))
+}
+
+#[cfg(not(unix))]
+fn name_path(name: &[u8]) -> PathBuf {
+    PathBuf::from(String::from_utf8_lossy(name).into_owned())
+}
+
+/// Mode and modification time of `meta` as upstream rsync records them.
+#[cfg(unix)]
+fn wire_mode_mtime(meta: &fs::Metadata) -> (u32, i64) {
+    (meta.mode(), meta.mtime())
+}
+
+#[cfg(not(unix))]
+fn wire_mode_mtime(meta: &fs::Metadata) -> (u32, i64) {
+    let mode = if meta.is_dir() {
+        S_IFDIR | 0o755
+    } else {
+        S_IFREG | 0o644
+    };
+    let mtime = meta
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map_or(0, |d| d.as_secs() as i64);
+    (mode, mtime)
+}
+
+fn collect_rsync_entries(
+    root: &Path,
+    rel: &Path,
+    matcher: &Matcher,
+    out: &mut Vec<RsyncBatchEntry>,
+) -> Result<()> {
+    let dir = root.join(rel);
+    for entry in fs::read_dir(&dir).map_err(|e| io_context(&dir, e))? {
+        let entry = entry.map_err(|e| io_context(&dir, e))?;
+        let rel = rel.join(entry.file_name());
+        let res = matcher.is_included_with_dir(&rel)?;
+        let path = entry.path();
+        let meta = fs::symlink_metadata(&path).map_err(|e| io_context(&path, e))?;
+        let (mode, mtime) = wire_mode_mtime(&meta);
+        if meta.is_dir() {
+            if res.include {
+                out.push(RsyncBatchEntry {
+                    name: name_bytes(&rel),
+                    mode,
+                    size: 0,
+                    mtime,
+                });
+            }
+            if res.include || res.descend {
+                collect_rsync_entries(root, &rel, matcher, out)?;
+            }
+        } else if meta.is_file() && res.include {
+            out.push(RsyncBatchEntry {
+                name: name_bytes(&rel),
+                mode,
+                size: meta.len(),
+                mtime,
+            });
+        }
+    }
+    Ok(())
+}
+
+pub(crate) fn write_rsync_batch(
+    src_root: &Path,
+    matcher: &Matcher,
+    opts: &SyncOptions,
+    batch_path: &Path,
+) -> Result<()> {
+    let meta = fs::metadata(src_root).map_err(|e| io_context(src_root, e))?;
+    let (mode, mtime) = wire_mode_mtime(&meta);
+    let mut entries = vec![RsyncBatchEntry {
+        name: b".".to_vec(),
+        mode,
+        size: 0,
+        mtime,
+    }];
+    collect_rsync_entries(src_root, Path::new(""), matcher, &mut entries)?;
+    entries[1..].sort_by_key(|e| e.sort_key());
+
+    let mut flags = BATCH_RECURSE | BATCH_XFER_DIRS;
+    if opts.inplace {
+        flags |= BATCH_INPLACE;
+    }
+    let mut out = encode_rsync_header(flags, 0);
+    out.extend(encode_rsync_flist(&entries));
+
+    let mut ndx_codec = NdxCodec::new(RSYNC_BATCH_PROTOCOL as u32);
+    let mut total_size = 0i64;
+    let mut written = 0i64;
+    for (ndx, entry) in entries.iter().enumerate() {
+        total_size += entry.size as i64;
+        if !entry.is_file() {
+            continue;
+        }
+        let path = src_root.join(name_path(&entry.name));
+        let data = fs::read(&path).map_err(|e| io_context(&path, e))?;
+        ndx_codec.write_ndx(&mut out, ndx as i32)?;
+        out.extend((ITEM_TRANSFER | ITEM_IS_NEW).to_le_bytes());
+        for _ in 0..4 {
+            write_int(&mut out, 0);
+        }
+        for chunk in data.chunks(CHUNK_SIZE) {
+            write_int(&mut out, chunk.len() as i32);
+            out.extend(chunk);
+        }
+        write_int(&mut out, 0);
+        out.extend(Md5::digest(&data));
+        written += data.len() as i64;
+    }
+    for _ in 0..3 {
+        ndx_codec.write_ndx(&mut out, NDX_DONE)?;
+    }
+    for stat in [0, written, total_size, 0, 0] {
+        write_varlong(&mut out, stat, 3);
     }
-    Ok(paths)
+    fs::write(batch_path, out).map_err(|e| io_context(batch_path, e))
 }
@@ -1,7 +1,17 @@
 // crates/engine/src/block.rs
 
+use std::collections::{HashMap, VecDeque};
+use std::io::{Read, Seek, SeekFrom};
+
+use checksums::ChecksumConfig;
+
+use crate::{EngineError, Result};
+
 const RSYNC_BLOCK_SIZE: usize = 700;
-const RSYNC_MAX_BLOCK_SIZE: usize = 1 << 17;
+pub const MAX_BLOCK_SIZE: usize = 1 << 17;
+pub(crate) const BLOCK_ENTRY_COST: usize = 64;
+/// The checksum header carries the block count as a signed 32-bit integer.
+pub const MAX_BLOCK_COUNT: u64 = i32::MAX as u64;
 
 pub fn block_size(len: u64) -> usize {
     if len <= (RSYNC_BLOCK_SIZE * RSYNC_BLOCK_SIZE) as u64 {
@@ -13,8 +23,8 @@ pub fn block_size(len: u64) -> usize {
         l >>= 2;
         c <<= 1;
     }
-    if c >= RSYNC_MAX_BLOCK_SIZE {
-        return RSYNC_MAX_BLOCK_SIZE;
+    if c >= MAX_BLOCK_SIZE {
+        return MAX_BLOCK_SIZE;
     }
     let mut blength = 0usize;
     while c >= 8 {
@@ -26,3 +36,90 @@ pub fn block_size(len: u64) -> usize {
     }
     blength.max(RSYNC_BLOCK_SIZE)
 }
+
+pub fn capped_block_size(len: u64, block_size: usize, max_blocks: usize) -> usize {
+    let block_size = block_size.max(1);
+    if max_blocks == 0 || len.div_ceil(block_size as u64) <= max_blocks as u64 {
+        return block_size;
+    }
+    len.div_ceil(max_blocks as u64) as usize
+}
+
+/// Number of `block_size` blocks covering `len` bytes. Fails when the count
+/// does not fit the checksum header or the offsets do not fit in `usize`.
+pub fn block_count(len: u64, block_size: usize) -> Result<u64> {
+    let block_size = block_size.max(1);
+    let count = len.div_ceil(block_size as u64);
+    if count > MAX_BLOCK_COUNT || usize::try_from(len).is_err() {
+        return Err(EngineError::Other(format!(
+            "file of {len} bytes needs {count} blocks of {block_size} bytes, \
+             more than the {MAX_BLOCK_COUNT} supported; use a larger block size"
+        )));
+    }
+    Ok(count)
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct BlockTable {
+    block_size: usize,
+    pub(crate) map: HashMap<u32, Vec<(Vec<u8>, usize, usize)>>,
+}
+
+impl BlockTable {
+    pub fn build<R: Read + Seek>(
+        cfg: &ChecksumConfig,
+        reader: &mut R,
+        block_size: usize,
+    ) -> Result<Self> {
+        Self::build_windowed(cfg, reader, block_size, usize::MAX)
+    }
+
+    pub(crate) fn build_windowed<R: Read + Seek>(
+        cfg: &ChecksumConfig,
+        basis: &mut R,
+        block_size: usize,
+        basis_window: usize,
+    ) -> Result<Self> {
+        let block_size = block_size.max(1);
+        basis.seek(SeekFrom::Start(0))?;
+        let mut map: HashMap<u32, Vec<(Vec<u8>, usize, usize)>> = HashMap::new();
+        let mut order: VecDeque<(u32, Vec<u8>, usize, usize)> = VecDeque::new();
+        let mut off = 0usize;
+        let mut buf = vec![0u8; block_size];
+        loop {
+            let n = basis.read(&mut buf)?;
+            if n == 0 {
+                break;
+            }
+            let sum = cfg.checksum(&buf[..n]);
+            map.entry(sum.weak)
+                .or_default()
+                .push((sum.strong.clone(), off, n));
+            order.push_back((sum.weak, sum.strong, off, n));
+            if order.len() > basis_window {
+                if let Some((w, s, o, l)) = order.pop_front() {
+                    if let Some(v) = map.get_mut(&w) {
+                        if let Some(pos) = v
+                            .iter()
+                            .position(|(ss, oo, ll)| *oo == o && *ll == l && *ss == s)
+                        {
+                            v.remove(pos);
+                        }
+                        if v.is_empty() {
+                            map.remove(&w);
+                        }
+                    }
+                }
+            }
+            off += n;
+            if n < block_size {
+                break;
+            }
+        }
+        Ok(Self { block_size, map })
+    }
+
+    pub fn block_size(&self) -> usize {
+        self.block_size
+    }
+}
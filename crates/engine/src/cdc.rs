@@ -0,0 +1,162 @@
+// crates/engine/src/cdc.rs
+
+use std::collections::HashMap;
+use std::io::{Read, Seek, SeekFrom};
+
+use checksums::ChecksumConfig;
+
+use crate::Result;
+use crate::delta::{DeltaIter, LIT_CAP, Op};
+
+pub(crate) const CDC_MIN_CHUNK: usize = 2 * 1024;
+pub(crate) const CDC_MAX_CHUNK: usize = 64 * 1024;
+const CDC_MASK: u64 = (1 << 13) - 1;
+
+const fn gear_table() -> [u64; 256] {
+    let mut table = [0u64; 256];
+    let mut state: u64 = 0x9e37_79b9_7f4a_7c15;
+    let mut i = 0;
+    while i < 256 {
+        state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        table[i] = z ^ (z >> 31);
+        i += 1;
+    }
+    table
+}
+
+static GEAR: [u64; 256] = gear_table();
+
+#[derive(Debug, Default, Clone)]
+pub(crate) struct Chunker {
+    hash: u64,
+    len: usize,
+}
+
+impl Chunker {
+    pub(crate) fn push(&mut self, byte: u8) -> bool {
+        self.hash = (self.hash << 1).wrapping_add(GEAR[byte as usize]);
+        self.len += 1;
+        let cut =
+            self.len >= CDC_MAX_CHUNK || (self.len >= CDC_MIN_CHUNK && self.hash & CDC_MASK == 0);
+        if cut {
+            self.hash = 0;
+            self.len = 0;
+        }
+        cut
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ChunkIndex {
+    map: HashMap<(Vec<u8>, usize), Vec<usize>>,
+}
+
+impl ChunkIndex {
+    pub(crate) fn build<R: Read + Seek>(cfg: &ChecksumConfig, basis: &mut R) -> Result<Self> {
+        basis.seek(SeekFrom::Start(0))?;
+        let mut index = Self::default();
+        let mut chunker = Chunker::default();
+        let mut chunk = Vec::with_capacity(CDC_MAX_CHUNK);
+        let mut buf = vec![0u8; CDC_MAX_CHUNK];
+        let mut off = 0usize;
+        loop {
+            let n = basis.read(&mut buf)?;
+            if n == 0 {
+                break;
+            }
+            for &b in &buf[..n] {
+                chunk.push(b);
+                if chunker.push(b) {
+                    index.insert(cfg, &chunk, off);
+                    off += chunk.len();
+                    chunk.clear();
+                }
+            }
+        }
+        if !chunk.is_empty() {
+            index.insert(cfg, &chunk, off);
+        }
+        Ok(index)
+    }
+
+    fn insert(&mut self, cfg: &ChecksumConfig, chunk: &[u8], off: usize) {
+        let sum = cfg.checksum(chunk);
+        self.map
+            .entry((sum.strong, chunk.len()))
+            .or_default()
+            .push(off);
+    }
+
+    pub(crate) fn find(&self, cfg: &ChecksumConfig, chunk: &[u8], min_off: usize) -> Option<usize> {
+        let sum = cfg.checksum(chunk);
+        self.map
+            .get(&(sum.strong, chunk.len()))?
+            .iter()
+            .copied()
+            .find(|&o| o >= min_off)
+    }
+}
+
+impl<R: Read + Seek> DeltaIter<'_, R> {
+    pub(crate) fn next_chunk(&mut self) -> Option<Result<Op>> {
+        loop {
+            if let Some(op) = self.pending.take() {
+                if let Op::Copy { len, .. } = op {
+                    self.pos += len;
+                }
+                return Some(Ok(op));
+            }
+            if self.done {
+                if self.lit.is_empty() {
+                    return None;
+                }
+                return Some(Ok(self.take_lit()));
+            }
+            loop {
+                match self.target.read(&mut self.byte) {
+                    Ok(0) => {
+                        self.done = true;
+                        break;
+                    }
+                    Ok(_) => {
+                        self.chunk.push(self.byte[0]);
+                        if self.chunker.push(self.byte[0]) {
+                            break;
+                        }
+                    }
+                    Err(e) => return Some(Err(e.into())),
+                }
+            }
+            if self.chunk.is_empty() {
+                continue;
+            }
+            let min_off = if self.inplace {
+                self.pos + self.lit.len()
+            } else {
+                0
+            };
+            let found = self
+                .cdc
+                .as_ref()
+                .and_then(|index| index.find(self.cfg, &self.chunk, min_off));
+            if let Some(offset) = found {
+                self.pending = Some(Op::Copy {
+                    offset,
+                    len: self.chunk.len(),
+                });
+                self.chunk.clear();
+                if !self.lit.is_empty() {
+                    return Some(Ok(self.take_lit()));
+                }
+            } else {
+                self.lit.append(&mut self.chunk);
+                if self.lit.len() >= LIT_CAP {
+                    return Some(Ok(self.take_lit()));
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,145 @@
+// crates/engine/src/checksum_cache.rs
+
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use crate::Result;
+
+pub const CACHE_FILE: &str = ".rsyncsums";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct CacheEntry {
+    size: u64,
+    mtime: (u64, u32),
+    sum: Vec<u8>,
+}
+
+#[derive(Debug, Default)]
+struct DirCache {
+    entries: HashMap<String, CacheEntry>,
+    dirty: bool,
+}
+
+#[derive(Debug)]
+pub struct ChecksumCache {
+    tag: String,
+    dirs: HashMap<PathBuf, DirCache>,
+}
+
+pub fn is_cache_file(path: &Path) -> bool {
+    path.file_name().is_some_and(|n| n == CACHE_FILE)
+}
+
+impl ChecksumCache {
+    pub fn new(tag: impl Into<String>) -> Self {
+        Self {
+            tag: tag.into(),
+            dirs: HashMap::new(),
+        }
+    }
+
+    pub fn checksum<F>(&mut self, path: &Path, compute: F) -> Result<Vec<u8>>
+    where
+        F: FnOnce(&Path) -> Result<Vec<u8>>,
+    {
+        let key = fs::metadata(path).ok().and_then(|meta| {
+            let mtime = meta.modified().ok()?.duration_since(UNIX_EPOCH).ok()?;
+            Some((meta.len(), (mtime.as_secs(), mtime.subsec_nanos())))
+        });
+        let (Some((size, mtime)), Some(dir), Some(name)) = (
+            key,
+            path.parent(),
+            path.file_name().and_then(|n| n.to_str()),
+        ) else {
+            return compute(path);
+        };
+        if name.contains('\n') {
+            return compute(path);
+        }
+        let tag = self.tag.clone();
+        let dir_cache = self
+            .dirs
+            .entry(dir.to_path_buf())
+            .or_insert_with(|| load(dir, &tag));
+        if let Some(e) = dir_cache.entries.get(name) {
+            if e.size == size && e.mtime == mtime {
+                return Ok(e.sum.clone());
+            }
+        }
+        let sum = compute(path)?;
+        dir_cache.entries.insert(
+            name.to_string(),
+            CacheEntry {
+                size,
+                mtime,
+                sum: sum.clone(),
+            },
+        );
+        dir_cache.dirty = true;
+        Ok(sum)
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        for (dir, cache) in self.dirs.iter_mut().filter(|(_, c)| c.dirty) {
+            let mut out = Vec::new();
+            let mut names: Vec<_> = cache.entries.keys().collect();
+            names.sort();
+            for name in names {
+                let e = &cache.entries[name];
+                writeln!(
+                    out,
+                    "{} {} {} {} {} {}",
+                    self.tag,
+                    e.size,
+                    e.mtime.0,
+                    e.mtime.1,
+                    hex::encode(&e.sum),
+                    name
+                )?;
+            }
+            fs::write(dir.join(CACHE_FILE), out)?;
+            cache.dirty = false;
+        }
+        Ok(())
+    }
+}
+
+fn load(dir: &Path, tag: &str) -> DirCache {
+    let mut cache = DirCache::default();
+    let Ok(data) = fs::read_to_string(dir.join(CACHE_FILE)) else {
+        return cache;
+    };
+    for line in data.lines() {
+        let mut parts = line.splitn(6, ' ');
+        let (Some(t), Some(size), Some(secs), Some(nanos), Some(sum), Some(name)) = (
+            parts.next(),
+            parts.next(),
+            parts.next(),
+            parts.next(),
+            parts.next(),
+            parts.next(),
+        ) else {
+            continue;
+        };
+        if t != tag {
+            continue;
+        }
+        let (Ok(size), Ok(secs), Ok(nanos), Ok(sum)) =
+            (size.parse(), secs.parse(), nanos.parse(), hex::decode(sum))
+        else {
+            continue;
+        };
+        cache.entries.insert(
+            name.to_string(),
+            CacheEntry {
+                size,
+                mtime: (secs, nanos),
+                sum,
+            },
+        );
+    }
+    cache
+}
@@ -3,16 +3,55 @@
 use rand::{Rng, distributions::Alphanumeric};
 use std::ffi::{OsStr, OsString};
 use std::fs::{self, File};
+use std::io::{Read, Seek, SeekFrom};
 use std::path::{Path, PathBuf};
 use tempfile::Builder;
 
-use crate::io::io_context;
+use crate::io::{data_regions, io_context};
 use crate::{Result, SyncOptions};
 
 #[cfg(unix)]
 use std::os::unix::fs::MetadataExt;
 
 pub(crate) fn atomic_rename(src: &Path, dst: &Path) -> Result<()> {
+    atomic_rename_with(src, dst, |from, to| fs::rename(from, to))
+}
+
+fn is_cross_device(e: &std::io::Error) -> bool {
+    #[cfg(unix)]
+    {
+        e.raw_os_error() == Some(nix::errno::Errno::EXDEV as i32)
+    }
+    #[cfg(windows)]
+    {
+        matches!(e.raw_os_error(), Some(17))
+    }
+    #[cfg(not(any(unix, windows)))]
+    {
+        let _ = e;
+        false
+    }
+}
+
+fn copy_into_place(src: &Path, dst: &Path) -> Result<()> {
+    let parent = dst.parent().unwrap_or_else(|| Path::new("."));
+    let base = dst.file_name().unwrap_or_default().to_string_lossy();
+    let tmp = Builder::new()
+        .prefix(&format!(".{}.", base))
+        .rand_bytes(6)
+        .tempfile_in(parent)
+        .map_err(|e| io_context(parent, e))?;
+    fs::copy(src, tmp.path()).map_err(|e| io_context(src, e))?;
+    tmp.persist(dst).map_err(|e| io_context(dst, e.error))?;
+    fs::remove_file(src).map_err(|e| io_context(src, e))?;
+    Ok(())
+}
+
+fn atomic_rename_with(
+    src: &Path,
+    dst: &Path,
+    rename: impl Fn(&Path, &Path) -> std::io::Result<()>,
+) -> Result<()> {
     let cross_device = {
         #[cfg(unix)]
         {
@@ -21,65 +60,33 @@ pub(crate) fn atomic_rename(src: &Path, dst: &Path) -> Result<()> {
             let dst_dev = fs::metadata(dst_parent).ok().map(|m| m.dev());
             src_dev.is_some() && dst_dev.is_some() && src_dev != dst_dev
         }
-        #[cfg(windows)]
-        {
-            false
-        }
-        #[cfg(not(any(unix, windows)))]
+        #[cfg(not(unix))]
         {
             false
         }
     };
     if cross_device {
-        let parent = dst.parent().unwrap_or_else(|| Path::new("."));
-        let base = dst.file_name().unwrap_or_default().to_string_lossy();
-        let tmp = Builder::new()
-            .prefix(&format!(".{}.", base))
-            .rand_bytes(6)
-            .tempfile_in(parent)
-            .map_err(|e| io_context(parent, e))?;
-        fs::copy(src, tmp.path()).map_err(|e| io_context(src, e))?;
-        tmp.persist(dst).map_err(|e| io_context(dst, e.error))?;
-        fs::remove_file(src).map_err(|e| io_context(src, e))?;
-        Ok(())
-    } else {
-        match fs::rename(src, dst) {
-            Ok(_) => Ok(()),
-            Err(e) => {
-                let cross_device_err = {
-                    #[cfg(unix)]
-                    {
-                        e.raw_os_error() == Some(nix::errno::Errno::EXDEV as i32)
-                    }
-                    #[cfg(windows)]
-                    {
-                        matches!(e.raw_os_error(), Some(17))
-                    }
-                    #[cfg(not(any(unix, windows)))]
-                    {
-                        false
-                    }
-                };
-                if cross_device_err {
-                    let parent = dst.parent().unwrap_or_else(|| Path::new("."));
-                    let base = dst.file_name().unwrap_or_default().to_string_lossy();
-                    let tmp = Builder::new()
-                        .prefix(&format!(".{}.", base))
-                        .rand_bytes(6)
-                        .tempfile_in(parent)
-                        .map_err(|e| io_context(parent, e))?;
-                    fs::copy(src, tmp.path()).map_err(|e| io_context(src, e))?;
-                    tmp.persist(dst).map_err(|e| io_context(dst, e.error))?;
-                    fs::remove_file(src).map_err(|e| io_context(src, e))?;
-                    Ok(())
-                } else {
-                    Err(io_context(src, e))
-                }
-            }
+        warn_cross_device(src, dst);
+        return copy_into_place(src, dst);
+    }
+    match rename(src, dst) {
+        Ok(()) => Ok(()),
+        Err(e) if is_cross_device(&e) => {
+            warn_cross_device(src, dst);
+            copy_into_place(src, dst)
         }
+        Err(e) => Err(io_context(src, e)),
     }
 }
 
+fn warn_cross_device(src: &Path, dst: &Path) {
+    tracing::warn!(
+        ?src,
+        ?dst,
+        "temp file is on another filesystem; copying into place"
+    );
+}
+
 pub(crate) fn partial_paths(dest: &Path, partial_dir: Option<&Path>) -> (PathBuf, Option<PathBuf>) {
     if let Some(dir) = partial_dir {
         let file = dest.file_name().unwrap_or_default();
@@ -217,17 +224,303 @@ pub fn fuzzy_match(dest: &Path) -> Option<PathBuf> {
     best.map(|(_, p)| p)
 }
 
-pub(crate) fn open_for_read(path: &Path, _opts: &SyncOptions) -> std::io::Result<File> {
+/// Opens `path` for reading, adding `O_NOATIME` and `O_DIRECT` on Linux when
+/// requested. `O_NOATIME` is refused with `EPERM` for files the caller does
+/// not own, in which case the file is opened without it.
+pub(crate) fn open_for_read(path: &Path, _opts: &SyncOptions) -> std::io::Result<SourceFile> {
     #[cfg(target_os = "linux")]
     {
-        use std::os::unix::fs::OpenOptionsExt;
-        if _opts.open_noatime {
-            let mut o = std::fs::OpenOptions::new();
-            o.read(true).custom_flags(libc::O_NOATIME);
-            if let Ok(f) = o.open(path) {
-                return Ok(f);
+        let noatime = if _opts.open_noatime {
+            libc::O_NOATIME
+        } else {
+            0
+        };
+        if _opts.direct_io {
+            let mut res = open_direct(path, noatime);
+            if noatime != 0 && matches!(&res, Err(err) if err.raw_os_error() == Some(libc::EPERM)) {
+                res = open_direct(path, 0);
             }
+            match res {
+                Ok(f) => return Ok(f),
+                Err(err) if err.raw_os_error() == Some(libc::EINVAL) => {
+                    tracing::warn!(?path, "O_DIRECT unsupported; using buffered reads");
+                }
+                Err(_) => {}
+            }
+        }
+        if noatime != 0
+            && let Ok(f) = open_with_flags(path, noatime)
+        {
+            return Ok(SourceFile::buffered(f));
+        }
+    }
+    File::open(path).map(SourceFile::buffered)
+}
+
+/// Size and alignment of the reads made on a file opened with `O_DIRECT`,
+/// which must cover the logical block size of the device.
+const DIRECT_BLOCK: usize = 4096;
+
+/// Number of blocks staged per `O_DIRECT` read.
+const DIRECT_BLOCKS_PER_READ: usize = 32;
+
+/// A file returned by [`open_for_read`]. Files opened with `O_DIRECT` are
+/// read through a block-aligned buffer, as the kernel rejects direct reads
+/// into unaligned memory or at unaligned offsets.
+pub(crate) struct SourceFile {
+    file: File,
+    direct: Option<DirectBuf>,
+}
+
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+struct DirectBuf {
+    buf: Vec<u8>,
+    align: usize,
+    start: u64,
+    len: usize,
+    pos: u64,
+}
+
+impl DirectBuf {
+    #[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+    fn new(blocks: usize) -> Self {
+        let buf = vec![0u8; (blocks + 1) * DIRECT_BLOCK];
+        let align = buf.as_ptr().align_offset(DIRECT_BLOCK);
+        Self {
+            buf,
+            align,
+            start: 0,
+            len: 0,
+            pos: 0,
         }
     }
-    File::open(path)
+
+    fn block_mut(&mut self) -> &mut [u8] {
+        let end = self.buf.len() - DIRECT_BLOCK + self.align;
+        &mut self.buf[self.align..end]
+    }
+
+    fn block(&self) -> &[u8] {
+        &self.buf[self.align..self.align + self.len]
+    }
+}
+
+impl SourceFile {
+    fn buffered(file: File) -> Self {
+        Self { file, direct: None }
+    }
+
+    pub(crate) fn metadata(&self) -> std::io::Result<fs::Metadata> {
+        self.file.metadata()
+    }
+
+    pub(crate) fn data_regions(&self) -> std::io::Result<Vec<(u64, u64)>> {
+        data_regions(&self.file)
+    }
+
+    /// Maps the file into memory, or `None` when it was opened with
+    /// `O_DIRECT` and must be read through the aligned buffer.
+    #[cfg(unix)]
+    pub(crate) fn map(&self) -> Option<std::io::Result<memmap2::Mmap>> {
+        if self.direct.is_some() {
+            return None;
+        }
+        // SAFETY: the mapping is only read from while `self` keeps the file open.
+        Some(unsafe { memmap2::Mmap::map(&self.file) })
+    }
+
+    /// Whether reads bypass the page cache.
+    #[cfg(test)]
+    pub(crate) fn is_direct(&self) -> bool {
+        self.direct.is_some()
+    }
+}
+
+impl Read for SourceFile {
+    fn read(&mut self, out: &mut [u8]) -> std::io::Result<usize> {
+        let Some(d) = self.direct.as_mut() else {
+            return self.file.read(out);
+        };
+        if d.pos < d.start || d.pos >= d.start + d.len as u64 {
+            let start = d.pos - d.pos % DIRECT_BLOCK as u64;
+            d.len = 0;
+            self.file.seek(SeekFrom::Start(start))?;
+            let n = self.file.read(d.block_mut())?;
+            d.start = start;
+            d.len = n;
+            if d.pos >= start + n as u64 {
+                return Ok(0);
+            }
+        }
+        let off = (d.pos - d.start) as usize;
+        let n = out.len().min(d.len - off);
+        out[..n].copy_from_slice(&d.block()[off..off + n]);
+        d.pos += n as u64;
+        Ok(n)
+    }
+}
+
+impl Seek for SourceFile {
+    fn seek(&mut self, from: SeekFrom) -> std::io::Result<u64> {
+        let Some(d) = self.direct.as_mut() else {
+            return self.file.seek(from);
+        };
+        let (base, delta) = match from {
+            SeekFrom::Start(pos) => {
+                d.pos = pos;
+                return Ok(pos);
+            }
+            SeekFrom::Current(delta) => (d.pos, delta),
+            SeekFrom::End(delta) => (self.file.metadata()?.len(), delta),
+        };
+        d.pos = base.checked_add_signed(delta).ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "invalid seek to a negative or overflowing position",
+            )
+        })?;
+        Ok(d.pos)
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn open_with_flags(path: &Path, flags: i32) -> std::io::Result<File> {
+    use std::os::unix::fs::OpenOptionsExt;
+    std::fs::OpenOptions::new()
+        .read(true)
+        .custom_flags(flags)
+        .open(path)
+}
+
+#[cfg(target_os = "linux")]
+fn open_direct(path: &Path, flags: i32) -> std::io::Result<SourceFile> {
+    let file = open_with_flags(path, flags | libc::O_DIRECT)?;
+    direct_read_probe(&file)?;
+    Ok(SourceFile {
+        file,
+        direct: Some(DirectBuf::new(DIRECT_BLOCKS_PER_READ)),
+    })
+}
+
+/// Reads the first block of `file` into an aligned buffer, so a filesystem
+/// that accepts `O_DIRECT` at open time but cannot serve direct reads is
+/// detected before the transfer starts.
+#[cfg(target_os = "linux")]
+fn direct_read_probe(file: &File) -> std::io::Result<()> {
+    use std::os::unix::fs::FileExt;
+    let mut probe = DirectBuf::new(1);
+    file.read_at(probe.block_mut(), 0).map(|_| ())
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+    use std::os::fd::AsRawFd;
+    use std::os::unix::fs::OpenOptionsExt;
+    use tempfile::tempdir;
+
+    fn fd_flags(file: &File) -> i32 {
+        let info = fs::read_to_string(format!("/proc/self/fdinfo/{}", file.as_raw_fd())).unwrap();
+        let flags = info
+            .lines()
+            .find_map(|l| l.strip_prefix("flags:"))
+            .unwrap()
+            .trim();
+        i32::from_str_radix(flags, 8).unwrap()
+    }
+
+    #[test]
+    fn direct_io_is_used_when_supported() {
+        let tmp = tempdir().unwrap();
+        let path = tmp.path().join("file");
+        let data: Vec<u8> = (0..3 * DIRECT_BLOCK + 123).map(|i| i as u8).collect();
+        fs::write(&path, &data).unwrap();
+        let supported = fs::OpenOptions::new()
+            .read(true)
+            .custom_flags(libc::O_DIRECT)
+            .open(&path)
+            .is_ok();
+        let opts = SyncOptions {
+            direct_io: true,
+            ..Default::default()
+        };
+        let mut file = open_for_read(&path, &opts).unwrap();
+        assert_eq!(fd_flags(&file.file) & libc::O_DIRECT != 0, supported);
+        assert_eq!(file.is_direct(), supported);
+
+        let mut read = Vec::new();
+        file.read_to_end(&mut read).unwrap();
+        assert_eq!(read, data);
+
+        let mut tail = [0u8; 10];
+        file.seek(SeekFrom::Start(DIRECT_BLOCK as u64 + 1)).unwrap();
+        file.read_exact(&mut tail).unwrap();
+        assert_eq!(tail, data[DIRECT_BLOCK + 1..DIRECT_BLOCK + 11]);
+        file.seek(SeekFrom::End(-3)).unwrap();
+        let mut end = Vec::new();
+        file.read_to_end(&mut end).unwrap();
+        assert_eq!(end, data[data.len() - 3..]);
+    }
+
+    #[test]
+    fn open_noatime_sets_flag_on_owned_files() {
+        let tmp = tempdir().unwrap();
+        let path = tmp.path().join("file");
+        fs::write(&path, b"data").unwrap();
+        let opts = SyncOptions {
+            open_noatime: true,
+            ..Default::default()
+        };
+        let file = open_for_read(&path, &opts).unwrap();
+        assert_ne!(fd_flags(&file.file) & libc::O_NOATIME, 0);
+        let file = open_for_read(&path, &SyncOptions::default()).unwrap();
+        assert_eq!(fd_flags(&file.file) & libc::O_NOATIME, 0);
+    }
+
+    #[test]
+    fn direct_io_disabled_by_default() {
+        let tmp = tempdir().unwrap();
+        let path = tmp.path().join("file");
+        fs::write(&path, b"data").unwrap();
+        let file = open_for_read(&path, &SyncOptions::default()).unwrap();
+        assert_eq!(fd_flags(&file.file) & libc::O_DIRECT, 0);
+    }
+
+    fn cross_device_rename(_from: &Path, _to: &Path) -> std::io::Result<()> {
+        Err(std::io::Error::from_raw_os_error(
+            nix::errno::Errno::EXDEV as i32,
+        ))
+    }
+
+    #[test]
+    fn rename_falls_back_to_copy_across_filesystems() {
+        let tmp = tempdir().unwrap();
+        let temp_dir = tmp.path().join("tmp");
+        let dest_dir = tmp.path().join("dest");
+        fs::create_dir_all(&temp_dir).unwrap();
+        fs::create_dir_all(&dest_dir).unwrap();
+        let src = temp_dir.join(".file.XXXXXX");
+        let dst = dest_dir.join("file");
+        fs::write(&src, b"new contents").unwrap();
+        fs::write(&dst, b"old").unwrap();
+
+        atomic_rename_with(&src, &dst, cross_device_rename).unwrap();
+
+        assert_eq!(fs::read(&dst).unwrap(), b"new contents");
+        assert!(!src.exists());
+        assert_eq!(fs::read_dir(&dest_dir).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn rename_reports_other_errors() {
+        let tmp = tempdir().unwrap();
+        let src = tmp.path().join("src");
+        fs::write(&src, b"data").unwrap();
+        let err = atomic_rename_with(&src, &tmp.path().join("dst"), |_, _| {
+            Err(std::io::Error::from(std::io::ErrorKind::PermissionDenied))
+        })
+        .unwrap_err();
+        assert!(err.to_string().contains("src"));
+        assert!(src.exists());
+    }
 }
@@ -1,16 +1,17 @@
 // crates/engine/src/delta.rs
 
 use std::any::Any;
-use std::collections::{HashMap, VecDeque};
+use std::borrow::Cow;
+use std::collections::VecDeque;
 use std::fs::File;
 use std::io::{Read, Seek, SeekFrom, Write};
-use std::path::Path;
-use std::time::Duration;
 
 use checksums::ChecksumConfig;
-use logging::{InfoFlag, Observer, progress_formatter, rate_formatter};
-use std::sync::Arc;
 
+use crate::block::{BLOCK_ENTRY_COST, BlockTable, block_count, capped_block_size};
+use crate::cdc::{CDC_MAX_CHUNK, ChunkIndex, Chunker};
+use crate::pool::BufferPool;
+use crate::progress::Progress;
 use crate::{EngineError, Result, SyncOptions, ensure_max_alloc};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -23,30 +24,64 @@ pub(crate) const DEFAULT_BASIS_WINDOW: usize = 8 * 1024;
 pub(crate) const LIT_CAP: usize = 1 << 20;
 
 pub struct DeltaIter<'a, R: Read + Seek> {
-    cfg: &'a ChecksumConfig,
-    target: &'a mut R,
+    pub(crate) cfg: &'a ChecksumConfig,
+    pub(crate) target: &'a mut R,
     block_size: usize,
-    map: HashMap<u32, Vec<(Vec<u8>, usize, usize)>>,
-    lit: Vec<u8>,
+    table: Cow<'a, BlockTable>,
+    pub(crate) lit: Vec<u8>,
     window: VecDeque<u8>,
-    byte: [u8; 1],
-    done: bool,
+    pub(crate) byte: [u8; 1],
+    pub(crate) done: bool,
+    pub(crate) inplace: bool,
+    pub(crate) pos: usize,
+    pub(crate) cdc: Option<ChunkIndex>,
+    pub(crate) chunker: Chunker,
+    pub(crate) chunk: Vec<u8>,
+    pub(crate) pending: Option<Op>,
+    holes: VecDeque<(usize, usize)>,
+    pool: Option<BufferPool>,
 }
 
 impl<'a, R: Read + Seek> Iterator for DeltaIter<'a, R> {
     type Item = Result<Op>;
 
     fn next(&mut self) -> Option<Self::Item> {
+        if self.cdc.is_some() {
+            return self.next_chunk();
+        }
         loop {
             if self.window.is_empty() {
+                let at = self.pos + self.lit.len();
+                if let Some(&(start, end)) = self.holes.front()
+                    && at >= start
+                {
+                    if !self.lit.is_empty() {
+                        return Some(Ok(self.take_lit()));
+                    }
+                    self.holes.pop_front();
+                    if end > at {
+                        if let Err(e) = self.target.seek(SeekFrom::Start(end as u64)) {
+                            return Some(Err(e.into()));
+                        }
+                        self.pos = end;
+                        return Some(Ok(Op::Copy {
+                            offset: at,
+                            len: end - at,
+                        }));
+                    }
+                    continue;
+                }
                 if self.done {
                     if self.lit.is_empty() {
                         return None;
                     } else {
-                        return Some(Ok(Op::Data(std::mem::take(&mut self.lit))));
+                        return Some(Ok(self.take_lit()));
                     }
                 }
-                while self.window.len() < self.block_size {
+                let limit = self.holes.front().map_or(self.block_size, |&(start, _)| {
+                    self.block_size.min(start - at)
+                });
+                while self.window.len() < limit {
                     match self.target.read(&mut self.byte) {
                         Ok(0) => {
                             self.done = true;
@@ -60,7 +95,7 @@ impl<'a, R: Read + Seek> Iterator for DeltaIter<'a, R> {
                     if self.lit.is_empty() {
                         return None;
                     } else {
-                        return Some(Ok(Op::Data(std::mem::take(&mut self.lit))));
+                        return Some(Ok(self.take_lit()));
                     }
                 }
             }
@@ -68,18 +103,25 @@ impl<'a, R: Read + Seek> Iterator for DeltaIter<'a, R> {
             let len = usize::min(self.window.len(), self.block_size);
             self.window.make_contiguous();
             let sum = self.cfg.checksum(&self.window.as_slices().0[..len]);
-            if let Some(candidates) = self.map.get(&sum.weak) {
+            let min_off = if self.inplace {
+                self.pos + self.lit.len()
+            } else {
+                0
+            };
+            if let Some(candidates) = self.table.map.get(&sum.weak) {
                 if let Some((_, off, blen)) = candidates
                     .iter()
-                    .find(|(s, _, l)| *s == sum.strong && *l == len)
+                    .find(|(s, o, l)| *s == sum.strong && *l == len && *o >= min_off)
                 {
                     if !self.lit.is_empty() {
-                        return Some(Ok(Op::Data(std::mem::take(&mut self.lit))));
+                        return Some(Ok(self.take_lit()));
                     }
+                    let (off, blen) = (*off, *blen);
                     self.window.drain(..len);
+                    self.pos += blen;
                     return Some(Ok(Op::Copy {
-                        offset: *off,
-                        len: *blen,
+                        offset: off,
+                        len: blen,
                     }));
                 }
             }
@@ -87,16 +129,65 @@ impl<'a, R: Read + Seek> Iterator for DeltaIter<'a, R> {
             if let Some(b) = self.window.pop_front() {
                 self.lit.push(b);
                 if self.lit.len() >= LIT_CAP {
-                    return Some(Ok(Op::Data(std::mem::take(&mut self.lit))));
+                    return Some(Ok(self.take_lit()));
                 }
             }
             if self.done && self.window.is_empty() {
-                return Some(Ok(Op::Data(std::mem::take(&mut self.lit))));
+                return Some(Ok(self.take_lit()));
+            }
+        }
+    }
+}
+
+impl<R: Read + Seek> DeltaIter<'_, R> {
+    /// Emits in-place copies for `holes` the basis shares with the target,
+    /// so the target is never read there. Only fixed-block deltas use this.
+    pub(crate) fn skip_holes(mut self, holes: Vec<(u64, u64)>) -> Self {
+        if self.cdc.is_none() && self.inplace {
+            self.holes = holes
+                .into_iter()
+                .map(|(start, end)| (start as usize, end as usize))
+                .collect();
+        }
+        self
+    }
+
+    /// Accumulates literals in buffers drawn from `pool`. Full literals hand
+    /// their buffer to the caller, who can [`BufferPool::put`] it back once
+    /// the op is consumed; shorter ones are copied out so the pooled buffer
+    /// keeps collecting the next literal.
+    pub fn with_pool(mut self, pool: BufferPool) -> Self {
+        let mut buf = pool.get();
+        buf.append(&mut self.lit);
+        self.lit = buf;
+        self.pool = Some(pool);
+        self
+    }
+
+    pub(crate) fn take_lit(&mut self) -> Op {
+        self.pos += self.lit.len();
+        match &self.pool {
+            Some(pool) if self.lit.len() >= LIT_CAP => {
+                Op::Data(std::mem::replace(&mut self.lit, pool.get()))
             }
+            Some(_) => {
+                let data = self.lit.to_vec();
+                self.lit.clear();
+                Op::Data(data)
+            }
+            None => Op::Data(std::mem::take(&mut self.lit)),
         }
     }
 }
 
+fn block_cap(opts: &SyncOptions) -> usize {
+    if opts.max_blocks != 0 {
+        opts.max_blocks
+    } else {
+        opts.max_alloc / BLOCK_ENTRY_COST
+    }
+}
+
 pub fn compute_delta<'a, R1: Read + Seek, R2: Read + Seek>(
     cfg: &'a ChecksumConfig,
     basis: &mut R1,
@@ -105,58 +196,108 @@ pub fn compute_delta<'a, R1: Read + Seek, R2: Read + Seek>(
     basis_window: usize,
     opts: &SyncOptions,
 ) -> Result<DeltaIter<'a, R2>> {
-    let block_size = block_size.max(1);
-    ensure_max_alloc(block_size as u64, opts)?;
-    basis.seek(SeekFrom::Start(0))?;
-    target.seek(SeekFrom::Start(0))?;
-    let mut map: HashMap<u32, Vec<(Vec<u8>, usize, usize)>> = HashMap::new();
-    let mut order: VecDeque<(u32, Vec<u8>, usize, usize)> = VecDeque::new();
-    let mut off = 0usize;
-    let mut buf = vec![0u8; block_size];
-    loop {
-        let n = basis.read(&mut buf)?;
-        if n == 0 {
-            break;
-        }
-        let sum = cfg.checksum(&buf[..n]);
-        map.entry(sum.weak)
-            .or_default()
-            .push((sum.strong.clone(), off, n));
-        order.push_back((sum.weak, sum.strong, off, n));
-        if order.len() > basis_window {
-            if let Some((w, s, o, l)) = order.pop_front() {
-                if let Some(v) = map.get_mut(&w) {
-                    if let Some(pos) = v
-                        .iter()
-                        .position(|(ss, oo, ll)| *oo == o && *ll == l && *ss == s)
-                    {
-                        v.remove(pos);
-                    }
-                    if v.is_empty() {
-                        map.remove(&w);
-                    }
-                }
-            }
-        }
-        off += n;
-        if n < block_size {
-            break;
-        }
+    if opts.cdc {
+        ensure_max_alloc(CDC_MAX_CHUNK as u64, opts)?;
+        let index = ChunkIndex::build(cfg, basis)?;
+        let mut iter = delta_iter(cfg, Cow::Owned(BlockTable::default()), target, opts)?;
+        iter.cdc = Some(index);
+        return Ok(iter);
     }
+    let basis_len = basis.seek(SeekFrom::End(0))?;
+    let block_size = capped_block_size(basis_len, block_size, block_cap(opts));
+    block_count(basis_len, block_size)?;
+    ensure_max_alloc(block_size as u64, opts)?;
+    let table = BlockTable::build_windowed(cfg, basis, block_size, basis_window)?;
+    delta_iter(cfg, Cow::Owned(table), target, opts)
+}
+
+pub fn compute_delta_with_table<'a, R: Read + Seek>(
+    cfg: &'a ChecksumConfig,
+    table: &'a BlockTable,
+    target: &'a mut R,
+    opts: &SyncOptions,
+) -> Result<DeltaIter<'a, R>> {
+    delta_iter(cfg, Cow::Borrowed(table), target, opts)
+}
 
+fn delta_iter<'a, R: Read + Seek>(
+    cfg: &'a ChecksumConfig,
+    table: Cow<'a, BlockTable>,
+    target: &'a mut R,
+    opts: &SyncOptions,
+) -> Result<DeltaIter<'a, R>> {
+    let block_size = table.block_size().max(1);
+    ensure_max_alloc(block_size as u64, opts)?;
+    target.seek(SeekFrom::Start(0))?;
     Ok(DeltaIter {
         cfg,
         target,
         block_size,
-        map,
+        table,
         lit: Vec::new(),
         window: VecDeque::new(),
         byte: [0u8; 1],
         done: false,
+        inplace: opts.inplace,
+        pos: 0,
+        cdc: None,
+        chunker: Chunker::default(),
+        chunk: Vec::new(),
+        pending: None,
+        holes: VecDeque::new(),
+        pool: None,
     })
 }
 
-fn write_sparse(file: &mut File, data: &[u8]) -> Result<()> {
+impl<R: Read + Seek> Drop for DeltaIter<'_, R> {
+    fn drop(&mut self) {
+        if let Some(pool) = &self.pool {
+            pool.put(std::mem::take(&mut self.lit));
+        }
+    }
+}
+
+fn punch_hole(file: &File, offset: u64, len: u64) -> std::io::Result<()> {
+    #[cfg(all(unix, any(target_os = "linux", target_os = "android")))]
+    {
+        use nix::fcntl::{FallocateFlags, fallocate};
+
+        fallocate(
+            file,
+            FallocateFlags::FALLOC_FL_PUNCH_HOLE | FallocateFlags::FALLOC_FL_KEEP_SIZE,
+            offset as i64,
+            len as i64,
+        )
+        .map_err(std::io::Error::from)
+    }
+    #[cfg(not(all(unix, any(target_os = "linux", target_os = "android"))))]
+    {
+        let _ = (file, offset, len);
+        Err(std::io::Error::from(std::io::ErrorKind::Unsupported))
+    }
+}
+
+fn write_zeros(file: &mut File, len: usize) -> Result<()> {
+    let zeros = [0u8; 8192];
+    let mut remaining = len;
+    while remaining > 0 {
+        let n = remaining.min(zeros.len());
+        file.write_all(&zeros[..n])?;
+        remaining -= n;
+    }
+    Ok(())
+}
+
+fn write_sparse(file: &mut File, data: &[u8], must_zero: bool) -> Result<()> {
+    write_sparse_with(file, data, must_zero, punch_hole)
+}
+
+fn write_sparse_with(
+    file: &mut File,
+    data: &[u8],
+    must_zero: bool,
+    punch: fn(&File, u64, u64) -> std::io::Result<()>,
+) -> Result<()> {
     let mut i = 0;
     while i < data.len() {
         if data[i] == 0 {
@@ -165,19 +306,12 @@ fn write_sparse(file: &mut File, data: &[u8]) -> Result<()> {
                 i += 1;
             }
             let len = i - start;
-            #[cfg(all(unix, any(target_os = "linux", target_os = "android")))]
-            {
-                use nix::fcntl::{FallocateFlags, fallocate};
-
-                let offset = file.stream_position()?;
-                let _ = fallocate(
-                    &*file,
-                    FallocateFlags::FALLOC_FL_PUNCH_HOLE | FallocateFlags::FALLOC_FL_KEEP_SIZE,
-                    offset as i64,
-                    len as i64,
-                );
+            let offset = file.stream_position()?;
+            if punch(file, offset, len as u64).is_err() && must_zero {
+                write_zeros(file, len)?;
+            } else {
+                file.seek(SeekFrom::Current(len as i64))?;
             }
-            file.seek(SeekFrom::Current(len as i64))?;
         } else {
             let start = i;
             while i < data.len() && data[i] != 0 {
@@ -189,125 +323,6 @@ fn write_sparse(file: &mut File, data: &[u8]) -> Result<()> {
     Ok(())
 }
 
-use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
-
-pub(crate) struct Progress {
-    total: u64,
-    written: u64,
-    start: std::time::Instant,
-    last_print: std::time::Instant,
-    human_readable: bool,
-    quiet: bool,
-    file_idx: usize,
-    sink: Arc<dyn Observer>,
-}
-
-pub(crate) static TOTAL_FILES: AtomicUsize = AtomicUsize::new(0);
-pub(crate) static FILE_COUNTER: AtomicUsize = AtomicUsize::new(0);
-pub(crate) static PROGRESS_HEADER: AtomicBool = AtomicBool::new(false);
-
-const PROGRESS_UPDATE_INTERVAL: Duration = Duration::from_secs(1);
-
-impl Progress {
-    pub(crate) fn new(
-        dest: &Path,
-        total: u64,
-        human_readable: bool,
-        initial: u64,
-        quiet: bool,
-        sink: Arc<dyn Observer>,
-    ) -> Self {
-        if !quiet {
-            use std::io::Write as _;
-            if !PROGRESS_HEADER.swap(true, Ordering::SeqCst) {
-                println!("sending incremental file list");
-            }
-            if let Some(name) = dest.file_name() {
-                println!("{}", name.to_string_lossy());
-            } else {
-                println!("{}", dest.display());
-            }
-            let _ = std::io::stdout().flush();
-        }
-        sink.start_file(dest, total, initial);
-        let now = std::time::Instant::now();
-        let idx = FILE_COUNTER.fetch_add(1, Ordering::SeqCst) + 1;
-        Self {
-            total,
-            written: initial,
-            start: now,
-            last_print: now - PROGRESS_UPDATE_INTERVAL,
-            human_readable,
-            quiet,
-            file_idx: idx,
-            sink,
-        }
-    }
-
-    fn add(&mut self, bytes: u64) {
-        self.written += bytes;
-        self.sink.update(self.written);
-        if !self.quiet
-            && self.last_print.elapsed() >= PROGRESS_UPDATE_INTERVAL
-            && self.written < self.total
-        {
-            self.print(false);
-            self.last_print = std::time::Instant::now();
-        }
-    }
-
-    pub(crate) fn finish(&mut self) {
-        self.sink.finish_file();
-        if !self.quiet {
-            self.print(true);
-        }
-    }
-
-    fn print(&self, done: bool) {
-        if self.quiet {
-            return;
-        }
-        use std::io::Write as _;
-        let bytes = progress_formatter(self.written, self.human_readable);
-        let percent = if self.total == 0 {
-            100
-        } else {
-            self.written * 100 / self.total
-        };
-        let elapsed = self.start.elapsed().as_secs_f64();
-        let rate_val = if elapsed > 0.0 {
-            self.written as f64 / elapsed
-        } else {
-            0.0
-        };
-        let rate = rate_formatter(rate_val);
-        let secs = self.start.elapsed().as_secs();
-        let h = secs / 3600;
-        let m = (secs % 3600) / 60;
-        let s = secs % 60;
-        let time = format!("{:02}:{:02}:{:02}", h, m, s);
-        let total_files = TOTAL_FILES.load(Ordering::SeqCst);
-        let remaining = total_files.saturating_sub(self.file_idx);
-        tracing::info!(
-            target: InfoFlag::Progress.target(),
-            written = self.written,
-            total = self.total,
-            percent,
-            rate = rate.as_str()
-        );
-        let line = format!(
-            "\r{:>15} {:>3}% {} {} (xfr#{}, to-chk={}/{})",
-            bytes, percent, rate, time, self.file_idx, remaining, total_files
-        );
-        if done {
-            println!("{line}");
-        } else {
-            print!("{line}");
-            let _ = std::io::stdout().flush();
-        }
-    }
-}
-
 fn apply_op_plain<R: Read + Seek, W: Write + Seek>(
     basis: &mut R,
     op: Op,
@@ -345,10 +360,15 @@ fn apply_op_inplace<R: Read + Seek>(
     out: &mut File,
     buf: &mut [u8],
     progress: &mut Option<Progress>,
+    sparse: bool,
 ) -> Result<()> {
     match op {
         Op::Data(d) => {
-            out.write_all(&d)?;
+            if sparse {
+                write_sparse(out, &d, true)?;
+            } else {
+                out.write_all(&d)?;
+            }
             if let Some(p) = progress {
                 p.add(d.len() as u64);
             }
@@ -366,7 +386,11 @@ fn apply_op_inplace<R: Read + Seek>(
                 while remaining > 0 {
                     let to_read = remaining.min(buf.len());
                     basis.read_exact(&mut buf[..to_read])?;
-                    out.write_all(&buf[..to_read])?;
+                    if sparse {
+                        write_sparse(out, &buf[..to_read], true)?;
+                    } else {
+                        out.write_all(&buf[..to_read])?;
+                    }
                     remaining -= to_read;
                     if let Some(p) = progress {
                         p.add(to_read as u64);
@@ -387,7 +411,7 @@ fn apply_op_sparse<R: Read + Seek>(
 ) -> Result<()> {
     match op {
         Op::Data(d) => {
-            write_sparse(out, &d)?;
+            write_sparse(out, &d, false)?;
             if let Some(p) = progress {
                 p.add(d.len() as u64);
             }
@@ -398,7 +422,7 @@ fn apply_op_sparse<R: Read + Seek>(
             while remaining > 0 {
                 let to_read = remaining.min(buf.len());
                 basis.read_exact(&mut buf[..to_read])?;
-                write_sparse(out, &buf[..to_read])?;
+                write_sparse(out, &buf[..to_read], false)?;
                 remaining -= to_read;
                 if let Some(p) = progress {
                     p.add(to_read as u64);
@@ -459,7 +483,7 @@ where
         for op in ops {
             let op = op?;
             if let Some(op) = adjust(op) {
-                apply_op_inplace(basis, op, file, &mut buf, progress)?;
+                apply_op_inplace(basis, op, file, &mut buf, progress, opts.sparse)?;
             }
         }
     } else if opts.sparse {
@@ -484,3 +508,38 @@ where
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+    use tempfile::tempfile;
+
+    fn reject_punch(_file: &File, _offset: u64, _len: u64) -> std::io::Result<()> {
+        Err(std::io::Error::from(std::io::ErrorKind::Unsupported))
+    }
+
+    #[test]
+    fn write_sparse_zero_fills_when_punch_fails() {
+        let mut file = tempfile().unwrap();
+        file.write_all(&[0xAA; 64]).unwrap();
+        file.seek(SeekFrom::Start(0)).unwrap();
+        let mut data = vec![0u8; 64];
+        data[0] = 1;
+        data[63] = 2;
+        write_sparse_with(&mut file, &data, true, reject_punch).unwrap();
+        assert_eq!(file.stream_position().unwrap(), 64);
+        let mut out = Vec::new();
+        file.seek(SeekFrom::Start(0)).unwrap();
+        file.read_to_end(&mut out).unwrap();
+        assert_eq!(out, data);
+    }
+
+    #[test]
+    fn write_sparse_skips_zeros_when_not_required() {
+        let mut file = tempfile().unwrap();
+        write_sparse_with(&mut file, &[0u8; 64], false, reject_punch).unwrap();
+        assert_eq!(file.stream_position().unwrap(), 64);
+        assert_eq!(file.metadata().unwrap().len(), 0);
+    }
+}
@@ -4,7 +4,16 @@ use filelist::{Decoder, Encoder, Entry};
 use protocol::CharsetConv;
 
 pub fn encode(entries: &[Entry], iconv: Option<&CharsetConv>) -> Vec<Vec<u8>> {
-    let mut enc = Encoder::new();
+    encode_with(Encoder::new(), entries, iconv)
+}
+
+/// Encodes `entries` along with the owner and group names of each uid/gid,
+/// as sent when `--numeric-ids` is off.
+pub fn encode_with_names(entries: &[Entry], iconv: Option<&CharsetConv>) -> Vec<Vec<u8>> {
+    encode_with(Encoder::with_names(), entries, iconv)
+}
+
+fn encode_with(mut enc: Encoder, entries: &[Entry], iconv: Option<&CharsetConv>) -> Vec<Vec<u8>> {
     entries
         .iter()
         .map(|e| {
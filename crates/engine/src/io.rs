@@ -3,9 +3,14 @@
 #![doc = include_str!("docs/io.md")]
 
 use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
 use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use checksums::{ChecksumConfig, StrongChecksum};
 
 use crate::EngineError;
+use crate::cleanup::SourceFile;
 
 pub fn io_context(path: &Path, err: std::io::Error) -> EngineError {
     EngineError::Io(std::io::Error::new(
@@ -112,6 +117,313 @@ pub fn preallocate(_file: &File, _len: u64) -> std::io::Result<()> {
     Ok(())
 }
 
+/// Preallocates `len` bytes, treating filesystems without preallocation
+/// support as a no-op so the transfer continues with sparse writes.
+pub(crate) fn try_preallocate(file: &File, len: u64) -> std::io::Result<bool> {
+    if len == 0 {
+        return Ok(false);
+    }
+    match preallocate(file, len) {
+        Ok(()) => Ok(true),
+        Err(err) if preallocate_unsupported(&err) => Ok(false),
+        Err(err) => Err(err),
+    }
+}
+
+#[cfg(unix)]
+pub(crate) type AppendLock = nix::fcntl::Flock<File>;
+#[cfg(not(unix))]
+pub(crate) type AppendLock = ();
+
+/// Takes an exclusive advisory lock on the file an `--append` transfer
+/// extends, so concurrent appenders to the same destination run one after
+/// another instead of interleaving their writes.
+#[cfg(unix)]
+pub(crate) fn lock_for_append(path: &Path) -> std::io::Result<AppendLock> {
+    use nix::fcntl::{Flock, FlockArg};
+    let file = File::open(path)?;
+    Flock::lock(file, FlockArg::LockExclusive).map_err(|(_, e)| std::io::Error::from(e))
+}
+
+#[cfg(not(unix))]
+pub(crate) fn lock_for_append(_path: &Path) -> std::io::Result<AppendLock> {
+    Ok(())
+}
+
+/// Size of the buffer whole-file checksums read through.
+const HASH_BUF_SIZE: usize = 128 * 1024;
+
+/// Files at least this large are hashed through a memory map.
+pub(crate) const MMAP_HASH_THRESHOLD: u64 = 4 * 1024 * 1024;
+
+/// Feeds the contents of `file` to `f`. Files of at least `mmap_threshold`
+/// bytes are mapped and passed as one slice; smaller files, files opened with
+/// `O_DIRECT` and files that fail to map are read one buffer at a time.
+#[cfg_attr(not(unix), allow(unused_variables))]
+pub(crate) fn for_each_chunk(
+    mut file: SourceFile,
+    mmap_threshold: u64,
+    mut f: impl FnMut(&[u8]),
+) -> std::io::Result<()> {
+    #[cfg(unix)]
+    if file.metadata()?.len() >= mmap_threshold
+        && let Some(map) = file.map()
+    {
+        match map {
+            Ok(map) => {
+                f(&map);
+                return Ok(());
+            }
+            Err(e) => tracing::debug!("mmap failed ({e}); falling back to buffered reads"),
+        }
+    }
+    let mut buf = vec![0u8; HASH_BUF_SIZE];
+    loop {
+        let n = match file.read(&mut buf) {
+            Ok(0) => return Ok(()),
+            Ok(n) => n,
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+        f(&buf[..n]);
+    }
+}
+
+/// Returns the `[start, end)` ranges of `file` that hold data, as reported by
+/// `SEEK_DATA`/`SEEK_HOLE`. Without hole reporting the whole file is one range.
+pub(crate) fn data_regions(file: &File) -> std::io::Result<Vec<(u64, u64)>> {
+    let len = file.metadata()?.len();
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    {
+        use nix::errno::Errno;
+        use nix::unistd::{Whence, lseek};
+
+        let saved = lseek(file, 0, Whence::SeekCur)?;
+        let mut regions = Vec::new();
+        let mut off = 0i64;
+        let res = loop {
+            let start = match lseek(file, off, Whence::SeekData) {
+                Ok(start) => start,
+                Err(Errno::ENXIO) => break Ok(()),
+                Err(e) => break Err(e),
+            };
+            let end = match lseek(file, start, Whence::SeekHole) {
+                Ok(end) => end,
+                Err(e) => break Err(e),
+            };
+            regions.push((start as u64, end as u64));
+            off = end;
+        };
+        lseek(file, saved, Whence::SeekSet)?;
+        match res {
+            Ok(()) => Ok(regions),
+            Err(Errno::EINVAL) => Ok(vec![(0, len)]),
+            Err(e) => Err(e.into()),
+        }
+    }
+    #[cfg(not(any(target_os = "linux", target_os = "android")))]
+    {
+        Ok(vec![(0, len)])
+    }
+}
+
+/// Ranges below `len` that are holes in both `a` and `b`, given their data
+/// regions.
+pub(crate) fn common_holes(a: &[(u64, u64)], b: &[(u64, u64)], len: u64) -> Vec<(u64, u64)> {
+    let mut data: Vec<(u64, u64)> = a.iter().chain(b).copied().collect();
+    data.sort_unstable();
+    let mut holes = Vec::new();
+    let mut pos = 0;
+    for (start, end) in data {
+        if start >= len {
+            break;
+        }
+        if start > pos {
+            holes.push((pos, start));
+        }
+        pos = pos.max(end);
+    }
+    if pos < len {
+        holes.push((pos, len));
+    }
+    holes
+}
+
+/// Reads `inner` like a sparse file whose data lies only in `regions`,
+/// returning zeros for holes without reading them.
+pub(crate) struct HoleReader<R> {
+    inner: R,
+    regions: Vec<(u64, u64)>,
+    len: u64,
+    pos: u64,
+}
+
+impl<R: Read + Seek> HoleReader<R> {
+    pub(crate) fn new(inner: R, regions: Vec<(u64, u64)>, len: u64) -> Self {
+        Self {
+            inner,
+            regions,
+            len,
+            pos: 0,
+        }
+    }
+}
+
+impl<R: Read + Seek> Read for HoleReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if self.pos >= self.len || buf.is_empty() {
+            return Ok(0);
+        }
+        let next = self.regions.iter().find(|&&(_, end)| end > self.pos);
+        let n = match next {
+            Some(&(start, end)) if start <= self.pos => {
+                let want = buf.len().min((end - self.pos) as usize);
+                self.inner.seek(SeekFrom::Start(self.pos))?;
+                self.inner.read(&mut buf[..want])?
+            }
+            next => {
+                let hole_end = next.map_or(self.len, |&(start, _)| start);
+                let n = buf.len().min((hole_end - self.pos) as usize);
+                buf[..n].fill(0);
+                n
+            }
+        };
+        self.pos += n as u64;
+        Ok(n)
+    }
+}
+
+impl<R: Read + Seek> Seek for HoleReader<R> {
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        let new = match pos {
+            SeekFrom::Start(off) => Some(off),
+            SeekFrom::End(off) => self.len.checked_add_signed(off),
+            SeekFrom::Current(off) => self.pos.checked_add_signed(off),
+        };
+        self.pos = new
+            .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::InvalidInput, "invalid seek"))?;
+        Ok(self.pos)
+    }
+}
+
+/// Whole-file checksum of `path` under the transfer's file checksum
+/// settings.
+pub(crate) fn file_strong_sum(cfg: &ChecksumConfig, path: &Path) -> crate::Result<Vec<u8>> {
+    let mut file = File::open(path).map_err(|e| io_context(path, e))?;
+    let mut hasher = cfg.file_hasher();
+    let mut buf = [0u8; 8192];
+    loop {
+        let n = file.read(&mut buf).map_err(|e| io_context(path, e))?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+    }
+    Ok(hasher.finalize())
+}
+
+/// Whole-file checksum of the data the sender read for a file, filled in
+/// once the source has been read to the end.
+pub type SentSum = Arc<Mutex<Option<Vec<u8>>>>;
+
+/// Hashes `inner` as it is read, publishing the digest to a [`SentSum`] at
+/// end of file. Bytes skipped by a forward seek are read and hashed too, and
+/// a backward seek starts the digest over, so it always covers the file from
+/// its first byte. Without a `SentSum` it only passes reads through.
+pub(crate) struct SumReader<R> {
+    inner: R,
+    cfg: ChecksumConfig,
+    hasher: Option<Box<dyn StrongChecksum>>,
+    pos: u64,
+    sum: Option<SentSum>,
+}
+
+impl<R: Read + Seek> SumReader<R> {
+    pub(crate) fn new(inner: R, cfg: &ChecksumConfig, sum: Option<SentSum>) -> Self {
+        Self {
+            inner,
+            cfg: cfg.clone(),
+            hasher: sum.as_ref().map(|_| cfg.file_hasher()),
+            pos: 0,
+            sum,
+        }
+    }
+
+    pub(crate) fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    fn restart(&mut self, sum: &SentSum) -> std::io::Result<()> {
+        self.inner.seek(SeekFrom::Start(0))?;
+        self.hasher = Some(self.cfg.file_hasher());
+        self.pos = 0;
+        *sum.lock().unwrap() = None;
+        Ok(())
+    }
+}
+
+impl<R: Read + Seek> Read for SumReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.pos += n as u64;
+        if let Some(sum) = &self.sum {
+            if n > 0 {
+                if let Some(hasher) = self.hasher.as_mut() {
+                    hasher.update(&buf[..n]);
+                }
+            } else if !buf.is_empty()
+                && let Some(hasher) = self.hasher.take()
+            {
+                *sum.lock().unwrap() = Some(hasher.finalize());
+            }
+        }
+        Ok(n)
+    }
+}
+
+impl<R: Read + Seek> Seek for SumReader<R> {
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        let Some(sum) = self.sum.clone() else {
+            self.pos = self.inner.seek(pos)?;
+            return Ok(self.pos);
+        };
+        let target = match pos {
+            SeekFrom::Start(off) => off,
+            _ => {
+                let end = self.inner.seek(pos)?;
+                self.inner.seek(SeekFrom::Start(self.pos))?;
+                end
+            }
+        };
+        if target == self.pos {
+            return Ok(self.pos);
+        }
+        if target < self.pos || self.hasher.is_none() {
+            self.restart(&sum)?;
+        }
+        let skip = target - self.pos;
+        std::io::copy(&mut (&mut *self).take(skip), &mut std::io::sink())?;
+        Ok(self.pos)
+    }
+}
+
+fn preallocate_unsupported(err: &std::io::Error) -> bool {
+    if err.kind() == std::io::ErrorKind::Unsupported {
+        return true;
+    }
+    #[cfg(unix)]
+    {
+        matches!(
+            err.raw_os_error(),
+            Some(libc::EOPNOTSUPP) | Some(libc::ENOSYS)
+        )
+    }
+    #[cfg(not(unix))]
+    {
+        false
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -128,5 +440,77 @@ mod tests {
         let file = std::fs::OpenOptions::new().read(true).open(&path).unwrap();
         let err = preallocate(&file, 1).unwrap_err();
         assert_eq!(err.raw_os_error(), Some(libc::EBADF));
+        assert!(try_preallocate(&file, 1).is_err());
+    }
+
+    #[test]
+    fn common_holes_exclude_data_from_either_side() {
+        let a = [(0, 10), (50, 60)];
+        let b = [(20, 30), (55, 70)];
+        assert_eq!(
+            common_holes(&a, &b, 100),
+            vec![(10, 20), (30, 50), (70, 100)]
+        );
+        assert_eq!(common_holes(&a, &b, 40), vec![(10, 20), (30, 40)]);
+        assert!(common_holes(&[(0, 100)], &[], 100).is_empty());
+    }
+
+    #[test]
+    fn hole_reader_zero_fills_holes() {
+        let data: Vec<u8> = (1..=40).collect();
+        let mut reader = HoleReader::new(std::io::Cursor::new(data.clone()), vec![(10, 20)], 40);
+        let mut out = Vec::new();
+        reader.read_to_end(&mut out).unwrap();
+        let mut expected = vec![0u8; 40];
+        expected[10..20].copy_from_slice(&data[10..20]);
+        assert_eq!(out, expected);
+        reader.seek(SeekFrom::Start(15)).unwrap();
+        let mut buf = [0u8; 3];
+        reader.read_exact(&mut buf).unwrap();
+        assert_eq!(buf, [16, 17, 18]);
+    }
+
+    #[test]
+    fn mmap_and_buffered_digests_match() {
+        use crate::SyncOptions;
+        use crate::cleanup::open_for_read;
+        use md5::{Digest, Md5};
+        use tempfile::tempdir;
+
+        let tmp = tempdir().unwrap();
+        let path = tmp.path().join("file");
+        let data: Vec<u8> = (0..MMAP_HASH_THRESHOLD as u32 + 3 * HASH_BUF_SIZE as u32 + 17)
+            .map(|i| (i.wrapping_mul(2_654_435_761) >> 13) as u8)
+            .collect();
+        std::fs::write(&path, &data).unwrap();
+
+        let digest = |threshold| {
+            let file = open_for_read(&path, &SyncOptions::default()).unwrap();
+            let mut hasher = Md5::new();
+            let mut chunks = 0;
+            for_each_chunk(file, threshold, |b| {
+                hasher.update(b);
+                chunks += 1;
+            })
+            .unwrap();
+            (hasher.finalize().to_vec(), chunks)
+        };
+        let (mapped, mapped_chunks) = digest(MMAP_HASH_THRESHOLD);
+        let (buffered, buffered_chunks) = digest(data.len() as u64 + 1);
+        assert_eq!(mapped, buffered);
+        assert_eq!(mapped, Md5::digest(&data).to_vec());
+        assert!(buffered_chunks > 1);
+        if cfg!(unix) {
+            assert_eq!(mapped_chunks, 1);
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn unsupported_preallocation_is_not_an_error() {
+        let err = std::io::Error::from_raw_os_error(libc::EOPNOTSUPP);
+        assert!(preallocate_unsupported(&err));
+        let err = std::io::Error::from_raw_os_error(libc::ENOSPC);
+        assert!(!preallocate_unsupported(&err));
     }
 }
@@ -9,28 +9,42 @@ use checksums::ChecksumConfig;
 use filters::ParseError;
 use thiserror::Error;
 
+mod cdc;
 mod cleanup;
 pub use cleanup::fuzzy_match;
 mod delta;
+mod pool;
+mod progress;
 mod receiver;
 pub mod remote;
 mod sender;
 
 pub mod batch;
 pub mod block;
+pub mod checksum_cache;
 pub mod flist;
 pub mod io;
 pub mod session;
 pub mod xattrs;
 
-pub use batch::{Batch, decode_batch, encode_batch};
-pub use block::block_size;
-pub use io::{io_context, is_device, preallocate};
-pub use session::{DeleteMode, IdMapper, Stats, SyncOptions, pipe_sessions, select_codec, sync};
+pub use batch::{
+    Batch, BatchFormat, RsyncBatchEntry, decode_batch, encode_batch, encode_rsync_flist,
+    encode_rsync_header,
+};
+pub use block::{
+    BlockTable, MAX_BLOCK_COUNT, MAX_BLOCK_SIZE, block_count, block_size, capped_block_size,
+};
+pub use io::{SentSum, io_context, is_device, preallocate};
+pub use session::{
+    DeleteMode, FileCompressor, FileDecompressor, IdMapper, PathTransform, Stats, SyncOptions,
+    ZSTD_LDM_THRESHOLD, file_compressor, file_decompressor, pipe_sessions, quick_check,
+    select_codec, sync,
+};
 
 pub use checksums::StrongHash;
-pub use delta::{DeltaIter, Op, compute_delta};
+pub use delta::{DeltaIter, Op, compute_delta, compute_delta_with_table};
 pub use meta::MetaOpts;
+pub use pool::BufferPool;
 pub use receiver::{Receiver, ReceiverState};
 pub use remote::{PathSpec, RemoteSpec, is_remote_spec, parse_remote_spec};
 pub use sender::{Sender, SenderState};
@@ -198,13 +212,37 @@ mod tests {
             tmp.write_all(&chunk).unwrap();
         }
         let path = tmp.path().to_path_buf();
-        let sender = Sender::new(Matcher::default(), None, SyncOptions::default());
+        let mut sender = Sender::new(Matcher::default(), None, SyncOptions::default());
         let new_sum = sender.strong_file_checksum(&path).unwrap();
         let data = std::fs::read(&path).unwrap();
         let old_sum = sender.cfg.checksum(&data).strong;
         assert_eq!(new_sum, old_sum);
     }
 
+    #[test]
+    fn checksum_choice_splits_block_and_file_hashes() {
+        use checksums::{StrongHash, strong_digest};
+        use md5::{Digest, Md5};
+
+        let mut tmp = NamedTempFile::new().unwrap();
+        tmp.write_all(b"block and file hashes").unwrap();
+        let data = std::fs::read(tmp.path()).unwrap();
+        let opts = SyncOptions {
+            strong: StrongHash::Xxh3,
+            file_strong: Some(StrongHash::Md5),
+            ..Default::default()
+        };
+        let mut sender = Sender::new(Matcher::default(), None, opts);
+        assert_eq!(
+            sender.cfg.checksum(&data).strong,
+            strong_digest(&data, StrongHash::Xxh3, 0)
+        );
+        assert_eq!(
+            sender.strong_file_checksum(tmp.path()).unwrap(),
+            Md5::digest(&data).to_vec()
+        );
+    }
+
     #[test]
     fn block_size_stats_literal_matches() {
         let cfg = ChecksumConfigBuilder::new().build();
@@ -0,0 +1,57 @@
+// crates/engine/src/pool.rs
+
+use std::sync::{Arc, Mutex};
+
+use crate::delta::LIT_CAP;
+
+const MAX_POOLED: usize = 4;
+
+/// Recycles literal buffers of [`LIT_CAP`] bytes between delta ops. Clones
+/// share the same free list. At most a few buffers are kept, and the sender
+/// clears the pool between files so idle buffers never outlive a transfer.
+#[derive(Debug, Clone, Default)]
+pub struct BufferPool {
+    free: Arc<Mutex<Vec<Vec<u8>>>>,
+}
+
+impl BufferPool {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns an empty buffer with room for a full literal.
+    pub fn get(&self) -> Vec<u8> {
+        self.lock()
+            .pop()
+            .unwrap_or_else(|| Vec::with_capacity(LIT_CAP))
+    }
+
+    /// Hands `buf` back for reuse. Buffers smaller than a full literal, or
+    /// any beyond the pool's limit, are dropped.
+    pub fn put(&self, mut buf: Vec<u8>) {
+        if buf.capacity() < LIT_CAP {
+            return;
+        }
+        let mut free = self.lock();
+        if free.len() < MAX_POOLED {
+            buf.clear();
+            free.push(buf);
+        }
+    }
+
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<Vec<u8>>> {
+        self.free.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
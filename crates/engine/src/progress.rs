@@ -0,0 +1,156 @@
+// crates/engine/src/progress.rs
+
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use logging::{
+    EtaEstimator, InfoFlag, Observer, progress_formatter, progress_line, progress_tty,
+    rate_formatter,
+};
+
+pub(crate) struct Progress {
+    total: u64,
+    written: u64,
+    start: std::time::Instant,
+    last_print: std::time::Instant,
+    reported: u64,
+    eta: EtaEstimator,
+    human_readable: bool,
+    quiet: bool,
+    file_idx: usize,
+    sink: Arc<dyn Observer>,
+}
+
+pub(crate) static TOTAL_FILES: AtomicUsize = AtomicUsize::new(0);
+pub(crate) static FILE_COUNTER: AtomicUsize = AtomicUsize::new(0);
+pub(crate) static CHECKED_FILES: AtomicUsize = AtomicUsize::new(0);
+pub(crate) static PROGRESS_HEADER: AtomicBool = AtomicBool::new(false);
+/// The last progress line printed: its byte/rate/time columns, transfer
+/// number and `to-chk` counts.
+pub(crate) static LAST_PROGRESS: Mutex<Option<(String, usize, usize, usize)>> = Mutex::new(None);
+
+/// Reprints the last progress line if the `to-chk` counts moved since it was
+/// printed, so entries handled after the last transfer, such as
+/// `--delete-after` deletions, still bring the remaining count to zero.
+pub(crate) fn finish_progress() {
+    let Some((columns, idx, remaining, total)) = LAST_PROGRESS.lock().unwrap().take() else {
+        return;
+    };
+    let total_files = TOTAL_FILES.load(Ordering::SeqCst);
+    let now_remaining = total_files.saturating_sub(CHECKED_FILES.load(Ordering::SeqCst));
+    if (now_remaining, total_files) == (remaining, total) {
+        return;
+    }
+    use std::io::Write as _;
+    let line = format!("{columns} (xfr#{idx}, to-chk={now_remaining}/{total_files})");
+    print!("{}", progress_line(&line, true, progress_tty()));
+    let _ = std::io::stdout().flush();
+}
+
+const PROGRESS_UPDATE_INTERVAL: Duration = Duration::from_secs(1);
+
+impl Progress {
+    pub(crate) fn new(
+        dest: &Path,
+        total: u64,
+        human_readable: bool,
+        initial: u64,
+        quiet: bool,
+        sink: Arc<dyn Observer>,
+    ) -> Self {
+        if !quiet {
+            use std::io::Write as _;
+            if !PROGRESS_HEADER.swap(true, Ordering::SeqCst) {
+                println!("sending incremental file list");
+            }
+            if let Some(name) = dest.file_name() {
+                println!("{}", name.to_string_lossy());
+            } else {
+                println!("{}", dest.display());
+            }
+            let _ = std::io::stdout().flush();
+        }
+        sink.start_file(dest, total, initial);
+        let now = std::time::Instant::now();
+        let idx = FILE_COUNTER.fetch_add(1, Ordering::SeqCst) + 1;
+        Self {
+            total,
+            written: initial,
+            start: now,
+            last_print: now - PROGRESS_UPDATE_INTERVAL,
+            reported: initial,
+            eta: EtaEstimator::default(),
+            human_readable,
+            quiet,
+            file_idx: idx,
+            sink,
+        }
+    }
+
+    pub(crate) fn add(&mut self, bytes: u64) {
+        self.written += bytes;
+        self.sink.update(self.written);
+        if !self.quiet
+            && self.last_print.elapsed() >= PROGRESS_UPDATE_INTERVAL
+            && self.written < self.total
+        {
+            let interval = self.last_print.max(self.start).elapsed();
+            self.eta.record(self.written - self.reported, interval);
+            self.reported = self.written;
+            self.print(false);
+            self.last_print = std::time::Instant::now();
+        }
+    }
+
+    pub(crate) fn finish(&mut self) {
+        self.sink.finish_file();
+        if !self.quiet {
+            self.print(true);
+        }
+    }
+
+    fn print(&self, done: bool) {
+        if self.quiet {
+            return;
+        }
+        use std::io::Write as _;
+        let bytes = progress_formatter(self.written, self.human_readable);
+        let percent = (self.written * 100).checked_div(self.total).unwrap_or(100);
+        let elapsed = self.start.elapsed().as_secs_f64();
+        let rate_val = if elapsed > 0.0 {
+            self.written as f64 / elapsed
+        } else {
+            0.0
+        };
+        let rate = rate_formatter(rate_val);
+        let secs = match self.eta.eta(self.total.saturating_sub(self.written)) {
+            Some(eta) if !done => eta.as_secs(),
+            _ => self.start.elapsed().as_secs(),
+        };
+        let h = secs / 3600;
+        let m = (secs % 3600) / 60;
+        let s = secs % 60;
+        let time = format!("{:02}:{:02}:{:02}", h, m, s);
+        let total_files = TOTAL_FILES.load(Ordering::SeqCst);
+        let remaining = total_files.saturating_sub(CHECKED_FILES.load(Ordering::SeqCst));
+        tracing::info!(
+            target: InfoFlag::Progress.target(),
+            written = self.written,
+            total = self.total,
+            percent,
+            rate = rate.as_str()
+        );
+        let columns = format!("{:>15} {:>3}% {} {}", bytes, percent, rate, time);
+        let line = format!(
+            "{} (xfr#{}, to-chk={}/{})",
+            columns, self.file_idx, remaining, total_files
+        );
+        print!("{}", progress_line(&line, done, progress_tty()));
+        if done {
+            *LAST_PROGRESS.lock().unwrap() = Some((columns, self.file_idx, remaining, total_files));
+        }
+        let _ = std::io::stdout().flush();
+    }
+}
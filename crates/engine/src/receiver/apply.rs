@@ -4,25 +4,110 @@ use nix::unistd::{Gid, Uid, chown};
 use std::fs::{self, File, OpenOptions};
 use std::io::{self, BufReader, Cursor, Seek, SeekFrom};
 #[cfg(unix)]
-use std::os::unix::fs::{FileTypeExt, MetadataExt};
+use std::os::unix::fs::{FileTypeExt, MetadataExt, PermissionsExt};
 use std::path::{Path, PathBuf};
 
-use compress::{Codec, Decompressor, ZlibX, Zstd, should_compress};
+use compress::Codec;
 
 use crate::block::block_size;
 use crate::cleanup::{
     TempFileGuard, atomic_rename, open_for_read, partial_paths, remove_basename_partial,
     tmp_file_path,
 };
-use crate::delta::{Op, Progress, apply_delta};
-use crate::io::{io_context, is_device, preallocate};
-use crate::{EngineError, ReadSeek, Result, ensure_max_alloc, last_good_block};
-use checksums::ChecksumConfigBuilder;
+use crate::delta::{Op, apply_delta};
+use crate::io::{file_strong_sum, io_context, is_device, try_preallocate};
+use crate::progress::Progress;
+use crate::{EngineError, ReadSeek, Result, ensure_max_alloc, file_decompressor, last_good_block};
+use checksums::{ChecksumConfig, ChecksumConfigBuilder};
 
 use super::{Receiver, ReceiverState};
 
 impl Receiver {
-    pub fn apply<I>(&mut self, src: &Path, dest: &Path, _rel: &Path, delta: I) -> Result<PathBuf>
+    fn verify_written(&self, cfg: &ChecksumConfig, written: &Path, dest: &Path) -> Result<()> {
+        let sent = self
+            .sent_sum
+            .as_ref()
+            .and_then(|sum| sum.lock().unwrap().clone());
+        if let Some(sum) = sent
+            && file_strong_sum(cfg, written)? == sum
+        {
+            return Ok(());
+        }
+        Err(EngineError::Other(format!(
+            "{} failed verification -- update discarded",
+            dest.display()
+        )))
+    }
+
+    pub(crate) fn create_dirs(&self, dir: &Path) -> Result<()> {
+        let missing: Vec<&Path> = dir.ancestors().take_while(|d| !d.exists()).collect();
+        fs::create_dir_all(dir).map_err(|e| io_context(dir, e))?;
+        #[cfg(unix)]
+        if let Some(mask) = self.new_entry_umask() {
+            for d in &missing {
+                fs::set_permissions(d, fs::Permissions::from_mode(0o777 & !mask))
+                    .map_err(|e| io_context(d, e))?;
+            }
+        }
+        #[cfg(unix)]
+        if let Some((uid, gid)) = self.opts.copy_as {
+            for d in missing.into_iter().rev() {
+                chown(d, Some(Uid::from_raw(uid)), gid.map(Gid::from_raw))
+                    .map_err(|e| io_context(d, std::io::Error::from(e)))?;
+            }
+        }
+        #[cfg(not(unix))]
+        let _ = missing;
+        Ok(())
+    }
+
+    /// The umask for entries the receiver creates, unless `--perms` copies modes.
+    pub(crate) fn new_entry_umask(&self) -> Option<u32> {
+        self.opts.umask.filter(|_| !self.opts.perms)
+    }
+
+    /// Rejects `dest` when it or one of its components is longer than the
+    /// `--max-path-len` / `--max-name-len` limits of the destination.
+    pub(crate) fn check_path_limits(&self, dest: &Path) -> Result<()> {
+        if let Some(max) = self.opts.max_name_len
+            && let Some(name) = dest.iter().find(|c| c.len() > max)
+        {
+            return Err(EngineError::Other(format!(
+                "{}: name {:?} exceeds {max} bytes",
+                dest.display(),
+                name
+            )));
+        }
+        if let Some(max) = self.opts.max_path_len
+            && dest.as_os_str().len() > max
+        {
+            return Err(EngineError::Other(format!(
+                "{}: path exceeds {max} bytes",
+                dest.display()
+            )));
+        }
+        Ok(())
+    }
+
+    /// Applies `delta` to `dest`; literal data is decompressed with the
+    /// receiver's session codec.
+    pub fn apply<I>(&mut self, src: &Path, dest: &Path, rel: &Path, delta: I) -> Result<PathBuf>
+    where
+        I: IntoIterator<Item = Result<Op>>,
+    {
+        self.apply_with_codec(src, dest, rel, self.codec, delta)
+    }
+
+    /// Applies `delta` to `dest`, decompressing literal data with the codec
+    /// the sender chose for this file.
+    pub fn apply_with_codec<I>(
+        &mut self,
+        src: &Path,
+        dest: &Path,
+        _rel: &Path,
+        file_codec: Option<Codec>,
+        delta: I,
+    ) -> Result<PathBuf>
     where
         I: IntoIterator<Item = Result<Op>>,
     {
@@ -57,6 +142,15 @@ impl Receiver {
             }
         }
         let src_len = fs::metadata(src).map(|m| m.len()).unwrap_or(0);
+        let dest_is_new = fs::symlink_metadata(&dest).is_err();
+        let kept_meta = if self.opts.keep_dest_perms {
+            if dest_is_new {
+                self.new_files.insert(dest.clone());
+            }
+            fs::symlink_metadata(&dest).ok().filter(|m| m.is_file())
+        } else {
+            None
+        };
         let (partial, basename_partial) = partial_paths(&dest, self.opts.partial_dir.as_deref());
         let mut existing_partial = if partial.exists() {
             Some(partial.clone())
@@ -92,7 +186,7 @@ impl Receiver {
             dest.clone()
         };
         let dest_parent = dest.parent().unwrap_or_else(|| Path::new("."));
-        fs::create_dir_all(dest_parent).map_err(|e| io_context(dest_parent, e))?;
+        self.create_dirs(dest_parent)?;
         let mut auto_tmp = false;
         let mut tmp_dest = if self.opts.inplace {
             dest.clone()
@@ -132,9 +226,12 @@ impl Receiver {
             auto_tmp = true;
             tmp_dest = tmp_file_path(dest_parent, &dest);
         }
+        let writes_partial = self.opts.partial
+            && (tmp_dest == partial || existing_partial.as_ref() == Some(&tmp_dest));
         let mut needs_rename = !self.opts.inplace
             && ((self.opts.partial || self.opts.append || self.opts.append_verify)
                 && existing_partial.is_some()
+                || writes_partial
                 || self.opts.temp_dir.is_some()
                 || auto_tmp);
         if self.opts.delay_updates && !self.opts.inplace && !self.opts.write_devices {
@@ -143,13 +240,14 @@ impl Receiver {
             }
             needs_rename = true;
         }
-        let mut tmp_guard = if needs_rename {
+        let mut tmp_guard = if needs_rename && !writes_partial {
             Some(TempFileGuard::new(tmp_dest.clone()))
         } else {
             None
         };
         let cfg = ChecksumConfigBuilder::new()
             .strong(self.opts.strong)
+            .file_strong(self.opts.file_strong.unwrap_or(self.opts.strong))
             .seed(self.opts.checksum_seed)
             .build();
         let block_size = if self.opts.block_size > 0 {
@@ -157,6 +255,11 @@ impl Receiver {
         } else {
             block_size(src_len)
         };
+        if self.opts.append || self.opts.append_verify {
+            let target = existing_partial.as_ref().unwrap_or(&dest);
+            self.lock_append_target(target)?;
+        }
+        let _append_lock = self.append_lock.take();
         let resume_basis = existing_partial.as_ref().unwrap_or(&tmp_dest);
         let mut resume = if self.opts.partial || self.opts.append || self.opts.append_verify {
             if self.opts.append && !self.opts.append_verify {
@@ -199,16 +302,7 @@ impl Receiver {
             }
         };
         let parent = tmp_dest.parent().unwrap_or_else(|| Path::new("."));
-        let created = !parent.exists();
-        fs::create_dir_all(parent).map_err(|e| io_context(parent, e))?;
-        #[cfg(unix)]
-        if created {
-            if let Some((uid, gid)) = self.opts.copy_as {
-                let gid = gid.map(Gid::from_raw);
-                chown(parent, Some(Uid::from_raw(uid)), gid)
-                    .map_err(|e| io_context(parent, std::io::Error::from(e)))?;
-            }
-        }
+        self.create_dirs(parent)?;
         #[cfg(unix)]
         if !self.opts.write_devices {
             let check_path: &Path = if auto_tmp { &dest } else { &tmp_dest };
@@ -240,42 +334,46 @@ impl Receiver {
                 .read(true)
                 .write(true)
                 .create(true)
-                .truncate(true)
+                .truncate(!self.opts.inplace)
                 .open(&tmp_dest)
                 .map_err(|e| io_context(&tmp_dest, e))?
         } else {
             File::create(&tmp_dest).map_err(|e| io_context(&tmp_dest, e))?
         };
-        let file_codec = if should_compress(src, &self.opts.skip_compress) {
-            self.codec
-        } else {
-            None
-        };
-
+        #[cfg(unix)]
+        if let Some(mask) = self.new_entry_umask().filter(|_| dest_is_new) {
+            let mode = fs::metadata(src)
+                .map_err(|e| io_context(src, e))?
+                .permissions()
+                .mode();
+            out.set_permissions(fs::Permissions::from_mode(mode & 0o777 & !mask))
+                .map_err(|e| io_context(&tmp_dest, e))?;
+        }
+        #[cfg(unix)]
+        if let Some(old) = kept_meta.as_ref() {
+            if let Err(err) = chown(
+                &tmp_dest,
+                Some(Uid::from_raw(old.uid())),
+                Some(Gid::from_raw(old.gid())),
+            ) {
+                tracing::debug!(?dest, %err, "unable to keep destination ownership");
+            }
+            out.set_permissions(old.permissions())
+                .map_err(|e| io_context(&tmp_dest, e))?;
+        }
+        #[cfg(not(unix))]
+        let _ = (dest_is_new, kept_meta);
+        let mut file_dec = file_codec
+            .map(file_decompressor)
+            .transpose()
+            .map_err(EngineError::from)?;
         let mut ops_vec = Vec::new();
         let mut dest_len = 0u64;
         for op_res in delta {
             let mut op = op_res?;
-            if let Some(codec) = file_codec {
+            if let Some(dec) = &mut file_dec {
                 if let Op::Data(ref mut d) = op {
-                    *d = match codec {
-                        Codec::Zlib | Codec::ZlibX => {
-                            let mut out = Vec::new();
-                            let mut cursor = d.as_slice();
-                            ZlibX::default()
-                                .decompress(&mut cursor, &mut out)
-                                .map_err(EngineError::from)?;
-                            out
-                        }
-                        Codec::Zstd => {
-                            let mut out = Vec::new();
-                            let mut cursor = d.as_slice();
-                            Zstd::default()
-                                .decompress(&mut cursor, &mut out)
-                                .map_err(EngineError::from)?;
-                            out
-                        }
-                    };
+                    *d = dec.decompress(d).map_err(EngineError::from)?;
                 }
             }
             dest_len += match &op {
@@ -286,10 +384,12 @@ impl Receiver {
         }
 
         if !self.opts.write_devices {
-            out.set_len(resume)?;
+            if !self.opts.inplace {
+                out.set_len(resume)?;
+            }
             out.seek(SeekFrom::Start(resume))?;
-            if self.opts.preallocate {
-                preallocate(&out, dest_len)?;
+            if self.opts.preallocate && !try_preallocate(&out, dest_len)? {
+                tracing::debug!(?dest, "preallocation unsupported; using sparse writes");
             }
         }
 
@@ -325,6 +425,9 @@ impl Receiver {
             out.sync_all().map_err(|e| io_context(&tmp_dest, e))?;
         }
         drop(out);
+        if self.opts.verify_after_write && !self.opts.write_devices {
+            self.verify_written(&cfg, &tmp_dest, &dest)?;
+        }
         if needs_rename {
             if self.opts.delay_updates {
                 self.delayed
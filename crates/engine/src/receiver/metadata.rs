@@ -20,6 +20,27 @@ use meta::decode_acl;
 use super::Receiver;
 
 impl Receiver {
+    /// `--fake-super` applies unless `--super` was given and the process can
+    /// actually change ownership; an unprivileged `--super` falls back to it.
+    #[cfg(unix)]
+    fn fake_super(&self) -> bool {
+        self.opts.fake_super && !(self.opts.super_user && meta::has_chown_privilege())
+    }
+
+    fn pace_fs_ops(&mut self, ops: u32) {
+        if ops > 0
+            && let Some(pacer) = self.op_pacer.as_mut()
+        {
+            pacer.pace(ops);
+        }
+    }
+
+    /// Number of metadata operations that went through the `--fs-op-rate`
+    /// pacer.
+    pub(crate) fn paced_fs_ops(&self) -> u64 {
+        self.op_pacer.as_ref().map_or(0, |p| p.paced())
+    }
+
     pub(crate) fn copy_metadata_now(
         &mut self,
         src: &Path,
@@ -46,12 +67,17 @@ impl Receiver {
             }
         }
 
+        let keep_perms = self.opts.keep_dest_perms
+            && !self.new_files.remove(dest)
+            && fs::symlink_metadata(dest).is_ok_and(|m| m.is_file());
+
         #[cfg(unix)]
         {
-            if self.opts.perms {
+            if self.opts.perms && !keep_perms {
                 let src_meta = fs::symlink_metadata(src).map_err(|e| io_context(src, e))?;
                 if !src_meta.file_type().is_symlink() {
                     let mode = meta::mode_from_metadata(&src_meta);
+                    self.pace_fs_ops(1);
                     fs::set_permissions(dest, fs::Permissions::from_mode(mode))
                         .map_err(|e| io_context(dest, e))?;
                 }
@@ -63,40 +89,54 @@ impl Receiver {
             let chown_uid = self.opts.chown.and_then(|(u, _)| u);
             let chown_gid = self.opts.chown.and_then(|(_, g)| g);
 
-            let uid_map: Option<Arc<dyn Fn(u32) -> u32 + Send + Sync>> = if self.opts.owner {
-                if let Some(ref map) = self.opts.uid_map {
-                    Some(map.0.clone())
-                } else if let Some(uid) = chown_uid {
+            let copy_as_uid = self.opts.copy_as.map(|(u, _)| u);
+            let copy_as_gid = self.opts.copy_as.and_then(|(_, g)| g);
+
+            let uid_map: Option<Arc<dyn Fn(u32) -> u32 + Send + Sync>> =
+                if let Some(uid) = copy_as_uid {
                     Some(Arc::new(move |_| uid))
+                } else if self.opts.owner {
+                    if let Some(ref map) = self.opts.uid_map {
+                        Some(map.0.clone())
+                    } else if let Some(uid) = chown_uid {
+                        Some(Arc::new(move |_| uid))
+                    } else if let Some((uid, _)) = self.list_owner {
+                        Some(Arc::new(move |_| uid))
+                    } else {
+                        None
+                    }
                 } else {
                     None
-                }
-            } else {
-                None
-            };
+                };
 
-            let gid_map: Option<Arc<dyn Fn(u32) -> u32 + Send + Sync>> = if self.opts.group {
-                if let Some(ref map) = self.opts.gid_map {
-                    Some(map.0.clone())
-                } else if let Some(gid) = chown_gid {
+            let gid_map: Option<Arc<dyn Fn(u32) -> u32 + Send + Sync>> =
+                if let Some(gid) = copy_as_gid {
                     Some(Arc::new(move |_| gid))
+                } else if self.opts.group {
+                    if let Some(ref map) = self.opts.gid_map {
+                        Some(map.0.clone())
+                    } else if let Some(gid) = chown_gid {
+                        Some(Arc::new(move |_| gid))
+                    } else if let Some((_, gid)) = self.list_owner {
+                        Some(Arc::new(move |_| gid))
+                    } else {
+                        None
+                    }
                 } else {
                     None
-                }
-            } else {
-                None
-            };
+                };
 
             #[cfg(feature = "xattr")]
             let m1 = self.matcher.clone();
             #[cfg(feature = "xattr")]
             let m2 = self.matcher.clone();
 
+            let fake_super = self.fake_super();
             let mut meta_opts = meta::Options {
                 xattrs: {
                     #[cfg(feature = "xattr")]
                     {
-                        self.opts.xattrs || (self.opts.fake_super && !self.opts.super_user)
+                        self.opts.xattrs || fake_super
                     }
                     #[cfg(not(feature = "xattr"))]
                     {
@@ -122,8 +162,8 @@ impl Receiver {
                     }
                 },
                 chmod: self.opts.chmod.clone(),
-                owner: self.opts.owner,
-                group: self.opts.group,
+                owner: self.opts.owner || copy_as_uid.is_some(),
+                group: self.opts.group || copy_as_gid.is_some(),
                 perms: self.opts.perms || {
                     #[cfg(feature = "acl")]
                     {
@@ -142,10 +182,19 @@ impl Receiver {
                 omit_link_times: self.opts.omit_link_times,
                 uid_map,
                 gid_map,
-                fake_super: self.opts.fake_super && !self.opts.super_user,
-                super_user: self.opts.super_user,
+                fake_super,
+                super_user: self.opts.super_user && !fake_super,
                 numeric_ids: self.opts.numeric_ids,
             };
+            if keep_perms {
+                meta_opts.owner = false;
+                meta_opts.group = false;
+                meta_opts.perms = false;
+                meta_opts.executability = false;
+                meta_opts.chmod = None;
+                meta_opts.uid_map = None;
+                meta_opts.gid_map = None;
+            }
 
             if meta_opts.needs_metadata() {
                 if let Ok(src_meta) = fs::symlink_metadata(src) {
@@ -165,23 +214,27 @@ impl Receiver {
                 }
             }
 
-            let meta = if meta_opts.needs_metadata() || (self.opts.acls && entry.is_none()) {
+            let mut meta = if meta_opts.needs_metadata() || (self.opts.acls && entry.is_none()) {
                 Some(meta::Metadata::from_path(src, meta_opts.clone()).map_err(EngineError::from)?)
             } else {
                 None
             };
+            if let (Some(meta), Some(entry)) = (meta.as_mut(), entry)
+                && !entry.times.is_empty()
+            {
+                meta.decode_times(&entry.times).map_err(EngineError::from)?;
+            }
 
             if let Some(ref meta) = meta {
                 if meta_opts.needs_metadata() {
+                    self.pace_fs_ops(
+                        (meta_opts.owner || meta_opts.group) as u32
+                            + (meta_opts.perms || meta_opts.chmod.is_some()) as u32
+                            + (meta_opts.times || meta_opts.atimes || meta_opts.crtimes) as u32,
+                    );
                     meta.apply(dest, meta_opts.clone())
                         .map_err(EngineError::from)?;
                 }
-                if self.opts.fake_super && !self.opts.super_user {
-                    #[cfg(feature = "xattr")]
-                    {
-                        meta::store_fake_super(dest, meta.uid, meta.gid, meta.mode);
-                    }
-                }
             }
 
             #[cfg(feature = "acl")]
@@ -1,6 +1,7 @@
 // crates/engine/src/receiver/mod.rs
 mod apply;
 mod metadata;
+mod pacer;
 mod state;
 
 pub use state::{Receiver, ReceiverState};
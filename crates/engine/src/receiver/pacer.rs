@@ -0,0 +1,36 @@
+// crates/engine/src/receiver/pacer.rs
+use std::time::{Duration, Instant};
+
+/// Spaces metadata calls (chmod, utimes, chown) on the destination evenly so
+/// that at most `rate` of them are issued per second.
+#[derive(Debug)]
+pub(super) struct OpPacer {
+    interval: Duration,
+    next: Instant,
+    paced: u64,
+}
+
+impl OpPacer {
+    pub(super) fn new(rate: u32) -> Self {
+        Self {
+            interval: Duration::from_secs(1) / rate.max(1),
+            next: Instant::now(),
+            paced: 0,
+        }
+    }
+
+    /// Blocks until the next slot is due, then reserves `ops` slots.
+    pub(super) fn pace(&mut self, ops: u32) {
+        let now = Instant::now();
+        if self.next > now {
+            std::thread::sleep(self.next - now);
+        }
+        self.next = self.next.max(now) + self.interval * ops;
+        self.paced += u64::from(ops);
+    }
+
+    /// Number of operations paced so far.
+    pub(super) fn paced(&self) -> u64 {
+        self.paced
+    }
+}
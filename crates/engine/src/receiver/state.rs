@@ -1,4 +1,5 @@
 // crates/engine/src/receiver/state.rs
+use std::collections::HashSet;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
@@ -6,7 +7,9 @@ use compress::Codec;
 use filters::Matcher;
 use logging::{NopObserver, Observer};
 
-use crate::SyncOptions;
+use super::pacer::OpPacer;
+use crate::io::{AppendLock, SentSum, io_context, lock_for_append};
+use crate::{Result, SyncOptions};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ReceiverState {
@@ -21,9 +24,14 @@ pub struct Receiver {
     pub(super) opts: SyncOptions,
     pub(crate) matcher: Matcher,
     pub(super) delayed: Vec<(PathBuf, PathBuf, PathBuf)>,
+    pub(super) new_files: HashSet<PathBuf>,
+    pub(super) list_owner: Option<(u32, u32)>,
+    pub(super) sent_sum: Option<SentSum>,
     #[cfg(unix)]
     pub(super) link_map: meta::HardLinks,
     pub(super) progress_sink: Arc<dyn Observer>,
+    pub(super) append_lock: Option<(PathBuf, AppendLock)>,
+    pub(super) op_pacer: Option<OpPacer>,
 }
 
 impl Default for Receiver {
@@ -34,15 +42,21 @@ impl Default for Receiver {
 
 impl Receiver {
     pub fn new(codec: Option<Codec>, opts: SyncOptions) -> Self {
+        let op_pacer = opts.fs_op_rate.map(OpPacer::new);
         Self {
             state: ReceiverState::Idle,
             codec,
             opts,
             matcher: Matcher::default(),
             delayed: Vec::new(),
+            new_files: HashSet::new(),
+            list_owner: None,
+            sent_sum: None,
             #[cfg(unix)]
             link_map: meta::HardLinks::default(),
             progress_sink: Arc::new(NopObserver),
+            append_lock: None,
+            op_pacer,
         }
     }
 
@@ -50,6 +64,32 @@ impl Receiver {
         self.progress_sink = sink;
     }
 
+    /// Locks `path` for an `--append` transfer and keeps the lock until the
+    /// next `apply` into it finishes, so the resume offset chosen before the
+    /// delta is built cannot be moved by a concurrent appender.
+    pub fn lock_append_target(&mut self, path: &Path) -> Result<()> {
+        if self.append_lock.as_ref().is_some_and(|(p, _)| p == path) {
+            return Ok(());
+        }
+        self.append_lock = None;
+        let lock = lock_for_append(path).map_err(|e| io_context(path, e))?;
+        self.append_lock = Some((path.to_path_buf(), lock));
+        Ok(())
+    }
+
+    /// Sets the checksum the sender computed over the data of the file being
+    /// received, which `--verify-after-write` checks the written file
+    /// against.
+    pub fn expect_sent_sum(&mut self, sum: Option<SentSum>) {
+        self.sent_sum = sum;
+    }
+
+    /// Sets the uid and gid carried by the file list entry being received;
+    /// `--owner`/`--group` apply them in place of the source file's ids.
+    pub(crate) fn set_list_owner(&mut self, owner: Option<(u32, u32)>) {
+        self.list_owner = owner;
+    }
+
     #[cfg(unix)]
     pub fn register_hard_link(&mut self, id: u64, path: &Path) -> bool {
         self.link_map.register(id, path)
@@ -1,26 +1,34 @@
 // crates/engine/src/sender.rs
 
-use std::fs::{self, File};
+use std::fs;
 use std::io::{BufReader, Cursor, Read};
 use std::path::Path;
-use std::time::Duration;
+use std::sync::Mutex;
 
 use checksums::{ChecksumConfig, ChecksumConfigBuilder};
-use compress::{Codec, Compressor, Zlib, Zstd, should_compress};
+use compress::{Codec, codec_for_path};
+use filelist::Entry;
 use filters::Matcher;
 use md4::{Digest, Md4};
 use md5::Md5;
 use sha1::Sha1;
+use xxhash_rust::xxh3::Xxh3;
 use xxhash_rust::xxh64::Xxh64;
 
 use crate::block::block_size;
+use crate::checksum_cache::ChecksumCache;
 use crate::cleanup::{atomic_rename, fuzzy_match, open_for_read, partial_paths};
 use crate::delta::{DEFAULT_BASIS_WINDOW, Op, compute_delta};
-use crate::io::{io_context, is_device};
+use crate::io::{
+    HoleReader, MMAP_HASH_THRESHOLD, SentSum, SumReader, common_holes, for_each_chunk, io_context,
+    is_device,
+};
+use crate::pool::BufferPool;
 use crate::receiver::Receiver;
+use crate::remote::path_from_bytes;
 use crate::{
     EngineError, ReadSeek, Result, Stats, StrongHash, SyncOptions, ensure_max_alloc,
-    last_good_block,
+    file_compressor, last_good_block, quick_check,
 };
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -35,6 +43,12 @@ pub struct Sender {
     pub(crate) cfg: ChecksumConfig,
     _matcher: Matcher,
     codec: Option<Codec>,
+    codecs: Vec<Codec>,
+    cache: Option<ChecksumCache>,
+    file_list: Option<Vec<Entry>>,
+    pool: BufferPool,
+    sent_sum: Option<Vec<u8>>,
+    file_checksums: u64,
     opts: SyncOptions,
 }
 
@@ -44,97 +58,164 @@ impl Sender {
             state: SenderState::Idle,
             cfg: ChecksumConfigBuilder::new()
                 .strong(opts.strong)
+                .file_strong(opts.file_strong.unwrap_or(opts.strong))
                 .seed(opts.checksum_seed)
                 .build(),
             _matcher: matcher,
             codec,
+            codecs: Vec::new(),
+            cache: opts.checksum_cache.then(|| {
+                ChecksumCache::new(format!(
+                    "{:?}:{}",
+                    opts.file_strong.unwrap_or(opts.strong),
+                    opts.checksum_seed
+                ))
+            }),
+            file_list: None,
+            pool: BufferPool::new(),
+            sent_sum: None,
+            file_checksums: 0,
             opts,
         }
     }
 
-    pub(crate) fn strong_file_checksum(&self, path: &Path) -> Result<Vec<u8>> {
-        let file = File::open(path).map_err(|e| io_context(path, e))?;
-        let mut reader = BufReader::new(file);
-        let mut buf = [0u8; 8192];
-        match self.opts.strong {
+    /// Sets the codecs both sides support, which `--compress-map` entries
+    /// are limited to.
+    pub fn with_codecs(mut self, codecs: &[Codec]) -> Self {
+        self.codecs = codecs.to_vec();
+        self
+    }
+
+    /// Uses `entries`, relative to the source root, instead of a directory walk.
+    pub fn with_file_list(mut self, entries: Vec<Entry>) -> Self {
+        self.file_list = Some(entries);
+        self
+    }
+
+    /// Transfers exactly the entries given to [`Sender::with_file_list`].
+    pub fn send_file_list(
+        &mut self,
+        src_root: &Path,
+        dst: &Path,
+        recv: &mut Receiver,
+        stats: &mut Stats,
+    ) -> Result<()> {
+        self.start();
+        for entry in self.file_list.take().unwrap_or_default() {
+            let rel = path_from_bytes(&entry.path);
+            let path = src_root.join(&rel);
+            let meta = match fs::metadata(&path) {
+                Ok(meta) => meta,
+                Err(e) => {
+                    tracing::warn!("{}", io_context(&path, e));
+                    stats.file_errors += 1;
+                    stats.had_partial_errors = true;
+                    continue;
+                }
+            };
+            if !meta.is_file() {
+                continue;
+            }
+            recv.set_list_owner(Some((entry.uid, entry.gid)));
+            let sent = self.process_file(&path, &dst.join(&rel), &rel, recv, stats);
+            recv.set_list_owner(None);
+            if sent? {
+                stats.files_transferred += 1;
+                stats.bytes_transferred += meta.len();
+            }
+        }
+        self.finish(stats);
+        recv.finalize()
+    }
+
+    /// Takes the whole-file checksum of the data the last `process_file`
+    /// call sent, if `--post-verify` or `--verify-after-write` asked for it.
+    pub(crate) fn take_sent_sum(&mut self) -> Option<Vec<u8>> {
+        self.sent_sum.take()
+    }
+
+    pub(crate) fn strong_file_checksum(&mut self, path: &Path) -> Result<Vec<u8>> {
+        self.file_checksums += 1;
+        let file = open_for_read(path, &self.opts).map_err(|e| io_context(path, e))?;
+        match self.opts.file_strong.unwrap_or(self.opts.strong) {
             StrongHash::Md4 => {
                 let mut hasher = Md4::new();
-                loop {
-                    let n = reader.read(&mut buf).map_err(|e| io_context(path, e))?;
-                    if n == 0 {
-                        break;
-                    }
-                    hasher.update(&buf[..n]);
-                }
+                for_each_chunk(file, MMAP_HASH_THRESHOLD, |b| hasher.update(b))
+                    .map_err(|e| io_context(path, e))?;
                 hasher.update(self.opts.checksum_seed.to_le_bytes());
                 Ok(hasher.finalize().to_vec())
             }
             StrongHash::Md5 => {
                 let mut hasher = Md5::new();
-                loop {
-                    let n = reader.read(&mut buf).map_err(|e| io_context(path, e))?;
-                    if n == 0 {
-                        break;
-                    }
-                    hasher.update(&buf[..n]);
-                }
+                for_each_chunk(file, MMAP_HASH_THRESHOLD, |b| hasher.update(b))
+                    .map_err(|e| io_context(path, e))?;
                 Ok(hasher.finalize().to_vec())
             }
             StrongHash::Sha1 => {
                 let mut hasher = Sha1::new();
-                loop {
-                    let n = reader.read(&mut buf).map_err(|e| io_context(path, e))?;
-                    if n == 0 {
-                        break;
-                    }
-                    hasher.update(&buf[..n]);
-                }
+                for_each_chunk(file, MMAP_HASH_THRESHOLD, |b| hasher.update(b))
+                    .map_err(|e| io_context(path, e))?;
                 Ok(hasher.finalize().to_vec())
             }
             StrongHash::XxHash => {
                 let mut hasher = Xxh64::new(self.opts.checksum_seed as u64);
-                loop {
-                    let n = reader.read(&mut buf).map_err(|e| io_context(path, e))?;
-                    if n == 0 {
-                        break;
-                    }
-                    hasher.update(&buf[..n]);
-                }
+                for_each_chunk(file, MMAP_HASH_THRESHOLD, |b| hasher.update(b))
+                    .map_err(|e| io_context(path, e))?;
+                Ok(hasher.digest().to_le_bytes().to_vec())
+            }
+            StrongHash::Xxh3 => {
+                let mut hasher = Xxh3::with_seed(self.opts.checksum_seed as u64);
+                for_each_chunk(file, MMAP_HASH_THRESHOLD, |b| hasher.update(b))
+                    .map_err(|e| io_context(path, e))?;
                 Ok(hasher.digest().to_le_bytes().to_vec())
             }
         }
     }
 
     fn metadata_unchanged(&self, path: &Path, dest: &Path) -> bool {
-        if self.opts.size_only {
-            if let (Ok(src_meta), Ok(dst_meta)) = (fs::metadata(path), fs::metadata(dest)) {
-                return src_meta.len() == dst_meta.len();
-            }
-            return false;
+        match (fs::metadata(path), fs::metadata(dest)) {
+            (Ok(src_meta), Ok(dst_meta)) => quick_check(&src_meta, &dst_meta, &self.opts),
+            _ => false,
         }
-        if self.opts.ignore_times {
-            return false;
+    }
+
+    /// Whether `dest` already holds the content of `path`: the quick check
+    /// (size and mtime) decides unless `--checksum` is set, and otherwise
+    /// the whole-file checksums are compared.
+    fn content_matches(&mut self, path: &Path, dest: &Path) -> Result<bool> {
+        if !self.opts.checksum && self.metadata_unchanged(path, dest) {
+            return Ok(true);
         }
-        if let (Ok(src_meta), Ok(dst_meta)) = (fs::metadata(path), fs::metadata(dest)) {
-            if src_meta.len() == dst_meta.len() {
-                if let (Ok(sm), Ok(dm)) = (src_meta.modified(), dst_meta.modified()) {
-                    let diff = if sm > dm {
-                        sm.duration_since(dm).unwrap_or(Duration::ZERO)
-                    } else {
-                        dm.duration_since(sm).unwrap_or(Duration::ZERO)
-                    };
-                    return diff <= self.opts.modify_window;
-                }
-            }
+        let src_len = fs::metadata(path).map_err(|e| io_context(path, e))?.len();
+        let dst_len = fs::metadata(dest).map_err(|e| io_context(dest, e))?.len();
+        if src_len != dst_len {
+            return Ok(false);
         }
-        false
+        Ok(self.cached_file_checksum(path)? == self.strong_file_checksum(dest)?)
     }
 
     pub(crate) fn start(&mut self) {
         self.state = SenderState::Walking;
     }
 
-    pub(crate) fn finish(&mut self) {
+    fn cached_file_checksum(&mut self, path: &Path) -> Result<Vec<u8>> {
+        match self.cache.take() {
+            Some(mut cache) => {
+                let res = cache.checksum(path, |p| self.strong_file_checksum(p));
+                self.cache = Some(cache);
+                res
+            }
+            None => self.strong_file_checksum(path),
+        }
+    }
+
+    pub(crate) fn finish(&mut self, stats: &mut Stats) {
+        stats.file_checksums += std::mem::take(&mut self.file_checksums);
+        if let Some(cache) = self.cache.as_mut() {
+            if let Err(e) = cache.flush() {
+                tracing::warn!("failed to write checksum cache: {e}");
+            }
+        }
         self.state = SenderState::Finished;
     }
 
@@ -146,15 +227,31 @@ impl Sender {
         recv: &mut Receiver,
         stats: &mut Stats,
     ) -> Result<bool> {
+        self.sent_sum = None;
         let mut dest = dest.to_path_buf();
         if dest.is_dir() {
             if let Some(name) = path.file_name() {
                 dest.push(name);
             }
         }
+        if self.opts.existing && fs::symlink_metadata(&dest).is_err() {
+            return Ok(false);
+        }
+        if self.opts.metadata_only {
+            match fs::metadata(&dest) {
+                Ok(dst_meta) if dst_meta.is_file() && self.content_matches(path, &dest)? => {
+                    recv.copy_metadata(path, &dest, None)?;
+                }
+                Ok(_) => {
+                    tracing::warn!("{}: content differs, not updating metadata", dest.display())
+                }
+                Err(_) => {}
+            }
+            return Ok(false);
+        }
         if self.opts.checksum {
             if let Ok(dst_sum) = self.strong_file_checksum(&dest) {
-                let src_sum = self.strong_file_checksum(path)?;
+                let src_sum = self.cached_file_checksum(path)?;
                 if src_sum == dst_sum {
                     recv.copy_metadata(path, &dest, None)?;
                     return Ok(false);
@@ -183,12 +280,16 @@ impl Sender {
             None
         };
         let src = open_for_read(path, &self.opts).map_err(|e| io_context(path, e))?;
-        let mut src_reader = BufReader::new(src);
-        let file_codec = if should_compress(path, &self.opts.skip_compress) {
-            self.codec
-        } else {
-            None
-        };
+        let sent_sum = (self.opts.verify_after_write || self.opts.post_verify)
+            .then(|| SentSum::new(Mutex::new(None)));
+        let mut src_reader = SumReader::new(BufReader::new(src), &self.cfg, sent_sum.clone());
+        let file_codec = codec_for_path(
+            path,
+            self.codec,
+            &self.codecs,
+            &self.opts.skip_compress,
+            &self.opts.compress_map,
+        );
         let (partial_path, basename_partial) =
             partial_paths(&dest, self.opts.partial_dir.as_deref());
         let existing_partial = if partial_path.exists() {
@@ -198,10 +299,18 @@ impl Sender {
         } else {
             None
         };
-        let basis_path = if self.opts.partial || self.opts.append || self.opts.append_verify {
-            existing_partial
-                .clone()
-                .ok_or_else(|| EngineError::MissingPartial(partial_path.clone()))?
+        let basis_path = if self.opts.append || self.opts.append_verify {
+            let target = match existing_partial.clone() {
+                Some(p) => p,
+                None if dest.exists() => dest.clone(),
+                None => return Err(EngineError::MissingPartial(partial_path.clone())),
+            };
+            recv.lock_append_target(&target)?;
+            target
+        } else if self.opts.partial
+            && let Some(p) = existing_partial.clone()
+        {
+            p
         } else if self.opts.fuzzy && !dest.exists() {
             fuzzy_match(&dest).unwrap_or_else(|| dest.clone())
         } else {
@@ -219,6 +328,8 @@ impl Sender {
         if resume > src_len {
             resume = src_len;
         }
+        let skip_holes = self.opts.sparse && self.opts.inplace && !self.opts.cdc;
+        let mut holes = Vec::new();
         let mut basis_reader: Box<dyn ReadSeek> = if self.opts.whole_file {
             Box::new(Cursor::new(Vec::new()))
         } else {
@@ -226,7 +337,15 @@ impl Sender {
                 Ok(f) => {
                     let len = f.metadata().map(|m| m.len()).unwrap_or(0);
                     ensure_max_alloc(len, &self.opts)?;
-                    Box::new(BufReader::new(f))
+                    if skip_holes
+                        && let Ok(basis_data) = f.data_regions()
+                        && let Ok(src_data) = src_reader.get_ref().get_ref().data_regions()
+                    {
+                        holes = common_holes(&basis_data, &src_data, len.min(src_len));
+                        Box::new(BufReader::new(HoleReader::new(f, basis_data, len)))
+                    } else {
+                        Box::new(BufReader::new(f))
+                    }
                 }
                 Err(_) => Box::new(Cursor::new(Vec::new())),
             }
@@ -245,14 +364,19 @@ impl Sender {
                     }
                 }))
             } else {
-                Box::new(compute_delta(
-                    &self.cfg,
-                    &mut basis_reader,
-                    &mut src_reader,
-                    block_size,
-                    DEFAULT_BASIS_WINDOW,
-                    &self.opts,
-                )?)
+                self.pool.clear();
+                Box::new(
+                    compute_delta(
+                        &self.cfg,
+                        &mut basis_reader,
+                        &mut src_reader,
+                        block_size,
+                        DEFAULT_BASIS_WINDOW,
+                        &self.opts,
+                    )?
+                    .skip_holes(holes)
+                    .with_pool(self.pool.clone()),
+                )
             };
         if self.opts.backup && dest.exists() {
             let backup_path = if let Some(ref dir) = self.opts.backup_dir {
@@ -316,46 +440,34 @@ impl Sender {
             }
             Err(e) => Some(Err(e)),
         });
-        let adjusted = adjusted.inspect(move |op_res| {
-            if let Ok(op) = op_res {
-                match op {
-                    Op::Data(d) => stats_ref.literal_data += d.len() as u64,
-                    Op::Copy { len, .. } => {
-                        stats_ref.matched_data += *len as u64;
-                    }
-                }
-            }
-        });
+        let mut file_comp = file_codec
+            .map(|codec| file_compressor(codec, src_len, &self.opts))
+            .transpose()
+            .map_err(EngineError::from)?;
+        let pool = &self.pool;
         let ops = adjusted.map(|op_res| {
             let mut op = op_res?;
-            if let Some(codec) = file_codec {
+            match &op {
+                Op::Data(d) => stats_ref.literal_data += d.len() as u64,
+                Op::Copy { len, .. } => {
+                    stats_ref.matched_data += *len as u64;
+                }
+            }
+            if let Some(comp) = &mut file_comp {
                 if let Op::Data(ref mut d) = op {
-                    *d = match codec {
-                        Codec::Zlib | Codec::ZlibX => {
-                            let lvl = self.opts.compress_level.unwrap_or(6);
-                            let mut out = Vec::new();
-                            let mut cursor = d.as_slice();
-                            Zlib::new(lvl)
-                                .compress(&mut cursor, &mut out)
-                                .map_err(EngineError::from)?;
-                            out
-                        }
-                        Codec::Zstd => {
-                            let lvl = self.opts.compress_level.unwrap_or(0);
-                            let mut out = Vec::new();
-                            let mut cursor = d.as_slice();
-                            Zstd::new(lvl)
-                                .compress(&mut cursor, &mut out)
-                                .map_err(EngineError::from)?;
-                            out
-                        }
-                    };
+                    stats_ref.compress_calls += 1;
+                    stats_ref.pre_compression_bytes += d.len() as u64;
+                    let out = comp.compress(d).map_err(EngineError::from)?;
+                    pool.put(std::mem::replace(d, out));
+                    stats_ref.post_compression_bytes += d.len() as u64;
                 }
             }
             Ok(op)
         });
         if !self.opts.only_write_batch {
-            recv.apply(path, &dest, rel, ops)?;
+            recv.expect_sent_sum(sent_sum.clone());
+            recv.apply_with_codec(path, &dest, rel, file_codec, ops)?;
+            self.sent_sum = sent_sum.and_then(|sum| sum.lock().unwrap().take());
             drop(atime_guard);
             recv.copy_metadata(path, &dest, None)?;
         } else {
@@ -0,0 +1,72 @@
+// crates/engine/src/session/batch.rs
+
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::Path;
+
+use crate::batch::{BatchFile, parse_batch_file};
+use crate::io::io_context;
+use crate::{EngineError, Receiver, Result, Sender};
+
+use super::verify::verify_file_count;
+use super::{Stats, SyncOptions};
+
+/// Replays the native batch at `batch_path` from `src_root` into `dst`,
+/// refusing to start if any recorded source checksum no longer matches.
+pub(super) fn replay_batch(
+    batch_path: &Path,
+    src_root: &Path,
+    dst: &Path,
+    sender: &mut Sender,
+    receiver: &mut Receiver,
+    opts: &SyncOptions,
+    stats: &mut Stats,
+) -> Result<()> {
+    let (files, listed) = parse_batch_file(batch_path)?;
+    for file in &files {
+        let Some(sum) = &file.sum else {
+            continue;
+        };
+        let path = src_root.join(&file.path);
+        if sender.strong_file_checksum(&path).ok().as_ref() != Some(sum) {
+            return Err(EngineError::Other(format!(
+                "batch does not match source: {} changed since the batch was written",
+                path.display()
+            )));
+        }
+    }
+    sender.start();
+    let mut received = 0usize;
+    for BatchFile { path: rel, .. } in files {
+        received += 1;
+        let path = src_root.join(&rel);
+        if !path.exists() {
+            continue;
+        }
+        let Some(dest_rel) = opts.dest_rel(&rel)? else {
+            continue;
+        };
+        let dest_path = dst.join(dest_rel);
+        if sender.process_file(&path, &dest_path, &rel, receiver, stats)? {
+            stats.files_transferred += 1;
+            stats.bytes_transferred += fs::metadata(&path).map_err(|e| io_context(&path, e))?.len();
+        }
+    }
+    sender.finish(stats);
+    receiver.finalize()?;
+    stats.paced_fs_ops = receiver.paced_fs_ops();
+    if let Some(listed) = listed {
+        verify_file_count(listed, received, opts, stats)?;
+    }
+    Ok(())
+}
+
+pub(super) fn write_batch_trailer(file: Option<File>, stats: &Stats) {
+    if let Some(mut f) = file {
+        let _ = writeln!(
+            f,
+            "files_transferred={} bytes_transferred={}",
+            stats.files_transferred, stats.bytes_transferred
+        );
+    }
+}
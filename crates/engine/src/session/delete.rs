@@ -0,0 +1,228 @@
+// crates/engine/src/session/delete.rs
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::Ordering;
+use std::time::Instant;
+
+use filters::Matcher;
+use logging::{InfoFlag, escape_path};
+use protocol::UnicodeForm;
+use walk::walk;
+
+use crate::cleanup::{atomic_rename, remove_dir_opts, remove_file_opts};
+use crate::io::io_context;
+use crate::progress::{CHECKED_FILES, TOTAL_FILES};
+use crate::{EngineError, Result};
+
+use super::run::check_time_limit;
+use super::{Stats, SyncOptions};
+
+pub(super) fn delete_limit_reached(opts: &SyncOptions, stats: &Stats) -> bool {
+    opts.max_delete
+        .is_some_and(|max| stats.files_deleted >= max)
+}
+
+/// Whether `src` holds an entry whose components match those of `rel` once
+/// both are put into `form`.
+fn normalized_source_exists(src: &Path, rel: &Path, form: UnicodeForm) -> bool {
+    let mut dir = src.to_path_buf();
+    for comp in rel.components() {
+        let name = comp.as_os_str();
+        if dir.join(name).symlink_metadata().is_ok() {
+            dir.push(name);
+            continue;
+        }
+        let Some(want) = name.to_str().map(|s| form.normalize_str(s)) else {
+            return false;
+        };
+        let Ok(entries) = fs::read_dir(&dir) else {
+            return false;
+        };
+        let Some(found) = entries
+            .flatten()
+            .map(|e| e.file_name())
+            .find(|n| n.to_str().is_some_and(|s| form.normalize_str(s) == want))
+        else {
+            return false;
+        };
+        dir.push(found);
+    }
+    true
+}
+
+pub(super) fn delete_extraneous(
+    src: &Path,
+    dst: &Path,
+    matcher: &Matcher,
+    opts: &SyncOptions,
+    stats: &mut Stats,
+    start: Instant,
+) -> Result<()> {
+    let mut walker = walk(dst, 1, None, opts.walk_links(), opts.one_file_system, &[])?;
+    let mut state = String::new();
+    let mut first_err: Option<EngineError> = None;
+    while let Some(batch) = walker.next() {
+        check_time_limit(start, opts)?;
+        let batch = batch.map_err(|e| EngineError::Other(e.to_string()))?;
+        let mut skip_dirs: Vec<PathBuf> = Vec::new();
+        for entry in batch {
+            check_time_limit(start, opts)?;
+            let path = entry.apply(&mut state);
+            if skip_dirs.iter().any(|d| path.starts_with(d)) {
+                continue;
+            }
+            let file_type = entry.file_type;
+            if let Ok(rel) = path.strip_prefix(dst) {
+                let res = matcher
+                    .is_included_for_delete_with_dir(rel)
+                    .map_err(|e| EngineError::Other(format!("{:?}", e)))?;
+                let src_exists = src.join(rel).exists()
+                    || opts
+                        .normalize_names
+                        .is_some_and(|form| normalized_source_exists(src, rel, form));
+                if file_type.is_dir() {
+                    if (res.include && !src_exists) || (!res.include && opts.delete_excluded) {
+                        if delete_limit_reached(opts, stats) {
+                            stats.deletes_skipped += 1;
+                            walker.skip_current_dir();
+                            skip_dirs.push(path.clone());
+                            continue;
+                        }
+                        if !opts.quiet {
+                            tracing::info!(
+                                target: InfoFlag::Del.target(),
+                                "deleting {}",
+                                escape_path(rel, opts.eight_bit_output)
+                            );
+                        }
+                        let res = if opts.dry_run || opts.only_write_batch {
+                            None
+                        } else if opts.backup {
+                            let backup_path = if let Some(ref dir) = opts.backup_dir {
+                                let mut p = dir.join(rel);
+                                if !opts.backup_suffix.is_empty() {
+                                    if let Some(name) = p.file_name() {
+                                        p = p.with_file_name(format!(
+                                            "{}{}",
+                                            name.to_string_lossy(),
+                                            &opts.backup_suffix
+                                        ));
+                                    } else {
+                                        p.push(&opts.backup_suffix);
+                                    }
+                                }
+                                p
+                            } else {
+                                let name = path
+                                    .file_name()
+                                    .map(|n| {
+                                        format!("{}{}", n.to_string_lossy(), &opts.backup_suffix)
+                                    })
+                                    .unwrap_or_else(|| opts.backup_suffix.clone());
+                                path.with_file_name(name)
+                            };
+                            let dir_res = if let Some(parent) = backup_path.parent() {
+                                fs::create_dir_all(parent).map_err(|e| io_context(parent, e))
+                            } else {
+                                Ok(())
+                            };
+                            dir_res
+                                .and_then(|_| atomic_rename(&path, &backup_path))
+                                .err()
+                        } else {
+                            remove_dir_opts(&path, opts).err()
+                        };
+                        walker.skip_current_dir();
+                        skip_dirs.push(path.clone());
+                        match res {
+                            None => {
+                                stats.files_deleted += 1;
+                                if opts.progress {
+                                    TOTAL_FILES.fetch_add(1, Ordering::SeqCst);
+                                    CHECKED_FILES.fetch_add(1, Ordering::SeqCst);
+                                }
+                            }
+                            Some(e) => {
+                                if first_err.is_none() {
+                                    first_err = Some(e);
+                                }
+                            }
+                        }
+                    } else if !res.include {
+                        walker.skip_current_dir();
+                        if !res.descend || file_type.is_dir() {
+                            skip_dirs.push(path.clone());
+                        }
+                    }
+                } else if (res.include && !src_exists) || (!res.include && opts.delete_excluded) {
+                    if delete_limit_reached(opts, stats) {
+                        stats.deletes_skipped += 1;
+                        continue;
+                    }
+                    if !opts.quiet {
+                        tracing::info!(
+                            target: InfoFlag::Del.target(),
+                            "deleting {}",
+                            escape_path(rel, opts.eight_bit_output)
+                        );
+                    }
+                    let res = if opts.dry_run || opts.only_write_batch {
+                        None
+                    } else if opts.backup {
+                        let backup_path = if let Some(ref dir) = opts.backup_dir {
+                            let mut p = dir.join(rel);
+                            if !opts.backup_suffix.is_empty() {
+                                if let Some(name) = p.file_name() {
+                                    p = p.with_file_name(format!(
+                                        "{}{}",
+                                        name.to_string_lossy(),
+                                        &opts.backup_suffix
+                                    ));
+                                } else {
+                                    p.push(&opts.backup_suffix);
+                                }
+                            }
+                            p
+                        } else {
+                            let name = path
+                                .file_name()
+                                .map(|n| format!("{}{}", n.to_string_lossy(), &opts.backup_suffix))
+                                .unwrap_or_else(|| opts.backup_suffix.clone());
+                            path.with_file_name(name)
+                        };
+                        let dir_res = if let Some(parent) = backup_path.parent() {
+                            fs::create_dir_all(parent).map_err(|e| io_context(parent, e))
+                        } else {
+                            Ok(())
+                        };
+                        dir_res
+                            .and_then(|_| atomic_rename(&path, &backup_path))
+                            .err()
+                    } else {
+                        remove_file_opts(&path, opts).err()
+                    };
+                    match res {
+                        None => {
+                            stats.files_deleted += 1;
+                            if opts.progress {
+                                TOTAL_FILES.fetch_add(1, Ordering::SeqCst);
+                                CHECKED_FILES.fetch_add(1, Ordering::SeqCst);
+                            }
+                        }
+                        Some(e) => {
+                            if first_err.is_none() {
+                                first_err = Some(e);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+    if let Some(e) = first_err {
+        if opts.ignore_errors { Ok(()) } else { Err(e) }
+    } else {
+        Ok(())
+    }
+}
@@ -0,0 +1,121 @@
+// crates/engine/src/session/links.rs
+
+use std::ffi::OsString;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use crate::Result;
+use crate::io::io_context;
+
+use super::SyncOptions;
+
+const MUNGE_PREFIX: &str = "/rsyncd-munged/";
+
+fn munge_target(target: &Path) -> PathBuf {
+    let mut munged = OsString::from(MUNGE_PREFIX);
+    munged.push(target.as_os_str());
+    PathBuf::from(munged)
+}
+
+#[cfg(unix)]
+fn unmunge_target(target: &Path) -> PathBuf {
+    use std::ffi::OsStr;
+    use std::os::unix::ffi::OsStrExt;
+
+    match target
+        .as_os_str()
+        .as_bytes()
+        .strip_prefix(MUNGE_PREFIX.as_bytes())
+    {
+        Some(rest) => PathBuf::from(OsStr::from_bytes(rest)),
+        None => target.to_path_buf(),
+    }
+}
+
+#[cfg(not(unix))]
+fn unmunge_target(target: &Path) -> PathBuf {
+    match target.as_os_str().to_str() {
+        Some(s) if s.starts_with(MUNGE_PREFIX) => PathBuf::from(&s[MUNGE_PREFIX.len()..]),
+        _ => target.to_path_buf(),
+    }
+}
+
+pub(super) fn is_unsafe_symlink(target: &Path, rel: &Path) -> bool {
+    if target.as_os_str().is_empty() || target.has_root() {
+        return true;
+    }
+    let mut depth = rel.parent().map_or(0, |p| p.components().count());
+    for comp in target.components() {
+        match comp {
+            Component::ParentDir => {
+                if depth == 0 {
+                    return true;
+                }
+                depth -= 1;
+            }
+            Component::Normal(_) => depth += 1,
+            Component::CurDir => {}
+            Component::RootDir | Component::Prefix(_) => return true,
+        }
+    }
+    false
+}
+
+pub(super) fn replace_dirlink(dest: &Path, opts: &SyncOptions) -> Result<()> {
+    let Ok(meta) = fs::symlink_metadata(dest) else {
+        return Ok(());
+    };
+    if !meta.file_type().is_symlink() || (opts.keep_dirlinks && dest.is_dir()) {
+        return Ok(());
+    }
+    fs::remove_file(dest).map_err(|e| io_context(dest, e))?;
+    fs::create_dir(dest).map_err(|e| io_context(dest, e))
+}
+
+pub(super) fn copies_referent(path: &Path, rel: &Path, opts: &SyncOptions) -> bool {
+    opts.copy_unsafe_links
+        && fs::read_link(path).is_ok_and(|target| is_unsafe_symlink(&target, rel))
+        && fs::metadata(path).is_ok_and(|meta| meta.is_file())
+}
+
+/// Recreates the symlink at `path` as `dest`, returning whether `dest` now
+/// holds it so the caller can apply its metadata.
+pub(super) fn transfer_symlink(
+    path: &Path,
+    dest: &Path,
+    rel: &Path,
+    opts: &SyncOptions,
+) -> Result<bool> {
+    if !opts.links {
+        return Ok(false);
+    }
+    let mut target = fs::read_link(path).map_err(|e| io_context(path, e))?;
+    if opts.munge_links {
+        target = unmunge_target(&target);
+    }
+    if opts.safe_links && is_unsafe_symlink(&target, rel) {
+        tracing::info!(
+            "ignoring unsafe symlink \"{}\" -> \"{}\"",
+            rel.display(),
+            target.display()
+        );
+        return Ok(false);
+    }
+    if opts.munge_links {
+        target = munge_target(&target);
+    }
+    if fs::read_link(dest).is_ok_and(|existing| existing == target) {
+        return Ok(true);
+    }
+    match fs::symlink_metadata(dest) {
+        Ok(meta) if meta.is_dir() => fs::remove_dir(dest).map_err(|e| io_context(dest, e))?,
+        Ok(_) => fs::remove_file(dest).map_err(|e| io_context(dest, e))?,
+        Err(_) => {}
+    }
+    if let Some(parent) = dest.parent() {
+        fs::create_dir_all(parent).map_err(|e| io_context(parent, e))?;
+    }
+    #[cfg(unix)]
+    std::os::unix::fs::symlink(&target, dest).map_err(|e| io_context(dest, e))?;
+    Ok(cfg!(unix))
+}
@@ -0,0 +1,126 @@
+// crates/engine/src/session/list.rs
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+use filters::Matcher;
+use logging::{escape_path, human_bytes, permstring, progress_formatter, timestring};
+use walk::walk;
+
+use crate::checksum_cache::is_cache_file;
+use crate::io::io_context;
+use crate::{EngineError, Result};
+
+use super::SyncOptions;
+use super::run::{check_time_limit, outside_age_bounds, outside_size_bounds};
+
+pub(super) fn list_entries(
+    src_root: &Path,
+    matcher: &Matcher,
+    opts: &SyncOptions,
+    start: Instant,
+) -> Result<()> {
+    let age_ref = opts.age_reference.unwrap_or_else(SystemTime::now);
+    let mut walker = walk(
+        src_root,
+        1024,
+        None,
+        opts.walk_links(),
+        opts.one_file_system,
+        &[],
+    )?
+    .follow_dirlinks(opts.copy_dirlinks);
+    let mut state = String::new();
+    while let Some(batch) = walker.next() {
+        check_time_limit(start, opts)?;
+        let batch = batch.map_err(|e| EngineError::Other(e.to_string()))?;
+        let mut skip_dirs: Vec<PathBuf> = Vec::new();
+        for entry in batch {
+            let path = entry.apply(&mut state);
+            if skip_dirs.iter().any(|d| path.starts_with(d)) {
+                continue;
+            }
+            if let Ok(rel) = path.strip_prefix(src_root) {
+                let res = matcher
+                    .is_included_with_dir(rel)
+                    .map_err(|e| EngineError::Other(format!("{:?}", e)))?;
+                if !res.include {
+                    if !res.descend && entry.file_type.is_dir() {
+                        walker.skip_current_dir();
+                        skip_dirs.push(path.clone());
+                    }
+                    continue;
+                }
+                if entry.file_type.is_dir() {
+                    if !rel.as_os_str().is_empty() && !res.descend {
+                        walker.skip_current_dir();
+                        skip_dirs.push(path.clone());
+                    }
+                } else if entry.file_type.is_file() {
+                    if opts.dirs_only || (opts.checksum_cache && is_cache_file(&path)) {
+                        continue;
+                    }
+                    let meta = fs::metadata(&path).map_err(|e| io_context(&path, e))?;
+                    if outside_size_bounds(meta.len(), opts)
+                        || outside_age_bounds(&meta, age_ref, opts)
+                    {
+                        continue;
+                    }
+                }
+                if !opts.quiet {
+                    println!("{}", list_line(&path, rel, opts)?);
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn list_line(path: &Path, rel: &Path, opts: &SyncOptions) -> Result<String> {
+    let meta = fs::symlink_metadata(path).map_err(|e| io_context(path, e))?;
+    #[cfg(unix)]
+    let mode = {
+        use std::os::unix::fs::MetadataExt;
+        meta.mode()
+    };
+    #[cfg(not(unix))]
+    let mode = {
+        let kind = if meta.is_dir() { 0o040000 } else { 0o100000 };
+        let perms = if meta.permissions().readonly() {
+            0o444
+        } else {
+            0o644
+        };
+        kind | perms
+    };
+    let size = if opts.human_readable {
+        human_bytes(meta.len())
+    } else {
+        progress_formatter(meta.len(), false)
+    };
+    let mtime = meta.modified().unwrap_or(UNIX_EPOCH);
+    let name = if !rel.as_os_str().is_empty() {
+        escape_path(rel, opts.eight_bit_output)
+    } else if meta.is_dir() {
+        ".".to_string()
+    } else {
+        path.file_name()
+            .map(|n| escape_path(Path::new(n), opts.eight_bit_output))
+            .unwrap_or_else(|| ".".to_string())
+    };
+    let mut line = format!(
+        "{} {:>14} {} {}",
+        permstring(mode),
+        size,
+        timestring(mtime),
+        name
+    );
+    if meta.file_type().is_symlink() {
+        if let Ok(target) = fs::read_link(path) {
+            line.push_str(" -> ");
+            line.push_str(&escape_path(&target, opts.eight_bit_output));
+        }
+    }
+    Ok(line)
+}
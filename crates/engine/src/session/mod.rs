@@ -1,19 +1,34 @@
 // crates/engine/src/session/mod.rs
 
-use std::collections::HashSet;
+use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::Arc;
-use std::time::{Duration, Instant, SystemTime};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
-use compress::Codec;
+use compress::{Codec, SkipCompress};
+use protocol::UnicodeForm;
 
 use crate::StrongHash;
+use crate::batch::BatchFormat;
 
+mod batch;
+mod delete;
+mod links;
+mod list;
+mod quick_check;
 mod run;
 mod setup;
+mod specials;
+mod transform;
+mod verify;
 
+pub use quick_check::quick_check;
 pub use run::{pipe_sessions, sync};
-pub use setup::select_codec;
+pub use setup::{
+    FileCompressor, FileDecompressor, ZSTD_LDM_THRESHOLD, file_compressor, file_decompressor,
+    select_codec,
+};
+pub use transform::{PathTransform, PathTransformFn};
 
 #[derive(Clone)]
 pub struct IdMapper(pub Arc<dyn Fn(u32) -> u32 + Send + Sync>);
@@ -42,10 +57,18 @@ pub struct SyncOptions {
     pub force: bool,
     pub max_delete: Option<usize>,
     pub max_alloc: usize,
+    pub max_blocks: usize,
+    pub cdc: bool,
     pub max_size: Option<u64>,
     pub min_size: Option<u64>,
+    pub exclude_older_than: Option<Duration>,
+    pub exclude_newer_than: Option<Duration>,
+    pub age_reference: Option<SystemTime>,
+    pub max_name_len: Option<usize>,
+    pub max_path_len: Option<usize>,
     pub preallocate: bool,
     pub checksum: bool,
+    pub checksum_cache: bool,
     pub compress: bool,
     pub dirs_only: bool,
     pub no_implied_dirs: bool,
@@ -59,8 +82,11 @@ pub struct SyncOptions {
     pub ignore_times: bool,
     pub perms: bool,
     pub executability: bool,
+    pub keep_dest_perms: bool,
+    pub umask: Option<u32>,
     pub times: bool,
     pub atimes: bool,
+    pub compare_atimes: bool,
     pub crtimes: bool,
     pub omit_dir_times: bool,
     pub omit_link_times: bool,
@@ -77,6 +103,10 @@ pub struct SyncOptions {
     pub devices: bool,
     pub specials: bool,
     pub fsync: bool,
+    pub verify_after_write: bool,
+    pub post_verify: bool,
+    pub fs_op_rate: Option<u32>,
+    pub metadata_only: bool,
     pub fuzzy: bool,
     pub super_user: bool,
     pub fake_super: bool,
@@ -86,11 +116,13 @@ pub struct SyncOptions {
     pub acls: bool,
     pub sparse: bool,
     pub strong: StrongHash,
+    pub file_strong: Option<StrongHash>,
     pub checksum_seed: u32,
     pub compress_level: Option<i32>,
     pub compress_choice: Option<Vec<Codec>>,
     pub whole_file: bool,
-    pub skip_compress: HashSet<String>,
+    pub skip_compress: SkipCompress,
+    pub compress_map: HashMap<String, Option<Codec>>,
     pub partial: bool,
     pub progress: bool,
     pub human_readable: bool,
@@ -107,6 +139,7 @@ pub struct SyncOptions {
     pub bwlimit: Option<u64>,
     pub stop_after: Option<Duration>,
     pub stop_at: Option<SystemTime>,
+    pub greeting_timeout: Option<Duration>,
     pub block_size: usize,
     pub link_dest: Option<PathBuf>,
     pub copy_dest: Option<PathBuf>,
@@ -120,18 +153,25 @@ pub struct SyncOptions {
     pub eight_bit_output: bool,
     pub blocking_io: bool,
     pub open_noatime: bool,
+    pub direct_io: bool,
     pub early_input: Option<PathBuf>,
+    pub protocol: Option<u32>,
     pub secluded_args: bool,
     pub sockopts: Vec<String>,
     pub remote_options: Vec<String>,
     pub write_batch: Option<PathBuf>,
     pub only_write_batch: bool,
     pub read_batch: Option<PathBuf>,
+    pub batch_format: BatchFormat,
     pub copy_devices: bool,
     pub write_devices: bool,
     pub quiet: bool,
     pub uid_map: Option<IdMapper>,
     pub gid_map: Option<IdMapper>,
+    pub path_transform: Option<PathTransform>,
+    /// Unicode form destination names are written in, so a decomposed source
+    /// name matches an existing composed destination name and vice versa.
+    pub normalize_names: Option<UnicodeForm>,
 }
 
 impl Default for SyncOptions {
@@ -146,10 +186,18 @@ impl Default for SyncOptions {
             force: false,
             max_delete: None,
             max_alloc: 0,
+            max_blocks: 0,
+            cdc: false,
             max_size: None,
             min_size: None,
+            exclude_older_than: None,
+            exclude_newer_than: None,
+            age_reference: None,
+            max_name_len: None,
+            max_path_len: None,
             preallocate: false,
             checksum: false,
+            checksum_cache: false,
             compress: false,
             dirs_only: false,
             no_implied_dirs: false,
@@ -163,8 +211,11 @@ impl Default for SyncOptions {
             ignore_times: false,
             perms: false,
             executability: false,
+            keep_dest_perms: false,
+            umask: None,
             times: false,
             atimes: false,
+            compare_atimes: false,
             crtimes: false,
             omit_dir_times: false,
             omit_link_times: false,
@@ -181,6 +232,10 @@ impl Default for SyncOptions {
             devices: false,
             specials: false,
             fsync: false,
+            verify_after_write: false,
+            post_verify: false,
+            fs_op_rate: None,
+            metadata_only: false,
             fuzzy: false,
             super_user: false,
             fake_super: false,
@@ -190,11 +245,13 @@ impl Default for SyncOptions {
             acls: false,
             sparse: false,
             strong: StrongHash::Md4,
+            file_strong: None,
             checksum_seed: 0,
             compress_level: None,
             compress_choice: None,
             whole_file: false,
-            skip_compress: HashSet::new(),
+            skip_compress: SkipCompress::default(),
+            compress_map: HashMap::new(),
             partial: false,
             progress: false,
             human_readable: false,
@@ -211,6 +268,7 @@ impl Default for SyncOptions {
             bwlimit: None,
             stop_after: None,
             stop_at: None,
+            greeting_timeout: None,
             block_size: 0,
             link_dest: None,
             copy_dest: None,
@@ -224,18 +282,23 @@ impl Default for SyncOptions {
             eight_bit_output: false,
             blocking_io: false,
             open_noatime: false,
+            direct_io: false,
             early_input: None,
+            protocol: None,
             secluded_args: false,
             sockopts: Vec::new(),
             remote_options: Vec::new(),
             write_batch: None,
             only_write_batch: false,
             read_batch: None,
+            batch_format: BatchFormat::Native,
             copy_devices: false,
             write_devices: false,
             quiet: false,
             uid_map: None,
             gid_map: None,
+            path_transform: None,
+            normalize_names: None,
         }
     }
 }
@@ -295,12 +358,21 @@ pub struct Stats {
     pub bytes_transferred: u64,
     pub literal_data: u64,
     pub matched_data: u64,
+    pub pre_compression_bytes: u64,
+    pub post_compression_bytes: u64,
+    pub compress_calls: u64,
+    pub file_checksums: u64,
+    pub paced_fs_ops: u64,
     pub file_list_size: u64,
     pub file_list_gen_time: Duration,
     pub file_list_transfer_time: Duration,
     pub bytes_sent: u64,
     pub bytes_received: u64,
+    pub had_partial_errors: bool,
+    pub file_errors: usize,
+    pub deletes_skipped: usize,
     pub start_time: Instant,
+    pub started_at: SystemTime,
 }
 
 impl Default for Stats {
@@ -316,12 +388,21 @@ impl Default for Stats {
             bytes_transferred: 0,
             literal_data: 0,
             matched_data: 0,
+            pre_compression_bytes: 0,
+            post_compression_bytes: 0,
+            compress_calls: 0,
+            file_checksums: 0,
+            paced_fs_ops: 0,
             file_list_size: 0,
             file_list_gen_time: Duration::default(),
             file_list_transfer_time: Duration::default(),
             bytes_sent: 0,
             bytes_received: 0,
+            had_partial_errors: false,
+            file_errors: 0,
+            deletes_skipped: 0,
             start_time: Instant::now(),
+            started_at: SystemTime::now(),
         }
     }
 }
@@ -330,4 +411,42 @@ impl Stats {
     pub fn elapsed(&self) -> Duration {
         self.start_time.elapsed()
     }
+
+    /// Literal bytes before compression per byte sent after it, or `1.0`
+    /// when nothing was compressed.
+    pub fn compression_ratio(&self) -> f64 {
+        if self.post_compression_bytes == 0 {
+            1.0
+        } else {
+            self.pre_compression_bytes as f64 / self.post_compression_bytes as f64
+        }
+    }
+
+    /// Wall-clock time the transfer ended, measured from `started_at`.
+    pub fn end_time(&self) -> SystemTime {
+        self.started_at + self.elapsed()
+    }
+
+    /// Renders the stats as space-separated `key=value` pairs for log
+    /// scraping. Timestamps are seconds since the Unix epoch.
+    pub fn machine_summary(&self) -> String {
+        let elapsed = self.elapsed();
+        let unix = |t: SystemTime| {
+            t.duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0)
+        };
+        format!(
+            "files_transferred={} files_deleted={} bytes_transferred={} bytes_sent={} \
+             bytes_received={} start_time={} end_time={} elapsed_secs={:.3}",
+            self.files_transferred,
+            self.files_deleted,
+            self.bytes_transferred,
+            self.bytes_sent,
+            self.bytes_received,
+            unix(self.started_at),
+            unix(self.started_at + elapsed),
+            elapsed.as_secs_f64()
+        )
+    }
 }
@@ -0,0 +1,41 @@
+// crates/engine/src/session/quick_check.rs
+
+use std::fs::Metadata;
+use std::time::{Duration, SystemTime};
+
+use super::SyncOptions;
+
+fn time_diff(a: SystemTime, b: SystemTime) -> Duration {
+    a.duration_since(b)
+        .or_else(|_| b.duration_since(a))
+        .unwrap_or(Duration::ZERO)
+}
+
+/// The rsync "quick check": returns `true` when `dst` can be assumed to match
+/// `src` without reading either file.
+///
+/// Sizes must be equal and modification times must lie within
+/// `modify_window` (plus access times with `compare_atimes`). `size_only`
+/// ignores times altogether, while `ignore_times` never considers a file
+/// unchanged.
+pub fn quick_check(src: &Metadata, dst: &Metadata, opts: &SyncOptions) -> bool {
+    if opts.size_only {
+        return src.len() == dst.len();
+    }
+    if opts.ignore_times || src.len() != dst.len() {
+        return false;
+    }
+    let (Ok(sm), Ok(dm)) = (src.modified(), dst.modified()) else {
+        return false;
+    };
+    if time_diff(sm, dm) > opts.modify_window {
+        return false;
+    }
+    if opts.atimes && opts.compare_atimes {
+        return match (src.accessed(), dst.accessed()) {
+            (Ok(sa), Ok(da)) => time_diff(sa, da) <= opts.modify_window,
+            _ => true,
+        };
+    }
+    true
+}
@@ -1,7 +1,7 @@
 // crates/engine/src/session/run.rs
 
+use std::borrow::Cow;
 use std::fs::{self, OpenOptions};
-use std::io::Write;
 use std::path::{Path, PathBuf};
 use std::sync::atomic::Ordering;
 use std::time::{Instant, SystemTime};
@@ -11,23 +11,32 @@ use nix::unistd::{Gid, Uid, chown};
 
 use compress::Codec;
 use filters::Matcher;
-use logging::{InfoFlag, escape_path};
 use protocol::ExitCode;
 use transport::{Transport, pipe};
 use walk::walk;
 
-use crate::batch::parse_batch_file;
+use crate::batch::{BatchFormat, write_batch_entry, write_rsync_batch};
+use crate::checksum_cache::is_cache_file;
 use crate::cleanup::{atomic_rename, remove_dir_opts, remove_file_opts};
-use crate::delta::{FILE_COUNTER, PROGRESS_HEADER, TOTAL_FILES};
 use crate::io::io_context;
+use crate::progress::{
+    CHECKED_FILES, FILE_COUNTER, LAST_PROGRESS, PROGRESS_HEADER, TOTAL_FILES, finish_progress,
+};
 use crate::{EngineError, Receiver, Result, Sender};
 
+use super::batch::{replay_batch, write_batch_trailer};
+use super::delete::{delete_extraneous, delete_limit_reached};
+use super::links::{copies_referent, replace_dirlink, transfer_symlink};
+use super::list::list_entries;
 use super::select_codec;
 use super::setup::count_entries;
+use super::specials::{recreates_node, transfer_node};
+use super::transform::check_path_transform;
+use super::verify::{verify_file_count, verify_files};
 use super::{DeleteMode, Stats, SyncOptions};
 use crate::is_remote_spec;
 
-fn check_time_limit(start: Instant, opts: &SyncOptions) -> Result<()> {
+pub(super) fn check_time_limit(start: Instant, opts: &SyncOptions) -> Result<()> {
     if let Some(limit) = opts.stop_after {
         if start.elapsed() >= limit {
             return Err(EngineError::Exit(
@@ -47,7 +56,7 @@ fn check_time_limit(start: Instant, opts: &SyncOptions) -> Result<()> {
     Ok(())
 }
 
-fn outside_size_bounds(len: u64, opts: &SyncOptions) -> bool {
+pub(super) fn outside_size_bounds(len: u64, opts: &SyncOptions) -> bool {
     if let Some(min) = opts.min_size {
         if len < min {
             return true;
@@ -61,168 +70,37 @@ fn outside_size_bounds(len: u64, opts: &SyncOptions) -> bool {
     false
 }
 
-fn delete_extraneous(
-    src: &Path,
-    dst: &Path,
-    matcher: &Matcher,
+/// Whether a file's mtime falls outside `--exclude-older-than` /
+/// `--exclude-newer-than` as measured from `reference`. A file exactly at a
+/// threshold is kept.
+pub(super) fn outside_age_bounds(
+    meta: &fs::Metadata,
+    reference: SystemTime,
     opts: &SyncOptions,
-    stats: &mut Stats,
-    start: Instant,
-) -> Result<()> {
-    let mut walker = walk(dst, 1, None, opts.walk_links(), opts.one_file_system, &[])?;
-    let mut state = String::new();
-    let mut first_err: Option<EngineError> = None;
-    while let Some(batch) = walker.next() {
-        check_time_limit(start, opts)?;
-        let batch = batch.map_err(|e| EngineError::Other(e.to_string()))?;
-        let mut skip_dirs: Vec<PathBuf> = Vec::new();
-        for entry in batch {
-            check_time_limit(start, opts)?;
-            let path = entry.apply(&mut state);
-            if skip_dirs.iter().any(|d| path.starts_with(d)) {
-                continue;
-            }
-            let file_type = entry.file_type;
-            if let Ok(rel) = path.strip_prefix(dst) {
-                let res = matcher
-                    .is_included_for_delete_with_dir(rel)
-                    .map_err(|e| EngineError::Other(format!("{:?}", e)))?;
-                let src_exists = src.join(rel).exists();
-                if file_type.is_dir() {
-                    if (res.include && !src_exists) || (!res.include && opts.delete_excluded) {
-                        if let Some(max) = opts.max_delete {
-                            if stats.files_deleted >= max {
-                                return Err(EngineError::Other("max-delete limit exceeded".into()));
-                            }
-                        }
-                        if !opts.quiet {
-                            tracing::info!(
-                                target: InfoFlag::Del.target(),
-                                "deleting {}",
-                                escape_path(rel, opts.eight_bit_output)
-                            );
-                        }
-                        let res = if opts.dry_run || opts.only_write_batch {
-                            None
-                        } else if opts.backup {
-                            let backup_path = if let Some(ref dir) = opts.backup_dir {
-                                let mut p = dir.join(rel);
-                                if !opts.backup_suffix.is_empty() {
-                                    if let Some(name) = p.file_name() {
-                                        p = p.with_file_name(format!(
-                                            "{}{}",
-                                            name.to_string_lossy(),
-                                            &opts.backup_suffix
-                                        ));
-                                    } else {
-                                        p.push(&opts.backup_suffix);
-                                    }
-                                }
-                                p
-                            } else {
-                                let name = path
-                                    .file_name()
-                                    .map(|n| {
-                                        format!("{}{}", n.to_string_lossy(), &opts.backup_suffix)
-                                    })
-                                    .unwrap_or_else(|| opts.backup_suffix.clone());
-                                path.with_file_name(name)
-                            };
-                            let dir_res = if let Some(parent) = backup_path.parent() {
-                                fs::create_dir_all(parent).map_err(|e| io_context(parent, e))
-                            } else {
-                                Ok(())
-                            };
-                            dir_res
-                                .and_then(|_| atomic_rename(&path, &backup_path))
-                                .err()
-                        } else {
-                            remove_dir_opts(&path, opts).err()
-                        };
-                        walker.skip_current_dir();
-                        skip_dirs.push(path.clone());
-                        match res {
-                            None => {
-                                stats.files_deleted += 1;
-                            }
-                            Some(e) => {
-                                if first_err.is_none() {
-                                    first_err = Some(e);
-                                }
-                            }
-                        }
-                    } else if !res.include {
-                        walker.skip_current_dir();
-                        if !res.descend || file_type.is_dir() {
-                            skip_dirs.push(path.clone());
-                        }
-                    }
-                } else if (res.include && !src_exists) || (!res.include && opts.delete_excluded) {
-                    if let Some(max) = opts.max_delete {
-                        if stats.files_deleted >= max {
-                            return Err(EngineError::Other("max-delete limit exceeded".into()));
-                        }
-                    }
-                    if !opts.quiet {
-                        tracing::info!(
-                            target: InfoFlag::Del.target(),
-                            "deleting {}",
-                            escape_path(rel, opts.eight_bit_output)
-                        );
-                    }
-                    let res = if opts.dry_run || opts.only_write_batch {
-                        None
-                    } else if opts.backup {
-                        let backup_path = if let Some(ref dir) = opts.backup_dir {
-                            let mut p = dir.join(rel);
-                            if !opts.backup_suffix.is_empty() {
-                                if let Some(name) = p.file_name() {
-                                    p = p.with_file_name(format!(
-                                        "{}{}",
-                                        name.to_string_lossy(),
-                                        &opts.backup_suffix
-                                    ));
-                                } else {
-                                    p.push(&opts.backup_suffix);
-                                }
-                            }
-                            p
-                        } else {
-                            let name = path
-                                .file_name()
-                                .map(|n| format!("{}{}", n.to_string_lossy(), &opts.backup_suffix))
-                                .unwrap_or_else(|| opts.backup_suffix.clone());
-                            path.with_file_name(name)
-                        };
-                        let dir_res = if let Some(parent) = backup_path.parent() {
-                            fs::create_dir_all(parent).map_err(|e| io_context(parent, e))
-                        } else {
-                            Ok(())
-                        };
-                        dir_res
-                            .and_then(|_| atomic_rename(&path, &backup_path))
-                            .err()
-                    } else {
-                        remove_file_opts(&path, opts).err()
-                    };
-                    match res {
-                        None => {
-                            stats.files_deleted += 1;
-                        }
-                        Some(e) => {
-                            if first_err.is_none() {
-                                first_err = Some(e);
-                            }
-                        }
-                    }
-                }
-            }
-        }
+) -> bool {
+    if opts.exclude_older_than.is_none() && opts.exclude_newer_than.is_none() {
+        return false;
     }
-    if let Some(e) = first_err {
-        if opts.ignore_errors { Ok(()) } else { Err(e) }
-    } else {
-        Ok(())
+    let Ok(mtime) = meta.modified() else {
+        return false;
+    };
+    let age = reference.duration_since(mtime).unwrap_or_default();
+    opts.exclude_older_than.is_some_and(|max| age > max)
+        || opts.exclude_newer_than.is_some_and(|min| age < min)
+}
+
+fn skips_new_entry(dest: &Path, opts: &SyncOptions) -> bool {
+    opts.existing && fs::symlink_metadata(dest).is_err()
+}
+
+fn exceeds_path_limits(receiver: &Receiver, dest: &Path, stats: &mut Stats) -> bool {
+    match receiver.check_path_limits(dest) {
+        Ok(()) => false,
+        Err(e) => {
+            tracing::warn!("skipping {e}");
+            stats.had_partial_errors = true;
+            true
+        }
     }
 }
 
@@ -246,9 +124,12 @@ pub fn sync(
     remote: &[Codec],
     opts: &SyncOptions,
 ) -> Result<Stats> {
-    let batch_file = opts
+    check_path_transform(opts)?;
+
+    let mut batch_file = opts
         .write_batch
         .as_ref()
+        .filter(|_| opts.batch_format == BatchFormat::Native)
         .and_then(|p| OpenOptions::new().create(true).append(true).open(p).ok());
     let src_is_remote = is_remote_spec(src.as_os_str());
     let dst_is_remote = is_remote_spec(dst.as_os_str());
@@ -259,13 +140,18 @@ pub fn sync(
     };
     let mut stats = Stats::default();
     let start = Instant::now();
+    let age_ref = opts.age_reference.unwrap_or_else(SystemTime::now);
     if !src_is_remote && !src_root.exists() {
         if opts.delete_missing_args {
-            if !dst_is_remote && dst.exists() {
-                if let Some(max) = opts.max_delete {
-                    if stats.files_deleted >= max {
-                        return Err(EngineError::Other("max-delete limit exceeded".into()));
-                    }
+            let target = match src.file_name() {
+                Some(name) if dst.is_dir() => dst.join(name),
+                _ => dst.to_path_buf(),
+            };
+            let dst = target.as_path();
+            if !dst_is_remote && fs::symlink_metadata(dst).is_ok() {
+                if delete_limit_reached(opts, &stats) {
+                    stats.deletes_skipped += 1;
+                    return Ok(stats);
                 }
                 let meta = fs::symlink_metadata(dst).map_err(|e| io_context(dst, e))?;
                 let res = if opts.backup {
@@ -334,61 +220,7 @@ pub fn sync(
     }
 
     if opts.list_only {
-        let mut walker = walk(
-            &src_root,
-            1024,
-            None,
-            opts.walk_links(),
-            opts.one_file_system,
-            &[],
-        )?;
-        let mut state = String::new();
-        while let Some(batch) = walker.next() {
-            check_time_limit(start, opts)?;
-            let batch = batch.map_err(|e| EngineError::Other(e.to_string()))?;
-            let mut skip_dirs: Vec<PathBuf> = Vec::new();
-            for entry in batch {
-                let path = entry.apply(&mut state);
-                if skip_dirs.iter().any(|d| path.starts_with(d)) {
-                    continue;
-                }
-                if let Ok(rel) = path.strip_prefix(&src_root) {
-                    let res = matcher
-                        .is_included_with_dir(rel)
-                        .map_err(|e| EngineError::Other(format!("{:?}", e)))?;
-                    if !res.include {
-                        if !res.descend && entry.file_type.is_dir() {
-                            walker.skip_current_dir();
-                            skip_dirs.push(path.clone());
-                        }
-                        continue;
-                    }
-                    if entry.file_type.is_dir() {
-                        if !rel.as_os_str().is_empty() && !res.descend {
-                            walker.skip_current_dir();
-                            skip_dirs.push(path.clone());
-                        }
-                    } else if entry.file_type.is_file() {
-                        if opts.dirs_only {
-                            continue;
-                        }
-                        let len = fs::metadata(&path).map_err(|e| io_context(&path, e))?.len();
-                        if outside_size_bounds(len, opts) {
-                            continue;
-                        }
-                    }
-                    if !opts.quiet {
-                        if rel.as_os_str().is_empty() {
-                            println!(".");
-                        } else if entry.file_type.is_dir() {
-                            println!("{}/", escape_path(rel, opts.eight_bit_output));
-                        } else {
-                            println!("{}", escape_path(rel, opts.eight_bit_output));
-                        }
-                    }
-                }
-            }
-        }
+        list_entries(&src_root, matcher, opts, start)?;
         return Ok(stats);
     }
 
@@ -402,7 +234,9 @@ pub fn sync(
     stats.total_file_size = total_size;
     if opts.progress {
         FILE_COUNTER.store(0, Ordering::SeqCst);
+        CHECKED_FILES.store(0, Ordering::SeqCst);
         PROGRESS_HEADER.store(false, Ordering::SeqCst);
+        *LAST_PROGRESS.lock().unwrap() = None;
         TOTAL_FILES.store(file_cnt, Ordering::SeqCst);
     }
     if opts.dry_run {
@@ -443,33 +277,21 @@ pub fn sync(
         }
     }
 
-    let mut sender = Sender::new(matcher.clone(), codec, opts.clone());
+    let mut sender = Sender::new(matcher.clone(), codec, opts.clone()).with_codecs(remote);
     let mut receiver = Receiver::new(codec, opts.clone());
     receiver.matcher = matcher.clone();
 
     if let Some(batch_path) = &opts.read_batch {
-        sender.start();
-        for rel in parse_batch_file(batch_path)? {
-            let path = src_root.join(&rel);
-            if !path.exists() {
-                continue;
-            }
-            let dest_path = dst.join(&rel);
-            if sender.process_file(&path, &dest_path, &rel, &mut receiver, &mut stats)? {
-                stats.files_transferred += 1;
-                stats.bytes_transferred +=
-                    fs::metadata(&path).map_err(|e| io_context(&path, e))?.len();
-            }
-        }
-        sender.finish();
-        receiver.finalize()?;
-        if let Some(mut f) = batch_file {
-            let _ = writeln!(
-                f,
-                "files_transferred={} bytes_transferred={}",
-                stats.files_transferred, stats.bytes_transferred
-            );
-        }
+        replay_batch(
+            batch_path,
+            &src_root,
+            dst,
+            &mut sender,
+            &mut receiver,
+            opts,
+            &mut stats,
+        )?;
+        write_batch_trailer(batch_file, &stats);
         return Ok(stats);
     }
     if !dst_is_remote && matches!(opts.delete, Some(DeleteMode::Before)) {
@@ -479,6 +301,9 @@ pub fn sync(
     sender.start();
     stats.file_list_transfer_time = flist_xfer_start.elapsed();
     let mut state = String::new();
+    let mut listed = 0usize;
+    let mut received = 0usize;
+    let mut transferred: Vec<(PathBuf, Option<Vec<u8>>)> = Vec::new();
     let mut walker = walk(
         &src_root,
         1024,
@@ -486,7 +311,8 @@ pub fn sync(
         opts.walk_links(),
         opts.one_file_system,
         &[],
-    )?;
+    )?
+    .follow_dirlinks(opts.copy_dirlinks);
     while let Some(batch) = walker.next() {
         check_time_limit(start, opts)?;
         let batch = batch.map_err(|e| EngineError::Other(e.to_string()))?;
@@ -506,8 +332,20 @@ pub fn sync(
                     }
                     continue;
                 }
+                let dest_rel = if rel.as_os_str().is_empty() {
+                    Some(Cow::Borrowed(rel))
+                } else {
+                    opts.dest_rel(rel)?
+                };
                 if entry.file_type.is_dir() {
-                    let dest_path = dst.join(rel);
+                    let Some(dest_rel) = dest_rel else {
+                        if !res.descend {
+                            walker.skip_current_dir();
+                            skip_dirs.push(path.clone());
+                        }
+                        continue;
+                    };
+                    let dest_path = dst.join(dest_rel);
                     if rel.as_os_str().is_empty() {
                         #[cfg(feature = "acl")]
                         if opts.acls && !dst_is_remote {
@@ -515,10 +353,18 @@ pub fn sync(
                         }
                         continue;
                     }
+                    if !dst_is_remote
+                        && (exceeds_path_limits(&receiver, &dest_path, &mut stats)
+                            || skips_new_entry(&dest_path, opts))
+                    {
+                        continue;
+                    }
+                    if !dst_is_remote {
+                        replace_dirlink(&dest_path, opts)?;
+                    }
                     if opts.dirs_only {
                         if !dst_is_remote {
-                            fs::create_dir_all(&dest_path)
-                                .map_err(|e| io_context(&dest_path, e))?;
+                            receiver.create_dirs(&dest_path)?;
                             receiver.copy_metadata_now(&path, &dest_path, None)?;
                             stats.files_created += 1;
                             stats.dirs_created += 1;
@@ -527,8 +373,7 @@ pub fn sync(
                     }
                     if !res.descend {
                         if !dst_is_remote {
-                            fs::create_dir_all(&dest_path)
-                                .map_err(|e| io_context(&dest_path, e))?;
+                            receiver.create_dirs(&dest_path)?;
                             receiver.copy_metadata_now(&path, &dest_path, None)?;
                             stats.files_created += 1;
                             stats.dirs_created += 1;
@@ -537,36 +382,92 @@ pub fn sync(
                         skip_dirs.push(path.clone());
                         continue;
                     }
-                } else if entry.file_type.is_file() {
-                    if opts.dirs_only {
+                } else if entry.file_type.is_file()
+                    || (entry.file_type.is_symlink() && copies_referent(&path, rel, opts))
+                {
+                    if opts.dirs_only || (opts.checksum_cache && is_cache_file(&path)) {
+                        continue;
+                    }
+                    listed += 1;
+                    if opts.progress {
+                        CHECKED_FILES.fetch_add(1, Ordering::SeqCst);
+                    }
+                    let Some(dest_rel) = dest_rel else {
+                        received += 1;
+                        continue;
+                    };
+                    let dest_path = dst.join(&dest_rel);
+                    if !dst_is_remote && exceeds_path_limits(&receiver, &dest_path, &mut stats) {
+                        received += 1;
                         continue;
                     }
-                    let len = fs::metadata(&path).map_err(|e| io_context(&path, e))?.len();
-                    if outside_size_bounds(len, opts) {
+                    let meta = fs::metadata(&path).map_err(|e| io_context(&path, e))?;
+                    let len = meta.len();
+                    if outside_size_bounds(len, opts) || outside_age_bounds(&meta, age_ref, opts) {
+                        received += 1;
+                        continue;
+                    }
+                    match sender.process_file(&path, &dest_path, rel, &mut receiver, &mut stats) {
+                        Ok(true) => {
+                            received += 1;
+                            stats.files_transferred += 1;
+                            stats.bytes_transferred += len;
+                            if let Some(f) = batch_file.as_mut() {
+                                write_batch_entry(f, rel, &sender.strong_file_checksum(&path)?)?;
+                            }
+                            if opts.post_verify && !dst_is_remote {
+                                transferred.push((rel.to_path_buf(), sender.take_sent_sum()));
+                            }
+                        }
+                        Ok(false) => received += 1,
+                        Err(e) if opts.ignore_errors => {
+                            received += 1;
+                            tracing::error!("{}: {e}", rel.display());
+                            stats.file_errors += 1;
+                            stats.had_partial_errors = true;
+                        }
+                        Err(e) => return Err(e),
+                    }
+                } else if let Some(dest_rel) = dest_rel {
+                    let dest_path = dst.join(dest_rel);
+                    if !dst_is_remote
+                        && (exceeds_path_limits(&receiver, &dest_path, &mut stats)
+                            || skips_new_entry(&dest_path, opts))
+                    {
                         continue;
                     }
-                    if sender.process_file(&path, &dst.join(rel), rel, &mut receiver, &mut stats)? {
-                        stats.files_transferred += 1;
-                        stats.bytes_transferred += len;
+                    if entry.file_type.is_symlink() && !dst_is_remote {
+                        if transfer_symlink(&path, &dest_path, rel, opts)? {
+                            receiver.copy_metadata_now(&path, &dest_path, None)?;
+                        }
+                    } else if !dst_is_remote && recreates_node(&entry.file_type, rel, opts) {
+                        transfer_node(&path, &dest_path)?;
+                        receiver.copy_metadata_now(&path, &dest_path, None)?;
                     }
                 }
             }
         }
     }
-    sender.finish();
+    sender.finish(&mut stats);
     receiver.finalize()?;
+    stats.paced_fs_ops = receiver.paced_fs_ops();
+    verify_file_count(listed, received, opts, &mut stats)?;
+    verify_files(&sender.cfg, dst, &transferred, opts)?;
     if !dst_is_remote && matches!(opts.delete, Some(DeleteMode::During)) {
         delete_extraneous(&src_root, dst, &matcher, opts, &mut stats, start)?;
     }
     if !dst_is_remote && matches!(opts.delete, Some(DeleteMode::After)) {
         delete_extraneous(&src_root, dst, &matcher, opts, &mut stats, start)?;
     }
-    if let Some(mut f) = batch_file {
-        let _ = writeln!(
-            f,
-            "files_transferred={} bytes_transferred={}",
-            stats.files_transferred, stats.bytes_transferred
-        );
+    if opts.progress {
+        finish_progress();
+    }
+    write_batch_trailer(batch_file, &stats);
+    if let Some(batch_path) = &opts.write_batch
+        && opts.batch_format == BatchFormat::Rsync
+        && !src_is_remote
+    {
+        write_rsync_batch(&src_root, &matcher, opts, batch_path)?;
     }
     Ok(stats)
 }
@@ -1,15 +1,21 @@
 // crates/engine/src/session/setup.rs
 
 use std::fs;
+use std::io;
 use std::path::{Path, PathBuf};
 
-use compress::Codec;
+use compress::{
+    Codec, Compressor, Decompressor, ZstdStream, ZstdStreamDecoder, compressor,
+    compressor_with_level, decompressor,
+};
 use filters::Matcher;
 use walk::walk;
 
 use crate::Result;
+use crate::checksum_cache::is_cache_file;
 
 use super::SyncOptions;
+use super::links::copies_referent;
 
 pub(crate) fn count_entries(
     src_root: &Path,
@@ -23,7 +29,8 @@ pub(crate) fn count_entries(
         opts.walk_links(),
         opts.one_file_system,
         &[],
-    )?;
+    )?
+    .follow_dirlinks(opts.copy_dirlinks);
     let mut state = String::new();
     let mut files = 0usize;
     let mut dirs = 0usize;
@@ -51,8 +58,10 @@ pub(crate) fn count_entries(
                         walker.skip_current_dir();
                         skip_dirs.push(path.clone());
                     }
-                } else if entry.file_type.is_file() {
-                    if opts.dirs_only {
+                } else if entry.file_type.is_file()
+                    || (entry.file_type.is_symlink() && copies_referent(&path, rel, opts))
+                {
+                    if opts.dirs_only || (opts.checksum_cache && is_cache_file(&path)) {
                         continue;
                     }
                     files += 1;
@@ -66,6 +75,64 @@ pub(crate) fn count_entries(
     Ok((files, dirs, size))
 }
 
+pub const ZSTD_LDM_THRESHOLD: u64 = 64 * 1024 * 1024;
+
+pub enum FileCompressor {
+    Chunks(Box<dyn Compressor>),
+    Zstd(ZstdStream),
+}
+
+impl FileCompressor {
+    pub fn compress(&mut self, chunk: &[u8]) -> io::Result<Vec<u8>> {
+        match self {
+            FileCompressor::Chunks(comp) => comp.compress_to_vec(chunk),
+            FileCompressor::Zstd(stream) => stream.compress_chunk(chunk),
+        }
+    }
+}
+
+pub enum FileDecompressor {
+    Chunks(Box<dyn Decompressor>),
+    Zstd(ZstdStreamDecoder),
+}
+
+impl FileDecompressor {
+    pub fn decompress(&mut self, chunk: &[u8]) -> io::Result<Vec<u8>> {
+        match self {
+            FileDecompressor::Chunks(dec) => dec.decompress_to_vec(chunk),
+            FileDecompressor::Zstd(stream) => stream.decompress_chunk(chunk),
+        }
+    }
+}
+
+pub fn file_compressor(
+    codec: Codec,
+    file_len: u64,
+    opts: &SyncOptions,
+) -> io::Result<FileCompressor> {
+    if codec == Codec::Zstd {
+        let range = codec.level_range();
+        let level = opts
+            .compress_level
+            .unwrap_or(0)
+            .clamp(*range.start(), *range.end());
+        let stream = ZstdStream::new(level, file_len >= ZSTD_LDM_THRESHOLD)?;
+        return Ok(FileCompressor::Zstd(stream));
+    }
+    match opts.compress_level {
+        Some(level) => compressor_with_level(codec, level),
+        None => compressor(codec),
+    }
+    .map(FileCompressor::Chunks)
+}
+
+pub fn file_decompressor(codec: Codec) -> io::Result<FileDecompressor> {
+    if codec == Codec::Zstd {
+        return ZstdStreamDecoder::new().map(FileDecompressor::Zstd);
+    }
+    decompressor(codec).map(FileDecompressor::Chunks)
+}
+
 pub fn select_codec(remote: &[Codec], opts: &SyncOptions) -> Option<Codec> {
     if !opts.compress || opts.compress_level == Some(0) {
         return None;
@@ -76,5 +143,8 @@ pub fn select_codec(remote: &[Codec], opts: &SyncOptions) -> Option<Codec> {
         v.push(Codec::Zlib);
         v
     });
+    if remote.is_empty() {
+        return choices.contains(&Codec::Zlib).then_some(Codec::Zlib);
+    }
     choices.into_iter().find(|c| remote.contains(c))
 }
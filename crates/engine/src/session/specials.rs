@@ -0,0 +1,99 @@
+// crates/engine/src/session/specials.rs
+
+use std::fs::{self, FileType};
+#[cfg(unix)]
+use std::os::unix::fs::FileTypeExt;
+use std::path::Path;
+
+#[cfg(target_os = "linux")]
+use caps::{CapSet, Capability};
+#[cfg(unix)]
+use nix::unistd::Uid;
+
+use crate::Result;
+use crate::io::{io_context, is_device};
+
+use super::SyncOptions;
+
+#[cfg(unix)]
+pub(super) fn is_special(file_type: &FileType) -> bool {
+    file_type.is_fifo() || file_type.is_socket()
+}
+
+#[cfg(not(unix))]
+pub(super) fn is_special(_file_type: &FileType) -> bool {
+    false
+}
+
+#[cfg(unix)]
+fn can_mknod(opts: &SyncOptions) -> bool {
+    if opts.super_user || Uid::effective().is_root() {
+        return true;
+    }
+    #[cfg(target_os = "linux")]
+    {
+        caps::has_cap(None, CapSet::Effective, Capability::CAP_MKNOD).unwrap_or(false)
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        false
+    }
+}
+
+#[cfg(not(unix))]
+fn can_mknod(_opts: &SyncOptions) -> bool {
+    false
+}
+
+pub(super) fn recreates_node(file_type: &FileType, rel: &Path, opts: &SyncOptions) -> bool {
+    if is_special(file_type) {
+        return opts.specials;
+    }
+    if !is_device(file_type) || !opts.devices {
+        return false;
+    }
+    if !can_mknod(opts) {
+        tracing::info!("skipping device \"{}\" without CAP_MKNOD", rel.display());
+        return false;
+    }
+    true
+}
+
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+pub(super) fn transfer_node(path: &Path, dest: &Path) -> Result<()> {
+    use meta::{Mode, SFlag};
+    use std::os::unix::fs::MetadataExt;
+
+    let src_meta = fs::symlink_metadata(path).map_err(|e| io_context(path, e))?;
+    let src_type = src_meta.file_type();
+    match fs::symlink_metadata(dest) {
+        Ok(existing) if existing.file_type() == src_type && existing.rdev() == src_meta.rdev() => {
+            return Ok(());
+        }
+        Ok(existing) if existing.is_dir() => {
+            fs::remove_dir(dest).map_err(|e| io_context(dest, e))?
+        }
+        Ok(_) => fs::remove_file(dest).map_err(|e| io_context(dest, e))?,
+        Err(_) => {}
+    }
+    if let Some(parent) = dest.parent() {
+        fs::create_dir_all(parent).map_err(|e| io_context(parent, e))?;
+    }
+    let perm = Mode::from_bits_truncate((src_meta.mode() & 0o7777) as _);
+    if src_type.is_fifo() {
+        return meta::mkfifo(dest, perm).map_err(|e| io_context(dest, e));
+    }
+    let (kind, rdev) = if src_type.is_char_device() {
+        (SFlag::S_IFCHR, src_meta.rdev())
+    } else if src_type.is_block_device() {
+        (SFlag::S_IFBLK, src_meta.rdev())
+    } else {
+        (SFlag::S_IFSOCK, 0)
+    };
+    meta::mknod(dest, kind, perm, rdev).map_err(|e| io_context(dest, e))
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+pub(super) fn transfer_node(_path: &Path, _dest: &Path) -> Result<()> {
+    Ok(())
+}
@@ -0,0 +1,74 @@
+// crates/engine/src/session/transform.rs
+
+use std::borrow::Cow;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+
+use protocol::{ExitCode, UnicodeForm};
+
+use crate::{EngineError, Result};
+
+use super::SyncOptions;
+
+pub type PathTransformFn = dyn Fn(&Path) -> Option<PathBuf> + Send + Sync;
+
+/// Maps a path relative to the transfer root to the path it is written to
+/// below the destination. Returning `None` skips the entry. Deletion compares
+/// untransformed names, so `sync` refuses a transform together with `--delete`.
+#[derive(Clone)]
+pub struct PathTransform(pub Arc<PathTransformFn>);
+
+impl std::fmt::Debug for PathTransform {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("PathTransform")
+    }
+}
+
+pub(super) fn check_path_transform(opts: &SyncOptions) -> Result<()> {
+    if opts.path_transform.is_some() && opts.delete.is_some() {
+        return Err(EngineError::Exit(
+            ExitCode::SyntaxOrUsage,
+            "a path transform cannot be combined with --delete".into(),
+        ));
+    }
+    Ok(())
+}
+
+impl SyncOptions {
+    /// Destination path for the source-relative `rel`, or `None` when the
+    /// path transform skips it. A transformed path must stay relative and
+    /// below the destination; the result is then put into `normalize_names`
+    /// form.
+    pub(crate) fn dest_rel<'a>(&self, rel: &'a Path) -> Result<Option<Cow<'a, Path>>> {
+        let rel = match &self.path_transform {
+            None => Cow::Borrowed(rel),
+            Some(transform) => {
+                let Some(mapped) = (transform.0)(rel) else {
+                    return Ok(None);
+                };
+                if mapped
+                    .components()
+                    .any(|c| !matches!(c, Component::Normal(_) | Component::CurDir))
+                {
+                    return Err(EngineError::Other(format!(
+                        "path transform mapped {} outside the destination: {}",
+                        rel.display(),
+                        mapped.display()
+                    )));
+                }
+                Cow::Owned(mapped)
+            }
+        };
+        Ok(Some(match self.normalize_names {
+            Some(form) => normalize_path(rel, form),
+            None => rel,
+        }))
+    }
+}
+
+fn normalize_path(path: Cow<'_, Path>, form: UnicodeForm) -> Cow<'_, Path> {
+    if let Some(Cow::Owned(s)) = path.to_str().map(|s| form.normalize_str(s)) {
+        return Cow::Owned(PathBuf::from(s));
+    }
+    path
+}
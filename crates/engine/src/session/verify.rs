@@ -0,0 +1,68 @@
+// crates/engine/src/session/verify.rs
+
+use std::path::{Path, PathBuf};
+
+use checksums::ChecksumConfig;
+use logging::escape_path;
+use protocol::ExitCode;
+
+use crate::io::file_strong_sum;
+use crate::{EngineError, Result};
+
+use super::{Stats, SyncOptions};
+
+/// Re-reads every transferred file in `files` on the destination and
+/// compares it with the whole-file checksum taken while it was sent, failing
+/// with a partial-transfer error if any copy differs.
+pub(super) fn verify_files(
+    cfg: &ChecksumConfig,
+    dst: &Path,
+    files: &[(PathBuf, Option<Vec<u8>>)],
+    opts: &SyncOptions,
+) -> Result<()> {
+    if files.is_empty() {
+        return Ok(());
+    }
+    let mut mismatched = 0usize;
+    for (rel, sent) in files {
+        let Some(dest_rel) = opts.dest_rel(rel)? else {
+            continue;
+        };
+        let actual = file_strong_sum(cfg, &dst.join(dest_rel)).ok();
+        if sent.is_none() || actual != *sent {
+            tracing::error!(
+                "verification failed: {}",
+                escape_path(rel, opts.eight_bit_output)
+            );
+            mismatched += 1;
+        }
+    }
+    if mismatched == 0 {
+        return Ok(());
+    }
+    Err(EngineError::Exit(
+        ExitCode::Partial,
+        format!("post-transfer verification failed for {mismatched} file(s)"),
+    ))
+}
+
+/// Compares the number of files in the sender's list with the number the
+/// receiver handled, so entries lost in between fail the transfer.
+pub(super) fn verify_file_count(
+    listed: usize,
+    received: usize,
+    opts: &SyncOptions,
+    stats: &mut Stats,
+) -> Result<()> {
+    if listed == received {
+        return Ok(());
+    }
+    let msg = format!("file list mismatch: {listed} files listed but {received} received");
+    if opts.ignore_errors {
+        tracing::warn!("{msg}");
+        stats.had_partial_errors = true;
+        Ok(())
+    } else {
+        Err(EngineError::Other(msg))
+    }
+}
@@ -2,10 +2,22 @@
 use std::fs;
 
 use compress::available_codecs;
-use engine::{SyncOptions, sync};
+use engine::{Stats, SyncOptions, sync};
 use filters::Matcher;
 use tempfile::tempdir;
 
+/// The parts of a replay's outcome that do not depend on timing.
+fn outcome(s: &Stats) -> [u64; 6] {
+    [
+        s.files_total as u64,
+        s.files_transferred as u64,
+        s.total_file_size,
+        s.literal_data,
+        s.matched_data,
+        s.bytes_transferred,
+    ]
+}
+
 #[test]
 fn replay_is_deterministic() {
     let tmp = tempdir().unwrap();
@@ -29,7 +41,6 @@ fn replay_is_deterministic() {
     .unwrap();
 
     fs::write(src.join("file2"), b"later").unwrap();
-    fs::write(src.join("file"), b"hello").unwrap();
 
     let dst1 = tmp.path().join("dst1");
     let dst2 = tmp.path().join("dst2");
@@ -60,11 +71,129 @@ fn replay_is_deterministic() {
     )
     .unwrap();
 
-    assert_eq!(stats1, stats2);
+    assert_eq!(outcome(&stats1), outcome(&stats2));
     assert_eq!(
         fs::read(dst1.join("file")).unwrap(),
         fs::read(dst2.join("file")).unwrap()
     );
+    assert_eq!(fs::read(dst1.join("file")).unwrap(), b"hi");
     assert!(!dst1.join("file2").exists());
     assert!(!dst2.join("file2").exists());
 }
+
+#[test]
+fn replay_rejects_modified_source() {
+    let tmp = tempdir().unwrap();
+    let src = tmp.path().join("src");
+    let record_dst = tmp.path().join("record");
+    fs::create_dir_all(&src).unwrap();
+    fs::create_dir_all(&record_dst).unwrap();
+    fs::write(src.join("file"), b"hi").unwrap();
+    fs::write(src.join("other"), b"same").unwrap();
+
+    let batch = tmp.path().join("batch.log");
+    sync(
+        &src,
+        &record_dst,
+        &Matcher::default(),
+        &available_codecs(),
+        &SyncOptions {
+            write_batch: Some(batch.clone()),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let replay = |dst: &std::path::Path| {
+        fs::create_dir_all(dst).unwrap();
+        sync(
+            &src,
+            dst,
+            &Matcher::default(),
+            &available_codecs(),
+            &SyncOptions {
+                read_batch: Some(batch.clone()),
+                ..Default::default()
+            },
+        )
+    };
+
+    let clean = tmp.path().join("clean");
+    replay(&clean).unwrap();
+    assert_eq!(fs::read(clean.join("file")).unwrap(), b"hi");
+    assert_eq!(fs::read(clean.join("other")).unwrap(), b"same");
+
+    fs::write(src.join("file"), b"changed").unwrap();
+    let stale = tmp.path().join("stale");
+    let err = replay(&stale).unwrap_err();
+    assert!(
+        err.to_string().contains("batch does not match source"),
+        "{err}"
+    );
+    assert!(!stale.join("file").exists());
+    assert!(!stale.join("other").exists());
+}
+
+#[test]
+fn replay_detects_truncated_file_list() {
+    let tmp = tempdir().unwrap();
+    let src = tmp.path().join("src");
+    let record_dst = tmp.path().join("record");
+    fs::create_dir_all(&src).unwrap();
+    fs::create_dir_all(&record_dst).unwrap();
+    for name in ["a", "b", "c"] {
+        fs::write(src.join(name), name).unwrap();
+    }
+
+    let batch = tmp.path().join("batch.log");
+    sync(
+        &src,
+        &record_dst,
+        &Matcher::default(),
+        &available_codecs(),
+        &SyncOptions {
+            write_batch: Some(batch.clone()),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let log = fs::read_to_string(&batch).unwrap();
+    let lines: Vec<&str> = log.lines().collect();
+    let at = lines.iter().position(|l| *l == "b").unwrap();
+    let truncated: Vec<&str> = lines[..at]
+        .iter()
+        .chain(&lines[at + 2..])
+        .copied()
+        .collect();
+    fs::write(&batch, truncated.join("\n")).unwrap();
+
+    let replay = |dst: &std::path::Path, ignore_errors: bool| {
+        fs::create_dir_all(dst).unwrap();
+        sync(
+            &src,
+            dst,
+            &Matcher::default(),
+            &available_codecs(),
+            &SyncOptions {
+                read_batch: Some(batch.clone()),
+                ignore_errors,
+                ..Default::default()
+            },
+        )
+    };
+
+    let err = replay(&tmp.path().join("strict"), false).unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("file list mismatch: 3 files listed but 2 received"),
+        "{err}"
+    );
+
+    let lenient = tmp.path().join("lenient");
+    let stats = replay(&lenient, true).unwrap();
+    assert!(stats.had_partial_errors);
+    assert_eq!(fs::read(lenient.join("a")).unwrap(), b"a");
+    assert_eq!(fs::read(lenient.join("c")).unwrap(), b"c");
+    assert!(!lenient.join("b").exists());
+}
@@ -1,8 +1,13 @@
 // crates/engine/tests/block_size.rs
 
 use checksums::ChecksumConfigBuilder;
-use engine::{Op, Stats, SyncOptions, compute_delta};
+use engine::{
+    MAX_BLOCK_COUNT, MAX_BLOCK_SIZE, Op, Stats, SyncOptions, block_count, capped_block_size,
+    compute_delta,
+};
+use std::fs::File;
 use std::io::Cursor;
+use tempfile::tempdir;
 
 #[test]
 fn block_size_literal_stats() {
@@ -79,3 +84,76 @@ fn block_size_unaligned_literal_stats() {
 
     assert_eq!(stats.literal_data, (block_size * 2) as u64);
 }
+
+#[test]
+fn block_cap_forces_larger_blocks() {
+    assert_eq!(capped_block_size(1 << 20, 700, 0), 700);
+    assert_eq!(capped_block_size(4096, 1024, 4), 1024);
+    assert_eq!(capped_block_size(64 * 1024, 512, 4), 16 * 1024);
+    assert_eq!(capped_block_size(10_001, 100, 10), 1001);
+
+    let len = 64 * 1024;
+    let basis: Vec<u8> = (0..len).map(|i| (i * 7 % 251) as u8).collect();
+    let mut target = basis.clone();
+    target[100..200].fill(0xEE);
+
+    let cfg = ChecksumConfigBuilder::new().build();
+    let mut basis_f = Cursor::new(basis.clone());
+    let mut target_f = Cursor::new(target.clone());
+    let opts = SyncOptions {
+        max_blocks: 4,
+        ..SyncOptions::default()
+    };
+    let ops: Vec<Op> = compute_delta(&cfg, &mut basis_f, &mut target_f, 512, usize::MAX, &opts)
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+
+    let mut out = Vec::new();
+    for op in ops {
+        match op {
+            Op::Data(d) => out.extend(d),
+            Op::Copy { offset, len } => {
+                assert_eq!(len, 16 * 1024);
+                out.extend(&basis[offset..offset + len]);
+            }
+        }
+    }
+    assert_eq!(out, target);
+}
+
+#[test]
+fn block_count_is_bounded() {
+    assert_eq!(block_count(0, 1).unwrap(), 0);
+    assert_eq!(block_count(MAX_BLOCK_COUNT, 1).unwrap(), MAX_BLOCK_COUNT);
+    assert_eq!(
+        block_count(MAX_BLOCK_COUNT * 700, 700).unwrap(),
+        MAX_BLOCK_COUNT
+    );
+    assert!(block_count(MAX_BLOCK_COUNT + 1, 1).is_err());
+    assert!(block_count(u64::MAX, MAX_BLOCK_SIZE).is_err());
+}
+
+#[test]
+fn oversized_block_count_errors_cleanly() {
+    let tmp = tempdir().unwrap();
+    let path = tmp.path().join("huge");
+    let file = File::create(&path).unwrap();
+    file.set_len(MAX_BLOCK_COUNT + 1).unwrap();
+    drop(file);
+
+    let cfg = ChecksumConfigBuilder::new().build();
+    let mut basis = File::open(&path).unwrap();
+    let mut target = Cursor::new(b"target".to_vec());
+    let err = compute_delta(
+        &cfg,
+        &mut basis,
+        &mut target,
+        1,
+        usize::MAX,
+        &SyncOptions::default(),
+    )
+    .err()
+    .expect("block count above the limit must be rejected");
+    assert!(err.to_string().contains("blocks of 1 bytes"), "{err}");
+}
@@ -0,0 +1,41 @@
+// crates/engine/tests/block_table.rs
+
+use checksums::ChecksumConfigBuilder;
+use engine::{BlockTable, Op, SyncOptions, compute_delta, compute_delta_with_table};
+use std::io::Cursor;
+
+#[test]
+fn shared_table_matches_per_file_delta() {
+    let block_size = 512usize;
+    let basis: Vec<u8> = (0..block_size * 8).map(|i| (i % 251) as u8).collect();
+    let mut first = basis.clone();
+    first[block_size..block_size * 2].fill(0x11);
+    let mut second = basis[block_size * 3..].to_vec();
+    second.extend_from_slice(b"tail");
+
+    let cfg = ChecksumConfigBuilder::new().build();
+    let opts = SyncOptions::default();
+    let table = BlockTable::build(&cfg, &mut Cursor::new(basis.clone()), block_size).unwrap();
+    assert_eq!(table.block_size(), block_size);
+
+    for target in [first, second] {
+        let shared: Vec<Op> =
+            compute_delta_with_table(&cfg, &table, &mut Cursor::new(target.clone()), &opts)
+                .unwrap()
+                .collect::<Result<_, _>>()
+                .unwrap();
+        let per_file: Vec<Op> = compute_delta(
+            &cfg,
+            &mut Cursor::new(basis.clone()),
+            &mut Cursor::new(target),
+            block_size,
+            usize::MAX,
+            &opts,
+        )
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+        assert!(shared.iter().any(|op| matches!(op, Op::Copy { .. })));
+        assert_eq!(shared, per_file);
+    }
+}
@@ -0,0 +1,119 @@
+// crates/engine/tests/buffer_pool.rs
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::io::Cursor;
+
+use checksums::ChecksumConfigBuilder;
+use engine::{BufferPool, Op, SyncOptions, compute_delta};
+
+struct CountingAlloc;
+
+thread_local! {
+    static ALLOCS: Cell<usize> = const { Cell::new(0) };
+    static REALLOCS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCS.with(|c| c.set(c.get() + 1));
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCS.with(|c| c.set(c.get() + 1));
+        REALLOCS.with(|c| c.set(c.get() + 1));
+        unsafe { System.realloc(ptr, layout, new_size) }
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+fn counts() -> (usize, usize) {
+    (ALLOCS.with(Cell::get), REALLOCS.with(Cell::get))
+}
+
+const BLOCK: usize = 512;
+const LITERAL: usize = 3 * BLOCK;
+
+fn pseudo_random(len: usize, mut state: u64) -> Vec<u8> {
+    (0..len)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state as u8
+        })
+        .collect()
+}
+
+fn transfer(basis: &[u8], target: &[u8], pool: Option<&BufferPool>) -> (Vec<u8>, (usize, usize)) {
+    let cfg = ChecksumConfigBuilder::new().build();
+    let mut basis_reader = Cursor::new(basis.to_vec());
+    let mut target_reader = Cursor::new(target.to_vec());
+    let mut out = Vec::with_capacity(target.len());
+    let before = counts();
+    let mut delta = compute_delta(
+        &cfg,
+        &mut basis_reader,
+        &mut target_reader,
+        BLOCK,
+        usize::MAX,
+        &SyncOptions::default(),
+    )
+    .unwrap();
+    if let Some(pool) = pool {
+        delta = delta.with_pool(pool.clone());
+    }
+    for op in delta {
+        match op.unwrap() {
+            Op::Data(d) => {
+                out.extend_from_slice(&d);
+                if let Some(pool) = pool {
+                    pool.put(d);
+                }
+            }
+            Op::Copy { offset, len } => out.extend_from_slice(&basis[offset..offset + len]),
+        }
+    }
+    let after = counts();
+    (out, (after.0 - before.0, after.1 - before.1))
+}
+
+#[test]
+fn pooled_literals_allocate_less() {
+    let basis = pseudo_random(32 * BLOCK, 0x2545_f491_4f6c_dd1d);
+    let fresh = pseudo_random(32 * LITERAL, 0x9e37_79b9_7f4a_7c15);
+    let mut target = Vec::new();
+    for (i, block) in basis.chunks(BLOCK).enumerate() {
+        target.extend_from_slice(block);
+        target.extend_from_slice(&fresh[i * LITERAL..(i + 1) * LITERAL]);
+    }
+
+    let (plain, without) = transfer(&basis, &target, None);
+    let pool = BufferPool::new();
+    let (pooled, with) = transfer(&basis, &target, Some(&pool));
+
+    assert_eq!(plain, target);
+    assert_eq!(pooled, target);
+    assert!(
+        with.0 < without.0,
+        "{} allocations with pool, {} without",
+        with.0,
+        without.0
+    );
+    assert!(
+        with.1 < without.1 / 4,
+        "{} reallocations with pool, {} without",
+        with.1,
+        without.1
+    );
+    assert!(!pool.is_empty());
+    pool.clear();
+    assert!(pool.is_empty());
+}
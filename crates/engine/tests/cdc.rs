@@ -0,0 +1,65 @@
+// crates/engine/tests/cdc.rs
+
+use checksums::ChecksumConfigBuilder;
+use engine::{Op, SyncOptions, compute_delta};
+use std::io::Cursor;
+
+fn pseudo_random(len: usize) -> Vec<u8> {
+    let mut state = 0x2545_f491_4f6c_dd1du64;
+    (0..len)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state as u8
+        })
+        .collect()
+}
+
+fn delta(basis: &[u8], target: &[u8], opts: &SyncOptions) -> (Vec<u8>, usize) {
+    let cfg = ChecksumConfigBuilder::new().build();
+    let ops: Vec<Op> = compute_delta(
+        &cfg,
+        &mut Cursor::new(basis.to_vec()),
+        &mut Cursor::new(target.to_vec()),
+        2048,
+        usize::MAX,
+        opts,
+    )
+    .unwrap()
+    .collect::<Result<_, _>>()
+    .unwrap();
+    let mut out = Vec::new();
+    let mut literal = 0;
+    for op in ops {
+        match op {
+            Op::Data(d) => {
+                literal += d.len();
+                out.extend_from_slice(&d);
+            }
+            Op::Copy { offset, len } => out.extend_from_slice(&basis[offset..offset + len]),
+        }
+    }
+    (out, literal)
+}
+
+#[test]
+fn cdc_transfers_less_than_fixed_blocks_after_insertion() {
+    let basis = pseudo_random(256 * 1024);
+    let mut target = b"inserted header bytes".repeat(5);
+    target.extend_from_slice(&basis);
+
+    let (fixed_out, fixed_lit) = delta(&basis, &target, &SyncOptions::default());
+    let (cdc_out, cdc_lit) = delta(
+        &basis,
+        &target,
+        &SyncOptions {
+            cdc: true,
+            ..Default::default()
+        },
+    );
+    assert_eq!(fixed_out, target);
+    assert_eq!(cdc_out, target);
+    assert!(cdc_lit < target.len() / 8);
+    assert!(cdc_lit * 10 < fixed_lit);
+}
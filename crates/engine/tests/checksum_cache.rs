@@ -0,0 +1,47 @@
+// crates/engine/tests/checksum_cache.rs
+use std::fs;
+
+use compress::available_codecs;
+use engine::checksum_cache::CACHE_FILE;
+use engine::{SyncOptions, sync};
+use filetime::{FileTime, set_file_mtime};
+use filters::Matcher;
+use tempfile::tempdir;
+
+#[test]
+fn checksum_cache_skips_rehash_until_file_changes() {
+    let tmp = tempdir().unwrap();
+    let src = tmp.path().join("src");
+    let dst = tmp.path().join("dst");
+    fs::create_dir_all(&src).unwrap();
+    fs::create_dir_all(&dst).unwrap();
+    let src_file = src.join("file");
+    fs::write(&src_file, b"aaaa").unwrap();
+    fs::write(dst.join("file"), b"aaaa").unwrap();
+
+    let opts = SyncOptions {
+        checksum: true,
+        checksum_cache: true,
+        ..Default::default()
+    };
+    let run = || {
+        let stats = sync(&src, &dst, &Matcher::default(), &available_codecs(), &opts).unwrap();
+        (stats.file_checksums, stats)
+    };
+
+    let (calls, stats) = run();
+    assert_eq!(calls, 2);
+    assert_eq!(stats.files_transferred, 0);
+    assert!(src.join(CACHE_FILE).exists());
+    assert!(!dst.join(CACHE_FILE).exists());
+
+    let (calls, _) = run();
+    assert_eq!(calls, 1);
+
+    fs::write(&src_file, b"bbbb").unwrap();
+    set_file_mtime(&src_file, FileTime::from_unix_time(1_000_000, 0)).unwrap();
+    let (calls, stats) = run();
+    assert_eq!(calls, 2);
+    assert_eq!(stats.files_transferred, 1);
+    assert_eq!(fs::read(dst.join("file")).unwrap(), b"bbbb");
+}
@@ -1,8 +1,12 @@
 // crates/engine/tests/compress.rs
+use std::collections::HashMap;
 use std::fs;
 
-use compress::Codec;
-use engine::{SyncOptions, select_codec, sync};
+use compress::{Codec, ZSTD_LDM_WINDOW_LOG};
+use engine::{
+    SyncOptions, ZSTD_LDM_THRESHOLD, file_compressor, file_decompressor, select_codec, sync,
+};
+use filetime::{FileTime, set_file_mtime};
 use filters::Matcher;
 use tempfile::tempdir;
 
@@ -98,3 +102,144 @@ fn codec_selection_respects_options() {
     };
     assert_eq!(select_codec(&[Codec::Zstd], &opts), None);
 }
+
+#[test]
+fn compress_map_selects_codec_per_file() {
+    let tmp = tempdir().unwrap();
+    let src = tmp.path().join("src");
+    let dst = tmp.path().join("dst");
+    fs::create_dir_all(&src).unwrap();
+    let text = b"hello world\n".repeat(64);
+    let image = b"\xff\xd8\xff\xe0 not really a jpeg".to_vec();
+    fs::write(src.join("notes.txt"), &text).unwrap();
+    fs::write(src.join("photo.jpg"), &image).unwrap();
+    let compress_map: HashMap<String, Option<Codec>> = [
+        ("txt".to_string(), Some(Codec::Zstd)),
+        ("jpg".to_string(), None),
+    ]
+    .into_iter()
+    .collect();
+    for (i, remote) in [&[Codec::Zlib][..], &[Codec::Zlib, Codec::Zstd]]
+        .into_iter()
+        .enumerate()
+    {
+        let dst = dst.join(i.to_string());
+        sync(
+            &src,
+            &dst,
+            &Matcher::default(),
+            remote,
+            &SyncOptions {
+                compress: true,
+                compress_map: compress_map.clone(),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(fs::read(dst.join("notes.txt")).unwrap(), text);
+        assert_eq!(fs::read(dst.join("photo.jpg")).unwrap(), image);
+    }
+}
+
+#[test]
+fn stats_track_compression_savings() {
+    let tmp = tempdir().unwrap();
+    let src = tmp.path().join("src");
+    let dst = tmp.path().join("dst");
+    fs::create_dir_all(&src).unwrap();
+    fs::write(src.join("file.txt"), b"compressible ".repeat(4096)).unwrap();
+    let stats = sync(
+        &src,
+        &dst,
+        &Matcher::default(),
+        &[Codec::Zlib],
+        &SyncOptions {
+            compress: true,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    assert_eq!(stats.pre_compression_bytes, 13 * 4096);
+    assert!(stats.post_compression_bytes > 0);
+    assert!(stats.post_compression_bytes < stats.pre_compression_bytes);
+    let expected = stats.pre_compression_bytes as f64 / stats.post_compression_bytes as f64;
+    assert_eq!(stats.compression_ratio(), expected);
+    assert!(stats.compression_ratio() > 1.0);
+
+    let plain = sync(
+        &src,
+        &tmp.path().join("plain"),
+        &Matcher::default(),
+        &[Codec::Zlib],
+        &SyncOptions::default(),
+    )
+    .unwrap();
+    assert_eq!(plain.pre_compression_bytes, 0);
+    assert_eq!(plain.compression_ratio(), 1.0);
+}
+
+#[test]
+fn matched_blocks_bypass_compressor() {
+    let tmp = tempdir().unwrap();
+    let src = tmp.path().join("src");
+    let dst = tmp.path().join("dst");
+    fs::create_dir_all(&src).unwrap();
+    fs::create_dir_all(&dst).unwrap();
+    let basis: Vec<u8> = (0..500)
+        .flat_map(|i| format!("line {i:010}\n").into_bytes())
+        .collect();
+    let mut target = basis.clone();
+    let mid = basis.len() / 2;
+    target[mid..mid + 100].fill(b'x');
+    fs::write(dst.join("file.bin"), &basis).unwrap();
+    fs::write(src.join("file.bin"), &target).unwrap();
+    set_file_mtime(dst.join("file.bin"), FileTime::from_unix_time(0, 0)).unwrap();
+
+    let stats = sync(
+        &src,
+        &dst,
+        &Matcher::default(),
+        &[Codec::Zlib],
+        &SyncOptions {
+            compress: true,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    assert_eq!(fs::read(dst.join("file.bin")).unwrap(), target);
+    assert!(stats.matched_data > 0);
+    assert!(stats.literal_data < target.len() as u64 / 4);
+    assert!(stats.compress_calls > 0);
+    assert_eq!(stats.pre_compression_bytes, stats.literal_data);
+}
+
+fn zstd_window_log(frame: &[u8]) -> u32 {
+    assert_eq!(&frame[..4], &[0x28, 0xb5, 0x2f, 0xfd]);
+    assert_eq!(frame[4] & 0x20, 0, "single-segment frame has no window");
+    10 + u32::from(frame[5] >> 3)
+}
+
+#[test]
+fn large_zstd_files_use_long_distance_matching() {
+    let chunk = b"long distance ".repeat(1024);
+    let frame = |file_len| {
+        let mut comp = file_compressor(Codec::Zstd, file_len, &SyncOptions::default()).unwrap();
+        (0..3)
+            .map(|_| comp.compress(&chunk).unwrap())
+            .collect::<Vec<_>>()
+    };
+
+    let large = frame(ZSTD_LDM_THRESHOLD);
+    let small = frame(ZSTD_LDM_THRESHOLD - 1);
+    assert_eq!(zstd_window_log(&large[0]), ZSTD_LDM_WINDOW_LOG);
+    assert!(zstd_window_log(&small[0]) < ZSTD_LDM_WINDOW_LOG);
+    assert!(large[1..].iter().all(|c| c.len() < large[0].len()));
+
+    for chunks in [large, small] {
+        let mut dec = file_decompressor(Codec::Zstd).unwrap();
+        for c in &chunks {
+            assert_eq!(dec.decompress(c).unwrap(), chunk);
+        }
+    }
+}
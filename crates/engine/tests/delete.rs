@@ -64,3 +64,8 @@ fn delete_after_respects_filters() {
 fn delete_during_respects_filters() {
     run_delete_filter(DeleteMode::During);
 }
+
+#[test]
+fn delete_before_respects_filters() {
+    run_delete_filter(DeleteMode::Before);
+}
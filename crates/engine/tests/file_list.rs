@@ -0,0 +1,106 @@
+// crates/engine/tests/file_list.rs
+use engine::{Receiver, Sender, Stats, SyncOptions};
+use filelist::Entry;
+use filters::Matcher;
+use std::fs;
+use tempfile::tempdir;
+
+fn entry(path: &str) -> Entry {
+    Entry {
+        path: path.as_bytes().to_vec(),
+        uid: 0,
+        gid: 0,
+        hardlink: None,
+        xattrs: Vec::new(),
+        acl: Vec::new(),
+        default_acl: Vec::new(),
+        times: Vec::new(),
+    }
+}
+
+#[test]
+fn sender_transfers_only_listed_entries() {
+    let tmp = tempdir().unwrap();
+    let src = tmp.path().join("src");
+    let dst = tmp.path().join("dst");
+    fs::create_dir_all(src.join("sub")).unwrap();
+    fs::create_dir_all(&dst).unwrap();
+    fs::write(src.join("a.txt"), b"alpha").unwrap();
+    fs::write(src.join("sub/b.txt"), b"beta").unwrap();
+    fs::write(src.join("skipped.txt"), b"not listed").unwrap();
+
+    let opts = SyncOptions::default();
+    let mut recv = Receiver::new(None, opts.clone());
+    let mut sender = Sender::new(Matcher::default(), None, opts)
+        .with_file_list(vec![entry("a.txt"), entry("sub/b.txt")]);
+    let mut stats = Stats::default();
+    sender
+        .send_file_list(&src, &dst, &mut recv, &mut stats)
+        .unwrap();
+
+    assert_eq!(stats.files_transferred, 2);
+    assert_eq!(fs::read(dst.join("a.txt")).unwrap(), b"alpha");
+    assert_eq!(fs::read(dst.join("sub/b.txt")).unwrap(), b"beta");
+    assert!(!dst.join("skipped.txt").exists());
+}
+
+#[test]
+fn missing_listed_entry_marks_transfer_partial() {
+    let tmp = tempdir().unwrap();
+    let src = tmp.path().join("src");
+    let dst = tmp.path().join("dst");
+    fs::create_dir_all(&src).unwrap();
+    fs::create_dir_all(&dst).unwrap();
+    fs::write(src.join("a.txt"), b"alpha").unwrap();
+
+    let opts = SyncOptions::default();
+    let mut recv = Receiver::new(None, opts.clone());
+    let mut sender = Sender::new(Matcher::default(), None, opts)
+        .with_file_list(vec![entry("missing.txt"), entry("a.txt")]);
+    let mut stats = Stats::default();
+    sender
+        .send_file_list(&src, &dst, &mut recv, &mut stats)
+        .unwrap();
+
+    assert_eq!(stats.files_transferred, 1);
+    assert_eq!(stats.file_errors, 1);
+    assert!(stats.had_partial_errors);
+    assert_eq!(fs::read(dst.join("a.txt")).unwrap(), b"alpha");
+}
+
+#[cfg(unix)]
+#[test]
+fn listed_owner_is_applied() {
+    use std::os::unix::fs::MetadataExt;
+
+    if !nix::unistd::Uid::effective().is_root() {
+        eprintln!("skipping: changing ownership requires root");
+        return;
+    }
+    let unnamed = (60000..65000)
+        .find(|&id| meta::uid_to_name(id).is_none())
+        .unwrap();
+
+    let tmp = tempdir().unwrap();
+    let src = tmp.path().join("src");
+    let dst = tmp.path().join("dst");
+    fs::create_dir_all(&src).unwrap();
+    fs::create_dir_all(&dst).unwrap();
+    fs::write(src.join("owned"), b"data").unwrap();
+
+    let opts = SyncOptions {
+        owner: true,
+        ..Default::default()
+    };
+    let mut recv = Receiver::new(None, opts.clone());
+    let mut sender = Sender::new(Matcher::default(), None, opts).with_file_list(vec![Entry {
+        uid: unnamed,
+        ..entry("owned")
+    }]);
+    let mut stats = Stats::default();
+    sender
+        .send_file_list(&src, &dst, &mut recv, &mut stats)
+        .unwrap();
+
+    assert_eq!(fs::metadata(dst.join("owned")).unwrap().uid(), unnamed);
+}
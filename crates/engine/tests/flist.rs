@@ -1,8 +1,11 @@
 // crates/engine/tests/flist.rs
+use std::time::Duration;
+
 use encoding_rs::Encoding;
-use engine::flist;
+use engine::{Receiver, SyncOptions, flist};
 use filelist::Entry;
-use protocol::CharsetConv;
+use filetime::FileTime;
+use protocol::{CharsetConv, UnicodeForm};
 
 #[test]
 fn roundtrip() {
@@ -15,6 +18,7 @@ fn roundtrip() {
             xattrs: vec![(b"user.test".to_vec(), b"1".to_vec())],
             acl: vec![1, 0, 0, 0, 0, 7, 0, 0, 0],
             default_acl: Vec::new(),
+            times: Vec::new(),
         },
         Entry {
             path: b"a/b".to_vec(),
@@ -24,6 +28,7 @@ fn roundtrip() {
             xattrs: Vec::new(),
             acl: Vec::new(),
             default_acl: vec![1, 0, 0, 0, 0, 7, 0, 0, 0],
+            times: Vec::new(),
         },
         Entry {
             path: b"c".to_vec(),
@@ -33,6 +38,7 @@ fn roundtrip() {
             xattrs: Vec::new(),
             acl: Vec::new(),
             default_acl: Vec::new(),
+            times: Vec::new(),
         },
     ];
     let payloads = flist::encode(&entries, None);
@@ -51,6 +57,7 @@ fn group_id_roundtrip() {
             xattrs: Vec::new(),
             acl: Vec::new(),
             default_acl: Vec::new(),
+            times: Vec::new(),
         },
         Entry {
             path: b"a/b".to_vec(),
@@ -60,6 +67,7 @@ fn group_id_roundtrip() {
             xattrs: Vec::new(),
             acl: Vec::new(),
             default_acl: Vec::new(),
+            times: Vec::new(),
         },
         Entry {
             path: b"c".to_vec(),
@@ -69,6 +77,7 @@ fn group_id_roundtrip() {
             xattrs: Vec::new(),
             acl: Vec::new(),
             default_acl: Vec::new(),
+            times: Vec::new(),
         },
     ];
     let payloads = flist::encode(&entries, None);
@@ -90,6 +99,7 @@ fn iconv_roundtrip() {
         xattrs: Vec::new(),
         acl: Vec::new(),
         default_acl: Vec::new(),
+        times: Vec::new(),
     }];
     let payloads = flist::encode(&entries, Some(&cv));
     let decoded = flist::decode(&payloads, Some(&cv)).unwrap();
@@ -110,8 +120,118 @@ fn iconv_non_utf8_local_roundtrip() {
         xattrs: Vec::new(),
         acl: Vec::new(),
         default_acl: Vec::new(),
+        times: Vec::new(),
     }];
     let payloads = flist::encode(&entries, Some(&cv));
     let decoded = flist::decode(&payloads, Some(&cv)).unwrap();
     assert_eq!(decoded, entries);
 }
+
+#[test]
+fn normalization_matches_composed_and_decomposed_names() {
+    let utf8 = Encoding::for_label(b"utf-8").unwrap();
+    let entry = |path: &str| Entry {
+        path: path.as_bytes().to_vec(),
+        uid: 0,
+        gid: 0,
+        hardlink: None,
+        xattrs: Vec::new(),
+        acl: Vec::new(),
+        default_acl: Vec::new(),
+        times: Vec::new(),
+    };
+    let nfd = vec![entry("cafe\u{301}")];
+    let nfc = vec![entry("caf\u{e9}")];
+    assert_ne!(nfd, nfc);
+
+    let payloads = flist::encode(&nfd, None);
+    let plain = flist::decode(&payloads, Some(&CharsetConv::new(utf8, utf8))).unwrap();
+    assert_eq!(plain, nfd);
+
+    let cv = CharsetConv::new(utf8, utf8).with_normalization(UnicodeForm::Nfc);
+    assert_eq!(flist::decode(&payloads, Some(&cv)).unwrap(), nfc);
+    let payloads = flist::encode(&nfc, Some(&cv));
+    assert_eq!(flist::decode(&payloads, None).unwrap(), nfc);
+}
+
+#[cfg(unix)]
+#[test]
+fn owner_names_map_to_local_ids() {
+    let Some(user) = meta::uid_to_name(0) else {
+        return;
+    };
+    let entry = Entry {
+        path: b"owned".to_vec(),
+        uid: 0,
+        gid: 0,
+        hardlink: None,
+        xattrs: Vec::new(),
+        acl: Vec::new(),
+        default_acl: Vec::new(),
+        times: Vec::new(),
+    };
+
+    let mut payloads = flist::encode_with_names(std::slice::from_ref(&entry), None);
+    let at = 2 + entry.path.len();
+    assert_eq!(payloads[0][at + 5], user.len() as u8);
+    assert_eq!(&payloads[0][at + 6..at + 6 + user.len()], user.as_bytes());
+
+    payloads[0][at + 1..at + 5].copy_from_slice(&4242u32.to_le_bytes());
+    let decoded = flist::decode(&payloads, None).unwrap();
+    assert_eq!(decoded[0].uid, meta::uid_from_name(&user).unwrap());
+
+    let numeric = flist::encode(&[Entry { uid: 4242, ..entry }], None);
+    assert_eq!(flist::decode(&numeric, None).unwrap()[0].uid, 4242);
+}
+
+#[test]
+fn time_frame_survives_transfer_within_modify_window() {
+    let tmp = tempfile::tempdir().unwrap();
+    let src = tmp.path().join("src");
+    let dst = tmp.path().join("dst");
+    std::fs::write(&src, b"hello").unwrap();
+    std::fs::write(&dst, b"hello").unwrap();
+    let mtime = FileTime::from_unix_time(1_000_000, 123_456_789);
+    let atime = FileTime::from_unix_time(2_000_000, 987_654_321);
+    filetime::set_file_times(&src, atime, mtime).unwrap();
+
+    let meta_opts = meta::Options {
+        times: true,
+        atimes: true,
+        crtimes: true,
+        ..Default::default()
+    };
+    let mut sent = meta::Metadata::from_path(&src, meta_opts.clone()).unwrap();
+    sent.crtime = Some(FileTime::from_unix_time(500_000, 42));
+    let entry = Entry {
+        path: b"dst".to_vec(),
+        uid: 0,
+        gid: 0,
+        hardlink: None,
+        xattrs: Vec::new(),
+        acl: Vec::new(),
+        default_acl: Vec::new(),
+        times: sent.encode_times(&meta_opts),
+    };
+    let decoded = flist::decode(&flist::encode(&[entry], None), None).unwrap();
+    assert_eq!(decoded[0].times.len(), 1 + 3 * 12);
+
+    let opts = SyncOptions {
+        times: true,
+        atimes: true,
+        crtimes: true,
+        modify_window: Duration::from_secs(1),
+        ..Default::default()
+    };
+    let mut recv = Receiver::new(None, opts.clone());
+    recv.copy_metadata(&dst, &dst, Some(&decoded[0])).unwrap();
+
+    let window = opts.modify_window.as_secs() as i64;
+    let within = |a: FileTime, b: FileTime| (a.unix_seconds() - b.unix_seconds()).abs() <= window;
+    let applied = meta::Metadata::from_path(&dst, meta_opts).unwrap();
+    assert!(within(applied.mtime, mtime));
+    assert!(within(applied.atime.unwrap(), atime));
+    if cfg!(target_os = "macos") {
+        assert!(within(applied.crtime.unwrap(), sent.crtime.unwrap()));
+    }
+}
@@ -0,0 +1,51 @@
+// crates/engine/tests/fs_op_rate.rs
+
+use std::fs;
+use std::time::{Duration, Instant};
+
+use compress::available_codecs;
+use engine::{Stats, SyncOptions, sync};
+use filters::Matcher;
+use tempfile::tempdir;
+
+const FILES: usize = 6;
+
+fn timed_sync(rate: Option<u32>) -> (Duration, Stats) {
+    let tmp = tempdir().unwrap();
+    let src = tmp.path().join("src");
+    let dst = tmp.path().join("dst");
+    fs::create_dir_all(&src).unwrap();
+    for i in 0..FILES {
+        fs::write(src.join(format!("f{i}")), b"data").unwrap();
+    }
+    let opts = SyncOptions {
+        perms: true,
+        times: true,
+        fs_op_rate: rate,
+        ..Default::default()
+    };
+    let start = Instant::now();
+    let stats = sync(&src, &dst, &Matcher::default(), &available_codecs(), &opts).unwrap();
+    let elapsed = start.elapsed();
+    for i in 0..FILES {
+        assert_eq!(fs::read(dst.join(format!("f{i}"))).unwrap(), b"data");
+    }
+    (elapsed, stats)
+}
+
+#[test]
+fn fs_op_rate_paces_metadata_operations() {
+    let (paced, stats) = timed_sync(Some(10));
+    assert!(paced >= Duration::from_millis(1100), "took {paced:?}");
+    assert!(
+        stats.paced_fs_ops >= 2 * FILES as u64,
+        "{}",
+        stats.paced_fs_ops
+    );
+}
+
+#[test]
+fn fs_op_rate_is_unlimited_by_default() {
+    let (_, stats) = timed_sync(None);
+    assert_eq!(stats.paced_fs_ops, 0);
+}
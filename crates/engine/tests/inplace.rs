@@ -0,0 +1,73 @@
+// crates/engine/tests/inplace.rs
+
+use std::fs;
+
+use compress::available_codecs;
+use engine::{SyncOptions, sync};
+use filetime::{FileTime, set_file_mtime};
+use filters::Matcher;
+use tempfile::tempdir;
+
+fn block(fill: u8, len: usize) -> Vec<u8> {
+    (0..len).map(|i| fill ^ (i % 251) as u8).collect()
+}
+
+#[test]
+fn inplace_reordered_blocks_are_not_read_after_overwrite() {
+    let tmp = tempdir().unwrap();
+    let src = tmp.path().join("src");
+    let dst = tmp.path().join("dst");
+    fs::create_dir_all(&src).unwrap();
+    fs::create_dir_all(&dst).unwrap();
+    let bs = 1024;
+    let (a, b) = (block(0x11, bs), block(0x77, bs));
+    fs::write(src.join("file"), [b.clone(), a.clone()].concat()).unwrap();
+    fs::write(dst.join("file"), [a, b].concat()).unwrap();
+    set_file_mtime(dst.join("file"), FileTime::from_unix_time(0, 0)).unwrap();
+    sync(
+        &src,
+        &dst,
+        &Matcher::default(),
+        &available_codecs(),
+        &SyncOptions {
+            inplace: true,
+            block_size: bs,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        fs::read(src.join("file")).unwrap(),
+        fs::read(dst.join("file")).unwrap()
+    );
+}
+
+#[test]
+fn inplace_truncates_shorter_file() {
+    let tmp = tempdir().unwrap();
+    let src = tmp.path().join("src");
+    let dst = tmp.path().join("dst");
+    fs::create_dir_all(&src).unwrap();
+    fs::create_dir_all(&dst).unwrap();
+    let bs = 1024;
+    let (a, b, c) = (block(0x11, bs), block(0x77, bs), block(0x33, bs));
+    for (new, sparse) in [([a.clone(), b.clone()].concat(), false), (a.clone(), true)] {
+        fs::write(src.join("file"), &new).unwrap();
+        fs::write(dst.join("file"), [a.clone(), b.clone(), c.clone()].concat()).unwrap();
+        set_file_mtime(dst.join("file"), FileTime::from_unix_time(0, 0)).unwrap();
+        sync(
+            &src,
+            &dst,
+            &Matcher::default(),
+            &available_codecs(),
+            &SyncOptions {
+                inplace: true,
+                sparse,
+                block_size: bs,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(fs::read(dst.join("file")).unwrap(), new);
+    }
+}
@@ -20,8 +20,9 @@ fn caps_extraneous_deletions() {
         max_delete: Some(0),
         ..Default::default()
     };
-    let err = sync(&src, &dst, &Matcher::default(), &available_codecs(), &opts).unwrap_err();
-    assert!(format!("{}", err).contains("max-delete"));
+    let stats = sync(&src, &dst, &Matcher::default(), &available_codecs(), &opts).unwrap();
+    assert_eq!(stats.files_deleted, 0);
+    assert_eq!(stats.deletes_skipped, 1);
     assert!(dst.join("extra.txt").exists());
 
     let opts = SyncOptions {
@@ -31,6 +32,7 @@ fn caps_extraneous_deletions() {
     };
     let stats = sync(&src, &dst, &Matcher::default(), &available_codecs(), &opts).unwrap();
     assert_eq!(stats.files_deleted, 1);
+    assert_eq!(stats.deletes_skipped, 0);
     assert!(!dst.join("extra.txt").exists());
 }
 
@@ -46,8 +48,8 @@ fn caps_missing_arg_deletions() {
         max_delete: Some(0),
         ..Default::default()
     };
-    let err = sync(&src, &dst, &Matcher::default(), &available_codecs(), &opts).unwrap_err();
-    assert!(format!("{}", err).contains("max-delete"));
+    let stats = sync(&src, &dst, &Matcher::default(), &available_codecs(), &opts).unwrap();
+    assert_eq!(stats.deletes_skipped, 1);
     assert!(dst.exists());
 
     let opts = SyncOptions {
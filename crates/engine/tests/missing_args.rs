@@ -19,3 +19,54 @@ fn deletes_missing_arg() {
     sync(&src, &dst, &Matcher::default(), &available_codecs(), &opts).unwrap();
     assert!(!dst.exists());
 }
+
+#[test]
+fn deletes_corresponding_path_in_destination_dir() {
+    let tmp = tempdir().unwrap();
+    let src = tmp.path().join("src/missing.txt");
+    let dst = tmp.path().join("dst");
+    fs::create_dir_all(&dst).unwrap();
+    fs::write(dst.join("missing.txt"), b"data").unwrap();
+    fs::write(dst.join("keep.txt"), b"keep").unwrap();
+    let opts = SyncOptions {
+        delete_missing_args: true,
+        ..Default::default()
+    };
+    sync(&src, &dst, &Matcher::default(), &available_codecs(), &opts).unwrap();
+    assert!(!dst.join("missing.txt").exists());
+    assert_eq!(fs::read(dst.join("keep.txt")).unwrap(), b"keep");
+}
+
+#[test]
+fn ignores_missing_arg() {
+    let tmp = tempdir().unwrap();
+    let src = tmp.path().join("missing.txt");
+    let dst = tmp.path().join("dst");
+    fs::create_dir_all(&dst).unwrap();
+    fs::write(dst.join("missing.txt"), b"data").unwrap();
+    let opts = SyncOptions {
+        ignore_missing_args: true,
+        ..Default::default()
+    };
+    sync(&src, &dst, &Matcher::default(), &available_codecs(), &opts).unwrap();
+    assert_eq!(fs::read(dst.join("missing.txt")).unwrap(), b"data");
+}
+
+#[test]
+fn missing_arg_errors_by_default() {
+    let tmp = tempdir().unwrap();
+    let src = tmp.path().join("missing.txt");
+    let dst = tmp.path().join("dst");
+    fs::create_dir_all(&dst).unwrap();
+    fs::write(dst.join("missing.txt"), b"data").unwrap();
+    let err = sync(
+        &src,
+        &dst,
+        &Matcher::default(),
+        &available_codecs(),
+        &SyncOptions::default(),
+    )
+    .unwrap_err();
+    assert!(err.to_string().contains("No such file or directory"));
+    assert_eq!(fs::read(dst.join("missing.txt")).unwrap(), b"data");
+}
@@ -0,0 +1,79 @@
+// crates/engine/tests/mtime_age.rs
+
+use std::fs;
+use std::time::{Duration, SystemTime};
+
+use compress::available_codecs;
+use engine::{SyncOptions, sync};
+use filetime::{FileTime, set_file_mtime};
+use filters::Matcher;
+use tempfile::tempdir;
+
+const DAY: u64 = 24 * 60 * 60;
+
+fn age_opts(reference: SystemTime) -> SyncOptions {
+    SyncOptions {
+        age_reference: Some(reference),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn exclude_older_than_skips_old_files() {
+    let tmp = tempdir().unwrap();
+    let src = tmp.path().join("src");
+    let dst = tmp.path().join("dst");
+    fs::create_dir_all(&src).unwrap();
+    let reference = SystemTime::UNIX_EPOCH + Duration::from_secs(100 * DAY);
+    for (name, days) in [("old", 8), ("edge", 7), ("new", 1)] {
+        fs::write(src.join(name), name).unwrap();
+        let mtime = reference - Duration::from_secs(days * DAY);
+        set_file_mtime(src.join(name), FileTime::from_system_time(mtime)).unwrap();
+    }
+
+    sync(
+        &src,
+        &dst,
+        &Matcher::default(),
+        &available_codecs(),
+        &SyncOptions {
+            exclude_older_than: Some(Duration::from_secs(7 * DAY)),
+            ..age_opts(reference)
+        },
+    )
+    .unwrap();
+
+    assert!(!dst.join("old").exists());
+    assert!(dst.join("edge").exists());
+    assert!(dst.join("new").exists());
+}
+
+#[test]
+fn exclude_newer_than_skips_recent_files() {
+    let tmp = tempdir().unwrap();
+    let src = tmp.path().join("src");
+    let dst = tmp.path().join("dst");
+    fs::create_dir_all(&src).unwrap();
+    let reference = SystemTime::UNIX_EPOCH + Duration::from_secs(100 * DAY);
+    for (name, secs) in [("old", 7200), ("edge", 3600), ("new", 60)] {
+        fs::write(src.join(name), name).unwrap();
+        let mtime = reference - Duration::from_secs(secs);
+        set_file_mtime(src.join(name), FileTime::from_system_time(mtime)).unwrap();
+    }
+
+    sync(
+        &src,
+        &dst,
+        &Matcher::default(),
+        &available_codecs(),
+        &SyncOptions {
+            exclude_newer_than: Some(Duration::from_secs(3600)),
+            ..age_opts(reference)
+        },
+    )
+    .unwrap();
+
+    assert!(dst.join("old").exists());
+    assert!(dst.join("edge").exists());
+    assert!(!dst.join("new").exists());
+}
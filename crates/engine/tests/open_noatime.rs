@@ -34,3 +34,30 @@ fn open_noatime_preserves_source_access_time() {
     let new_atime = FileTime::from_last_access_time(&meta);
     assert_eq!(new_atime.unix_seconds(), epoch_atime.unix_seconds());
 }
+
+#[test]
+fn open_noatime_covers_checksum_reads() {
+    let tmp = tempdir().unwrap();
+    let src = tmp.path().join("src");
+    let dst = tmp.path().join("dst");
+    fs::create_dir_all(&src).unwrap();
+    fs::create_dir_all(&dst).unwrap();
+    let file = src.join("file.txt");
+    fs::write(&file, b"same").unwrap();
+    fs::write(dst.join("file.txt"), b"same").unwrap();
+
+    let epoch_atime = FileTime::from_unix_time(0, 0);
+    let mtime = FileTime::from_system_time(SystemTime::now());
+    set_file_times(&file, epoch_atime, mtime).unwrap();
+
+    let opts = SyncOptions {
+        open_noatime: true,
+        checksum: true,
+        ..Default::default()
+    };
+    sync(&src, &dst, &Matcher::default(), &available_codecs(), &opts).unwrap();
+
+    let meta = fs::metadata(&file).unwrap();
+    let new_atime = FileTime::from_last_access_time(&meta);
+    assert_eq!(new_atime.unix_seconds(), epoch_atime.unix_seconds());
+}
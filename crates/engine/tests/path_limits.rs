@@ -0,0 +1,92 @@
+// crates/engine/tests/path_limits.rs
+
+use std::fs;
+
+use compress::available_codecs;
+use engine::{SyncOptions, sync};
+use filters::Matcher;
+use tempfile::tempdir;
+
+#[test]
+fn long_names_are_skipped_and_reported() {
+    let tmp = tempdir().unwrap();
+    let src = tmp.path().join("src");
+    let dst = tmp.path().join("dst");
+    let long = "n".repeat(40);
+    fs::create_dir_all(src.join(&long)).unwrap();
+    fs::create_dir_all(src.join("ok")).unwrap();
+    fs::create_dir_all(&dst).unwrap();
+    fs::write(src.join("short"), b"short").unwrap();
+    fs::write(src.join("ok/file"), b"nested").unwrap();
+    fs::write(src.join(format!("{long}.txt")), b"long").unwrap();
+    fs::write(src.join(&long).join("inner"), b"inner").unwrap();
+
+    let stats = sync(
+        &src,
+        &dst,
+        &Matcher::default(),
+        &available_codecs(),
+        &SyncOptions {
+            max_name_len: Some(32),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    assert!(stats.had_partial_errors);
+    assert_eq!(fs::read(dst.join("short")).unwrap(), b"short");
+    assert_eq!(fs::read(dst.join("ok/file")).unwrap(), b"nested");
+    assert!(!dst.join(format!("{long}.txt")).exists());
+    assert!(!dst.join(&long).exists());
+}
+
+#[test]
+fn long_paths_are_skipped_and_reported() {
+    let tmp = tempdir().unwrap();
+    let src = tmp.path().join("src");
+    let dst = tmp.path().join("dst");
+    fs::create_dir_all(src.join("a/b/c")).unwrap();
+    fs::create_dir_all(&dst).unwrap();
+    fs::write(src.join("top"), b"top").unwrap();
+    fs::write(src.join("a/b/f"), b"fits").unwrap();
+    fs::write(src.join("a/b/c/deep"), b"deep").unwrap();
+    let limit = dst.join("a/b/c").as_os_str().len();
+
+    let stats = sync(
+        &src,
+        &dst,
+        &Matcher::default(),
+        &available_codecs(),
+        &SyncOptions {
+            max_path_len: Some(limit),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    assert!(stats.had_partial_errors);
+    assert_eq!(fs::read(dst.join("top")).unwrap(), b"top");
+    assert_eq!(fs::read(dst.join("a/b/f")).unwrap(), b"fits");
+    assert!(!dst.join("a/b/c/deep").exists());
+}
+
+#[test]
+fn no_limits_transfer_everything() {
+    let tmp = tempdir().unwrap();
+    let src = tmp.path().join("src");
+    let dst = tmp.path().join("dst");
+    fs::create_dir_all(&src).unwrap();
+    fs::write(src.join("n".repeat(100)), b"data").unwrap();
+
+    let stats = sync(
+        &src,
+        &dst,
+        &Matcher::default(),
+        &available_codecs(),
+        &SyncOptions::default(),
+    )
+    .unwrap();
+
+    assert!(!stats.had_partial_errors);
+    assert!(dst.join("n".repeat(100)).exists());
+}
@@ -0,0 +1,97 @@
+// crates/engine/tests/path_transform.rs
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use compress::available_codecs;
+use engine::{DeleteMode, EngineError, PathTransform, SyncOptions, sync};
+use filters::Matcher;
+use tempfile::tempdir;
+
+fn transform_opts(
+    transform: impl Fn(&Path) -> Option<PathBuf> + Send + Sync + 'static,
+) -> SyncOptions {
+    SyncOptions {
+        path_transform: Some(PathTransform(Arc::new(transform))),
+        ..Default::default()
+    }
+}
+
+fn sync_with(
+    src: &Path,
+    dst: &Path,
+    transform: impl Fn(&Path) -> Option<PathBuf> + Send + Sync + 'static,
+) {
+    let opts = transform_opts(transform);
+    sync(src, dst, &Matcher::default(), &available_codecs(), &opts).unwrap();
+}
+
+#[test]
+fn transform_strips_leading_directory() {
+    let tmp = tempdir().unwrap();
+    let src = tmp.path().join("src");
+    let dst = tmp.path().join("dst");
+    fs::create_dir_all(src.join("top/nested")).unwrap();
+    fs::write(src.join("top/a.txt"), b"a").unwrap();
+    fs::write(src.join("top/nested/b.txt"), b"b").unwrap();
+
+    sync_with(&src, &dst, |rel| {
+        Some(rel.strip_prefix("top").unwrap_or(rel).to_path_buf())
+    });
+
+    assert_eq!(fs::read(dst.join("a.txt")).unwrap(), b"a");
+    assert_eq!(fs::read(dst.join("nested/b.txt")).unwrap(), b"b");
+    assert!(!dst.join("top").exists());
+}
+
+#[test]
+fn transform_returning_none_skips_file() {
+    let tmp = tempdir().unwrap();
+    let src = tmp.path().join("src");
+    let dst = tmp.path().join("dst");
+    fs::create_dir_all(&src).unwrap();
+    fs::write(src.join("keep.txt"), b"keep").unwrap();
+    fs::write(src.join("skip.txt"), b"skip").unwrap();
+
+    sync_with(&src, &dst, |rel| {
+        (rel != Path::new("skip.txt")).then(|| rel.to_path_buf())
+    });
+
+    assert_eq!(fs::read(dst.join("keep.txt")).unwrap(), b"keep");
+    assert!(!dst.join("skip.txt").exists());
+}
+
+#[test]
+fn transform_escaping_destination_is_rejected() {
+    let tmp = tempdir().unwrap();
+    let src = tmp.path().join("src");
+    let dst = tmp.path().join("dst");
+    let outside = tmp.path().join("outside");
+    fs::create_dir_all(src.join("top")).unwrap();
+    fs::write(src.join("top/a.txt"), b"a").unwrap();
+
+    for mapped in [outside.join("a.txt"), PathBuf::from("../outside/a.txt")] {
+        let opts = transform_opts(move |_| Some(mapped.clone()));
+        let err = sync(&src, &dst, &Matcher::default(), &available_codecs(), &opts).unwrap_err();
+        assert!(err.to_string().contains("outside the destination"), "{err}");
+    }
+    assert!(!outside.exists());
+}
+
+#[test]
+fn transform_is_refused_with_delete() {
+    let tmp = tempdir().unwrap();
+    let src = tmp.path().join("src");
+    let dst = tmp.path().join("dst");
+    fs::create_dir_all(src.join("top")).unwrap();
+    fs::write(src.join("top/a.txt"), b"a").unwrap();
+
+    let opts = SyncOptions {
+        delete: Some(DeleteMode::During),
+        ..transform_opts(|rel| Some(rel.strip_prefix("top").unwrap_or(rel).to_path_buf()))
+    };
+    let err = sync(&src, &dst, &Matcher::default(), &available_codecs(), &opts).unwrap_err();
+    assert!(matches!(err, EngineError::Exit(..)), "{err}");
+    assert!(!dst.join("a.txt").exists());
+}
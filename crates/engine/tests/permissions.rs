@@ -17,7 +17,7 @@ use engine::{IdMapper, SyncOptions, sync};
 use filetime::{FileTime, set_file_mtime, set_file_times, set_symlink_file_times};
 use filters::Matcher;
 use meta::{IdKind, parse_chmod, parse_chown, parse_id_map};
-use nix::unistd::{Gid, Uid, chown};
+use nix::unistd::{Gid, Group, Uid, User, chown};
 use tempfile::tempdir;
 
 mod tests;
@@ -103,6 +103,81 @@ fn chown_applies() {
     assert_eq!(meta.gid(), 1);
 }
 
+#[test]
+fn chown_names_set_owner_and_group_together() {
+    if !tests::requires_capability(tests::CapabilityCheck::CapChown) {
+        return;
+    }
+    let (Ok(Some(user)), Ok(Some(group))) =
+        (User::from_name("nobody"), Group::from_name("nogroup"))
+    else {
+        return;
+    };
+    let tmp = tempdir().unwrap();
+    let src = tmp.path().join("src");
+    let dst = tmp.path().join("dst");
+    fs::create_dir_all(&src).unwrap();
+    fs::create_dir_all(&dst).unwrap();
+    fs::write(src.join("file"), b"hi").unwrap();
+    std::os::unix::fs::symlink("file", src.join("link")).unwrap();
+    sync(
+        &src,
+        &dst,
+        &Matcher::default(),
+        &available_codecs(),
+        &SyncOptions {
+            owner: true,
+            group: true,
+            links: true,
+            chown: Some(parse_chown("nobody:nogroup").unwrap()),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    for rel in ["file", "link"] {
+        let meta = fs::symlink_metadata(dst.join(rel)).unwrap();
+        assert_eq!(
+            (meta.uid(), meta.gid()),
+            (user.uid.as_raw(), group.gid.as_raw()),
+            "{rel}"
+        );
+    }
+}
+
+#[test]
+fn copy_as_overrides_source_ownership() {
+    if !tests::requires_capability(tests::CapabilityCheck::CapChown) {
+        return;
+    }
+    let tmp = tempdir().unwrap();
+    let src = tmp.path().join("src");
+    let dst = tmp.path().join("dst");
+    fs::create_dir_all(src.join("sub")).unwrap();
+    fs::create_dir_all(&dst).unwrap();
+    fs::write(src.join("file"), b"hi").unwrap();
+    fs::write(src.join("sub/nested"), b"there").unwrap();
+    for path in [src.join("file"), src.join("sub"), src.join("sub/nested")] {
+        chown(&path, Some(Uid::from_raw(1)), Some(Gid::from_raw(1))).unwrap();
+    }
+    sync(
+        &src,
+        &dst,
+        &Matcher::default(),
+        &available_codecs(),
+        &SyncOptions {
+            owner: true,
+            group: true,
+            copy_as: Some((2, Some(3))),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    for rel in ["file", "sub", "sub/nested"] {
+        let meta = fs::metadata(dst.join(rel)).unwrap();
+        assert_eq!((meta.uid(), meta.gid()), (2, 3), "{rel}");
+    }
+}
+
 #[test]
 fn chown_matches_rsync() {
     if !tests::requires_capability(tests::CapabilityCheck::CapChown) {
@@ -254,6 +329,57 @@ fn atimes_roundtrip() {
     assert_ne!(dst_mtime, mtime);
 }
 
+#[test]
+fn compare_atimes_resyncs_atime_only_changes() {
+    let tmp = tempdir().unwrap();
+    let src = tmp.path().join("src");
+    let dst = tmp.path().join("dst");
+    fs::create_dir_all(&src).unwrap();
+    fs::create_dir_all(&dst).unwrap();
+    let mtime = FileTime::from_unix_time(1_000_000, 0);
+    let src_atime = FileTime::from_unix_time(2_000_000, 0);
+    let dst_atime = FileTime::from_unix_time(1_500_000, 0);
+    let reset = || {
+        fs::write(src.join("file"), b"new").unwrap();
+        fs::write(dst.join("file"), b"old").unwrap();
+        set_file_times(src.join("file"), src_atime, mtime).unwrap();
+        set_file_times(dst.join("file"), dst_atime, mtime).unwrap();
+    };
+
+    reset();
+    sync(
+        &src,
+        &dst,
+        &Matcher::default(),
+        &available_codecs(),
+        &SyncOptions {
+            times: true,
+            atimes: true,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    assert_eq!(fs::read(dst.join("file")).unwrap(), b"old");
+
+    reset();
+    sync(
+        &src,
+        &dst,
+        &Matcher::default(),
+        &available_codecs(),
+        &SyncOptions {
+            times: true,
+            atimes: true,
+            compare_atimes: true,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    let meta = fs::metadata(dst.join("file")).unwrap();
+    assert_eq!(fs::read(dst.join("file")).unwrap(), b"new");
+    assert_eq!(FileTime::from_last_access_time(&meta), src_atime);
+}
+
 #[test]
 fn symlink_atimes_roundtrip() {
     let tmp = tempdir().unwrap();
@@ -531,3 +657,139 @@ fn usermap_groupmap_names() {
     assert_eq!(meta.uid(), 0);
     assert_eq!(meta.gid(), 0);
 }
+
+#[test]
+fn metadata_only_updates_permissions_without_data() {
+    let tmp = tempdir().unwrap();
+    let src = tmp.path().join("src");
+    let dst = tmp.path().join("dst");
+    fs::create_dir_all(&src).unwrap();
+    fs::create_dir_all(&dst).unwrap();
+    fs::write(src.join("file"), b"same").unwrap();
+    fs::write(dst.join("file"), b"same").unwrap();
+    fs::write(src.join("new"), b"new").unwrap();
+    fs::set_permissions(src.join("file"), fs::Permissions::from_mode(0o600)).unwrap();
+    fs::set_permissions(dst.join("file"), fs::Permissions::from_mode(0o644)).unwrap();
+    set_file_mtime(src.join("file"), FileTime::from_unix_time(1_000, 0)).unwrap();
+    let ino = fs::metadata(dst.join("file")).unwrap().ino();
+
+    let stats = sync(
+        &src,
+        &dst,
+        &Matcher::default(),
+        &available_codecs(),
+        &SyncOptions {
+            perms: true,
+            metadata_only: true,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let meta = fs::metadata(dst.join("file")).unwrap();
+    assert_eq!(meta.permissions().mode() & 0o777, 0o600);
+    assert_eq!(meta.ino(), ino);
+    assert_eq!(fs::read(dst.join("file")).unwrap(), b"same");
+    assert!(!dst.join("new").exists());
+    assert_eq!(stats.literal_data, 0);
+    assert_eq!(stats.files_transferred, 0);
+}
+
+#[test]
+fn metadata_only_leaves_same_size_file_with_other_content() {
+    let tmp = tempdir().unwrap();
+    let src = tmp.path().join("src");
+    let dst = tmp.path().join("dst");
+    fs::create_dir_all(&src).unwrap();
+    fs::create_dir_all(&dst).unwrap();
+    fs::write(src.join("file"), b"same").unwrap();
+    fs::write(dst.join("file"), b"diff").unwrap();
+    fs::set_permissions(src.join("file"), fs::Permissions::from_mode(0o600)).unwrap();
+    fs::set_permissions(dst.join("file"), fs::Permissions::from_mode(0o644)).unwrap();
+    set_file_mtime(src.join("file"), FileTime::from_unix_time(1_000, 0)).unwrap();
+
+    let stats = sync(
+        &src,
+        &dst,
+        &Matcher::default(),
+        &available_codecs(),
+        &SyncOptions {
+            perms: true,
+            metadata_only: true,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let meta = fs::metadata(dst.join("file")).unwrap();
+    assert_eq!(meta.permissions().mode() & 0o777, 0o644);
+    assert_eq!(fs::read(dst.join("file")).unwrap(), b"diff");
+    assert_eq!(stats.literal_data, 0);
+}
+
+#[test]
+fn keep_dest_perms_preserves_existing_mode() {
+    let tmp = tempdir().unwrap();
+    let src = tmp.path().join("src");
+    let dst = tmp.path().join("dst");
+    fs::create_dir_all(&src).unwrap();
+    fs::create_dir_all(&dst).unwrap();
+    fs::write(src.join("existing"), b"new contents").unwrap();
+    fs::set_permissions(src.join("existing"), fs::Permissions::from_mode(0o644)).unwrap();
+    fs::write(src.join("fresh"), b"fresh").unwrap();
+    fs::set_permissions(src.join("fresh"), fs::Permissions::from_mode(0o640)).unwrap();
+    fs::write(dst.join("existing"), b"old").unwrap();
+    fs::set_permissions(dst.join("existing"), fs::Permissions::from_mode(0o604)).unwrap();
+    let owner = fs::metadata(dst.join("existing")).unwrap();
+
+    sync(
+        &src,
+        &dst,
+        &Matcher::default(),
+        &available_codecs(),
+        &SyncOptions {
+            perms: true,
+            owner: true,
+            group: true,
+            keep_dest_perms: true,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    assert_eq!(fs::read(dst.join("existing")).unwrap(), b"new contents");
+    let meta = fs::metadata(dst.join("existing")).unwrap();
+    assert_eq!(meta.permissions().mode() & 0o777, 0o604);
+    assert_eq!((meta.uid(), meta.gid()), (owner.uid(), owner.gid()));
+    let meta = fs::metadata(dst.join("fresh")).unwrap();
+    assert_eq!(meta.permissions().mode() & 0o777, 0o640);
+}
+
+#[test]
+fn keep_dest_perms_keeps_setuid_bit() {
+    let tmp = tempdir().unwrap();
+    let src = tmp.path().join("src");
+    let dst = tmp.path().join("dst");
+    fs::create_dir_all(&src).unwrap();
+    fs::create_dir_all(&dst).unwrap();
+    fs::write(src.join("tool"), b"new tool").unwrap();
+    fs::write(dst.join("tool"), b"old").unwrap();
+    fs::set_permissions(dst.join("tool"), fs::Permissions::from_mode(0o4755)).unwrap();
+
+    sync(
+        &src,
+        &dst,
+        &Matcher::default(),
+        &available_codecs(),
+        &SyncOptions {
+            perms: true,
+            keep_dest_perms: true,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    assert_eq!(fs::read(dst.join("tool")).unwrap(), b"new tool");
+    let meta = fs::metadata(dst.join("tool")).unwrap();
+    assert_eq!(meta.permissions().mode() & 0o7777, 0o4755);
+}
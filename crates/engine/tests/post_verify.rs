@@ -0,0 +1,53 @@
+// crates/engine/tests/post_verify.rs
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use compress::available_codecs;
+use engine::{EngineError, PathTransform, SyncOptions, sync};
+use filters::Matcher;
+use protocol::ExitCode;
+use tempfile::tempdir;
+
+#[test]
+fn post_verify_accepts_transferred_files() {
+    let tmp = tempdir().unwrap();
+    let src = tmp.path().join("src");
+    let dst = tmp.path().join("dst");
+    fs::create_dir_all(src.join("sub")).unwrap();
+    fs::write(src.join("a"), b"alpha").unwrap();
+    fs::write(src.join("sub/b"), b"bravo").unwrap();
+    let opts = SyncOptions {
+        post_verify: true,
+        ..Default::default()
+    };
+
+    sync(&src, &dst, &Matcher::default(), &available_codecs(), &opts).unwrap();
+    assert_eq!(fs::read(dst.join("sub/b")).unwrap(), b"bravo");
+}
+
+#[test]
+fn post_verify_detects_destination_not_matching_sent_data() {
+    let tmp = tempdir().unwrap();
+    let src = tmp.path().join("src");
+    let dst = tmp.path().join("dst");
+    fs::create_dir_all(&src).unwrap();
+    fs::write(src.join("a"), b"alpha").unwrap();
+    fs::write(src.join("b"), b"bravo").unwrap();
+    let opts = SyncOptions {
+        post_verify: true,
+        path_transform: Some(PathTransform(Arc::new(|_: &Path| {
+            Some(PathBuf::from("merged"))
+        }))),
+        ..Default::default()
+    };
+
+    match sync(&src, &dst, &Matcher::default(), &available_codecs(), &opts) {
+        Err(EngineError::Exit(code, msg)) => {
+            assert_eq!(code, ExitCode::Partial);
+            assert!(msg.contains("1 file(s)"), "{msg}");
+        }
+        other => panic!("expected verification failure, got {other:?}"),
+    }
+}
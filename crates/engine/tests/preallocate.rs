@@ -1,8 +1,10 @@
 // crates/engine/tests/preallocate.rs
 #![cfg(unix)]
 
-use engine::preallocate;
-use tempfile::NamedTempFile;
+use compress::available_codecs;
+use engine::{SyncOptions, preallocate, sync};
+use filters::Matcher;
+use tempfile::{NamedTempFile, tempdir};
 
 #[test]
 fn preallocate_sets_file_length() {
@@ -12,3 +14,43 @@ fn preallocate_sets_file_length() {
     preallocate(&file, size).expect("preallocate failed");
     assert_eq!(file.metadata().unwrap().len(), size);
 }
+
+#[cfg(target_os = "linux")]
+#[test]
+fn preallocate_reserves_blocks_before_data_is_written() {
+    use std::os::unix::fs::MetadataExt;
+
+    let tmp = NamedTempFile::new().unwrap();
+    let file = tmp.reopen().unwrap();
+    let size = 1 << 20;
+    preallocate(&file, size).expect("preallocate failed");
+    let meta = file.metadata().unwrap();
+    assert_eq!(meta.len(), size);
+    assert!(
+        meta.blocks() * 512 >= size,
+        "only {} bytes allocated",
+        meta.blocks() * 512
+    );
+}
+
+#[test]
+fn sync_with_preallocate_copies_data() {
+    let tmp = tempdir().unwrap();
+    let src = tmp.path().join("src");
+    let dst = tmp.path().join("dst");
+    std::fs::create_dir_all(&src).unwrap();
+    let data: Vec<u8> = (0..64 * 1024).map(|i| (i % 253) as u8).collect();
+    std::fs::write(src.join("file"), &data).unwrap();
+    sync(
+        &src,
+        &dst,
+        &Matcher::default(),
+        &available_codecs(),
+        &SyncOptions {
+            preallocate: true,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    assert_eq!(std::fs::read(dst.join("file")).unwrap(), data);
+}
@@ -64,3 +64,87 @@ fn receiver_emits_progress_events() {
     let events = sink.events.lock().unwrap().clone();
     assert_eq!(events, vec!["start:4:0", "update:4", "finish"]);
 }
+
+struct InterruptSink {
+    limit: u64,
+    last: Mutex<u64>,
+}
+
+impl Observer for InterruptSink {
+    fn start_file(&self, _path: &Path, _total: u64, _written: u64) {}
+
+    fn update(&self, written: u64) {
+        *self.last.lock().unwrap() = written;
+        if written >= self.limit {
+            panic!("interrupted");
+        }
+    }
+
+    fn finish_file(&self) {}
+
+    fn progress(&self, _line: &str) {}
+}
+
+#[test]
+fn interrupted_partial_matches_reported_progress() {
+    let dir = tempdir().unwrap();
+    let src_path = dir.path().join("src");
+    std::fs::write(&src_path, vec![b'x'; 10_000]).unwrap();
+    let dest_path = dir.path().join("dest");
+
+    let sink = Arc::new(InterruptSink {
+        limit: 4_000,
+        last: Mutex::new(0),
+    });
+    let mut recv = Receiver::new(
+        None,
+        SyncOptions {
+            partial: true,
+            progress: true,
+            quiet: true,
+            ..Default::default()
+        },
+    );
+    recv.set_progress_sink(sink.clone());
+
+    let delta: Vec<_> = (0..10).map(|_| Ok(Op::Data(vec![b'x'; 1_000]))).collect();
+    let res = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        recv.apply(&src_path, &dest_path, Path::new(""), delta)
+    }));
+    assert!(res.is_err());
+
+    let reported = *sink.last.lock().unwrap();
+    assert_eq!(reported, 4_000);
+    let partial = dir.path().join("dest.partial");
+    assert_eq!(std::fs::metadata(&partial).unwrap().len(), reported);
+    assert!(!dest_path.exists());
+}
+
+#[test]
+fn partial_progress_transfer_renames_into_place() {
+    let dir = tempdir().unwrap();
+    let src_path = dir.path().join("src");
+    std::fs::write(&src_path, b"abcd").unwrap();
+    let dest_path = dir.path().join("dest");
+
+    let sink = Arc::new(MockSink::default());
+    let mut recv = Receiver::new(
+        None,
+        SyncOptions {
+            partial: true,
+            progress: true,
+            quiet: true,
+            ..Default::default()
+        },
+    );
+    recv.set_progress_sink(sink.clone());
+
+    let delta = vec![Ok(Op::Data(b"abcd".to_vec()))];
+    recv.apply(&src_path, &dest_path, Path::new(""), delta)
+        .unwrap();
+
+    assert_eq!(std::fs::read(&dest_path).unwrap(), b"abcd");
+    assert!(!dir.path().join("dest.partial").exists());
+    let events = sink.events.lock().unwrap().clone();
+    assert_eq!(events, vec!["start:4:0", "update:4", "finish"]);
+}
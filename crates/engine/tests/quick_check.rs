@@ -0,0 +1,78 @@
+// crates/engine/tests/quick_check.rs
+
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+use engine::{SyncOptions, quick_check};
+use filetime::{FileTime, set_file_mtime};
+use tempfile::tempdir;
+
+fn file(path: &Path, data: &[u8], mtime: i64) -> fs::Metadata {
+    fs::write(path, data).unwrap();
+    set_file_mtime(path, FileTime::from_unix_time(mtime, 0)).unwrap();
+    fs::metadata(path).unwrap()
+}
+
+#[test]
+fn equal_size_and_mtime_pass() {
+    let tmp = tempdir().unwrap();
+    let src = file(&tmp.path().join("a"), b"same", 1_000);
+    let dst = file(&tmp.path().join("b"), b"diff", 1_000);
+    assert!(quick_check(&src, &dst, &SyncOptions::default()));
+}
+
+#[test]
+fn size_difference_fails() {
+    let tmp = tempdir().unwrap();
+    let src = file(&tmp.path().join("a"), b"short", 1_000);
+    let dst = file(&tmp.path().join("b"), b"longer", 1_000);
+    assert!(!quick_check(&src, &dst, &SyncOptions::default()));
+}
+
+#[test]
+fn mtime_respects_modify_window() {
+    let tmp = tempdir().unwrap();
+    let src = file(&tmp.path().join("a"), b"data", 1_000);
+    let dst = file(&tmp.path().join("b"), b"data", 1_002);
+    assert!(!quick_check(&src, &dst, &SyncOptions::default()));
+
+    let within = SyncOptions {
+        modify_window: Duration::from_secs(2),
+        ..Default::default()
+    };
+    assert!(quick_check(&src, &dst, &within));
+    assert!(quick_check(&dst, &src, &within));
+
+    let outside = SyncOptions {
+        modify_window: Duration::from_secs(1),
+        ..Default::default()
+    };
+    assert!(!quick_check(&src, &dst, &outside));
+}
+
+#[test]
+fn size_only_ignores_mtime() {
+    let tmp = tempdir().unwrap();
+    let src = file(&tmp.path().join("a"), b"data", 1_000);
+    let dst = file(&tmp.path().join("b"), b"data", 5_000);
+    let opts = SyncOptions {
+        size_only: true,
+        ..Default::default()
+    };
+    assert!(quick_check(&src, &dst, &opts));
+    let longer = file(&tmp.path().join("c"), b"data!", 1_000);
+    assert!(!quick_check(&src, &longer, &opts));
+}
+
+#[test]
+fn ignore_times_never_matches() {
+    let tmp = tempdir().unwrap();
+    let src = file(&tmp.path().join("a"), b"data", 1_000);
+    let dst = file(&tmp.path().join("b"), b"data", 1_000);
+    let opts = SyncOptions {
+        ignore_times: true,
+        ..Default::default()
+    };
+    assert!(!quick_check(&src, &dst, &opts));
+}
@@ -1,7 +1,11 @@
 // crates/engine/tests/receiver.rs
-use engine::{Op, Receiver, SyncOptions};
+use checksums::ChecksumConfigBuilder;
+use compress::available_codecs;
+use engine::{Op, Receiver, SentSum, SyncOptions, sync};
+use filters::Matcher;
 use std::fs;
 use std::path::Path;
+use std::sync::{Arc, Mutex};
 use tempfile::tempdir;
 
 #[test]
@@ -73,3 +77,70 @@ fn apply_with_existing_partial() {
     assert_eq!(output, b"old!");
     assert!(!partial.exists());
 }
+
+fn sent_sum(opts: &SyncOptions, data: &[u8]) -> SentSum {
+    let mut hasher = ChecksumConfigBuilder::new()
+        .strong(opts.strong)
+        .file_strong(opts.file_strong.unwrap_or(opts.strong))
+        .seed(opts.checksum_seed)
+        .build()
+        .file_hasher();
+    hasher.update(data);
+    Arc::new(Mutex::new(Some(hasher.finalize())))
+}
+
+#[test]
+fn verify_after_write_discards_corrupted_file() {
+    let tmp = tempdir().unwrap();
+    let src = tmp.path().join("src.txt");
+    let dest = tmp.path().join("dest.txt");
+    fs::write(&src, b"hello").unwrap();
+
+    let opts = SyncOptions {
+        verify_after_write: true,
+        ..Default::default()
+    };
+    let sum = sent_sum(&opts, b"hello");
+    let mut recv = Receiver::new(None, opts);
+    recv.expect_sent_sum(Some(sum));
+
+    let delta = vec![Ok(Op::Data(b"hallo".to_vec()))];
+    let err = recv.apply(&src, &dest, Path::new(""), delta).unwrap_err();
+    assert!(err.to_string().contains("failed verification"), "{err}");
+    assert!(!dest.exists());
+    assert_eq!(fs::read_dir(tmp.path()).unwrap().count(), 1);
+
+    fs::write(&src, b"changed after sending").unwrap();
+    let delta = vec![Ok(Op::Data(b"hello".to_vec()))];
+    recv.apply(&src, &dest, Path::new(""), delta).unwrap();
+    assert_eq!(fs::read(&dest).unwrap(), b"hello");
+}
+
+#[test]
+fn verify_after_write_accepts_delta_transfer() {
+    let tmp = tempdir().unwrap();
+    let src = tmp.path().join("src");
+    let dst = tmp.path().join("dst");
+    fs::create_dir_all(&src).unwrap();
+    fs::create_dir_all(&dst).unwrap();
+    let data: Vec<u8> = (0..200_000u32).map(|i| (i % 251) as u8).collect();
+    let mut old = data.clone();
+    old[100_000] ^= 0xff;
+    fs::write(src.join("file"), &data).unwrap();
+    fs::write(dst.join("file"), &old).unwrap();
+
+    let stats = sync(
+        &src,
+        &dst,
+        &Matcher::default(),
+        &available_codecs(),
+        &SyncOptions {
+            verify_after_write: true,
+            ignore_times: true,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    assert!(stats.matched_data > 0);
+    assert_eq!(fs::read(dst.join("file")).unwrap(), data);
+}
@@ -0,0 +1,74 @@
+// crates/engine/tests/sparse_inplace.rs
+#![cfg(target_os = "linux")]
+
+use std::fs::{self, OpenOptions};
+use std::os::unix::fs::{FileExt, MetadataExt};
+use std::path::Path;
+
+use compress::available_codecs;
+use engine::{SyncOptions, sync};
+use filetime::{FileTime, set_file_mtime};
+use filters::Matcher;
+use tempfile::tempdir;
+
+const LEN: u64 = 32 * 1024 * 1024;
+
+fn bytes_read() -> u64 {
+    fs::read_to_string("/proc/self/io")
+        .unwrap()
+        .lines()
+        .find_map(|l| l.strip_prefix("rchar:"))
+        .unwrap()
+        .trim()
+        .parse()
+        .unwrap()
+}
+
+fn write_sparse(path: &Path, fill: u8) {
+    let file = OpenOptions::new()
+        .create(true)
+        .truncate(true)
+        .write(true)
+        .open(path)
+        .unwrap();
+    file.set_len(LEN).unwrap();
+    file.write_all_at(&[fill; 64 * 1024], 0).unwrap();
+    file.write_all_at(&[fill; 64 * 1024], LEN / 2).unwrap();
+}
+
+#[test]
+fn inplace_sparse_transfer_skips_shared_holes() {
+    let tmp = tempdir().unwrap();
+    let src = tmp.path().join("src");
+    let dst = tmp.path().join("dst");
+    fs::create_dir_all(&src).unwrap();
+    fs::create_dir_all(&dst).unwrap();
+    write_sparse(&src.join("file"), 1);
+    write_sparse(&dst.join("file"), 2);
+    set_file_mtime(dst.join("file"), FileTime::from_unix_time(0, 0)).unwrap();
+    if fs::metadata(src.join("file")).unwrap().blocks() * 512 >= LEN {
+        return;
+    }
+
+    let before = bytes_read();
+    sync(
+        &src,
+        &dst,
+        &Matcher::default(),
+        &available_codecs(),
+        &SyncOptions {
+            sparse: true,
+            inplace: true,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    let read = bytes_read() - before;
+
+    assert!(read < LEN / 16, "read {read} bytes of {LEN}");
+    assert_eq!(
+        fs::read(dst.join("file")).unwrap(),
+        fs::read(src.join("file")).unwrap()
+    );
+    assert!(fs::metadata(dst.join("file")).unwrap().blocks() * 512 < LEN);
+}
@@ -57,6 +57,85 @@ fn devices_roundtrip() {
     assert_eq!(meta.rdev(), meta::makedev(1, 3));
 }
 
+#[test]
+fn devices_replace_node_with_other_numbers() {
+    if !tests::requires_capability(tests::CapabilityCheck::CapMknod) {
+        return;
+    }
+    let tmp = tempdir().unwrap();
+    let src = tmp.path().join("src");
+    let dst = tmp.path().join("dst");
+    fs::create_dir_all(&src).unwrap();
+    fs::create_dir_all(&dst).unwrap();
+    #[allow(clippy::useless_conversion)]
+    for (dir, minor) in [(&src, 5), (&dst, 3)] {
+        mknod(
+            &dir.join("dev"),
+            SFlag::S_IFCHR,
+            Mode::from_bits_truncate(0o640),
+            meta::makedev(1, minor).try_into().unwrap(),
+        )
+        .unwrap();
+    }
+    sync(
+        &src,
+        &dst,
+        &Matcher::default(),
+        &available_codecs(),
+        &SyncOptions {
+            devices: true,
+            perms: true,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    let meta = fs::symlink_metadata(dst.join("dev")).unwrap();
+    assert!(meta.file_type().is_char_device());
+    assert_eq!(meta::major(meta.rdev()), 1);
+    assert_eq!(meta::minor(meta.rdev()), 5);
+    assert_eq!(meta.mode() & 0o777, 0o640);
+}
+
+#[test]
+fn unchanged_nodes_are_not_recreated() {
+    if !tests::requires_capability(tests::CapabilityCheck::CapMknod) {
+        return;
+    }
+    let tmp = tempdir().unwrap();
+    let src = tmp.path().join("src");
+    let dst = tmp.path().join("dst");
+    fs::create_dir_all(&src).unwrap();
+    fs::create_dir_all(&dst).unwrap();
+    #[allow(clippy::useless_conversion)]
+    mknod(
+        &src.join("null"),
+        SFlag::S_IFCHR,
+        Mode::from_bits_truncate(0o600),
+        meta::makedev(1, 3).try_into().unwrap(),
+    )
+    .unwrap();
+    mkfifo(&src.join("fifo"), Mode::from_bits_truncate(0o600)).unwrap();
+    let opts = SyncOptions {
+        devices: true,
+        specials: true,
+        perms: true,
+        ..Default::default()
+    };
+    let run = || {
+        sync(&src, &dst, &Matcher::default(), &available_codecs(), &opts).unwrap();
+        (
+            fs::symlink_metadata(dst.join("null")).unwrap().ino(),
+            fs::symlink_metadata(dst.join("fifo")).unwrap().ino(),
+        )
+    };
+    let first = run();
+    let second = run();
+    assert_eq!(first, second);
+    let meta = fs::symlink_metadata(dst.join("null")).unwrap();
+    assert!(meta.file_type().is_char_device());
+    assert_eq!(meta.rdev(), meta::makedev(1, 3));
+}
+
 #[test]
 fn copy_devices_creates_regular_files() {
     if !tests::requires_capability(tests::CapabilityCheck::CapMknod) {
@@ -152,6 +231,42 @@ fn specials_roundtrip() {
     assert!(meta.file_type().is_fifo());
 }
 
+#[test]
+fn specials_roundtrip_socket_and_skip_without_flag() {
+    let tmp = tempdir().unwrap();
+    let src = tmp.path().join("src");
+    fs::create_dir_all(&src).unwrap();
+    mkfifo(&src.join("fifo"), Mode::from_bits_truncate(0o640)).unwrap();
+    let _listener = std::os::unix::net::UnixListener::bind(src.join("sock")).unwrap();
+
+    for specials in [true, false] {
+        let dst = tmp.path().join(format!("dst_{specials}"));
+        fs::create_dir_all(&dst).unwrap();
+        sync(
+            &src,
+            &dst,
+            &Matcher::default(),
+            &available_codecs(),
+            &SyncOptions {
+                specials,
+                perms: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        if specials {
+            let fifo = fs::symlink_metadata(dst.join("fifo")).unwrap();
+            assert!(fifo.file_type().is_fifo());
+            assert_eq!(fifo.mode() & 0o777, 0o640);
+            let sock = fs::symlink_metadata(dst.join("sock")).unwrap();
+            assert!(sock.file_type().is_socket());
+        } else {
+            assert!(fs::symlink_metadata(dst.join("fifo")).is_err());
+            assert!(fs::symlink_metadata(dst.join("sock")).is_err());
+        }
+    }
+}
+
 #[test]
 fn sparse_roundtrip() {
     let tmp = tempdir().unwrap();
@@ -284,3 +399,47 @@ fn sparse_trailing_hole() {
     assert_eq!(src_meta.blocks(), dst_meta.blocks());
     assert!(dst_meta.blocks() * 512 < dst_meta.len());
 }
+
+#[cfg(target_os = "linux")]
+#[test]
+fn sparse_inplace_punches_holes_in_existing_file() {
+    let tmp = tempdir().unwrap();
+    let src = tmp.path().join("src");
+    let dst = tmp.path().join("dst");
+    fs::create_dir_all(&src).unwrap();
+    fs::create_dir_all(&dst).unwrap();
+    let len = 1 << 20;
+    let sp = src.join("sparse");
+    {
+        let mut f = File::create(&sp).unwrap();
+        f.write_all(b"start").unwrap();
+        f.seek(SeekFrom::Start(len - 3)).unwrap();
+        f.write_all(b"end").unwrap();
+    }
+    fs::write(dst.join("sparse"), vec![0xAAu8; len as usize]).unwrap();
+    filetime::set_file_mtime(dst.join("sparse"), filetime::FileTime::from_unix_time(0, 0)).unwrap();
+    sync(
+        &src,
+        &dst,
+        &Matcher::default(),
+        &available_codecs(),
+        &SyncOptions {
+            sparse: true,
+            inplace: true,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    let src_meta = fs::metadata(&sp).unwrap();
+    if src_meta.blocks() * 512 >= src_meta.len() {
+        eprintln!("skipping test: filesystem lacks sparse-file support");
+        return;
+    }
+    assert_eq!(
+        fs::read(&sp).unwrap(),
+        fs::read(dst.join("sparse")).unwrap()
+    );
+    let dst_meta = fs::metadata(dst.join("sparse")).unwrap();
+    assert_eq!(src_meta.len(), dst_meta.len());
+    assert!(dst_meta.blocks() * 512 < dst_meta.len());
+}
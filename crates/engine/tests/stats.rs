@@ -0,0 +1,63 @@
+// crates/engine/tests/stats.rs
+
+use std::collections::HashMap;
+use std::fs;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use compress::available_codecs;
+use engine::{Stats, SyncOptions, sync};
+use filters::Matcher;
+use tempfile::tempdir;
+
+fn fields(line: &str) -> HashMap<&str, &str> {
+    line.split_whitespace()
+        .map(|kv| kv.split_once('=').expect("key=value"))
+        .collect()
+}
+
+#[test]
+fn machine_summary_reports_start_and_elapsed() {
+    let tmp = tempdir().unwrap();
+    let src = tmp.path().join("src");
+    let dst = tmp.path().join("dst");
+    fs::create_dir_all(&src).unwrap();
+    fs::write(src.join("file"), b"hello").unwrap();
+
+    let before = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+    let stats = sync(
+        &src,
+        &dst,
+        &Matcher::default(),
+        &available_codecs(),
+        &SyncOptions::default(),
+    )
+    .unwrap();
+    let after = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+
+    let line = stats.machine_summary();
+    let map = fields(&line);
+    assert_eq!(map["files_transferred"], "1");
+    assert_eq!(map["bytes_transferred"], "5");
+    let start: u64 = map["start_time"].parse().unwrap();
+    let end: u64 = map["end_time"].parse().unwrap();
+    let elapsed: f64 = map["elapsed_secs"].parse().unwrap();
+    assert!(before.as_secs() <= start && start <= after.as_secs());
+    assert!(start <= end && end <= after.as_secs() + 1);
+    assert!((0.0..60.0).contains(&elapsed), "{line}");
+}
+
+#[test]
+fn end_time_follows_elapsed() {
+    let stats = Stats {
+        start_time: Instant::now() - Duration::from_secs(3),
+        started_at: UNIX_EPOCH + Duration::from_secs(1_000),
+        ..Stats::default()
+    };
+    let end = stats.end_time().duration_since(UNIX_EPOCH).unwrap();
+    assert!((1_003..1_005).contains(&end.as_secs()));
+    let line = stats.machine_summary();
+    let map = fields(&line);
+    assert_eq!(map["start_time"], "1000");
+    let elapsed: f64 = map["elapsed_secs"].parse().unwrap();
+    assert!((3.0..5.0).contains(&elapsed), "{line}");
+}
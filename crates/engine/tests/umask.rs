@@ -0,0 +1,46 @@
+// crates/engine/tests/umask.rs
+#![cfg(unix)]
+
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+
+use compress::available_codecs;
+use engine::{SyncOptions, sync};
+use filters::Matcher;
+use nix::sys::stat::{Mode, umask};
+use tempfile::tempdir;
+
+fn mode(path: &std::path::Path) -> u32 {
+    fs::metadata(path).unwrap().permissions().mode() & 0o7777
+}
+
+#[test]
+fn umask_option_overrides_process_umask() {
+    for ambient in [0o000, 0o077] {
+        let tmp = tempdir().unwrap();
+        let src = tmp.path().join("src");
+        let dst = tmp.path().join("dst");
+        fs::create_dir_all(src.join("sub")).unwrap();
+        fs::create_dir_all(&dst).unwrap();
+        let file = src.join("sub/file");
+        fs::write(&file, b"data").unwrap();
+        fs::set_permissions(&file, fs::Permissions::from_mode(0o777)).unwrap();
+
+        let old = umask(Mode::from_bits_truncate(ambient));
+        let res = sync(
+            &src,
+            &dst,
+            &Matcher::default(),
+            &available_codecs(),
+            &SyncOptions {
+                umask: Some(0o027),
+                ..Default::default()
+            },
+        );
+        umask(old);
+        res.unwrap();
+
+        assert_eq!(mode(&dst.join("sub/file")), 0o750);
+        assert_eq!(mode(&dst.join("sub")), 0o750);
+    }
+}
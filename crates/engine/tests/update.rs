@@ -63,3 +63,45 @@ fn update_skips_new_files() {
     sync(&src, &dst, &Matcher::default(), &available_codecs(), &opts).unwrap();
     assert!(!dst.join("new.txt").exists());
 }
+
+#[test]
+fn existing_creates_nothing_in_empty_destination() {
+    let tmp = tempdir().unwrap();
+    let src = tmp.path().join("src");
+    let dst = tmp.path().join("dst");
+    fs::create_dir_all(src.join("sub/deep")).unwrap();
+    fs::create_dir_all(src.join("empty")).unwrap();
+    fs::create_dir_all(&dst).unwrap();
+    fs::write(src.join("top.txt"), b"top").unwrap();
+    fs::write(src.join("sub/a.txt"), b"a").unwrap();
+    fs::write(src.join("sub/deep/b.txt"), b"b").unwrap();
+    let opts = SyncOptions {
+        existing: true,
+        ..Default::default()
+    };
+    sync(&src, &dst, &Matcher::default(), &available_codecs(), &opts).unwrap();
+    assert_eq!(fs::read_dir(&dst).unwrap().count(), 0);
+}
+
+#[test]
+fn existing_updates_files_in_existing_dirs() {
+    let tmp = tempdir().unwrap();
+    let src = tmp.path().join("src");
+    let dst = tmp.path().join("dst");
+    fs::create_dir_all(src.join("sub")).unwrap();
+    fs::create_dir_all(src.join("new")).unwrap();
+    fs::create_dir_all(dst.join("sub")).unwrap();
+    fs::write(src.join("sub/a.txt"), b"new").unwrap();
+    fs::write(src.join("sub/b.txt"), b"b").unwrap();
+    fs::write(src.join("new/c.txt"), b"c").unwrap();
+    fs::write(dst.join("sub/a.txt"), b"old").unwrap();
+    set_file_mtime(dst.join("sub/a.txt"), FileTime::from_unix_time(0, 0)).unwrap();
+    let opts = SyncOptions {
+        existing: true,
+        ..Default::default()
+    };
+    sync(&src, &dst, &Matcher::default(), &available_codecs(), &opts).unwrap();
+    assert_eq!(fs::read(dst.join("sub/a.txt")).unwrap(), b"new");
+    assert!(!dst.join("sub/b.txt").exists());
+    assert!(!dst.join("new").exists());
+}
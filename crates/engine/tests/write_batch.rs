@@ -2,7 +2,9 @@
 use std::fs;
 
 use compress::available_codecs;
-use engine::{SyncOptions, sync};
+use engine::{
+    BatchFormat, RsyncBatchEntry, SyncOptions, encode_rsync_flist, encode_rsync_header, sync,
+};
 use filters::Matcher;
 use tempfile::tempdir;
 
@@ -68,3 +70,70 @@ fn reads_batch_file() {
     assert!(dst2.join("file").exists());
     assert!(!dst2.join("file2").exists());
 }
+
+#[test]
+fn rsync_header_encodes_stream_flags() {
+    let header = encode_rsync_header(1 | 1 << 7, 0x1234);
+    assert_eq!(
+        header,
+        [0x81, 0, 0, 0, 30, 0, 0, 0, 0, 0x34, 0x12, 0, 0].to_vec()
+    );
+}
+
+#[test]
+fn rsync_flist_compresses_shared_prefixes() {
+    let entries = [
+        RsyncBatchEntry {
+            name: b".".to_vec(),
+            mode: 0o40755,
+            size: 0,
+            mtime: 0,
+        },
+        RsyncBatchEntry {
+            name: b"ab".to_vec(),
+            mode: 0o100644,
+            size: 5,
+            mtime: 1,
+        },
+        RsyncBatchEntry {
+            name: b"ac".to_vec(),
+            mode: 0o100644,
+            size: 300,
+            mtime: 1,
+        },
+    ];
+    let flist = encode_rsync_flist(&entries);
+    let mut expected = vec![0x01, 1, b'.', 0, 0, 0, 0, 0, 0, 0, 0xed, 0x41, 0, 0];
+    expected.extend([0x01, 2, b'a', b'b', 0, 5, 0, 0, 1, 0, 0, 0xa4, 0x81, 0, 0]);
+    expected.extend([
+        0x20, 1, 1, b'c', 0, 0x2c, 0x01, 0, 1, 0, 0, 0xa4, 0x81, 0, 0,
+    ]);
+    expected.push(0);
+    assert_eq!(flist, expected);
+}
+
+#[test]
+fn writes_rsync_batch_format() {
+    let tmp = tempdir().unwrap();
+    let src = tmp.path().join("src");
+    let dst = tmp.path().join("dst");
+    fs::create_dir_all(&src).unwrap();
+    fs::write(src.join("file"), b"hi").unwrap();
+    let batch = tmp.path().join("batch.bin");
+    sync(
+        &src,
+        &dst,
+        &Matcher::default(),
+        &available_codecs(),
+        &SyncOptions {
+            write_batch: Some(batch.clone()),
+            batch_format: BatchFormat::Rsync,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    let bytes = fs::read(batch).unwrap();
+    assert!(bytes.starts_with(&encode_rsync_header(1 | 1 << 7, 0)));
+    assert!(bytes.windows(2).any(|w| w == b"hi"));
+    assert_eq!(fs::read(dst.join("file")).unwrap(), b"hi");
+}
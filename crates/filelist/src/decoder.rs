@@ -5,6 +5,7 @@ use std::io::Read;
 use thiserror::Error;
 
 use crate::entry::Entry;
+use crate::{NAMED_ID_TAG, NEW_ID_TAG};
 
 #[derive(Debug, Default)]
 pub struct Decoder {
@@ -109,6 +110,14 @@ impl Decoder {
         }
         let default_acl = rest[..dacl_len].to_vec();
         rest = &rest[dacl_len..];
+        let Some((&times_len, tail)) = rest.split_first() else {
+            return Err(DecodeError::ShortInput);
+        };
+        if tail.len() < times_len as usize {
+            return Err(DecodeError::ShortInput);
+        }
+        let times = tail[..times_len as usize].to_vec();
+        rest = &tail[times_len as usize..];
         debug_assert!(rest.is_empty());
         self.prev_path = path.clone();
         Ok(Entry {
@@ -119,6 +128,7 @@ impl Decoder {
             xattrs,
             acl,
             default_acl,
+            times,
         })
     }
 }
@@ -133,7 +143,7 @@ fn decode_id<'a>(
     }
     let tag = input[0];
     input = &input[1..];
-    if tag == 0xFF {
+    if tag == NEW_ID_TAG || tag == NAMED_ID_TAG {
         if input.len() < 4 {
             return Err(DecodeError::ShortInput);
         }
@@ -141,9 +151,23 @@ fn decode_id<'a>(
         let mut buf = [0u8; 4];
         rdr.read_exact(&mut buf)
             .map_err(|_| DecodeError::ShortInput)?;
-        let id = u32::from_le_bytes(buf);
+        let mut id = u32::from_le_bytes(buf);
+        input = &input[4..];
+        if tag == NAMED_ID_TAG {
+            let Some((&len, rest)) = input.split_first() else {
+                return Err(DecodeError::ShortInput);
+            };
+            if rest.len() < len as usize {
+                return Err(DecodeError::ShortInput);
+            }
+            let name = String::from_utf8_lossy(&rest[..len as usize]);
+            if let Some(local) = local_id(&name, is_uid) {
+                id = local;
+            }
+            input = &rest[len as usize..];
+        }
         table.push(id);
-        Ok((id, &input[4..]))
+        Ok((id, input))
     } else {
         let idx = tag as usize;
         if idx >= table.len() {
@@ -156,3 +180,17 @@ fn decode_id<'a>(
         Ok((table[idx], input))
     }
 }
+
+#[cfg(unix)]
+fn local_id(name: &str, is_uid: bool) -> Option<u32> {
+    if is_uid {
+        meta::uid_from_name(name)
+    } else {
+        meta::gid_from_name(name)
+    }
+}
+
+#[cfg(not(unix))]
+fn local_id(_name: &str, _is_uid: bool) -> Option<u32> {
+    None
+}
@@ -3,12 +3,14 @@
 use std::collections::HashMap;
 
 use crate::entry::Entry;
+use crate::{NAMED_ID_TAG, NEW_ID_TAG};
 
 #[derive(Debug, Default)]
 pub struct Encoder {
     prev_path: Vec<u8>,
     uid_table: HashMap<u32, u8>,
     gid_table: HashMap<u32, u8>,
+    send_names: bool,
 }
 
 impl Encoder {
@@ -16,6 +18,15 @@ impl Encoder {
         Self::default()
     }
 
+    /// Creates an encoder that sends the user and group name alongside each
+    /// newly seen uid/gid so the receiver can map them to its own ids.
+    pub fn with_names() -> Self {
+        Self {
+            send_names: true,
+            ..Self::default()
+        }
+    }
+
     pub fn encode_entry(&mut self, entry: &Entry) -> Vec<u8> {
         let mut out = Vec::new();
         let common = common_prefix(&self.prev_path, &entry.path) as u8;
@@ -23,11 +34,13 @@ impl Encoder {
         out.push(common);
         out.push(suffix.len() as u8);
         out.extend_from_slice(suffix);
-        out.extend_from_slice(&encode_id(entry.uid, &mut self.uid_table));
-        out.extend_from_slice(&encode_id(entry.gid, &mut self.gid_table));
+        let uid_name = self.send_names.then(|| user_name(entry.uid)).flatten();
+        let gid_name = self.send_names.then(|| group_name(entry.gid)).flatten();
+        out.extend_from_slice(&encode_id(entry.uid, uid_name, &mut self.uid_table));
+        out.extend_from_slice(&encode_id(entry.gid, gid_name, &mut self.gid_table));
         if let Some(group) = entry.hardlink {
             out.push(1);
-            out.extend_from_slice(&encode_id(group, &mut self.gid_table));
+            out.extend_from_slice(&encode_id(group, None, &mut self.gid_table));
         } else {
             out.push(0);
         }
@@ -42,6 +55,8 @@ impl Encoder {
         out.extend_from_slice(&entry.acl);
         out.extend_from_slice(&(entry.default_acl.len() as u32).to_le_bytes());
         out.extend_from_slice(&entry.default_acl);
+        out.push(entry.times.len() as u8);
+        out.extend_from_slice(&entry.times);
         self.prev_path = entry.path.clone();
         out
     }
@@ -51,14 +66,46 @@ fn common_prefix(a: &[u8], b: &[u8]) -> usize {
     a.iter().zip(b.iter()).take_while(|(x, y)| x == y).count()
 }
 
-fn encode_id(id: u32, table: &mut HashMap<u32, u8>) -> Vec<u8> {
+#[cfg(unix)]
+fn user_name(uid: u32) -> Option<String> {
+    meta::uid_to_name(uid)
+}
+
+#[cfg(not(unix))]
+fn user_name(_uid: u32) -> Option<String> {
+    None
+}
+
+#[cfg(unix)]
+fn group_name(gid: u32) -> Option<String> {
+    meta::gid_to_name(gid)
+}
+
+#[cfg(not(unix))]
+fn group_name(_gid: u32) -> Option<String> {
+    None
+}
+
+fn encode_id(id: u32, name: Option<String>, table: &mut HashMap<u32, u8>) -> Vec<u8> {
     if let Some(&idx) = table.get(&id) {
-        vec![idx]
-    } else {
-        let idx = table.len() as u8;
+        return vec![idx];
+    }
+    let idx = table.len() as u8;
+    if idx < NAMED_ID_TAG {
         table.insert(id, idx);
-        let mut out = vec![0xFF];
-        out.extend_from_slice(&id.to_le_bytes());
-        out
+    }
+    match name.filter(|n| !n.is_empty() && n.len() <= u8::MAX as usize) {
+        Some(name) => {
+            let mut out = vec![NAMED_ID_TAG];
+            out.extend_from_slice(&id.to_le_bytes());
+            out.push(name.len() as u8);
+            out.extend_from_slice(name.as_bytes());
+            out
+        }
+        None => {
+            let mut out = vec![NEW_ID_TAG];
+            out.extend_from_slice(&id.to_le_bytes());
+            out
+        }
     }
 }
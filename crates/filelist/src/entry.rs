@@ -11,6 +11,7 @@ pub struct Entry {
     pub xattrs: Vec<(Vec<u8>, Vec<u8>)>,
     pub acl: Vec<u8>,
     pub default_acl: Vec<u8>,
+    pub times: Vec<u8>,
 }
 
 #[cfg(unix)]
@@ -24,6 +25,7 @@ pub struct InodeEntry {
     pub xattrs: Vec<(Vec<u8>, Vec<u8>)>,
     pub acl: Vec<u8>,
     pub default_acl: Vec<u8>,
+    pub times: Vec<u8>,
 }
 
 #[cfg(unix)]
@@ -56,6 +58,7 @@ pub fn group_by_inode(entries: &[InodeEntry]) -> Vec<Entry> {
             xattrs: e.xattrs.clone(),
             acl: e.acl.clone(),
             default_acl: e.default_acl.clone(),
+            times: e.times.clone(),
         });
     }
     out
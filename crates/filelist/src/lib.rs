@@ -11,6 +11,11 @@ pub use decoder::{DecodeError, Decoder};
 pub use encoder::Encoder;
 pub use entry::{Entry, InodeEntry, group_by_inode};
 
+/// Tag introducing a uid/gid that has not been sent before.
+pub(crate) const NEW_ID_TAG: u8 = 0xFF;
+/// Tag introducing a new uid/gid followed by its user or group name.
+pub(crate) const NAMED_ID_TAG: u8 = 0xFE;
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -26,6 +31,7 @@ mod tests {
                 xattrs: vec![(b"user.test".to_vec(), b"val".to_vec())],
                 acl: vec![1, 0, 0, 0, 0, 7, 0, 0, 0],
                 default_acl: Vec::new(),
+                times: Vec::new(),
             },
             Entry {
                 path: b"dir/file2".to_vec(),
@@ -35,6 +41,7 @@ mod tests {
                 xattrs: Vec::new(),
                 acl: Vec::new(),
                 default_acl: vec![1, 0, 0, 0, 0, 7, 0, 0, 0],
+                times: Vec::new(),
             },
             Entry {
                 path: b"other".to_vec(),
@@ -44,6 +51,7 @@ mod tests {
                 xattrs: Vec::new(),
                 acl: Vec::new(),
                 default_acl: Vec::new(),
+                times: Vec::new(),
             },
         ];
         let mut enc = Encoder::new();
@@ -65,6 +73,7 @@ mod tests {
             xattrs: Vec::new(),
             acl: Vec::new(),
             default_acl: Vec::new(),
+            times: Vec::new(),
         };
         let e2 = Entry {
             path: b"dir/file2".to_vec(),
@@ -74,6 +83,7 @@ mod tests {
             xattrs: Vec::new(),
             acl: Vec::new(),
             default_acl: Vec::new(),
+            times: Vec::new(),
         };
         let mut enc = Encoder::new();
         let mut dec = Decoder::new();
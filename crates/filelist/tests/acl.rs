@@ -11,6 +11,7 @@ fn roundtrip_acl_entries() {
         xattrs: Vec::new(),
         acl: vec![1, 0, 0, 0, 0, 7, 0, 0, 0],
         default_acl: vec![1, 0, 0, 0, 0, 7, 0, 0, 0],
+        times: Vec::new(),
     };
     let mut enc = Encoder::new();
     let payload = enc.encode_entry(&entry);
@@ -29,6 +30,7 @@ fn roundtrip_root_default_acl() {
         xattrs: Vec::new(),
         acl: Vec::new(),
         default_acl: vec![1, 0, 0, 0, 0, 7, 0, 0, 0],
+        times: Vec::new(),
     };
     let mut enc = Encoder::new();
     let payload = enc.encode_entry(&entry);
@@ -31,6 +31,7 @@ fn encodes_paths_and_reuses_id_tables() {
         xattrs: Vec::new(),
         acl: Vec::new(),
         default_acl: Vec::new(),
+        times: Vec::new(),
     };
     let entry2 = Entry {
         path: dir
@@ -44,6 +45,7 @@ fn encodes_paths_and_reuses_id_tables() {
         xattrs: Vec::new(),
         acl: Vec::new(),
         default_acl: Vec::new(),
+        times: Vec::new(),
     };
 
     let mut enc = Encoder::new();
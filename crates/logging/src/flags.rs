@@ -2,6 +2,7 @@
 #![allow(missing_docs)]
 
 use clap::ValueEnum;
+use std::io::IsTerminal;
 use std::path::PathBuf;
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq, ValueEnum)]
@@ -210,6 +211,8 @@ pub struct SubscriberConfig {
     pub journald: bool,
     pub colored: bool,
     pub timestamps: bool,
+    pub tty: bool,
+    pub nul_terminated: bool,
 }
 
 impl Default for SubscriberConfig {
@@ -226,6 +229,8 @@ impl Default for SubscriberConfig {
             journald: false,
             colored: true,
             timestamps: false,
+            tty: std::io::stdout().is_terminal(),
+            nul_terminated: false,
         }
     }
 }
@@ -305,6 +310,18 @@ impl SubscriberConfigBuilder {
         self
     }
 
+    pub fn tty(mut self, tty: bool) -> Self {
+        self.cfg.tty = tty;
+        self
+    }
+
+    /// Ends each record written to the session output with NUL instead of a
+    /// newline, for `--out-format-nul`.
+    pub fn nul_terminated(mut self, nul_terminated: bool) -> Self {
+        self.cfg.nul_terminated = nul_terminated;
+        self
+    }
+
     pub fn build(self) -> SubscriberConfig {
         self.cfg
     }
@@ -11,6 +11,7 @@ use tracing_subscriber::registry::LookupSpan;
 
 pub struct RsyncFormatter {
     tokens: Option<Vec<Token>>,
+    nul_terminated: bool,
 }
 
 impl RsyncFormatter {
@@ -19,7 +20,22 @@ impl RsyncFormatter {
             let fmt = parse_escapes(&f);
             parse_tokens(&fmt)
         });
-        Self { tokens }
+        Self {
+            tokens,
+            nul_terminated: false,
+        }
+    }
+
+    /// Writes each record whole and ends it with NUL rather than wrapping it
+    /// into newline-terminated lines, so names containing newlines or spaces
+    /// survive intact.
+    pub fn nul_terminated(mut self, nul_terminated: bool) -> Self {
+        self.nul_terminated = nul_terminated;
+        self
+    }
+
+    fn terminator(&self) -> char {
+        if self.nul_terminated { '\0' } else { '\n' }
     }
 
     fn columns() -> usize {
@@ -299,13 +315,17 @@ where
                 }
             }
             writer.write_str(&out)?;
-            writer.write_char('\n')
+            writer.write_char(self.terminator())
         } else {
             let msg = if visitor.msg.is_empty() {
                 event.metadata().target()
             } else {
                 &visitor.msg
             };
+            if self.nul_terminated {
+                writer.write_str(msg)?;
+                return writer.write_char(self.terminator());
+            }
             let width = Self::columns();
             let wrapped = Self::wrap(msg, width);
             for (i, line) in wrapped.lines().enumerate() {
@@ -17,6 +17,7 @@ pub use formatter::RsyncFormatter;
 pub use sink::{NopObserver, Observer};
 pub use subscriber::{init, subscriber};
 pub use util::{
-    OutFormatOptions, escape_path, human_bytes, parse_escapes, progress_formatter, rate_formatter,
-    render_out_format,
+    EtaEstimator, OutFormatOptions, escape_path, human_bytes, parse_escapes, permstring,
+    progress_formatter, progress_line, progress_tty, rate_formatter, render_out_format,
+    set_progress_tty, timestring,
 };
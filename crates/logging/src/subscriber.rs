@@ -5,6 +5,7 @@ use crate::flags::{LogFormat, SubscriberConfig};
 use crate::formatter::RsyncFormatter;
 use crate::json_format::JsonFormatter;
 use crate::sink::{FileWriter, LogWriter};
+use crate::util::{set_progress_tty, set_raw_names};
 use std::fmt;
 use std::fs::OpenOptions;
 use std::io;
@@ -167,7 +168,11 @@ pub fn subscriber(cfg: SubscriberConfig) -> io::Result<Box<dyn tracing::Subscrib
         journald,
         colored,
         timestamps,
+        tty,
+        nul_terminated,
     } = cfg;
+    set_progress_tty(tty);
+    set_raw_names(nul_terminated);
 
     let mut level = if quiet {
         LevelFilter::ERROR
@@ -219,13 +224,17 @@ pub fn subscriber(cfg: SubscriberConfig) -> io::Result<Box<dyn tracing::Subscrib
     let fmt_layer = if timestamps {
         match format {
             LogFormat::Json => base.event_format(JsonFormatter).boxed(),
-            LogFormat::Text => base.event_format(RsyncFormatter::new(None)).boxed(),
+            LogFormat::Text => base
+                .event_format(RsyncFormatter::new(None).nul_terminated(nul_terminated))
+                .boxed(),
         }
     } else {
         let base = base.without_time();
         match format {
             LogFormat::Json => base.event_format(JsonFormatter).boxed(),
-            LogFormat::Text => base.event_format(RsyncFormatter::new(None)).boxed(),
+            LogFormat::Text => base
+                .event_format(RsyncFormatter::new(None).nul_terminated(nul_terminated))
+                .boxed(),
         }
     };
 
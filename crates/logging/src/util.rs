@@ -2,7 +2,9 @@
 #![allow(missing_docs)]
 
 use std::path::Path;
-use time::{OffsetDateTime, macros::format_description};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, SystemTime};
+use time::{OffsetDateTime, UtcOffset, macros::format_description};
 
 pub fn human_bytes(bytes: u64) -> String {
     const UNITS: [&str; 9] = ["", "K", "M", "G", "T", "P", "E", "Z", "Y"];
@@ -35,6 +37,76 @@ pub fn progress_formatter(bytes: u64, human_readable: bool) -> String {
     }
 }
 
+static PROGRESS_TTY: AtomicBool = AtomicBool::new(false);
+
+pub fn set_progress_tty(tty: bool) {
+    PROGRESS_TTY.store(tty, Ordering::Relaxed);
+}
+
+pub fn progress_tty() -> bool {
+    PROGRESS_TTY.load(Ordering::Relaxed)
+}
+
+static RAW_NAMES: AtomicBool = AtomicBool::new(false);
+
+pub(crate) fn set_raw_names(raw: bool) {
+    RAW_NAMES.store(raw, Ordering::Relaxed);
+}
+
+pub fn progress_line(line: &str, done: bool, tty: bool) -> String {
+    if done || !tty {
+        format!("\r{line}\n")
+    } else {
+        format!("\r{line}")
+    }
+}
+
+pub fn permstring(mode: u32) -> String {
+    const BITS: [(u32, char); 9] = [
+        (0o400, 'r'),
+        (0o200, 'w'),
+        (0o100, 'x'),
+        (0o040, 'r'),
+        (0o020, 'w'),
+        (0o010, 'x'),
+        (0o004, 'r'),
+        (0o002, 'w'),
+        (0o001, 'x'),
+    ];
+    let mut out: Vec<char> = vec![match mode & 0o170000 {
+        0o040000 => 'd',
+        0o120000 => 'l',
+        0o020000 => 'c',
+        0o060000 => 'b',
+        0o010000 => 'p',
+        0o140000 => 's',
+        _ => '-',
+    }];
+    out.extend(
+        BITS.iter()
+            .map(|&(bit, ch)| if mode & bit != 0 { ch } else { '-' }),
+    );
+    for (bit, idx, set, unset) in [
+        (0o4000, 3, 's', 'S'),
+        (0o2000, 6, 's', 'S'),
+        (0o1000, 9, 't', 'T'),
+    ] {
+        if mode & bit != 0 {
+            out[idx] = if out[idx] == 'x' { set } else { unset };
+        }
+    }
+    out.into_iter().collect()
+}
+
+pub fn timestring(t: SystemTime) -> String {
+    let offset = UtcOffset::current_local_offset().unwrap_or(UtcOffset::UTC);
+    let fmt = format_description!("[year]/[month]/[day] [hour]:[minute]:[second]");
+    OffsetDateTime::from(t)
+        .to_offset(offset)
+        .format(&fmt)
+        .unwrap_or_else(|_| String::from("0000/00/00 00:00:00"))
+}
+
 pub fn rate_formatter(bytes_per_sec: f64) -> String {
     let mut rate = bytes_per_sec / 1024.0;
     let mut units = "KB/s";
@@ -49,6 +121,49 @@ pub fn rate_formatter(bytes_per_sec: f64) -> String {
     format!("{:>7.2}{}", rate, units)
 }
 
+#[derive(Debug, Clone, Copy)]
+pub struct EtaEstimator {
+    alpha: f64,
+    rate: Option<f64>,
+}
+
+impl Default for EtaEstimator {
+    fn default() -> Self {
+        Self::new(0.3)
+    }
+}
+
+impl EtaEstimator {
+    pub fn new(alpha: f64) -> Self {
+        Self {
+            alpha: alpha.clamp(f64::MIN_POSITIVE, 1.0),
+            rate: None,
+        }
+    }
+
+    pub fn record(&mut self, bytes: u64, interval: Duration) {
+        let secs = interval.as_secs_f64();
+        if secs <= 0.0 {
+            return;
+        }
+        let sample = bytes as f64 / secs;
+        self.rate = Some(match self.rate {
+            Some(rate) => self.alpha * sample + (1.0 - self.alpha) * rate,
+            None => sample,
+        });
+    }
+
+    pub fn rate(&self) -> Option<f64> {
+        self.rate
+    }
+
+    pub fn eta(&self, remaining: u64) -> Option<Duration> {
+        self.rate
+            .filter(|rate| *rate > 0.0)
+            .map(|rate| Duration::from_secs_f64(remaining as f64 / rate))
+    }
+}
+
 fn escape_bytes(bytes: &[u8], eight_bit_output: bool) -> String {
     let mut out = String::new();
     for &b in bytes {
@@ -61,11 +176,20 @@ fn escape_bytes(bytes: &[u8], eight_bit_output: bool) -> String {
     out
 }
 
+/// Renders `path` for log output, escaping control and (without
+/// `eight_bit_output`) non-ASCII bytes as `\#ooo`. Once NUL-terminated
+/// records are enabled, names that are valid UTF-8 are written as-is.
 pub fn escape_path(path: &Path, eight_bit_output: bool) -> String {
     #[cfg(unix)]
     {
         use std::os::unix::ffi::OsStrExt;
-        escape_bytes(path.as_os_str().as_bytes(), eight_bit_output)
+        let bytes = path.as_os_str().as_bytes();
+        if RAW_NAMES.load(Ordering::Relaxed)
+            && let Ok(name) = std::str::from_utf8(bytes)
+        {
+            return name.to_string();
+        }
+        escape_bytes(bytes, eight_bit_output)
     }
     #[cfg(not(unix))]
     {
@@ -0,0 +1,37 @@
+// crates/logging/tests/eta.rs
+use logging::EtaEstimator;
+use std::time::Duration;
+
+#[test]
+fn eta_tracks_rate_changes_faster_than_cumulative_average() {
+    let mut eta = EtaEstimator::default();
+    let tick = Duration::from_secs(1);
+    let mut sent = 0u64;
+    for _ in 0..10 {
+        eta.record(100, tick);
+        sent += 100;
+    }
+    let remaining = 6000;
+    let before = eta.eta(remaining).unwrap();
+    assert!((before.as_secs_f64() - 60.0).abs() < 1.0, "{before:?}");
+
+    for _ in 0..10 {
+        eta.record(200, tick);
+        sent += 200;
+    }
+    let after = eta.eta(remaining).unwrap();
+    let cumulative = Duration::from_secs_f64(remaining as f64 / (sent as f64 / 20.0));
+    assert!(after < before, "{after:?} >= {before:?}");
+    assert!(after < cumulative, "{after:?} >= {cumulative:?}");
+    assert!((after.as_secs_f64() - 30.0).abs() < 1.0, "{after:?}");
+}
+
+#[test]
+fn eta_unknown_without_samples() {
+    let mut eta = EtaEstimator::default();
+    assert!(eta.eta(100).is_none());
+    eta.record(100, Duration::ZERO);
+    assert!(eta.rate().is_none());
+    eta.record(0, Duration::from_secs(1));
+    assert!(eta.eta(100).is_none());
+}
@@ -0,0 +1,27 @@
+// crates/logging/tests/progress_line.rs
+use logging::{SubscriberConfig, progress_line, progress_tty, subscriber};
+
+#[test]
+fn non_tty_progress_emits_a_line_per_update() {
+    let update = progress_line("  1,024  50%", false, false);
+    let done = progress_line("  2,048 100%", true, false);
+    assert!(update.ends_with('\n'));
+    assert!(done.ends_with('\n'));
+    assert_eq!(format!("{update}{done}").lines().count(), 2);
+}
+
+#[test]
+fn tty_progress_redraws_in_place() {
+    let update = progress_line("  1,024  50%", false, true);
+    let done = progress_line("  2,048 100%", true, true);
+    assert_eq!(update, "\r  1,024  50%");
+    assert_eq!(done, "\r  2,048 100%\n");
+}
+
+#[test]
+fn subscriber_records_tty_flag() {
+    let _ = subscriber(SubscriberConfig::builder().tty(true).build()).unwrap();
+    assert!(progress_tty());
+    let _ = subscriber(SubscriberConfig::builder().tty(false).build()).unwrap();
+    assert!(!progress_tty());
+}
@@ -0,0 +1,108 @@
+// crates/meta/src/diff.rs
+use std::fmt;
+
+use crate::Metadata;
+
+/// Set of metadata fields that differ between two [`Metadata`] snapshots.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct MetaDiff(u8);
+
+impl MetaDiff {
+    pub const UID: MetaDiff = MetaDiff(1 << 0);
+    pub const GID: MetaDiff = MetaDiff(1 << 1);
+    pub const MODE: MetaDiff = MetaDiff(1 << 2);
+    pub const MTIME: MetaDiff = MetaDiff(1 << 3);
+    pub const ATIME: MetaDiff = MetaDiff(1 << 4);
+    pub const CRTIME: MetaDiff = MetaDiff(1 << 5);
+    pub const XATTRS: MetaDiff = MetaDiff(1 << 6);
+    pub const ACL: MetaDiff = MetaDiff(1 << 7);
+
+    const NAMES: [(MetaDiff, &'static str); 8] = [
+        (Self::UID, "uid"),
+        (Self::GID, "gid"),
+        (Self::MODE, "mode"),
+        (Self::MTIME, "mtime"),
+        (Self::ATIME, "atime"),
+        (Self::CRTIME, "crtime"),
+        (Self::XATTRS, "xattrs"),
+        (Self::ACL, "acl"),
+    ];
+
+    pub const fn empty() -> Self {
+        MetaDiff(0)
+    }
+
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    pub const fn contains(self, other: MetaDiff) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    pub fn insert(&mut self, other: MetaDiff) {
+        self.0 |= other.0;
+    }
+
+    fn set(&mut self, other: MetaDiff, changed: bool) {
+        if changed {
+            self.insert(other);
+        }
+    }
+}
+
+impl std::ops::BitOr for MetaDiff {
+    type Output = MetaDiff;
+
+    fn bitor(self, rhs: MetaDiff) -> MetaDiff {
+        MetaDiff(self.0 | rhs.0)
+    }
+}
+
+impl fmt::Debug for MetaDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set()
+            .entries(
+                Self::NAMES
+                    .iter()
+                    .filter(|(flag, _)| self.contains(*flag))
+                    .map(|(_, name)| name),
+            )
+            .finish()
+    }
+}
+
+impl Metadata {
+    /// Compares every field with `other` and reports which ones differ.
+    /// Extended attributes are compared by name, so their listing order does
+    /// not matter; ACLs cover both the access and default entries.
+    pub fn diff(&self, other: &Metadata) -> MetaDiff {
+        let mut diff = MetaDiff::empty();
+        diff.set(MetaDiff::UID, self.uid != other.uid);
+        diff.set(MetaDiff::GID, self.gid != other.gid);
+        diff.set(MetaDiff::MODE, self.mode != other.mode);
+        diff.set(MetaDiff::MTIME, self.mtime != other.mtime);
+        diff.set(MetaDiff::ATIME, self.atime != other.atime);
+        diff.set(MetaDiff::CRTIME, self.crtime != other.crtime);
+        #[cfg(all(unix, feature = "xattr"))]
+        diff.set(MetaDiff::XATTRS, !same_xattrs(&self.xattrs, &other.xattrs));
+        #[cfg(all(unix, feature = "acl"))]
+        diff.set(
+            MetaDiff::ACL,
+            self.acl != other.acl || self.default_acl != other.default_acl,
+        );
+        diff
+    }
+}
+
+#[cfg(all(unix, feature = "xattr"))]
+fn same_xattrs(a: &[(std::ffi::OsString, Vec<u8>)], b: &[(std::ffi::OsString, Vec<u8>)]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut a: Vec<_> = a.iter().collect();
+    let mut b: Vec<_> = b.iter().collect();
+    a.sort_unstable();
+    b.sort_unstable();
+    a == b
+}
@@ -27,8 +27,13 @@ mod stub;
 #[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
 pub use stub::*;
 
+mod diff;
 mod parse;
+mod times;
+mod xattr_filter;
+pub use diff::MetaDiff;
 pub use parse::{IdKind, parse_chmod, parse_chmod_spec, parse_chown, parse_id_map};
+pub use xattr_filter::XattrFilter;
 
 #[derive(Debug, Clone, Copy, Default)]
 pub struct MetaOpts {
@@ -161,6 +166,19 @@ impl GidTable {
         self.table.get(idx).copied()
     }
 
+    /// Looks up the index of `gid` without adding it to the table.
+    pub fn index_of(&self, gid: u32) -> Option<usize> {
+        self.map.get(&gid).copied()
+    }
+
+    pub fn len(&self) -> usize {
+        self.table.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.table.is_empty()
+    }
+
     pub fn as_slice(&self) -> &[u32] {
         &self.table
     }
@@ -183,17 +201,30 @@ pub fn hard_link_id(dev: u64, ino: u64) -> u64 {
 #[derive(Debug, Default)]
 pub struct HardLinks {
     map: HashMap<u64, Vec<PathBuf>>,
+    groups: HashMap<PathBuf, u64>,
 }
 
 #[cfg(unix)]
 impl HardLinks {
+    /// Adds `path` to link group `id`, returning `true` if it is the first
+    /// member. A path already claimed by another group stays in that group.
     pub fn register(&mut self, id: u64, path: &Path) -> bool {
+        match self.groups.get(path) {
+            Some(&group) if group != id => {
+                tracing::warn!(
+                    ?path,
+                    "path listed in two hard-link groups; keeping the first"
+                );
+                return false;
+            }
+            Some(_) => return false,
+            None => {
+                self.groups.insert(path.to_path_buf(), id);
+            }
+        }
         match self.map.entry(id) {
             Entry::Occupied(mut e) => {
-                let paths = e.get_mut();
-                if !paths.iter().any(|p| p == path) {
-                    paths.push(path.to_path_buf());
-                }
+                e.get_mut().push(path.to_path_buf());
                 false
             }
             Entry::Vacant(v) => {
@@ -204,7 +235,10 @@ impl HardLinks {
     }
 
     pub fn finalize(&mut self) -> io::Result<()> {
-        for (_, mut paths) in std::mem::take(&mut self.map) {
+        self.groups.clear();
+        let mut groups: Vec<_> = std::mem::take(&mut self.map).into_iter().collect();
+        groups.sort_unstable_by_key(|(id, _)| *id);
+        for (_, mut paths) in groups {
             let Some(src_idx) = paths.iter().position(|p| p.exists()) else {
                 continue;
             };
@@ -239,6 +273,35 @@ pub fn apply_xattrs(
     xattrs: &[(OsString, Vec<u8>)],
     include: Option<&dyn Fn(&OsStr) -> bool>,
     include_for_delete: Option<&dyn Fn(&OsStr) -> bool>,
+) -> io::Result<()> {
+    write_xattrs(path, xattrs, include, include_for_delete, true)
+}
+
+#[cfg(unix)]
+#[doc = include_str!("docs/apply_xattrs_filtered.md")]
+pub fn apply_xattrs_filtered(
+    path: &Path,
+    xattrs: &[(OsString, Vec<u8>)],
+    filter: &XattrFilter,
+) -> io::Result<()> {
+    let include = |name: &OsStr| filter.is_included(name);
+    let include_for_delete = |name: &OsStr| filter.may_delete(name);
+    write_xattrs(
+        path,
+        xattrs,
+        Some(&include),
+        Some(&include_for_delete),
+        false,
+    )
+}
+
+#[cfg(unix)]
+fn write_xattrs(
+    path: &Path,
+    xattrs: &[(OsString, Vec<u8>)],
+    include: Option<&dyn Fn(&OsStr) -> bool>,
+    include_for_delete: Option<&dyn Fn(&OsStr) -> bool>,
+    protect: bool,
 ) -> io::Result<()> {
     let mut existing: HashSet<OsString> = match xattr::list(path) {
         Ok(list) => list.collect(),
@@ -261,7 +324,10 @@ pub fn apply_xattrs(
             let del_ok = include_for_delete
                 .map(|f| f(name.as_os_str()))
                 .unwrap_or(true);
-            if !inc_ok && !del_ok && let Ok(Some(val)) = xattr::get(path, name) {
+            if !inc_ok
+                && !del_ok
+                && let Ok(Some(val)) = xattr::get(path, name)
+            {
                 preserved.push((name.clone(), val));
             }
         }
@@ -281,11 +347,7 @@ pub fn apply_xattrs(
         if include_for_delete.is_some_and(|filter| !filter(name.as_os_str())) {
             continue;
         }
-        if name.to_str().is_some_and(|s| {
-            s == "system.posix_acl_access"
-                || s == "system.posix_acl_default"
-                || s.starts_with("security.")
-        }) {
+        if protect && xattr_filter::is_protected(&name) {
             continue;
         }
         if let Err(err) = xattr::remove(path, &name)
@@ -328,6 +390,19 @@ impl UidTable {
         self.table.get(idx).copied()
     }
 
+    /// Looks up the index of `uid` without adding it to the table.
+    pub fn index_of(&self, uid: u32) -> Option<usize> {
+        self.map.get(&uid).copied()
+    }
+
+    pub fn len(&self) -> usize {
+        self.table.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.table.is_empty()
+    }
+
     pub fn as_slice(&self) -> &[u32] {
         &self.table
     }
@@ -0,0 +1,82 @@
+// crates/meta/src/times.rs
+use std::io;
+
+use filetime::FileTime;
+
+use crate::{Metadata, Options};
+
+const TIME_MTIME: u8 = 1 << 0;
+const TIME_ATIME: u8 = 1 << 1;
+const TIME_CRTIME: u8 = 1 << 2;
+const TIME_LEN: usize = 12;
+
+fn put_time(out: &mut Vec<u8>, t: FileTime) {
+    out.extend_from_slice(&t.unix_seconds().to_le_bytes());
+    out.extend_from_slice(&t.nanoseconds().to_le_bytes());
+}
+
+fn get_time(data: &[u8]) -> FileTime {
+    let secs = i64::from_le_bytes(data[..8].try_into().unwrap_or([0; 8]));
+    let nanos = u32::from_le_bytes(data[8..TIME_LEN].try_into().unwrap_or([0; 4]));
+    FileTime::from_unix_time(secs, nanos)
+}
+
+impl Metadata {
+    #[doc = include_str!("docs/encode_times.md")]
+    pub fn encode_times(&self, opts: &Options) -> Vec<u8> {
+        let mut flags = 0u8;
+        let mut out = vec![0u8];
+        if opts.times {
+            flags |= TIME_MTIME;
+            put_time(&mut out, self.mtime);
+        }
+        if opts.atimes
+            && let Some(atime) = self.atime
+        {
+            flags |= TIME_ATIME;
+            put_time(&mut out, atime);
+        }
+        if opts.crtimes
+            && let Some(crtime) = self.crtime
+        {
+            flags |= TIME_CRTIME;
+            put_time(&mut out, crtime);
+        }
+        out[0] = flags;
+        out
+    }
+
+    pub fn decode_times(&mut self, data: &[u8]) -> io::Result<()> {
+        let (&flags, mut rest) = data
+            .split_first()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::UnexpectedEof, "empty time frame"))?;
+        if flags & !(TIME_MTIME | TIME_ATIME | TIME_CRTIME) != 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("unknown time flags {flags:#x}"),
+            ));
+        }
+        let expected = (flags.count_ones() as usize) * TIME_LEN;
+        if rest.len() != expected {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("time frame has {} bytes, expected {expected}", rest.len()),
+            ));
+        }
+        let mut next = || {
+            let t = get_time(rest);
+            rest = &rest[TIME_LEN..];
+            t
+        };
+        if flags & TIME_MTIME != 0 {
+            self.mtime = next();
+        }
+        if flags & TIME_ATIME != 0 {
+            self.atime = Some(next());
+        }
+        if flags & TIME_CRTIME != 0 {
+            self.crtime = Some(next());
+        }
+        Ok(())
+    }
+}
@@ -11,7 +11,7 @@ use std::fmt;
 use std::rc::Rc;
 use std::sync::Arc;
 #[cfg(unix)]
-pub type XattrFilter = Rc<dyn Fn(&OsStr) -> bool>;
+pub type XattrPredicate = Rc<dyn Fn(&OsStr) -> bool>;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ChmodTarget {
@@ -56,9 +56,9 @@ pub struct Options {
     pub uid_map: Option<Arc<dyn Fn(u32) -> u32 + Send + Sync>>,
     pub gid_map: Option<Arc<dyn Fn(u32) -> u32 + Send + Sync>>,
     #[cfg(unix)]
-    pub xattr_filter: Option<XattrFilter>,
+    pub xattr_filter: Option<XattrPredicate>,
     #[cfg(unix)]
-    pub xattr_filter_delete: Option<XattrFilter>,
+    pub xattr_filter_delete: Option<XattrPredicate>,
 }
 
 impl fmt::Debug for Options {
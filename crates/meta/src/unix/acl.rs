@@ -219,6 +219,15 @@ pub fn write_acl(
     let meta = fs::symlink_metadata(path)?;
     let is_dir = meta.file_type().is_dir();
     let cur_mode = normalize_mode(meta.permissions().mode());
+    let default_acl = match default_acl {
+        Some(d) if !is_dir => {
+            if !d.is_empty() {
+                tracing::debug!(?path, "ignoring default ACL for non-directory");
+            }
+            None
+        }
+        other => other,
+    };
 
     let empty: &[posix_acl::ACLEntry] = &[];
     let acl_eff = if is_trivial_acl(acl, cur_mode) {
@@ -250,6 +259,9 @@ fn apply_access_acl_if_nontrivial(path: &Path, acl: &[posix_acl::ACLEntry]) -> i
     for e in acl {
         obj.set(e.qual, e.perm);
     }
+    if acl.iter().any(|e| e.qual == posix_acl::Qualifier::Mask) {
+        return write_access_acl_keep_mask(path, obj);
+    }
     match obj.write_acl(path) {
         Ok(_) => Ok(()),
         Err(err) if should_ignore_acl_error(&err) => Ok(()),
@@ -257,6 +269,43 @@ fn apply_access_acl_if_nontrivial(path: &Path, acl: &[posix_acl::ACLEntry]) -> i
     }
 }
 
+#[doc = include_str!("docs/write_access_acl_keep_mask.md")]
+fn write_access_acl_keep_mask(path: &Path, obj: posix_acl::PosixACL) -> io::Result<()> {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    const ACL_TYPE_ACCESS: libc::c_uint = 0x8000;
+
+    unsafe extern "C" {
+        fn acl_set_file(
+            path_p: *const libc::c_char,
+            acl_type: libc::c_uint,
+            acl: *mut libc::c_void,
+        ) -> libc::c_int;
+    }
+
+    match obj.validate() {
+        Ok(()) => {}
+        Err(err) if should_ignore_acl_error(&err) => return Ok(()),
+        Err(err) => return Err(acl_to_io(err)),
+    }
+    let c_path = CString::new(path.as_os_str().as_bytes())?;
+    let raw = obj.into_raw();
+    // SAFETY: `c_path` is a valid C string and `raw` is a live ACL handle obtained from `into_raw`.
+    let ret = unsafe { acl_set_file(c_path.as_ptr(), ACL_TYPE_ACCESS, raw.cast()) };
+    let err = io::Error::last_os_error();
+    // SAFETY: `raw` came from `into_raw` and ownership is handed back exactly once so it is freed.
+    drop(unsafe { posix_acl::PosixACL::from_raw(raw) });
+    if ret == 0 {
+        Ok(())
+    } else {
+        match err.raw_os_error() {
+            Some(code) if should_ignore_acl_errno(code) => Ok(()),
+            _ => Err(err),
+        }
+    }
+}
+
 #[doc = include_str!("docs/apply_default_acl_option.md")]
 fn apply_default_acl_option(
     path: &Path,
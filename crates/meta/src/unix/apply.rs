@@ -4,8 +4,6 @@ use std::io;
 use std::path::Path;
 
 use crate::{ChmodOp, ChmodTarget, Metadata, Options, normalize_mode};
-#[cfg(target_os = "linux")]
-use caps::{CapSet, Capability};
 use filetime::{self, FileTime};
 use nix::errno::Errno;
 use nix::fcntl::{AT_FDCWD, AtFlags};
@@ -14,7 +12,10 @@ use nix::unistd::{self, Gid, Uid};
 use std::os::unix::fs::{MetadataExt, PermissionsExt};
 
 use super::acl::write_acl;
-use super::{gid_from_name, gid_to_name, nix_to_io, set_file_crtime, uid_from_name, uid_to_name};
+use super::{
+    gid_from_name, gid_to_name, has_chown_privilege, nix_to_io, set_file_crtime, store_fake_super,
+    uid_from_name, uid_to_name,
+};
 
 impl Metadata {
     pub fn apply(&self, path: &Path, opts: Options) -> io::Result<()> {
@@ -26,6 +27,8 @@ impl Metadata {
         let mut expected_uid = self.uid;
         let mut expected_gid = self.gid;
         let mut chown_failed = false;
+        let privileged = has_chown_privilege();
+        let fake_super = opts.fake_super && !(opts.super_user && privileged);
         if opts.owner || opts.group {
             let uid = if let Some(ref map) = opts.uid_map {
                 map(self.uid)
@@ -52,19 +55,7 @@ impl Metadata {
             expected_uid = uid;
             expected_gid = gid;
 
-            #[cfg(target_os = "linux")]
-            let mut can_chown = unistd::Uid::effective().is_root();
-            #[cfg(not(target_os = "linux"))]
-            let can_chown = unistd::Uid::effective().is_root();
-            #[cfg(target_os = "linux")]
-            {
-                if !can_chown {
-                    can_chown = caps::has_cap(None, CapSet::Effective, Capability::CAP_CHOWN)
-                        .unwrap_or(false);
-                }
-            }
-
-            if can_chown {
+            if !fake_super && privileged {
                 let res = if is_symlink {
                     match unistd::fchownat(
                         AT_FDCWD,
@@ -113,6 +104,15 @@ impl Metadata {
                 };
                 if let Err(err) = res {
                     match err {
+                        Errno::EPERM | Errno::EACCES if opts.super_user => {
+                            return Err(io::Error::new(
+                                io::ErrorKind::PermissionDenied,
+                                format!(
+                                    "{}: unable to change owner/group with --super: {err}",
+                                    path.display()
+                                ),
+                            ));
+                        }
                         Errno::EPERM | Errno::EACCES => {
                             chown_failed = true;
                             tracing::warn!(?path, ?err, "unable to change owner/group");
@@ -122,6 +122,12 @@ impl Metadata {
                 }
             } else {
                 chown_failed = true;
+                if opts.super_user && !fake_super {
+                    tracing::warn!(
+                        ?path,
+                        "--super without root or CAP_CHOWN: leaving owner/group unchanged"
+                    );
+                }
             }
         }
 
@@ -190,45 +196,25 @@ impl Metadata {
             }
         }
 
-        if opts.atimes || opts.times {
+        if opts.atimes || opts.times || opts.crtimes {
             let skip_mtime =
                 (is_dir && opts.omit_dir_times) || (is_symlink && opts.omit_link_times);
-            if is_symlink {
-                let cur_mtime = FileTime::from_last_modification_time(&meta);
-                let cur_atime = FileTime::from_last_access_time(&meta);
-                if opts.atimes {
-                    if let Some(atime) = self.atime {
-                        let mtime = if opts.times && !skip_mtime {
-                            self.mtime
-                        } else {
-                            cur_mtime
-                        };
-                        filetime::set_symlink_file_times(path, atime, mtime)?;
-                    } else if opts.times && !skip_mtime {
-                        filetime::set_symlink_file_times(path, cur_atime, self.mtime)?;
-                    }
-                } else if opts.times && !skip_mtime {
-                    filetime::set_symlink_file_times(path, cur_atime, self.mtime)?;
-                }
-            } else if opts.atimes {
-                if let Some(atime) = self.atime {
-                    if opts.times && !skip_mtime {
-                        filetime::set_file_times(path, atime, self.mtime)?;
-                    } else {
-                        filetime::set_file_atime(path, atime)?;
-                    }
-                } else if opts.times && !skip_mtime {
-                    filetime::set_file_mtime(path, self.mtime)?;
+            let atime = self.atime.filter(|_| opts.atimes);
+            let mtime = Some(self.mtime).filter(|_| opts.times && !skip_mtime);
+            if atime.is_some() || mtime.is_some() {
+                let atime = atime.unwrap_or_else(|| FileTime::from_last_access_time(&meta));
+                let mtime = mtime.unwrap_or_else(|| FileTime::from_last_modification_time(&meta));
+                if is_symlink {
+                    filetime::set_symlink_file_times(path, atime, mtime)?;
+                } else {
+                    filetime::set_file_times(path, atime, mtime)?;
                 }
-            } else if opts.times && !skip_mtime {
-                filetime::set_file_mtime(path, self.mtime)?;
             }
-        }
-
-        if opts.crtimes
-            && let Some(crtime) = self.crtime
-        {
-            let _ = set_file_crtime(path, crtime);
+            if opts.crtimes
+                && let Some(crtime) = self.crtime
+            {
+                let _ = set_file_crtime(path, crtime);
+            }
         }
 
         #[cfg(feature = "xattr")]
@@ -241,6 +227,17 @@ impl Metadata {
             )?;
         }
 
+        if fake_super && (opts.owner || opts.group || opts.perms) {
+            let uid = if opts.owner { expected_uid } else { meta.uid() };
+            let gid = if opts.group { expected_gid } else { meta.gid() };
+            let mode = if opts.perms {
+                normalize_mode(self.mode)
+            } else {
+                normalize_mode(meta.mode())
+            };
+            store_fake_super(path, uid, gid, mode);
+        }
+
         if opts.acl {
             let dacl = if is_dir {
                 if self.default_acl.is_empty() {
@@ -8,6 +8,7 @@ use std::fs;
 use std::io;
 use std::os::unix::fs::MetadataExt;
 use std::path::Path;
+use std::sync::OnceLock;
 
 use crate::{Metadata, Options, normalize_mode};
 use filetime::{self, FileTime};
@@ -132,6 +133,27 @@ impl Metadata {
         })
     }
 }
+/// Whether this process can give files to other owners: effective root, or
+/// `CAP_CHOWN` on Linux. `--super` only takes effect when this holds. The
+/// answer is probed once and reused for every file.
+pub fn has_chown_privilege() -> bool {
+    static PRIVILEGED: OnceLock<bool> = OnceLock::new();
+    *PRIVILEGED.get_or_init(|| {
+        if nix::unistd::Uid::effective().is_root() {
+            return true;
+        }
+        #[cfg(target_os = "linux")]
+        {
+            caps::has_cap(None, caps::CapSet::Effective, caps::Capability::CAP_CHOWN)
+                .unwrap_or(false)
+        }
+        #[cfg(not(target_os = "linux"))]
+        {
+            false
+        }
+    })
+}
+
 #[cfg(any(target_os = "linux", target_os = "macos"))]
 pub fn mknod(path: &Path, kind: SFlag, perm: Mode, dev: u64) -> io::Result<()> {
     use nix::libc::dev_t;
@@ -248,7 +270,49 @@ pub(super) fn set_file_crtime(path: &Path, crtime: FileTime) -> io::Result<()> {
     }
 }
 
-#[cfg(not(target_os = "macos"))]
+/// Linux has no generic call to set a file's birth time, so this goes
+/// through the extended attributes that NTFS (ntfs-3g) and SMB mounts expose
+/// for it. On other filesystems, such as ext4, btrfs or tmpfs, the crtime is
+/// left unchanged.
+#[cfg(target_os = "linux")]
+pub(super) fn set_file_crtime(path: &Path, crtime: FileTime) -> io::Result<()> {
+    use nix::sys::statfs::statfs;
+
+    const NTFS_CRTIME_XATTR: &str = "system.ntfs_crtime";
+    const CIFS_CRTIME_XATTR: &str = "user.cifs.creationtime";
+    const CIFS_MAGIC: u32 = 0xFF53_4D42;
+    const SMB2_MAGIC: u32 = 0xFE53_4D42;
+    const NTFS_MAGIC: u32 = 0x5346_544E;
+    const NTFS3_MAGIC: u32 = 0x7366_746E;
+    const FUSEBLK_MAGIC: u32 = 0x6573_5546;
+    const NT_EPOCH_OFFSET: i64 = 11_644_473_600;
+
+    let fs_type = statfs(path).map_err(nix_to_io)?.filesystem_type().0 as u32;
+    let name = match fs_type {
+        CIFS_MAGIC | SMB2_MAGIC => CIFS_CRTIME_XATTR,
+        NTFS_MAGIC | NTFS3_MAGIC | FUSEBLK_MAGIC => NTFS_CRTIME_XATTR,
+        _ => return Ok(()),
+    };
+    let Ok(secs) = u64::try_from(crtime.unix_seconds() + NT_EPOCH_OFFSET) else {
+        return Ok(());
+    };
+    let ticks = secs * 10_000_000 + u64::from(crtime.nanoseconds() / 100);
+    match xattr::set(path, name, &ticks.to_le_bytes()) {
+        Ok(()) => Ok(()),
+        Err(err)
+            if matches!(
+                err.raw_os_error(),
+                Some(libc::EOPNOTSUPP | libc::ENODATA | libc::EINVAL)
+            ) =>
+        {
+            tracing::debug!(?path, %err, "filesystem cannot set crtime; leaving it unchanged");
+            Ok(())
+        }
+        Err(err) => Err(err),
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
 pub(super) fn set_file_crtime(_path: &Path, _crtime: FileTime) -> io::Result<()> {
     Ok(())
 }
@@ -68,7 +68,7 @@ pub fn copy_xattrs(
     include_for_delete: Option<&dyn Fn(&OsStr) -> bool>,
 ) -> io::Result<()> {
     let mut attrs = Vec::new();
-    match xattr::list_deref(src) {
+    match list_deref(src) {
         Ok(list) => {
             for attr in list {
                 if let Some(name) = attr.to_str()
@@ -83,7 +83,7 @@ pub fn copy_xattrs(
                 {
                     continue;
                 }
-                match xattr::get_deref(src, &attr) {
+                match get_deref(src, &attr) {
                     Ok(Some(value)) => attrs.push((attr, value)),
                     Ok(None) => {}
                     Err(err) => {
@@ -0,0 +1,73 @@
+// crates/meta/src/xattr_filter.rs
+use std::ffi::OsStr;
+use std::result::Result as StdResult;
+
+use globset::{Glob, GlobMatcher};
+
+#[doc = include_str!("docs/xattr_filter.md")]
+#[derive(Debug, Clone, Default)]
+pub struct XattrFilter {
+    namespaces: Vec<String>,
+    rules: Vec<(bool, GlobMatcher)>,
+    delete_protected: bool,
+}
+
+impl XattrFilter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn namespace(mut self, namespace: &str) -> Self {
+        let mut prefix = namespace.to_string();
+        if !prefix.ends_with('.') {
+            prefix.push('.');
+        }
+        self.namespaces.push(prefix);
+        self
+    }
+
+    pub fn include(self, pattern: &str) -> StdResult<Self, String> {
+        self.rule(true, pattern)
+    }
+
+    pub fn exclude(self, pattern: &str) -> StdResult<Self, String> {
+        self.rule(false, pattern)
+    }
+
+    pub fn allow_protected_delete(mut self, allow: bool) -> Self {
+        self.delete_protected = allow;
+        self
+    }
+
+    fn rule(mut self, include: bool, pattern: &str) -> StdResult<Self, String> {
+        let glob =
+            Glob::new(pattern).map_err(|e| format!("invalid xattr pattern {pattern}: {e}"))?;
+        self.rules.push((include, glob.compile_matcher()));
+        Ok(self)
+    }
+
+    pub fn is_included(&self, name: &OsStr) -> bool {
+        let Some(name) = name.to_str() else {
+            return self.namespaces.is_empty() && self.rules.is_empty();
+        };
+        if !self.namespaces.is_empty() && !self.namespaces.iter().any(|ns| name.starts_with(ns)) {
+            return false;
+        }
+        self.rules
+            .iter()
+            .find(|(_, glob)| glob.is_match(name))
+            .is_none_or(|(include, _)| *include)
+    }
+
+    pub fn may_delete(&self, name: &OsStr) -> bool {
+        self.is_included(name) && (self.delete_protected || !is_protected(name))
+    }
+}
+
+pub(crate) fn is_protected(name: &OsStr) -> bool {
+    name.to_str().is_some_and(|s| {
+        s == "system.posix_acl_access"
+            || s == "system.posix_acl_default"
+            || s.starts_with("security.")
+    })
+}
@@ -60,3 +60,74 @@ fn roundtrip_default_acl_rw() -> std::io::Result<()> {
     assert_eq!(default_entries, dacl_applied);
     Ok(())
 }
+
+#[test]
+fn default_acl_only_applies_to_directories() -> std::io::Result<()> {
+    let dir = tempdir()?;
+    let src = dir.path().join("src");
+    let dst_dir = dir.path().join("dst");
+    let dst_file = dir.path().join("file");
+    fs::create_dir(&src)?;
+    fs::create_dir(&dst_dir)?;
+    fs::write(&dst_file, b"data")?;
+
+    let mut dacl = PosixACL::new(0o755);
+    dacl.set(Qualifier::User(12345), ACL_READ);
+    dacl.write_default_acl(&src).map_err(acl_to_io)?;
+    let (_, default_entries) = read_acl(&src, false)?;
+    assert!(!default_entries.is_empty());
+
+    write_acl(&dst_dir, &[], Some(&default_entries), false, false)?;
+    let (_, dir_default) = read_acl(&dst_dir, false)?;
+    assert_eq!(dir_default, default_entries);
+
+    write_acl(&dst_file, &[], Some(&default_entries), false, false)?;
+    let (_, file_default) = read_acl(&dst_file, false)?;
+    assert!(file_default.is_empty());
+    Ok(())
+}
+
+#[test]
+fn named_entries_recalculate_mask() -> std::io::Result<()> {
+    use posix_acl::{ACL_WRITE, ACLEntry};
+
+    let dir = tempdir()?;
+    let dst = dir.path().join("dst");
+    fs::write(&dst, b"data")?;
+
+    let mut acl = PosixACL::new(0o600);
+    acl.set(Qualifier::User(12345), ACL_READ | ACL_WRITE);
+    let entries: Vec<ACLEntry> = acl
+        .entries()
+        .into_iter()
+        .filter(|e| e.qual != Qualifier::Mask)
+        .collect();
+    write_acl(&dst, &entries, None, false, false)?;
+
+    let applied = PosixACL::read_acl(&dst).map_err(acl_to_io)?;
+    let mask = applied.get(Qualifier::Mask).unwrap_or(0);
+    assert_eq!(mask & (ACL_READ | ACL_WRITE), ACL_READ | ACL_WRITE);
+    Ok(())
+}
+
+#[test]
+fn explicit_mask_is_preserved() -> std::io::Result<()> {
+    use posix_acl::ACL_WRITE;
+
+    let dir = tempdir()?;
+    let dst = dir.path().join("dst");
+    fs::write(&dst, b"data")?;
+
+    let mut acl = PosixACL::new(0o600);
+    acl.set(Qualifier::User(12345), ACL_READ | ACL_WRITE);
+    acl.set(Qualifier::Mask, ACL_READ);
+    write_acl(&dst, &acl.entries(), None, false, false)?;
+
+    let applied = PosixACL::read_acl(&dst).map_err(acl_to_io)?;
+    assert_eq!(applied.get(Qualifier::Mask), Some(ACL_READ));
+    assert_eq!(
+        applied.get(Qualifier::User(12345)),
+        Some(ACL_READ | ACL_WRITE)
+    );
+    Ok(())
+}
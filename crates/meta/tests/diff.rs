@@ -0,0 +1,80 @@
+// crates/meta/tests/diff.rs
+
+use std::fs;
+
+use filetime::FileTime;
+use meta::{MetaDiff, Metadata, Options};
+use tempfile::tempdir;
+
+fn snapshot() -> std::io::Result<Metadata> {
+    let dir = tempdir()?;
+    let file = dir.path().join("file");
+    fs::write(&file, b"data")?;
+    filetime::set_file_times(
+        &file,
+        FileTime::from_unix_time(2_000_000, 0),
+        FileTime::from_unix_time(1_000_000, 0),
+    )?;
+    Metadata::from_path(
+        &file,
+        Options {
+            times: true,
+            atimes: true,
+            ..Default::default()
+        },
+    )
+}
+
+#[test]
+fn identical_metadata_has_empty_diff() -> std::io::Result<()> {
+    let meta = snapshot()?;
+    assert!(meta.diff(&meta.clone()).is_empty());
+    Ok(())
+}
+
+#[test]
+fn only_atime_differs() -> std::io::Result<()> {
+    let meta = snapshot()?;
+    let mut other = meta.clone();
+    other.atime = Some(FileTime::from_unix_time(3_000_000, 5));
+    let diff = meta.diff(&other);
+    assert_eq!(diff, MetaDiff::ATIME);
+    assert!(!diff.contains(MetaDiff::MTIME));
+    assert_eq!(format!("{diff:?}"), r#"{"atime"}"#);
+    Ok(())
+}
+
+#[test]
+fn ownership_and_mode_changes_are_combined() -> std::io::Result<()> {
+    let meta = snapshot()?;
+    let mut other = meta.clone();
+    other.uid = meta.uid.wrapping_add(1);
+    other.mode ^= 0o100;
+    other.mtime = FileTime::from_unix_time(0, 0);
+    assert_eq!(
+        meta.diff(&other),
+        MetaDiff::UID | MetaDiff::MODE | MetaDiff::MTIME
+    );
+    Ok(())
+}
+
+#[cfg(all(unix, feature = "xattr"))]
+#[test]
+fn xattr_order_is_ignored() -> std::io::Result<()> {
+    use std::ffi::OsString;
+
+    let mut meta = snapshot()?;
+    meta.xattrs = vec![
+        (OsString::from("user.a"), b"1".to_vec()),
+        (OsString::from("user.b"), b"2".to_vec()),
+    ];
+    let mut other = meta.clone();
+    other.xattrs.reverse();
+    assert!(meta.diff(&other).is_empty());
+
+    other.xattrs[0].1 = b"3".to_vec();
+    assert_eq!(meta.diff(&other), MetaDiff::XATTRS);
+    other.xattrs.pop();
+    assert_eq!(meta.diff(&other), MetaDiff::XATTRS);
+    Ok(())
+}
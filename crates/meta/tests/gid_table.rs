@@ -14,6 +14,21 @@ fn gid_table_deduplicates_and_indexes() {
     assert_eq!(table.gid(2), None);
 }
 
+#[test]
+fn gid_table_index_of_does_not_insert() {
+    let mut table = GidTable::new();
+    assert!(table.is_empty());
+    assert_eq!(table.index_of(100), None);
+    assert!(table.is_empty());
+
+    table.push(100);
+    table.push(200);
+    assert_eq!(table.index_of(200), Some(1));
+    assert_eq!(table.index_of(3), None);
+    assert_eq!(table.len(), 2);
+    assert_eq!(table.as_slice(), &[100, 200]);
+}
+
 #[cfg(unix)]
 #[test]
 fn resolves_group_names_and_maps() {
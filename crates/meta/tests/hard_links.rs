@@ -0,0 +1,45 @@
+// crates/meta/tests/hard_links.rs
+#![cfg(unix)]
+
+use std::fs;
+use std::os::unix::fs::MetadataExt;
+
+use meta::HardLinks;
+use tempfile::tempdir;
+
+#[test]
+fn path_in_two_groups_stays_in_the_first() {
+    let dir = tempdir().unwrap();
+    let a = dir.path().join("a");
+    let b = dir.path().join("b");
+    let c = dir.path().join("c");
+    fs::write(&a, b"a").unwrap();
+    fs::write(&b, b"b").unwrap();
+    fs::write(&c, b"c").unwrap();
+
+    let mut links = HardLinks::default();
+    assert!(links.register(2, &c));
+    assert!(links.register(1, &a));
+    assert!(!links.register(1, &b));
+    assert!(!links.register(2, &b));
+    assert!(!links.register(1, &b));
+    links.finalize().unwrap();
+
+    let ino = |p: &std::path::Path| fs::metadata(p).unwrap().ino();
+    assert_eq!(ino(&a), ino(&b));
+    assert_ne!(ino(&b), ino(&c));
+    assert_eq!(fs::read(&b).unwrap(), b"a");
+    assert_eq!(fs::read(&c).unwrap(), b"c");
+}
+
+#[test]
+fn group_without_existing_members_is_skipped() {
+    let dir = tempdir().unwrap();
+    let mut links = HardLinks::default();
+    assert!(links.register(7, &dir.path().join("missing1")));
+    assert!(!links.register(7, &dir.path().join("missing2")));
+    links.finalize().unwrap();
+    assert!(!dir.path().join("missing2").exists());
+
+    assert!(links.register(7, &dir.path().join("missing1")));
+}
@@ -0,0 +1,146 @@
+// crates/meta/tests/super_modes.rs
+use std::fs;
+use std::os::unix::fs::MetadataExt;
+
+use meta::{Metadata, Options};
+use nix::unistd::Uid;
+use tempfile::tempdir;
+
+fn owner_opts() -> Options {
+    Options {
+        owner: true,
+        group: true,
+        ..Default::default()
+    }
+}
+
+fn foreign_metadata(path: &std::path::Path) -> std::io::Result<Metadata> {
+    let mut meta = Metadata::from_path(path, owner_opts())?;
+    meta.uid = meta.uid.wrapping_add(4242);
+    meta.gid = meta.gid.wrapping_add(4242);
+    Ok(meta)
+}
+
+#[test]
+fn super_user_chowns_when_privileged() -> std::io::Result<()> {
+    if !meta::has_chown_privilege() {
+        eprintln!("skipping super_user_chowns_when_privileged: requires root or CAP_CHOWN");
+        return Ok(());
+    }
+    let dir = tempdir()?;
+    let dst = dir.path().join("dst");
+    fs::write(&dst, b"data")?;
+    let meta = foreign_metadata(&dst)?;
+    let opts = Options {
+        super_user: true,
+        fake_super: true,
+        ..owner_opts()
+    };
+    meta.apply(&dst, opts)?;
+
+    let applied = fs::symlink_metadata(&dst)?;
+    assert_eq!(applied.uid(), meta.uid);
+    assert_eq!(applied.gid(), meta.gid);
+    if meta::xattrs_supported() {
+        assert_eq!(xattr::get(&dst, "user.rsync.uid")?, None);
+    }
+    Ok(())
+}
+
+#[test]
+fn unprivileged_super_skips_chown() -> std::io::Result<()> {
+    if meta::has_chown_privilege() {
+        eprintln!("skipping unprivileged_super_skips_chown: requires no chown privilege");
+        return Ok(());
+    }
+    let dir = tempdir()?;
+    let dst = dir.path().join("dst");
+    fs::write(&dst, b"data")?;
+    let before = fs::symlink_metadata(&dst)?;
+    let meta = foreign_metadata(&dst)?;
+    let opts = Options {
+        super_user: true,
+        ..owner_opts()
+    };
+    meta.apply(&dst, opts)?;
+
+    let after = fs::symlink_metadata(&dst)?;
+    assert_eq!(after.uid(), before.uid());
+    assert_eq!(after.gid(), before.gid());
+    Ok(())
+}
+
+#[test]
+fn unprivileged_super_falls_back_to_fake_super() -> std::io::Result<()> {
+    if meta::has_chown_privilege() || !meta::xattrs_supported() {
+        eprintln!("skipping unprivileged_super_falls_back_to_fake_super: needs xattrs, no root");
+        return Ok(());
+    }
+    let dir = tempdir()?;
+    let dst = dir.path().join("dst");
+    fs::write(&dst, b"data")?;
+    let meta = foreign_metadata(&dst)?;
+    let opts = Options {
+        super_user: true,
+        fake_super: true,
+        ..owner_opts()
+    };
+    meta.apply(&dst, opts)?;
+
+    assert_eq!(
+        xattr::get(&dst, "user.rsync.uid")?,
+        Some(meta.uid.to_string().into_bytes())
+    );
+    Ok(())
+}
+
+#[test]
+fn fake_super_stores_ownership_without_chown() -> std::io::Result<()> {
+    if !meta::xattrs_supported() {
+        eprintln!("skipping fake_super_stores_ownership_without_chown: no xattr support");
+        return Ok(());
+    }
+    let dir = tempdir()?;
+    let dst = dir.path().join("dst");
+    fs::write(&dst, b"data")?;
+    let before = fs::symlink_metadata(&dst)?;
+    let meta = foreign_metadata(&dst)?;
+    let opts = Options {
+        fake_super: true,
+        ..owner_opts()
+    };
+    meta.apply(&dst, opts)?;
+
+    let after = fs::symlink_metadata(&dst)?;
+    assert_eq!(after.uid(), before.uid());
+    assert_eq!(after.gid(), before.gid());
+    assert_eq!(
+        xattr::get(&dst, "user.rsync.uid")?,
+        Some(meta.uid.to_string().into_bytes())
+    );
+    assert_eq!(
+        xattr::get(&dst, "user.rsync.gid")?,
+        Some(meta.gid.to_string().into_bytes())
+    );
+    Ok(())
+}
+
+#[test]
+fn nonroot_without_super_skips_chown() -> std::io::Result<()> {
+    if Uid::effective().is_root() {
+        eprintln!("skipping nonroot_without_super_skips_chown: requires non-root");
+        return Ok(());
+    }
+    let dir = tempdir()?;
+    let dst = dir.path().join("dst");
+    fs::write(&dst, b"data")?;
+    let before = fs::symlink_metadata(&dst)?;
+    let meta = foreign_metadata(&dst)?;
+    meta.apply(&dst, owner_opts())?;
+
+    let after = fs::symlink_metadata(&dst)?;
+    assert_eq!(after.uid(), before.uid());
+    assert_eq!(after.gid(), before.gid());
+    assert_eq!(xattr::get(&dst, "user.rsync.uid")?, None);
+    Ok(())
+}
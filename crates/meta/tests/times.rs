@@ -0,0 +1,79 @@
+// crates/meta/tests/times.rs
+
+use std::fs;
+
+use filetime::FileTime;
+use meta::{Metadata, Options};
+use tempfile::tempdir;
+
+mod common;
+use common::full_metadata_opts;
+
+fn within_window(a: FileTime, b: FileTime, window: i64) -> bool {
+    (a.unix_seconds() - b.unix_seconds()).abs() <= window
+}
+
+#[test]
+fn all_time_attributes_roundtrip_in_one_apply() -> std::io::Result<()> {
+    let dir = tempdir()?;
+    let src = dir.path().join("src");
+    let dst = dir.path().join("dst");
+    fs::write(&src, b"hello")?;
+    fs::write(&dst, b"world")?;
+    let mtime = FileTime::from_unix_time(1_000_000, 123_456_789);
+    let atime = FileTime::from_unix_time(2_000_000, 987_654_321);
+    filetime::set_file_times(&src, atime, mtime)?;
+
+    let opts = Options {
+        times: true,
+        atimes: true,
+        crtimes: true,
+        ..Default::default()
+    };
+    let mut sent = Metadata::from_path(&src, opts.clone())?;
+    sent.crtime = Some(FileTime::from_unix_time(500_000, 42));
+    sent.apply(&dst, opts.clone())?;
+
+    let applied = Metadata::from_path(&dst, opts)?;
+    assert!(within_window(applied.mtime, mtime, 1));
+    assert!(within_window(applied.atime.unwrap(), atime, 1));
+    if cfg!(target_os = "macos") {
+        assert!(within_window(
+            applied.crtime.unwrap(),
+            sent.crtime.unwrap(),
+            1
+        ));
+    }
+    Ok(())
+}
+
+#[test]
+fn frame_only_carries_requested_times() -> std::io::Result<()> {
+    let dir = tempdir()?;
+    let src = dir.path().join("src");
+    fs::write(&src, b"hello")?;
+    let meta = Metadata::from_path(&src, full_metadata_opts())?;
+    let opts = Options {
+        times: true,
+        ..Default::default()
+    };
+    let frame = meta.encode_times(&opts);
+    assert_eq!(frame.len(), 1 + 12);
+
+    let mut other = meta.clone();
+    other.mtime = FileTime::from_unix_time(1, 0);
+    other.decode_times(&frame)?;
+    assert_eq!(other, meta);
+    Ok(())
+}
+
+#[test]
+fn malformed_time_frames_are_rejected() {
+    let dir = tempdir().unwrap();
+    let src = dir.path().join("src");
+    fs::write(&src, b"hello").unwrap();
+    let mut meta = Metadata::from_path(&src, full_metadata_opts()).unwrap();
+    assert!(meta.decode_times(&[]).is_err());
+    assert!(meta.decode_times(&[1, 0, 0]).is_err());
+    assert!(meta.decode_times(&[0x80]).is_err());
+}
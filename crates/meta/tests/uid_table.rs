@@ -14,6 +14,21 @@ fn uid_table_deduplicates_and_indexes() {
     assert_eq!(table.uid(2), None);
 }
 
+#[test]
+fn uid_table_index_of_does_not_insert() {
+    let mut table = UidTable::new();
+    assert!(table.is_empty());
+    assert_eq!(table.index_of(1000), None);
+    assert!(table.is_empty());
+
+    table.push(1000);
+    table.push(2000);
+    assert_eq!(table.index_of(2000), Some(1));
+    assert_eq!(table.index_of(3), None);
+    assert_eq!(table.len(), 2);
+    assert_eq!(table.as_slice(), &[1000, 2000]);
+}
+
 #[cfg(unix)]
 #[test]
 fn resolves_user_names_and_maps() {
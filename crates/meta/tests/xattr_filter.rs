@@ -0,0 +1,63 @@
+// crates/meta/tests/xattr_filter.rs
+#![cfg(unix)]
+
+use std::ffi::{OsStr, OsString};
+use std::fs;
+
+use meta::{XattrFilter, apply_xattrs_filtered};
+use tempfile::tempdir;
+
+#[test]
+fn namespaces_and_globs_select_names() {
+    let filter = XattrFilter::new()
+        .namespace("user")
+        .exclude("user.tmp.*")
+        .unwrap()
+        .include("user.*")
+        .unwrap();
+    assert!(filter.is_included(OsStr::new("user.mime_type")));
+    assert!(!filter.is_included(OsStr::new("user.tmp.cache")));
+    assert!(!filter.is_included(OsStr::new("trusted.overlay")));
+    assert!(!filter.is_included(OsStr::new("security.selinux")));
+
+    let all = XattrFilter::new();
+    assert!(all.is_included(OsStr::new("security.selinux")));
+    assert!(!all.may_delete(OsStr::new("security.selinux")));
+    assert!(!all.may_delete(OsStr::new("system.posix_acl_access")));
+    assert!(all.may_delete(OsStr::new("user.a")));
+    assert!(
+        all.allow_protected_delete(true)
+            .may_delete(OsStr::new("security.selinux"))
+    );
+
+    assert!(XattrFilter::new().include("[").is_err());
+}
+
+#[test]
+fn filtered_apply_copies_only_allowed_namespace() -> std::io::Result<()> {
+    let dir = tempdir()?;
+    let file = dir.path().join("file");
+    fs::write(&file, b"data")?;
+    if xattr::set(&file, "user.stale", b"x").is_err() {
+        return Ok(());
+    }
+    xattr::set(&file, "user.skip.keep", b"k")?;
+
+    let filter = XattrFilter::new()
+        .namespace("user.")
+        .exclude("user.skip.*")
+        .unwrap();
+    let attrs = vec![
+        (OsString::from("user.new"), b"1".to_vec()),
+        (OsString::from("user.skip.other"), b"2".to_vec()),
+        (OsString::from("trusted.other"), b"3".to_vec()),
+    ];
+    apply_xattrs_filtered(&file, &attrs, &filter)?;
+
+    assert_eq!(xattr::get(&file, "user.new")?, Some(b"1".to_vec()));
+    assert_eq!(xattr::get(&file, "user.stale")?, None);
+    assert_eq!(xattr::get(&file, "user.skip.other")?, None);
+    assert_eq!(xattr::get(&file, "user.skip.keep")?, Some(b"k".to_vec()));
+    assert_eq!(xattr::get(&file, "trusted.other")?, None);
+    Ok(())
+}
@@ -25,6 +25,7 @@ pub struct Demux {
     errors: VecDeque<String>,
     error_sockets: VecDeque<String>,
     error_utf8s: VecDeque<String>,
+    error_xfer_count: usize,
     successes: VecDeque<u32>,
     deletions: VecDeque<u32>,
     nosends: VecDeque<u32>,
@@ -56,6 +57,7 @@ impl Demux {
             errors: VecDeque::new(),
             error_sockets: VecDeque::new(),
             error_utf8s: VecDeque::new(),
+            error_xfer_count: 0,
             successes: VecDeque::new(),
             deletions: VecDeque::new(),
             nosends: VecDeque::new(),
@@ -100,6 +102,7 @@ impl Demux {
     pub fn ingest_message(&mut self, id: u16, msg: Message) -> std::io::Result<()> {
         match &msg {
             Message::ErrorXfer(text) => {
+                self.error_xfer_count += 1;
                 Self::push_limited(&mut self.error_xfers, text.clone(), self.msg_capacity);
                 if self.remote_error.is_none() {
                     self.remote_error = Some(text.clone());
@@ -207,6 +210,14 @@ impl Demux {
         self.error_utf8s.drain(..).collect()
     }
 
+    pub fn error_xfer_count(&self) -> usize {
+        self.error_xfer_count
+    }
+
+    pub fn partial_exit_code(&self) -> Option<ExitCode> {
+        (self.error_xfer_count > 0).then_some(ExitCode::Partial)
+    }
+
     pub fn take_successes(&mut self) -> Vec<u32> {
         self.successes.drain(..).collect()
     }
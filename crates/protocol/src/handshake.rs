@@ -30,6 +30,14 @@ pub fn negotiate_version(local: u32, peer: u32) -> Result<u32, VersionError> {
     Err(VersionError(local.min(peer)))
 }
 
+pub fn negotiate_forced_version(forced: u32, peer: u32) -> Result<u32, VersionError> {
+    if SUPPORTED_PROTOCOLS.contains(&forced) && peer >= forced {
+        Ok(forced)
+    } else {
+        Err(VersionError(forced))
+    }
+}
+
 pub fn negotiate_caps(local: u32, peer: u32) -> u32 {
     (local & peer) & SUPPORTED_CAPS
 }
@@ -5,6 +5,7 @@
 
 pub mod frames;
 pub mod handshake;
+pub mod ndx;
 pub mod types;
 pub mod versions;
 
@@ -17,8 +18,11 @@ pub use mux::{ChannelError, Mux};
 pub use server::Server;
 
 pub use frames::{Frame, FrameCodec, FrameHeader};
-pub use handshake::{VersionError, negotiate_caps, negotiate_version};
-pub use types::{CharsetConv, ExitCode, Message, Msg, Tag, UnknownExit, UnknownMsg, UnknownTag};
+pub use handshake::{VersionError, negotiate_caps, negotiate_forced_version, negotiate_version};
+pub use ndx::{NDX_DEL_STATS, NDX_DONE, NDX_FLIST_EOF, NDX_FLIST_OFFSET, NdxCodec};
+pub use types::{
+    CharsetConv, ExitCode, Message, Msg, Tag, UnicodeForm, UnknownExit, UnknownMsg, UnknownTag,
+};
 pub use versions::{
     CAP_ACLS, CAP_CODECS, CAP_XATTRS, CAP_ZSTD, LATEST_VERSION, MIN_VERSION, SUPPORTED_CAPS,
     SUPPORTED_PROTOCOLS, V30, V31, V32,
@@ -0,0 +1,101 @@
+// crates/protocol/src/ndx.rs
+use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
+use std::io::{self, Read, Write};
+
+use crate::versions::V30;
+
+pub const NDX_DONE: i32 = -1;
+pub const NDX_FLIST_EOF: i32 = -2;
+pub const NDX_DEL_STATS: i32 = -3;
+pub const NDX_FLIST_OFFSET: i32 = -101;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NdxCodec {
+    version: u32,
+    prev_positive: i32,
+    prev_negative: i32,
+}
+
+impl NdxCodec {
+    pub fn new(version: u32) -> Self {
+        Self {
+            version,
+            prev_positive: -1,
+            prev_negative: 1,
+        }
+    }
+
+    pub fn write_ndx<W: Write>(&mut self, w: &mut W, ndx: i32) -> io::Result<()> {
+        if self.version < V30 {
+            return w.write_i32::<LittleEndian>(ndx);
+        }
+        let mut b = Vec::with_capacity(6);
+        let (ndx, diff) = if ndx >= 0 {
+            let diff = ndx - self.prev_positive;
+            self.prev_positive = ndx;
+            (ndx, diff)
+        } else if ndx == NDX_DONE {
+            return w.write_u8(0);
+        } else {
+            b.push(0xFF);
+            let ndx = -ndx;
+            let diff = ndx - self.prev_negative;
+            self.prev_negative = ndx;
+            (ndx, diff)
+        };
+        if diff > 0 && diff < 0xFE {
+            b.push(diff as u8);
+        } else if !(0..=0x7FFF).contains(&diff) {
+            b.push(0xFE);
+            b.push(((ndx >> 24) as u8) | 0x80);
+            b.push(ndx as u8);
+            b.push((ndx >> 8) as u8);
+            b.push((ndx >> 16) as u8);
+        } else {
+            b.push(0xFE);
+            b.push((diff >> 8) as u8);
+            b.push(diff as u8);
+        }
+        w.write_all(&b)
+    }
+
+    pub fn read_ndx<R: Read>(&mut self, r: &mut R) -> io::Result<i32> {
+        if self.version < V30 {
+            return r.read_i32::<LittleEndian>();
+        }
+        let mut first = r.read_u8()?;
+        let negative = match first {
+            0 => return Ok(NDX_DONE),
+            0xFF => {
+                first = r.read_u8()?;
+                true
+            }
+            _ => false,
+        };
+        let prev = if negative {
+            self.prev_negative
+        } else {
+            self.prev_positive
+        };
+        let num = if first == 0xFE {
+            let hi = r.read_u8()?;
+            let lo = r.read_u8()?;
+            if hi & 0x80 != 0 {
+                let b2 = r.read_u8()?;
+                let b3 = r.read_u8()?;
+                i32::from_le_bytes([lo, b2, b3, hi & !0x80])
+            } else {
+                ((hi as i32) << 8) + lo as i32 + prev
+            }
+        } else {
+            first as i32 + prev
+        };
+        if negative {
+            self.prev_negative = num;
+            Ok(-num)
+        } else {
+            self.prev_positive = num;
+            Ok(num)
+        }
+    }
+}
@@ -1,11 +1,94 @@
 // crates/protocol/src/types/charset.rs
 use encoding_rs::Encoding;
 use std::borrow::Cow;
+use unicode_normalization::{UnicodeNormalization, is_nfc, is_nfd};
+
+static CHARSETS: &[&Encoding] = &[
+    encoding_rs::UTF_8,
+    encoding_rs::IBM866,
+    encoding_rs::ISO_8859_2,
+    encoding_rs::ISO_8859_3,
+    encoding_rs::ISO_8859_4,
+    encoding_rs::ISO_8859_5,
+    encoding_rs::ISO_8859_6,
+    encoding_rs::ISO_8859_7,
+    encoding_rs::ISO_8859_8,
+    encoding_rs::ISO_8859_8_I,
+    encoding_rs::ISO_8859_10,
+    encoding_rs::ISO_8859_13,
+    encoding_rs::ISO_8859_14,
+    encoding_rs::ISO_8859_15,
+    encoding_rs::ISO_8859_16,
+    encoding_rs::KOI8_R,
+    encoding_rs::KOI8_U,
+    encoding_rs::MACINTOSH,
+    encoding_rs::WINDOWS_874,
+    encoding_rs::WINDOWS_1250,
+    encoding_rs::WINDOWS_1251,
+    encoding_rs::WINDOWS_1252,
+    encoding_rs::WINDOWS_1253,
+    encoding_rs::WINDOWS_1254,
+    encoding_rs::WINDOWS_1255,
+    encoding_rs::WINDOWS_1256,
+    encoding_rs::WINDOWS_1257,
+    encoding_rs::WINDOWS_1258,
+    encoding_rs::X_MAC_CYRILLIC,
+    encoding_rs::GBK,
+    encoding_rs::GB18030,
+    encoding_rs::BIG5,
+    encoding_rs::EUC_JP,
+    encoding_rs::ISO_2022_JP,
+    encoding_rs::SHIFT_JIS,
+    encoding_rs::EUC_KR,
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UnicodeForm {
+    Nfc,
+    Nfd,
+}
+
+impl UnicodeForm {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "nfc" => Some(Self::Nfc),
+            "nfd" => Some(Self::Nfd),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Nfc => "nfc",
+            Self::Nfd => "nfd",
+        }
+    }
+
+    /// Rewrites a UTF-8 name into this form; other bytes pass through.
+    pub fn normalize<'a>(self, b: &'a [u8]) -> Cow<'a, [u8]> {
+        let Ok(s) = std::str::from_utf8(b) else {
+            return Cow::Borrowed(b);
+        };
+        match self.normalize_str(s) {
+            Cow::Borrowed(_) => Cow::Borrowed(b),
+            Cow::Owned(s) => Cow::Owned(s.into_bytes()),
+        }
+    }
+
+    pub fn normalize_str(self, s: &str) -> Cow<'_, str> {
+        match self {
+            Self::Nfc if !is_nfc(s) => Cow::Owned(s.nfc().collect()),
+            Self::Nfd if !is_nfd(s) => Cow::Owned(s.nfd().collect()),
+            _ => Cow::Borrowed(s),
+        }
+    }
+}
 
 #[derive(Clone)]
 pub struct CharsetConv {
     remote: &'static Encoding,
     local: &'static Encoding,
+    form: Option<UnicodeForm>,
 }
 
 impl CharsetConv {
@@ -20,24 +103,60 @@ impl CharsetConv {
     }
 
     pub fn to_remote<'a>(&self, b: &'a [u8]) -> Cow<'a, [u8]> {
-        if self.remote == self.local {
+        let out = if self.remote == self.local {
             Cow::Borrowed(b)
         } else {
             let (s, _, _) = self.local.decode(b);
             Cow::Owned(self.remote.encode(&s).0.into_owned())
-        }
+        };
+        self.normalize(self.remote, out)
     }
 
     pub fn to_local<'a>(&self, b: &'a [u8]) -> Cow<'a, [u8]> {
-        if self.remote == self.local {
+        let out = if self.remote == self.local {
             Cow::Borrowed(b)
         } else {
             let (s, _, _) = self.remote.decode(b);
             Cow::Owned(self.local.encode(&s).0.into_owned())
+        };
+        self.normalize(self.local, out)
+    }
+
+    fn normalize<'a>(&self, enc: &'static Encoding, b: Cow<'a, [u8]>) -> Cow<'a, [u8]> {
+        match self.form {
+            Some(form) if enc == encoding_rs::UTF_8 => match b {
+                Cow::Borrowed(b) => form.normalize(b),
+                Cow::Owned(v) => Cow::Owned(form.normalize(&v).into_owned()),
+            },
+            _ => b,
         }
     }
 
     pub fn new(remote: &'static Encoding, local: &'static Encoding) -> Self {
-        Self { remote, local }
+        Self {
+            remote,
+            local,
+            form: None,
+        }
+    }
+
+    /// Canonicalizes UTF-8 file names to `form` in both directions so that
+    /// names differing only in composition (e.g. macOS NFD vs Linux NFC)
+    /// refer to the same entry.
+    pub fn with_normalization(mut self, form: UnicodeForm) -> Self {
+        self.form = Some(form);
+        self
+    }
+
+    pub fn normalization(&self) -> Option<UnicodeForm> {
+        self.form
+    }
+
+    pub fn lookup(label: &str) -> Option<&'static Encoding> {
+        Encoding::for_label(label.as_bytes()).filter(|enc| enc.output_encoding() == *enc)
+    }
+
+    pub fn supported_charsets() -> impl Iterator<Item = &'static str> {
+        CHARSETS.iter().map(|enc| enc.name())
     }
 }
@@ -3,6 +3,6 @@ mod charset;
 mod codes;
 mod message;
 
-pub use charset::CharsetConv;
+pub use charset::{CharsetConv, UnicodeForm};
 pub use codes::{ExitCode, Msg, Tag, UnknownExit, UnknownMsg, UnknownTag};
 pub use message::Message;
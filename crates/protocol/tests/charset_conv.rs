@@ -1,6 +1,6 @@
 // crates/protocol/tests/charset_conv.rs
 use encoding_rs::Encoding;
-use protocol::CharsetConv;
+use protocol::{CharsetConv, UnicodeForm};
 use std::borrow::Cow;
 
 #[test]
@@ -37,3 +37,29 @@ fn identity_conversions_borrow() {
     let to_local = cv.to_local(s.as_bytes());
     assert!(matches!(to_local, Cow::Borrowed(_)));
 }
+
+#[test]
+fn lookup_accepts_only_encodable_charsets() {
+    assert_eq!(CharsetConv::lookup("utf-8"), Some(encoding_rs::UTF_8));
+    assert_eq!(
+        CharsetConv::lookup("latin1"),
+        Some(encoding_rs::WINDOWS_1252)
+    );
+    assert!(CharsetConv::lookup("utf-16le").is_none());
+    assert!(CharsetConv::lookup("BOGUS").is_none());
+    for name in CharsetConv::supported_charsets() {
+        assert!(CharsetConv::lookup(name).is_some(), "{name}");
+    }
+}
+
+#[test]
+fn normalization_rewrites_only_utf8_names() {
+    let utf8 = Encoding::for_label(b"utf-8").unwrap();
+    let cv = CharsetConv::new(utf8, utf8).with_normalization(UnicodeForm::Nfd);
+    let local = cv.to_local("caf\u{e9}".as_bytes());
+    assert_eq!(local.as_ref(), "cafe\u{301}".as_bytes());
+    assert!(matches!(cv.to_local(b"plain"), Cow::Borrowed(_)));
+    assert_eq!(cv.to_local(b"f\xF8o").as_ref(), b"f\xF8o");
+    assert_eq!(UnicodeForm::parse("NFC"), Some(UnicodeForm::Nfc));
+    assert_eq!(UnicodeForm::parse("nfkc"), None);
+}
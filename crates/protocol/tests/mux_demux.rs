@@ -2,7 +2,7 @@
 use std::thread::sleep;
 use std::time::Duration;
 
-use protocol::{Message, demux::Demux, mux::Mux};
+use protocol::{ExitCode, Message, demux::Demux, mux::Mux};
 
 #[test]
 fn multiplex_multiple_channels() {
@@ -105,6 +105,40 @@ fn error_xfer_sets_remote_error() {
     assert_eq!(demux.take_remote_error(), Some("oops".into()));
 }
 
+#[test]
+fn error_xfer_marks_transfer_partial() {
+    let mut mux = Mux::new(Duration::from_millis(50));
+    let mut demux = Demux::new(Duration::from_millis(50));
+
+    let tx = mux.register_channel(1).unwrap();
+    let rx = demux.register_channel(1);
+
+    tx.send(Message::Data(b"a.txt".to_vec())).unwrap();
+    mux.send_error_xfer(1, "b.txt: Permission denied (13)")
+        .unwrap();
+    tx.send(Message::Data(b"c.txt".to_vec())).unwrap();
+
+    let mut frames = Vec::new();
+    while frames.len() < 3 {
+        if let Some(frame) = mux.poll() {
+            frames.push(frame);
+        }
+    }
+    for frame in frames {
+        demux.ingest(frame).unwrap();
+    }
+
+    let received: Vec<_> = rx.try_iter().collect();
+    assert!(received.contains(&Message::Data(b"a.txt".to_vec())));
+    assert!(received.contains(&Message::Data(b"c.txt".to_vec())));
+    assert_eq!(
+        demux.take_error_xfers(),
+        vec!["b.txt: Permission denied (13)".to_string()]
+    );
+    assert_eq!(demux.error_xfer_count(), 1);
+    assert_eq!(demux.partial_exit_code(), Some(ExitCode::Partial));
+}
+
 #[test]
 fn progress_attrs_and_xattrs() {
     let mut mux = Mux::new(Duration::from_millis(50));
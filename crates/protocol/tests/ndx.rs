@@ -0,0 +1,62 @@
+// crates/protocol/tests/ndx.rs
+use protocol::{NDX_DEL_STATS, NDX_DONE, NDX_FLIST_EOF, NDX_FLIST_OFFSET, NdxCodec, V30, V31};
+
+#[test]
+fn varint_ndx_matches_rsync_wire_format() {
+    let mut out = Vec::new();
+    let mut codec = NdxCodec::new(V31);
+    for ndx in [0, 1, 500, 100_000, NDX_DONE, NDX_FLIST_EOF, 99_999] {
+        codec.write_ndx(&mut out, ndx).unwrap();
+    }
+    assert_eq!(
+        out,
+        [
+            0x01, 0x01, 0xFE, 0x01, 0xF3, 0xFE, 0x80, 0xA0, 0x86, 0x01, 0x00, 0xFF, 0x01, 0xFE,
+            0x80, 0x9F, 0x86, 0x01,
+        ]
+    );
+}
+
+#[test]
+fn varint_ndx_round_trips() {
+    let seq = [
+        0,
+        1,
+        2,
+        3,
+        300,
+        301,
+        40_000,
+        7,
+        i32::MAX,
+        NDX_DONE,
+        NDX_FLIST_EOF,
+        NDX_DEL_STATS,
+        NDX_FLIST_OFFSET,
+        NDX_FLIST_OFFSET - 70_000,
+        NDX_DONE,
+        8,
+    ];
+    for version in [29, V30, V31] {
+        let mut out = Vec::new();
+        let mut writer = NdxCodec::new(version);
+        for &ndx in &seq {
+            writer.write_ndx(&mut out, ndx).unwrap();
+        }
+        let mut reader = NdxCodec::new(version);
+        let mut cursor = &out[..];
+        let decoded: Vec<i32> = seq
+            .iter()
+            .map(|_| reader.read_ndx(&mut cursor).unwrap())
+            .collect();
+        assert_eq!(decoded, seq);
+        assert!(cursor.is_empty());
+    }
+}
+
+#[test]
+fn legacy_ndx_uses_fixed_int() {
+    let mut out = Vec::new();
+    NdxCodec::new(29).write_ndx(&mut out, NDX_DONE).unwrap();
+    assert_eq!(out, (-1i32).to_le_bytes());
+}
@@ -3,7 +3,8 @@ use encoding_rs::Encoding;
 use filelist::{Decoder as FDecoder, Encoder as FEncoder, Entry as FEntry};
 use protocol::{
     CAP_ACLS, CAP_CODECS, CAP_XATTRS, CAP_ZSTD, CharsetConv, Frame, MIN_VERSION, Message, Msg,
-    SUPPORTED_PROTOCOLS, Tag, negotiate_caps, negotiate_version,
+    SUPPORTED_PROTOCOLS, Tag, V30, V31, V32, negotiate_caps, negotiate_forced_version,
+    negotiate_version,
 };
 use std::io;
 
@@ -57,6 +58,14 @@ fn version_negotiation() {
     assert!(negotiate_version(latest, MIN_VERSION - 1).is_err());
 }
 
+#[test]
+fn forced_version_negotiation() {
+    assert_eq!(negotiate_forced_version(V30, V32), Ok(V30));
+    assert_eq!(negotiate_forced_version(V31, V31), Ok(V31));
+    assert!(negotiate_forced_version(V32, V31).is_err());
+    assert!(negotiate_forced_version(MIN_VERSION - 1, V32).is_err());
+}
+
 #[test]
 fn capability_negotiation() {
     let local = CAP_CODECS | CAP_ACLS | CAP_XATTRS;
@@ -74,6 +83,7 @@ fn captured_frames_roundtrip() -> io::Result<()> {
         xattrs: vec![(b"user.test".to_vec(), b"1".to_vec())],
         acl: vec![1, 0, 0, 0, 0, 7, 0, 0, 0],
         default_acl: Vec::new(),
+        times: Vec::new(),
     };
     let mut fenc = FEncoder::new();
     let payload = fenc.encode_entry(&entry);
@@ -340,6 +350,7 @@ fn filelist_iconv_roundtrip() {
         xattrs: Vec::new(),
         acl: Vec::new(),
         default_acl: Vec::new(),
+        times: Vec::new(),
     };
     let mut enc = FEncoder::new();
     let msg = Message::from_file_list(&entry, &mut enc, Some(&cv));
@@ -1,10 +1,44 @@
 // crates/transport/src/daemon.rs
+use std::io;
 use std::path::Path;
 use std::time::Duration;
 
 use crate::Transport;
 
-pub trait DaemonTransport: Transport {}
+pub trait DaemonTransport: Transport {
+    fn list_modules<F>(&mut self, mut on_module: F) -> io::Result<()>
+    where
+        F: FnMut(&str, &str),
+        Self: Sized,
+    {
+        self.send(b"#list\n")?;
+        let mut pending = Vec::new();
+        let mut buf = [0u8; 4096];
+        loop {
+            let n = self.receive(&mut buf)?;
+            if n == 0 {
+                return Ok(());
+            }
+            pending.extend_from_slice(&buf[..n]);
+            while let Some(pos) = pending.iter().position(|&b| b == b'\n') {
+                let line: Vec<u8> = pending.drain(..=pos).collect();
+                let line = String::from_utf8_lossy(&line[..pos]);
+                let line = line.trim_end_matches('\r');
+                if line.is_empty() || line == "@RSYNCD: EXIT" {
+                    return Ok(());
+                }
+                if let Some(err) = line.strip_prefix("@ERROR: ") {
+                    return Err(io::Error::new(
+                        io::ErrorKind::PermissionDenied,
+                        err.trim().to_string(),
+                    ));
+                }
+                let (name, comment) = line.split_once('\t').unwrap_or((line, ""));
+                on_module(name.trim_end(), comment);
+            }
+        }
+    }
+}
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum SockOpt {
@@ -139,3 +173,15 @@ pub fn daemon_remote_opts(base: &[String], path: &Path) -> Vec<String> {
     }
     opts
 }
+
+/// Extracts the protocol version from an `@RSYNCD: <version>` greeting,
+/// accepting a `major.minor` subprotocol suffix and ignoring any trailing
+/// tokens such as advertised checksum names.
+pub fn parse_greeting(line: &str) -> Option<u32> {
+    let rest = line
+        .trim_end_matches(['\r', '\n'])
+        .strip_prefix("@RSYNCD: ")?;
+    let token = rest.split_whitespace().next()?;
+    let major = token.split_once('.').map_or(token, |(major, _)| major);
+    major.parse().ok()
+}
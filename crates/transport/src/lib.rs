@@ -2,6 +2,7 @@
 #![doc = include_str!("../../../docs/crates/transport/lib.md")]
 
 use std::io;
+use std::net::SocketAddr;
 use std::time::Duration;
 
 mod config;
@@ -15,7 +16,7 @@ pub mod tcp;
 mod temp;
 
 pub use config::TransportConfig;
-pub use daemon::{DaemonTransport, SockOpt, daemon_remote_opts, parse_sockopts};
+pub use daemon::{DaemonTransport, SockOpt, daemon_remote_opts, parse_greeting, parse_sockopts};
 pub use factory::TransportFactory;
 pub use rate::RateLimitedTransport;
 #[cfg(unix)]
@@ -23,7 +24,7 @@ pub use ssh::session::SshStdioTransport;
 #[cfg(unix)]
 pub use ssh::{io as ssh_io, session as ssh_session, spawn as ssh_spawn};
 pub use stdio::{LocalPipeTransport, TimeoutTransport};
-pub use tcp::TcpTransport;
+pub use tcp::{DEFAULT_GREETING_TIMEOUT, TcpTransport};
 pub use temp::{TempFileGuard, TempSocketGuard};
 
 #[cfg(not(unix))]
@@ -216,6 +217,17 @@ where
     Ok(total)
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RemoteBinaryError(pub String);
+
+impl std::fmt::Display for RemoteBinaryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::error::Error for RemoteBinaryError {}
+
 #[derive(Clone, Copy, Debug)]
 pub enum AddressFamily {
     V4,
@@ -240,6 +252,10 @@ pub trait Transport {
     }
 
     fn update_timeout(&mut self) {}
+
+    fn peer_addr(&self) -> Option<SocketAddr> {
+        None
+    }
 }
 
 pub trait SshTransport: Transport {}
@@ -1,4 +1,5 @@
 // crates/transport/src/rate.rs
+use std::net::SocketAddr;
 use std::time::{Duration, Instant};
 
 use crate::Transport;
@@ -87,4 +88,8 @@ impl<T: Transport> Transport for RateLimitedTransport<T> {
     fn set_write_timeout(&mut self, dur: Option<Duration>) -> std::io::Result<()> {
         self.inner.set_write_timeout(dur)
     }
+
+    fn peer_addr(&self) -> Option<SocketAddr> {
+        self.inner.peer_addr()
+    }
 }
@@ -2,20 +2,21 @@
 
 use std::io::{self, BufReader};
 use std::os::fd::AsRawFd;
-use std::process::{Child, ChildStdin, ChildStdout};
+use std::process::{Child, ChildStdin, ChildStdout, ExitStatus};
 use std::sync::{Arc, Mutex};
 use std::thread::JoinHandle;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use checksums::{StrongHash, strong_digest};
 use compress::{self, Codec, available_codecs};
 use protocol::{CAP_CODECS, Frame, FrameHeader, Message, Msg, Tag, negotiate_version};
 
-use crate::{LocalPipeTransport, SshTransport, Transport};
+use crate::{LocalPipeTransport, RemoteBinaryError, SshTransport, Transport};
 
 pub(crate) const SSH_IO_BUF_SIZE: usize = 32 * 1024;
 pub(crate) const SSH_STDERR_CAP: usize = 32 * 1024;
 pub const MAX_FRAME_LEN: usize = 16 * 1024 * 1024;
+const MAX_PEER_VERSION: u32 = 40;
 
 pub struct SshStdioTransport {
     pub(crate) inner: Option<LocalPipeTransport<BufReader<ChildStdout>, ChildStdin>>,
@@ -103,7 +104,18 @@ impl SshStdioTransport {
             read += n;
         }
         let peer = u32::from_be_bytes(ver_buf);
-        negotiate_version(version, peer).map_err(|e| io::Error::other(e.to_string()))?;
+        if peer > MAX_PEER_VERSION {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                RemoteBinaryError(format!(
+                    "unexpected greeting {:?}",
+                    String::from_utf8_lossy(&ver_buf)
+                )),
+            ));
+        }
+        negotiate_version(version, peer).map_err(|e| {
+            io::Error::new(io::ErrorKind::InvalidData, RemoteBinaryError(e.to_string()))
+        })?;
 
         let local_caps = caps | CAP_CODECS;
         transport.send(&local_caps.to_be_bytes())?;
@@ -211,6 +223,27 @@ impl SshStdioTransport {
         }
     }
 
+    pub(crate) fn wait_exit(&mut self, grace: Duration) -> Option<ExitStatus> {
+        let handle = self.handle.as_mut()?;
+        let deadline = Instant::now() + grace;
+        loop {
+            let status = handle.child.try_wait().ok()?;
+            let drained = handle
+                .stderr_thread
+                .as_ref()
+                .is_none_or(|t| t.is_finished());
+            if let Some(status) = status
+                && (drained || Instant::now() >= deadline)
+            {
+                return Some(status);
+            }
+            if Instant::now() >= deadline {
+                return None;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+    }
+
     pub fn into_inner(mut self) -> io::Result<(BufReader<ChildStdout>, ChildStdin)> {
         if let Some(handle) = self.handle.take() {
             std::mem::forget(handle);
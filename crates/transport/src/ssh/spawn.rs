@@ -9,12 +9,14 @@ use std::time::{Duration, Instant};
 
 use compress::Codec;
 
-use crate::{AddressFamily, LocalPipeTransport, Transport};
+use crate::{AddressFamily, LocalPipeTransport, RemoteBinaryError, Transport};
 
 use super::session::{
     CapturedStderr, ProcessHandle, SSH_IO_BUF_SIZE, SSH_STDERR_CAP, SshStdioTransport,
 };
 
+const REMOTE_EXIT_GRACE: Duration = Duration::from_millis(200);
+
 impl SshStdioTransport {
     pub fn spawn<I, S>(program: &str, args: I) -> io::Result<Self>
     where
@@ -280,6 +282,11 @@ impl SshStdioTransport {
             match Self::handshake(&mut t, rsync_env, remote_opts, token, version, caps) {
                 Ok(v) => v,
                 Err(mut e) => {
+                    let status = t.wait_exit(REMOTE_EXIT_GRACE);
+                    let incompatible = e
+                        .get_ref()
+                        .is_some_and(|inner| inner.is::<RemoteBinaryError>())
+                        || status.is_some_and(|s| s.code().is_some_and(|c| c != 0 && c != 255));
                     let (stderr, _) = t.stderr();
                     if !stderr.is_empty() {
                         let mut msg = String::from_utf8_lossy(&stderr).into_owned();
@@ -302,7 +309,17 @@ impl SshStdioTransport {
                         } else {
                             io::ErrorKind::UnexpectedEof
                         };
+                        if incompatible && kind == io::ErrorKind::UnexpectedEof {
+                            let detail = String::from_utf8_lossy(&stderr).trim_end().to_string();
+                            return Err(io::Error::other(RemoteBinaryError(detail)));
+                        }
                         e = io::Error::new(kind, msg);
+                    } else if incompatible {
+                        let detail = match status {
+                            Some(s) if !s.success() => format!("remote command {s}"),
+                            _ => e.to_string(),
+                        };
+                        e = io::Error::other(RemoteBinaryError(detail));
                     }
                     return Err(e);
                 }
@@ -1,5 +1,6 @@
 // crates/transport/src/stdio.rs
 use std::io::{self, Read, Write};
+use std::net::SocketAddr;
 use std::time::{Duration, Instant};
 
 use crate::Transport;
@@ -110,6 +111,10 @@ impl<T: Transport> Transport for TimeoutTransport<T> {
     fn update_timeout(&mut self) {
         self.refresh();
     }
+
+    fn peer_addr(&self) -> Option<SocketAddr> {
+        self.inner.peer_addr()
+    }
 }
 
 #[cfg(test)]
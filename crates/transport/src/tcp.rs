@@ -4,14 +4,18 @@ use std::net::{
     IpAddr, Ipv4Addr, Ipv6Addr, Shutdown, SocketAddr, TcpListener, TcpStream, ToSocketAddrs,
 };
 use std::os::fd::{AsRawFd, BorrowedFd, RawFd};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use nix::poll::{PollFd, PollFlags, PollTimeout, poll};
 
 use ipnet::IpNet;
 use socket2::SockRef;
 
-use crate::{AddressFamily, DaemonTransport, SockOpt, Transport};
+use crate::{AddressFamily, DaemonTransport, RateLimitedTransport, SockOpt, Transport};
+
+/// How long a client waits for the daemon's version greeting when no
+/// explicit greeting timeout is configured.
+pub const DEFAULT_GREETING_TIMEOUT: Duration = Duration::from_secs(30);
 
 pub struct TcpTransport {
     stream: TcpStream,
@@ -261,9 +265,123 @@ impl TcpTransport {
         self.stream.set_read_timeout(dur)
     }
 
+    /// Reads the daemon's 4-byte version greeting, failing with `TimedOut`
+    /// if it has not fully arrived within `timeout`.
+    pub fn read_greeting(&mut self, timeout: Duration) -> io::Result<u32> {
+        let timed_out = || {
+            io::Error::new(
+                io::ErrorKind::TimedOut,
+                "timed out waiting for daemon greeting",
+            )
+        };
+        let deadline = Instant::now() + timeout;
+        let fd = self.stream.as_raw_fd();
+        let mut buf = [0u8; 4];
+        let mut filled = 0;
+        while filled < buf.len() {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return Err(timed_out());
+            }
+            wait_fd(fd, PollFlags::POLLIN, Some(remaining)).map_err(|e| {
+                if e.kind() == io::ErrorKind::TimedOut {
+                    timed_out()
+                } else {
+                    e
+                }
+            })?;
+            match self.stream.read(&mut buf[filled..]) {
+                Ok(0) => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::UnexpectedEof,
+                        "connection closed before daemon greeting",
+                    ));
+                }
+                Ok(n) => filled += n,
+                Err(e)
+                    if matches!(
+                        e.kind(),
+                        io::ErrorKind::WouldBlock | io::ErrorKind::Interrupted
+                    ) => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(u32::from_be_bytes(buf))
+    }
+
     pub fn set_write_timeout(&self, dur: Option<Duration>) -> io::Result<()> {
         self.stream.set_write_timeout(dur)
     }
+
+    pub fn set_max_pacing_rate(&self, bytes_per_sec: u64) -> io::Result<()> {
+        #[cfg(target_os = "linux")]
+        {
+            let rate: libc::c_ulong = bytes_per_sec as libc::c_ulong;
+            // SAFETY: the descriptor is owned by `self.stream` and `rate` outlives the call.
+            let ret = unsafe {
+                libc::setsockopt(
+                    self.stream.as_raw_fd(),
+                    libc::SOL_SOCKET,
+                    libc::SO_MAX_PACING_RATE,
+                    (&rate as *const libc::c_ulong).cast(),
+                    std::mem::size_of::<libc::c_ulong>() as libc::socklen_t,
+                )
+            };
+            if ret == -1 {
+                return Err(io::Error::last_os_error());
+            }
+            Ok(())
+        }
+        #[cfg(not(target_os = "linux"))]
+        {
+            let _ = bytes_per_sec;
+            Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "SO_MAX_PACING_RATE is only supported on Linux",
+            ))
+        }
+    }
+
+    pub fn max_pacing_rate(&self) -> io::Result<u64> {
+        #[cfg(target_os = "linux")]
+        {
+            let mut rate: libc::c_ulong = 0;
+            let mut len = std::mem::size_of::<libc::c_ulong>() as libc::socklen_t;
+            // SAFETY: `rate` and `len` are valid for writes and sized for the option value.
+            let ret = unsafe {
+                libc::getsockopt(
+                    self.stream.as_raw_fd(),
+                    libc::SOL_SOCKET,
+                    libc::SO_MAX_PACING_RATE,
+                    (&mut rate as *mut libc::c_ulong).cast(),
+                    &mut len,
+                )
+            };
+            if ret == -1 {
+                return Err(io::Error::last_os_error());
+            }
+            Ok(rate as u64)
+        }
+        #[cfg(not(target_os = "linux"))]
+        {
+            Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "SO_MAX_PACING_RATE is only supported on Linux",
+            ))
+        }
+    }
+
+    pub fn with_bwlimit(self, bwlimit: u64, kernel_pacing: bool) -> Box<dyn Transport> {
+        if kernel_pacing {
+            match self.set_max_pacing_rate(bwlimit) {
+                Ok(()) => return Box::new(self),
+                Err(e) => {
+                    tracing::debug!("kernel pacing unavailable ({e}); using --bwlimit throttling")
+                }
+            }
+        }
+        Box::new(RateLimitedTransport::new(self, bwlimit))
+    }
 }
 
 fn host_matches(ip: &IpAddr, pat: &str) -> bool {
@@ -343,6 +461,10 @@ impl Transport for TcpTransport {
     fn close(&mut self) -> io::Result<()> {
         self.stream.shutdown(Shutdown::Both)
     }
+
+    fn peer_addr(&self) -> Option<SocketAddr> {
+        self.stream.peer_addr().ok()
+    }
 }
 
 impl DaemonTransport for TcpTransport {}
@@ -0,0 +1,24 @@
+// crates/transport/tests/greeting.rs
+use transport::parse_greeting;
+
+#[test]
+fn parses_plain_version() {
+    assert_eq!(parse_greeting("@RSYNCD: 31\n"), Some(31));
+}
+
+#[test]
+fn parses_major_minor_version() {
+    assert_eq!(parse_greeting("@RSYNCD: 31.0\n"), Some(31));
+}
+
+#[test]
+fn ignores_trailing_capabilities() {
+    assert_eq!(parse_greeting("@RSYNCD: 30 md5 md4\r\n"), Some(30));
+}
+
+#[test]
+fn rejects_non_version_lines() {
+    assert_eq!(parse_greeting("@RSYNCD: OK\n"), None);
+    assert_eq!(parse_greeting("@RSYNCD: EXIT"), None);
+    assert_eq!(parse_greeting("welcome 31\n"), None);
+}
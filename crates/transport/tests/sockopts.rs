@@ -254,3 +254,40 @@ fn apply_sockopt_bind_device_unsupported() {
             .is_err()
     );
 }
+
+#[cfg(target_os = "linux")]
+#[test]
+fn bwlimit_kernel_pacing_sets_max_pacing_rate() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    thread::spawn(move || {
+        let _ = listener.accept().unwrap();
+    });
+
+    let stream = TcpStream::connect(addr).unwrap();
+    let probe = TcpTransport::from_stream(stream.try_clone().unwrap());
+    let _paced = TcpTransport::from_stream(stream).with_bwlimit(64 * 1024, true);
+
+    assert_eq!(probe.max_pacing_rate().unwrap(), 64 * 1024);
+}
+
+#[cfg(not(target_os = "linux"))]
+#[test]
+fn bwlimit_kernel_pacing_falls_back() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    thread::spawn(move || {
+        let _ = listener.accept().unwrap();
+    });
+
+    let stream = TcpStream::connect(addr).unwrap();
+    let transport = TcpTransport::from_stream(stream);
+
+    assert_eq!(
+        transport.set_max_pacing_rate(64 * 1024).unwrap_err().kind(),
+        std::io::ErrorKind::Unsupported
+    );
+    let _limited = transport.with_bwlimit(64 * 1024, true);
+}
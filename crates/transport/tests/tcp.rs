@@ -2,8 +2,11 @@
 use std::io::{Read, Write};
 use std::net::TcpListener;
 use std::thread;
+use std::time::{Duration, Instant};
 
-use transport::{Transport, tcp::TcpTransport};
+use transport::{
+    DaemonTransport, LocalPipeTransport, RateLimitedTransport, Transport, tcp::TcpTransport,
+};
 
 #[test]
 fn send_receive_over_tcp() {
@@ -25,3 +28,97 @@ fn send_receive_over_tcp() {
     assert_eq!(n, 4);
     assert_eq!(&buf, b"ping");
 }
+
+#[test]
+fn peer_addr_reports_both_ends() {
+    let listener = TcpListener::bind("127.0.0.1:0").expect("bind");
+    let addr = listener.local_addr().unwrap();
+
+    let client =
+        TcpTransport::connect(&addr.ip().to_string(), addr.port(), None, None).expect("connect");
+    let (stream, client_addr) = listener.accept().unwrap();
+    let server = TcpTransport::from_stream(stream);
+
+    assert_eq!(client.peer_addr(), Some(addr));
+    assert_eq!(server.peer_addr(), Some(client_addr));
+    assert_eq!(
+        RateLimitedTransport::new(server, 1024).peer_addr(),
+        Some(client_addr)
+    );
+    let pipe = LocalPipeTransport::new(std::io::empty(), std::io::sink());
+    assert_eq!(pipe.peer_addr(), None);
+}
+
+#[test]
+fn list_modules_streams_each_module_in_order() {
+    let listener = TcpListener::bind("127.0.0.1:0").expect("bind");
+    let addr = listener.local_addr().unwrap();
+
+    let server = thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut req = [0u8; 6];
+        stream.read_exact(&mut req).unwrap();
+        assert_eq!(&req, b"#list\n");
+        for i in 0..2000 {
+            writeln!(stream, "mod{i:04}         \tcomment {i}").unwrap();
+            if i % 100 == 0 {
+                stream.flush().unwrap();
+            }
+        }
+        stream.write_all(b"@RSYNCD: EXIT\n").unwrap();
+    });
+
+    let mut transport =
+        TcpTransport::connect(&addr.ip().to_string(), addr.port(), None, None).expect("connect");
+    let mut seen = Vec::new();
+    transport
+        .list_modules(|name, comment| seen.push((name.to_string(), comment.to_string())))
+        .expect("list");
+    server.join().unwrap();
+
+    assert_eq!(seen.len(), 2000);
+    for (i, (name, comment)) in seen.iter().enumerate() {
+        assert_eq!(name, &format!("mod{i:04}"));
+        assert_eq!(comment, &format!("comment {i}"));
+    }
+}
+
+#[test]
+fn read_greeting_times_out_when_daemon_never_greets() {
+    let listener = TcpListener::bind("127.0.0.1:0").expect("bind");
+    let addr = listener.local_addr().unwrap();
+
+    let server = thread::spawn(move || {
+        let (_stream, _) = listener.accept().unwrap();
+        thread::sleep(Duration::from_secs(2));
+    });
+
+    let mut transport =
+        TcpTransport::connect(&addr.ip().to_string(), addr.port(), None, None).expect("connect");
+    let start = Instant::now();
+    let err = transport
+        .read_greeting(Duration::from_millis(100))
+        .unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::TimedOut);
+    assert!(start.elapsed() < Duration::from_secs(1));
+    server.join().unwrap();
+}
+
+#[test]
+fn read_greeting_reassembles_split_version() {
+    let listener = TcpListener::bind("127.0.0.1:0").expect("bind");
+    let addr = listener.local_addr().unwrap();
+
+    thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let bytes = 31u32.to_be_bytes();
+        stream.write_all(&bytes[..1]).unwrap();
+        stream.flush().unwrap();
+        thread::sleep(Duration::from_millis(50));
+        stream.write_all(&bytes[1..]).unwrap();
+    });
+
+    let mut transport =
+        TcpTransport::connect(&addr.ip().to_string(), addr.port(), None, None).expect("connect");
+    assert_eq!(transport.read_greeting(Duration::from_secs(5)).unwrap(), 31);
+}
@@ -33,10 +33,12 @@ impl Entry {
 
 pub struct Walk {
     iter: walkdir::IntoIter,
+    root: PathBuf,
     prev_path: String,
     batch_size: usize,
     max_file_size: Option<u64>,
     include_links: bool,
+    follow_dirlinks: bool,
     one_file_system: bool,
     root_dev: u64,
     ignore: Ignore,
@@ -50,6 +52,15 @@ pub struct Walk {
     inode_table: Vec<u64>,
 }
 
+fn dir_iter(root: &Path, follow_links: bool) -> walkdir::IntoIter {
+    #[cfg(windows)]
+    let root = normalize_path(root);
+    WalkDir::new(root)
+        .follow_links(follow_links)
+        .sort_by(|a, b| a.file_name().cmp(b.file_name()))
+        .into_iter()
+}
+
 impl Walk {
     fn new(
         root: PathBuf,
@@ -59,39 +70,23 @@ impl Walk {
         one_file_system: bool,
         ignore: Ignore,
     ) -> std::io::Result<Self> {
-        #[cfg(windows)]
-        let walk_root = normalize_path(&root);
-        #[cfg(windows)]
-        let iter = WalkDir::new(walk_root)
-            .sort_by(|a, b| a.file_name().cmp(b.file_name()))
-            .into_iter();
-        #[cfg(windows)]
-        let root_dev = 0;
-
         #[cfg(unix)]
         let root_dev = if one_file_system {
             std::fs::symlink_metadata(&root)?.dev()
         } else {
             0
         };
-        #[cfg(unix)]
-        let iter = WalkDir::new(root)
-            .sort_by(|a, b| a.file_name().cmp(b.file_name()))
-            .into_iter();
-
-        #[cfg(not(any(unix, windows)))]
+        #[cfg(not(unix))]
         let root_dev = 0;
-        #[cfg(not(any(unix, windows)))]
-        let iter = WalkDir::new(root)
-            .sort_by(|a, b| a.file_name().cmp(b.file_name()))
-            .into_iter();
 
         Ok(Walk {
-            iter,
+            iter: dir_iter(&root, false),
+            root,
             prev_path: String::new(),
             batch_size,
             max_file_size,
             include_links,
+            follow_dirlinks: false,
             one_file_system,
             root_dev,
             ignore,
@@ -106,6 +101,12 @@ impl Walk {
         })
     }
 
+    pub fn follow_dirlinks(mut self, follow: bool) -> Self {
+        self.follow_dirlinks = follow;
+        self.iter = dir_iter(&self.root, follow);
+        self
+    }
+
     pub fn skip_current_dir(&mut self) {
         self.iter.skip_current_dir();
     }
@@ -125,6 +126,93 @@ impl Walk {
     pub fn inodes(&self) -> &[u64] {
         &self.inode_table
     }
+
+    fn make_entry(
+        &mut self,
+        fs_path: &Path,
+        file_type: FileType,
+    ) -> Option<std::io::Result<Entry>> {
+        if file_type.is_symlink() && !self.include_links {
+            return None;
+        }
+        if self.ignore.is_ignored(fs_path) {
+            if file_type.is_dir() {
+                self.iter.skip_current_dir();
+            }
+            return None;
+        }
+        let path = {
+            #[cfg(windows)]
+            let mut p = fs_path.to_string_lossy().into_owned();
+            #[cfg(not(windows))]
+            let p = fs_path.to_string_lossy().into_owned();
+            #[cfg(windows)]
+            {
+                use crate::fs::VERBATIM_PREFIX;
+                if let Some(stripped) = p.strip_prefix(VERBATIM_PREFIX) {
+                    p = stripped.to_string();
+                }
+            }
+            p
+        };
+        let prefix = common_prefix_len(&self.prev_path, &path);
+        let suffix = path[prefix..].to_string();
+
+        let meta = if file_type.is_dir() {
+            std::fs::metadata(fs_path)
+        } else {
+            std::fs::symlink_metadata(fs_path)
+        };
+        let meta = match meta {
+            Ok(m) => m,
+            Err(e) => return Some(Err(e)),
+        };
+        if let Some(max) = self.max_file_size {
+            if meta.is_file() && meta.len() > max {
+                return None;
+            }
+        }
+
+        #[cfg(unix)]
+        let (uid, gid, dev, ino) = (meta.uid(), meta.gid(), meta.dev(), meta.ino());
+        #[cfg(not(unix))]
+        let (uid, gid, dev, ino) = (0u32, 0u32, 0u64, 0u64);
+
+        if self.one_file_system && dev != self.root_dev {
+            if file_type.is_dir() {
+                self.iter.skip_current_dir();
+            }
+            return None;
+        }
+
+        let uid_idx = *self.uid_map.entry(uid).or_insert_with(|| {
+            self.uid_table.push(uid);
+            self.uid_table.len() - 1
+        });
+        let gid_idx = *self.gid_map.entry(gid).or_insert_with(|| {
+            self.gid_table.push(gid);
+            self.gid_table.len() - 1
+        });
+        let dev_idx = *self.dev_map.entry(dev).or_insert_with(|| {
+            self.dev_table.push(dev);
+            self.dev_table.len() - 1
+        });
+        let inode_idx = *self.inode_map.entry((dev_idx, ino)).or_insert_with(|| {
+            self.inode_table.push(ino);
+            self.inode_table.len() - 1
+        });
+
+        self.prev_path = path;
+        Some(Ok(Entry {
+            prefix_len: prefix,
+            suffix,
+            file_type,
+            uid: uid_idx,
+            gid: gid_idx,
+            dev: dev_idx,
+            inode: inode_idx,
+        }))
+    }
 }
 
 pub fn walk(
@@ -152,95 +240,41 @@ impl Iterator for Walk {
     fn next(&mut self) -> Option<Self::Item> {
         let mut batch = Vec::new();
         while batch.len() < self.batch_size {
-            match self.iter.next() {
+            let made = match self.iter.next() {
                 Some(Ok(entry)) => {
-                    let file_type = entry.file_type();
-                    if file_type.is_symlink() && !self.include_links {
-                        continue;
-                    }
-                    if self.ignore.is_ignored(entry.path()) {
-                        if file_type.is_dir() {
-                            self.iter.skip_current_dir();
-                        }
-                        continue;
+                    let mut file_type = entry.file_type();
+                    if self.follow_dirlinks && entry.path_is_symlink() && !file_type.is_dir() {
+                        file_type = match std::fs::symlink_metadata(entry.path()) {
+                            Ok(m) => m.file_type(),
+                            Err(e) => return Some(Err(e)),
+                        };
                     }
-                    let path = {
-                        #[cfg(windows)]
-                        let mut p = entry.path().to_string_lossy().into_owned();
-                        #[cfg(not(windows))]
-                        let p = entry.path().to_string_lossy().into_owned();
-                        #[cfg(windows)]
-                        {
-                            use crate::fs::VERBATIM_PREFIX;
-                            if let Some(stripped) = p.strip_prefix(VERBATIM_PREFIX) {
-                                p = stripped.to_string();
-                            }
-                        }
-                        p
-                    };
-                    let prefix = common_prefix_len(&self.prev_path, &path);
-                    let suffix = path[prefix..].to_string();
-
-                    let meta = match std::fs::symlink_metadata(entry.path()) {
-                        Ok(m) => m,
-                        Err(e) => return Some(Err(e)),
-                    };
-                    if let Some(max) = self.max_file_size {
-                        if meta.is_file() && meta.len() > max {
-                            continue;
-                        }
-                    }
-
-                    #[cfg(unix)]
-                    let (uid, gid, dev, ino) = (meta.uid(), meta.gid(), meta.dev(), meta.ino());
-                    #[cfg(not(unix))]
-                    let (uid, gid, dev, ino) = (0u32, 0u32, 0u64, 0u64);
-
-                    if self.one_file_system && dev != self.root_dev {
-                        if file_type.is_dir() {
-                            self.iter.skip_current_dir();
-                        }
-                        continue;
-                    }
-
-                    let uid_idx = *self.uid_map.entry(uid).or_insert_with(|| {
-                        self.uid_table.push(uid);
-                        self.uid_table.len() - 1
-                    });
-                    let gid_idx = *self.gid_map.entry(gid).or_insert_with(|| {
-                        self.gid_table.push(gid);
-                        self.gid_table.len() - 1
-                    });
-                    let dev_idx = *self.dev_map.entry(dev).or_insert_with(|| {
-                        self.dev_table.push(dev);
-                        self.dev_table.len() - 1
-                    });
-                    let inode_idx = *self.inode_map.entry((dev_idx, ino)).or_insert_with(|| {
-                        self.inode_table.push(ino);
-                        self.inode_table.len() - 1
-                    });
-
-                    batch.push(Entry {
-                        prefix_len: prefix,
-                        suffix,
-                        file_type,
-                        uid: uid_idx,
-                        gid: gid_idx,
-                        dev: dev_idx,
-                        inode: inode_idx,
-                    });
-
-                    self.prev_path = path;
+                    self.make_entry(entry.path(), file_type)
                 }
                 Some(Err(err)) => {
-                    let msg = err.to_string();
-                    let io_err = match err.into_io_error() {
-                        Some(inner) => inner,
-                        None => std::io::Error::other(msg),
-                    };
-                    return Some(Err(io_err));
+                    let link = err
+                        .path()
+                        .filter(|_| self.follow_dirlinks)
+                        .and_then(|p| Some((p.to_path_buf(), std::fs::symlink_metadata(p).ok()?)))
+                        .filter(|(_, m)| m.file_type().is_symlink());
+                    match link {
+                        Some((path, meta)) => self.make_entry(&path, meta.file_type()),
+                        None => {
+                            let msg = err.to_string();
+                            let io_err = match err.into_io_error() {
+                                Some(inner) => inner,
+                                None => std::io::Error::other(msg),
+                            };
+                            return Some(Err(io_err));
+                        }
+                    }
                 }
                 None => break,
+            };
+            match made {
+                Some(Ok(entry)) => batch.push(entry),
+                Some(Err(e)) => return Some(Err(e)),
+                None => {}
             }
         }
 
@@ -188,6 +188,43 @@ fn walk_handles_symlink_loops() {
     assert_eq!(count, 3);
 }
 
+#[cfg(unix)]
+#[test]
+fn walk_follow_dirlinks_descends_into_directory_symlinks() {
+    let tmp = tempdir().unwrap();
+    let root = tmp.path();
+    fs::create_dir(root.join("dir")).unwrap();
+    fs::write(root.join("dir/file"), b"hi").unwrap();
+    symlink("dir", root.join("dirlink")).unwrap();
+    symlink("dir/file", root.join("filelink")).unwrap();
+    symlink("missing", root.join("dangling")).unwrap();
+    symlink(root, root.join("dir/loop")).unwrap();
+
+    let mut entries = Vec::new();
+    let mut state = String::new();
+    for batch in walk(root, 10, None, true, false, &[])
+        .unwrap()
+        .follow_dirlinks(true)
+    {
+        for e in batch.unwrap() {
+            let path = e.apply(&mut state);
+            entries.push((path.strip_prefix(root).unwrap().to_path_buf(), e.file_type));
+        }
+    }
+    let kind = |name: &str| {
+        entries
+            .iter()
+            .find(|(p, _)| p.as_path() == std::path::Path::new(name))
+            .map(|(_, t)| *t)
+            .unwrap_or_else(|| panic!("{name} missing from {entries:?}"))
+    };
+    assert!(kind("dirlink").is_dir());
+    assert!(kind("dirlink/file").is_file());
+    assert!(kind("filelink").is_symlink());
+    assert!(kind("dangling").is_symlink());
+    assert!(kind("dir/loop").is_symlink());
+}
+
 #[cfg(windows)]
 #[test]
 fn walk_normalizes_verbatim_paths() {
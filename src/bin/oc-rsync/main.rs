@@ -8,7 +8,9 @@ use protocol::ExitCode;
 
 fn main() {
     let args: Vec<_> = std::env::args_os().collect();
-    if oc_rsync_cli::print_version_if_requested(args.iter().cloned()) {
+    if oc_rsync_cli::print_version_if_requested(args.iter().cloned())
+        || oc_rsync_cli::print_iconv_charsets_if_requested(args.iter().cloned())
+    {
         return;
     }
     let mut cmd = cli_command();
@@ -95,7 +95,7 @@ impl std::error::Error for StdBufferError {}
 
 fn set_std_buffering_raw_impl<F>(
     mode: libc::c_int,
-    orig_mode: libc::c_int,
+    (out_orig, err_orig): (libc::c_int, libc::c_int),
     out: *mut libc::FILE,
     err: *mut libc::FILE,
     set_stream: F,
@@ -105,15 +105,15 @@ where
 {
     let out_res = set_stream(out, mode);
     if out_res.is_err() && !out.is_null() {
-        let _ = set_stream(out, orig_mode);
+        let _ = set_stream(out, out_orig);
     }
     let err_res = set_stream(err, mode);
     if err_res.is_err() {
         if !err.is_null() {
-            let _ = set_stream(err, orig_mode);
+            let _ = set_stream(err, err_orig);
         }
         if out_res.is_ok() && !out.is_null() {
-            let _ = set_stream(out, orig_mode);
+            let _ = set_stream(out, out_orig);
         }
     }
     match (out_res, err_res) {
@@ -129,27 +129,36 @@ where
 
 fn set_std_buffering_raw(
     mode: libc::c_int,
-    orig_mode: libc::c_int,
+    orig: (libc::c_int, libc::c_int),
     out: *mut libc::FILE,
     err: *mut libc::FILE,
 ) -> Result<(), StdBufferError> {
-    set_std_buffering_raw_impl(mode, orig_mode, out, err, set_stream_buffer)
+    set_std_buffering_raw_impl(mode, orig, out, err, set_stream_buffer)
 }
 
 static STDOUT_MODE: AtomicI32 = AtomicI32::new(libc::_IOLBF);
+static STDERR_MODE: AtomicI32 = AtomicI32::new(libc::_IONBF);
 
-pub fn set_std_buffering(mode: OutBuf) -> Result<(), StdBufferError> {
-    let mode = match mode {
+pub(crate) fn buffer_mode(mode: OutBuf) -> libc::c_int {
+    match mode {
         OutBuf::N => libc::_IONBF,
         OutBuf::L => libc::_IOLBF,
         OutBuf::B => libc::_IOFBF,
-    };
+    }
+}
+
+pub fn set_std_buffering(mode: OutBuf) -> Result<(), StdBufferError> {
+    let mode = buffer_mode(mode);
     let out = stdout_stream().map_err(StdBufferError::Stdout)?.as_ptr();
     let err = stderr_stream().map_err(StdBufferError::Stderr)?.as_ptr();
-    let orig = STDOUT_MODE.load(Ordering::SeqCst);
+    let orig = (
+        STDOUT_MODE.load(Ordering::SeqCst),
+        STDERR_MODE.load(Ordering::SeqCst),
+    );
     match set_std_buffering_raw(mode, orig, out, err) {
         Ok(()) => {
             STDOUT_MODE.store(mode, Ordering::SeqCst);
+            STDERR_MODE.store(mode, Ordering::SeqCst);
             Ok(())
         }
         Err(e) => Err(e),
@@ -167,7 +176,7 @@ pub(crate) fn set_std_buffering_for_test<F>(
 where
     F: Fn(*mut libc::FILE, libc::c_int) -> io::Result<()>,
 {
-    set_std_buffering_raw_impl(mode, orig_mode, out, err, set_stream)
+    set_std_buffering_raw_impl(mode, (orig_mode, orig_mode), out, err, set_stream)
 }
 
 #[cfg(test)]
@@ -243,6 +252,40 @@ mod tests {
         );
     }
 
+    #[test]
+    fn stderr_failure_restores_each_stream_mode() {
+        let mut out_stub = std::mem::MaybeUninit::<libc::FILE>::uninit();
+        let mut err_stub = std::mem::MaybeUninit::<libc::FILE>::uninit();
+        let out: *mut libc::FILE = out_stub.as_mut_ptr();
+        let err: *mut libc::FILE = err_stub.as_mut_ptr();
+        let calls = RefCell::new(Vec::new());
+        let set_stream = |stream: *mut libc::FILE, mode: libc::c_int| {
+            calls.borrow_mut().push((stream, mode));
+            if stream == err && mode == libc::_IOFBF {
+                Err(io::Error::other("stderr failure"))
+            } else {
+                Ok(())
+            }
+        };
+        let res = set_std_buffering_raw_impl(
+            libc::_IOFBF,
+            (libc::_IOLBF, libc::_IONBF),
+            out,
+            err,
+            set_stream,
+        );
+        assert!(matches!(res, Err(StdBufferError::Stderr(_))));
+        assert_eq!(
+            calls.into_inner(),
+            vec![
+                (out, libc::_IOFBF),
+                (err, libc::_IOFBF),
+                (err, libc::_IONBF),
+                (out, libc::_IOLBF),
+            ]
+        );
+    }
+
     #[test]
     fn both_failure() {
         let out: *mut libc::FILE = std::ptr::dangling_mut();
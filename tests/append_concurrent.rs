@@ -0,0 +1,52 @@
+// tests/append_concurrent.rs
+#![cfg(unix)]
+
+use assert_cmd::cargo::cargo_bin;
+use std::fs;
+use std::process::Command;
+use tempfile::tempdir;
+
+#[test]
+fn concurrent_appends_serialize() {
+    let dir = tempdir().unwrap();
+    let dst = dir.path().join("dst");
+    fs::create_dir_all(&dst).unwrap();
+    let header = b"header\n".to_vec();
+    fs::write(dst.join("log.txt"), &header).unwrap();
+
+    let mut expected = Vec::new();
+    let mut children = Vec::new();
+    for fill in [b'a', b'b'] {
+        let src = dir.path().join(format!("src_{}", fill as char));
+        fs::create_dir_all(&src).unwrap();
+        let mut data = header.clone();
+        data.extend(std::iter::repeat_n(fill, 64 * 1024));
+        fs::write(src.join("log.txt"), &data).unwrap();
+        expected.push(data);
+        children.push(
+            Command::new(cargo_bin("oc-rsync"))
+                .args([
+                    "--append",
+                    "--inplace",
+                    "--bwlimit",
+                    "65536",
+                    &format!("{}/", src.display()),
+                    dst.to_str().unwrap(),
+                ])
+                .spawn()
+                .unwrap(),
+        );
+    }
+    for mut child in children {
+        assert!(child.wait().unwrap().success());
+    }
+
+    let out = fs::read(dst.join("log.txt")).unwrap();
+    assert!(
+        expected.contains(&out),
+        "interleaved append: {} bytes, {} a, {} b",
+        out.len(),
+        out.iter().filter(|&&c| c == b'a').count(),
+        out.iter().filter(|&&c| c == b'b').count()
+    );
+}
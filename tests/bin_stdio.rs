@@ -3,9 +3,12 @@
 mod stdio;
 
 use oc_rsync_cli::options::OutBuf;
+use std::cell::RefCell;
 use std::mem;
 use std::ptr;
-use stdio::{StdBufferError, set_std_buffering, set_std_buffering_for_test, set_stream_buffer};
+use stdio::{
+    StdBufferError, buffer_mode, set_std_buffering, set_std_buffering_for_test, set_stream_buffer,
+};
 
 #[cfg(not(target_os = "windows"))]
 #[allow(unsafe_op_in_unsafe_fn)]
@@ -52,6 +55,31 @@ fn mode_changes_ok() {
     set_std_buffering(OutBuf::B).unwrap();
 }
 
+#[test]
+fn every_outbuf_mode_applies_to_stdout_and_stderr() {
+    for mode in [OutBuf::N, OutBuf::L, OutBuf::B] {
+        set_std_buffering(mode).unwrap();
+        let (out, err) = unsafe { (stdout_ptr(), stderr_ptr()) };
+        let calls = RefCell::new(Vec::new());
+        let res = set_std_buffering_for_test(
+            buffer_mode(mode),
+            libc::_IOLBF,
+            out,
+            err,
+            |stream: *mut libc::FILE, m: libc::c_int| {
+                calls.borrow_mut().push((stream, m));
+                set_stream_buffer(stream, m)
+            },
+        );
+        res.unwrap();
+        assert_eq!(
+            calls.into_inner(),
+            vec![(out, buffer_mode(mode)), (err, buffer_mode(mode))]
+        );
+    }
+    set_std_buffering(OutBuf::L).unwrap();
+}
+
 #[test]
 fn invalid_setvbuf_returns_error() {
     unsafe {
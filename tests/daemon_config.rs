@@ -2,6 +2,8 @@
 
 use assert_cmd::prelude::*;
 use daemon::{Handler, drop_privileges, handle_connection, load_config, parse_config};
+use engine::SyncOptions;
+use oc_rsync_cli::spawn_daemon_session;
 use protocol::LATEST_VERSION;
 use serial_test::serial;
 use std::collections::HashMap;
@@ -139,7 +141,7 @@ fn daemon_config_authentication() {
     let module = cfg.modules[0].clone();
     let mut modules = HashMap::new();
     modules.insert(module.name.clone(), module);
-    let handler: Arc<Handler> = Arc::new(|_, _| Ok(()));
+    let handler: Arc<Handler> = Arc::new(|_, _, _| Ok(()));
     let (uid, gid) = uid_gid();
     if drop_privileges(uid, gid).is_err() {
         eprintln!("skipping test: cannot drop privileges");
@@ -185,7 +187,7 @@ fn daemon_config_motd_suppression() {
     let module = cfg.modules[0].clone();
     let mut modules = HashMap::new();
     modules.insert(module.name.clone(), module);
-    let handler: Arc<Handler> = Arc::new(|_, _| Ok(()));
+    let handler: Arc<Handler> = Arc::new(|_, _, _| Ok(()));
     let (uid, gid) = uid_gid();
     if drop_privileges(uid, gid).is_err() {
         eprintln!("skipping test: cannot drop privileges");
@@ -294,7 +296,7 @@ fn daemon_config_module_secrets_file() {
     let module = cfg.modules[0].clone();
     let mut modules = HashMap::new();
     modules.insert(module.name.clone(), module);
-    let handler: Arc<Handler> = Arc::new(|_, _| Ok(()));
+    let handler: Arc<Handler> = Arc::new(|_, _, _| Ok(()));
     let (uid, gid) = uid_gid();
     if drop_privileges(uid, gid).is_err() {
         eprintln!("skipping test: cannot drop privileges");
@@ -422,6 +424,78 @@ fn daemon_config_write_only_module_rejects_reads() {
     assert!(n == 0 || msg.contains("write only") || msg.contains("@RSYNCD: EXIT"));
 }
 
+#[cfg(unix)]
+#[test]
+fn daemon_config_pre_xfer_exec_receives_early_input() {
+    let dir = tempfile::tempdir().unwrap();
+    let data = dir.path().join("data");
+    fs::create_dir(&data).unwrap();
+    let out = dir.path().join("hook");
+    let cfg = format!(
+        "port = 0\nuse chroot = no\n[data]\n    path = {}\n    pre-xfer exec = cat > {out}.stdin; echo \"$RSYNC_ARG0 $RSYNC_MODULE_NAME\" > {out}.env\n",
+        data.display(),
+        out = out.display()
+    );
+    let cfg = parse_config(&cfg).unwrap();
+    let module = cfg.modules[0].clone();
+    let mut modules = HashMap::new();
+    modules.insert(module.name.clone(), module);
+    let early = dir.path().join("early");
+    fs::write(&early, b"pre-auth payload\n").unwrap();
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let server = std::thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        let mut t = TcpTransport::from_stream(stream);
+        let handler: Arc<Handler> = Arc::new(|_, _, _| Ok(()));
+        let (uid, gid) = uid_gid();
+        handle_connection(
+            &mut t,
+            &modules,
+            None,
+            None,
+            None,
+            None,
+            None,
+            true,
+            &[],
+            "127.0.0.1",
+            uid,
+            gid,
+            &handler,
+            None,
+        )
+    });
+    let session = spawn_daemon_session(
+        "127.0.0.1",
+        "data",
+        Some(port),
+        None,
+        true,
+        None,
+        None,
+        None,
+        &[],
+        &SyncOptions::default(),
+        LATEST_VERSION,
+        Some(&early),
+        None,
+    )
+    .unwrap();
+    drop(session);
+    server.join().unwrap().unwrap();
+
+    assert_eq!(
+        fs::read(out.with_extension("stdin")).unwrap(),
+        b"pre-auth payload\n"
+    );
+    assert_eq!(
+        fs::read_to_string(out.with_extension("env")).unwrap(),
+        "rsyncd data\n"
+    );
+}
+
 #[test]
 fn parse_config_global_directives() {
     let cfg = parse_config(
@@ -443,6 +517,16 @@ fn parse_config_global_write_only() {
     assert_eq!(cfg.write_only, Some(true));
 }
 
+#[test]
+fn parse_config_module_write_only_overrides_global() {
+    let cfg = parse_config(
+        "write only = yes\n[data]\n    path = /tmp\n[open]\n    path = /tmp\n    write only = no\n",
+    )
+    .unwrap();
+    assert!(cfg.modules[0].write_only);
+    assert!(!cfg.modules[1].write_only);
+}
+
 #[test]
 fn parse_config_module_comment_and_write_only() {
     let cfg = parse_config("[data]\npath=/tmp\ncomment = test\nwrite only = yes\n").unwrap();
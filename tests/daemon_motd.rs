@@ -0,0 +1,72 @@
+// tests/daemon_motd.rs
+use assert_cmd::Command;
+use protocol::SUPPORTED_PROTOCOLS;
+use std::io::{Read, Write};
+use std::net::TcpListener;
+use std::thread;
+use std::time::Duration;
+
+fn mock_daemon(motd: &'static [u8]) -> (u16, thread::JoinHandle<()>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let handle = thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut buf = [0u8; 4];
+        stream.read_exact(&mut buf).unwrap();
+        stream
+            .write_all(&SUPPORTED_PROTOCOLS[0].to_be_bytes())
+            .unwrap();
+        let mut b = [0u8; 1];
+        loop {
+            stream.read_exact(&mut b).unwrap();
+            if b[0] == b'\n' {
+                break;
+            }
+        }
+        stream.write_all(motd).unwrap();
+        stream.write_all(b"@ERROR: unknown module\n").unwrap();
+    });
+    (port, handle)
+}
+
+fn mock_daemon_with_motd() -> (u16, thread::JoinHandle<()>) {
+    mock_daemon(b"Welcome to the mock daemon\n")
+}
+
+fn run_client(port: u16, extra: &[&str]) -> String {
+    let out = Command::cargo_bin("oc-rsync")
+        .unwrap()
+        .args(extra)
+        .arg(format!("rsync://127.0.0.1:{port}/mod/"))
+        .arg(format!("rsync://127.0.0.1:{port}/dst/"))
+        .timeout(Duration::from_secs(10))
+        .output()
+        .unwrap();
+    assert!(!out.status.success());
+    String::from_utf8_lossy(&out.stdout).into_owned()
+}
+
+#[test]
+fn daemon_motd_is_printed_by_default() {
+    let (port, handle) = mock_daemon_with_motd();
+    let stdout = run_client(port, &[]);
+    handle.join().unwrap();
+    assert!(stdout.contains("Welcome to the mock daemon"), "{stdout}");
+}
+
+#[test]
+fn no_motd_suppresses_daemon_motd() {
+    let (port, handle) = mock_daemon_with_motd();
+    let stdout = run_client(port, &["--no-motd"]);
+    handle.join().unwrap();
+    assert!(!stdout.contains("Welcome to the mock daemon"), "{stdout}");
+}
+
+#[test]
+fn motd_lines_resembling_a_greeting_are_printed() {
+    let (port, handle) = mock_daemon(b"@RSYNCD: 31.0\nWelcome\n@RSYNCD: 2024 maintenance\n");
+    let stdout = run_client(port, &[]);
+    handle.join().unwrap();
+    assert!(!stdout.contains("31.0"), "{stdout}");
+    assert!(stdout.contains("Welcome\n2024 maintenance"), "{stdout}");
+}
@@ -117,7 +117,7 @@ fn module_authentication_and_hosts_enforced() {
     };
     let mut modules = HashMap::new();
     modules.insert(module.name.clone(), module);
-    let handler: Arc<Handler> = Arc::new(|_, _| Ok(()));
+    let handler: Arc<Handler> = Arc::new(|_, _, _| Ok(()));
     let mut ok_t = pipe_transport("alice", "data");
     handle_connection(
         &mut ok_t,
@@ -188,7 +188,7 @@ fn host_deny_blocks_connection() {
     };
     let mut modules = HashMap::new();
     modules.insert(module.name.clone(), module);
-    let handler: Arc<Handler> = Arc::new(|_, _| Ok(()));
+    let handler: Arc<Handler> = Arc::new(|_, _, _| Ok(()));
     let mut t = pipe_transport("", "data");
     let err = handle_connection(
         &mut t,
@@ -230,7 +230,7 @@ fn daemon_refuses_configured_option() {
     };
     let mut modules = HashMap::new();
     modules.insert(module.name.clone(), module);
-    let handler: Arc<Handler> = Arc::new(|_, _| Ok(()));
+    let handler: Arc<Handler> = Arc::new(|_, _, _| Ok(()));
     let mut t = pipe_transport_opts("", "data", &["--delete"]);
     let err = handle_connection(
         &mut t,
@@ -263,7 +263,7 @@ fn daemon_refuses_numeric_ids_option() {
     };
     let mut modules = HashMap::new();
     modules.insert(module.name.clone(), module);
-    let handler: Arc<Handler> = Arc::new(|_, _| Ok(()));
+    let handler: Arc<Handler> = Arc::new(|_, _, _| Ok(()));
     let mut t = pipe_transport_opts("", "data", &["--numeric-ids"]);
     let err = handle_connection(
         &mut t,
@@ -297,7 +297,7 @@ fn daemon_refuses_no_numeric_ids_option() {
     };
     let mut modules = HashMap::new();
     modules.insert(module.name.clone(), module);
-    let handler: Arc<Handler> = Arc::new(|_, _| Ok(()));
+    let handler: Arc<Handler> = Arc::new(|_, _, _| Ok(()));
     let mut t = pipe_transport_opts("", "data", &["--no-numeric-ids"]);
     let err = handle_connection(
         &mut t,
@@ -335,7 +335,7 @@ fn rejects_missing_token() {
     };
     let mut modules = HashMap::new();
     modules.insert(module.name.clone(), module);
-    let handler: Arc<Handler> = Arc::new(|_, _| Ok(()));
+    let handler: Arc<Handler> = Arc::new(|_, _, _| Ok(()));
     let mut t = pipe_transport("", "data");
     let err = handle_connection(
         &mut t,
@@ -379,7 +379,7 @@ fn anonymous_module_listing_only_shows_listed_modules() {
         ..Module::default()
     };
     modules.insert("private".into(), private);
-    let handler: Arc<Handler> = Arc::new(|_, _| Ok(()));
+    let handler: Arc<Handler> = Arc::new(|_, _, _| Ok(()));
 
     handle_connection(
         &mut transport,
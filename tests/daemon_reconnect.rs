@@ -0,0 +1,132 @@
+// tests/daemon_reconnect.rs
+use assert_cmd::Command;
+use engine::SyncOptions;
+use oc_rsync_cli::{reconnect_daemon_session, spawn_daemon_session};
+use protocol::SUPPORTED_PROTOCOLS;
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::thread;
+use tempfile::tempdir;
+
+fn greet(stream: &mut TcpStream) {
+    let mut buf = [0u8; 4];
+    stream.read_exact(&mut buf).unwrap();
+    stream
+        .write_all(&SUPPORTED_PROTOCOLS[0].to_be_bytes())
+        .unwrap();
+    let mut b = [0u8; 1];
+    loop {
+        stream.read_exact(&mut b).unwrap();
+        if b[0] == b'\n' {
+            break;
+        }
+    }
+}
+
+fn mock_daemon(rejections: usize, connections: usize) -> (u16, thread::JoinHandle<usize>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let handle = thread::spawn(move || {
+        let mut accepted = 0;
+        for (i, stream) in listener.incoming().take(connections).enumerate() {
+            let mut stream = stream.unwrap();
+            accepted += 1;
+            greet(&mut stream);
+            if i < rejections {
+                stream
+                    .write_all(b"@ERROR: max connections reached\n")
+                    .unwrap();
+                continue;
+            }
+            stream.write_all(b"@RSYNCD: OK\n").unwrap();
+            let mut b = [0u8; 1];
+            let mut line = Vec::new();
+            loop {
+                stream.read_exact(&mut b).unwrap();
+                if b[0] != b'\n' {
+                    line.push(b[0]);
+                    continue;
+                }
+                if line.is_empty() {
+                    break;
+                }
+                line.clear();
+            }
+        }
+        accepted
+    });
+    (port, handle)
+}
+
+fn connect(port: u16) -> engine::Result<transport::TcpTransport> {
+    spawn_daemon_session(
+        "127.0.0.1",
+        "mod",
+        Some(port),
+        None,
+        true,
+        None,
+        None,
+        None,
+        &[],
+        &SyncOptions::default(),
+        SUPPORTED_PROTOCOLS[0],
+        None,
+        None,
+    )
+}
+
+#[test]
+fn reconnects_after_transient_rejection() {
+    let (port, handle) = mock_daemon(1, 2);
+    reconnect_daemon_session(2, || connect(port)).unwrap();
+    assert_eq!(handle.join().unwrap(), 2);
+}
+
+#[test]
+fn fails_without_retries() {
+    let (port, handle) = mock_daemon(1, 1);
+    let Err(err) = reconnect_daemon_session(0, || connect(port)) else {
+        panic!("connected without retrying");
+    };
+    assert!(err.to_string().contains("max connections"), "{err}");
+    assert_eq!(handle.join().unwrap(), 1);
+}
+
+#[test]
+fn does_not_retry_rejected_session() {
+    for reply in [&b"@ERROR: auth failed on module mod\n"[..], b""] {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            greet(&mut stream);
+            stream.write_all(reply).unwrap();
+            listener
+        });
+        assert!(reconnect_daemon_session(2, || connect(port)).is_err());
+        let listener = server.join().unwrap();
+        listener.set_nonblocking(true).unwrap();
+        assert!(listener.accept().is_err());
+    }
+}
+
+#[test]
+fn client_push_retries_daemon_handshake() {
+    let tmp = tempdir().unwrap();
+    let src = tmp.path().join("src");
+    std::fs::create_dir_all(&src).unwrap();
+    std::fs::write(src.join("file"), b"data").unwrap();
+    let (port, handle) = mock_daemon(1, 2);
+    Command::cargo_bin("oc-rsync")
+        .unwrap()
+        .current_dir(&tmp)
+        .args([
+            "--daemon-retries=1",
+            &format!("{}/", src.display()),
+            &format!("rsync://127.0.0.1:{port}/mod/"),
+        ])
+        .assert()
+        .success();
+    assert_eq!(handle.join().unwrap(), 2);
+}
@@ -0,0 +1,151 @@
+// tests/daemon_timeout.rs
+
+use assert_cmd::prelude::*;
+use protocol::LATEST_VERSION;
+use serial_test::serial;
+use std::fs;
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::process::{Child, Command as StdCommand, Stdio};
+use std::thread::sleep;
+use std::time::{Duration, Instant};
+mod common;
+use common::daemon::DaemonGuard;
+
+fn read_port(child: &mut Child) -> u16 {
+    let stdout = child.stdout.as_mut().unwrap();
+    let mut buf = Vec::new();
+    let mut byte = [0u8; 1];
+    while stdout.read(&mut byte).unwrap() == 1 {
+        if byte[0] == b'\n' {
+            break;
+        }
+        buf.push(byte[0]);
+    }
+    String::from_utf8(buf).unwrap().trim().parse().unwrap()
+}
+
+#[test]
+#[serial]
+fn daemon_disconnects_idle_client_after_timeout() {
+    let dir = tempfile::tempdir().unwrap();
+    let data = dir.path().join("data");
+    fs::create_dir(&data).unwrap();
+    let log = dir.path().join("rsyncd.log");
+    let cfg_path = dir.path().join("rsyncd.conf");
+    fs::write(
+        &cfg_path,
+        format!(
+            "port = 0\ntimeout = 1\nlog file = {}\n[data]\n    path = {}\n",
+            log.display(),
+            data.display()
+        ),
+    )
+    .unwrap();
+    let mut cmd = StdCommand::cargo_bin("oc-rsync").unwrap();
+    cmd.args([
+        "--daemon",
+        "--config",
+        cfg_path.to_str().unwrap(),
+        "--no-detach",
+    ])
+    .stdout(Stdio::piped())
+    .stderr(Stdio::null());
+    let mut child = DaemonGuard::spawn(cmd);
+    let port = read_port(&mut child);
+
+    let mut idle = TcpStream::connect(("127.0.0.1", port)).unwrap();
+    idle.set_read_timeout(Some(Duration::from_secs(10)))
+        .unwrap();
+    let start = Instant::now();
+    let mut resp = Vec::new();
+    idle.read_to_end(&mut resp).unwrap();
+    let elapsed = start.elapsed();
+    assert!(elapsed >= Duration::from_millis(900), "{elapsed:?}");
+    assert!(elapsed < Duration::from_secs(5), "{elapsed:?}");
+    assert!(String::from_utf8_lossy(&resp).contains("@ERROR: timeout"));
+
+    let mut next = TcpStream::connect(("127.0.0.1", port)).unwrap();
+    next.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+    next.write_all(&LATEST_VERSION.to_be_bytes()).unwrap();
+    let mut ver = [0u8; 4];
+    next.read_exact(&mut ver).unwrap();
+    assert!(child.try_wait().unwrap().is_none());
+
+    let mut logged = String::new();
+    for _ in 0..20 {
+        logged = fs::read_to_string(&log).unwrap_or_default();
+        if logged.contains("disconnecting idle client") {
+            break;
+        }
+        sleep(Duration::from_millis(50));
+    }
+    assert!(logged.contains("disconnecting idle client"), "{logged}");
+}
+
+#[test]
+#[serial]
+fn daemon_keeps_accepting_after_failed_connection() {
+    let dir = tempfile::tempdir().unwrap();
+    let data = dir.path().join("data");
+    fs::create_dir(&data).unwrap();
+    let log = dir.path().join("rsyncd.log");
+    let cfg_path = dir.path().join("rsyncd.conf");
+    fs::write(
+        &cfg_path,
+        format!(
+            "port = 0\nlog file = {}\n[data]\n    path = {}\n",
+            log.display(),
+            data.display()
+        ),
+    )
+    .unwrap();
+    let mut cmd = StdCommand::cargo_bin("oc-rsync").unwrap();
+    cmd.args([
+        "--daemon",
+        "--config",
+        cfg_path.to_str().unwrap(),
+        "--no-detach",
+    ])
+    .stdout(Stdio::piped())
+    .stderr(Stdio::null());
+    let mut child = DaemonGuard::spawn(cmd);
+    let port = read_port(&mut child);
+
+    let mut bad = TcpStream::connect(("127.0.0.1", port)).unwrap();
+    bad.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+    bad.write_all(&LATEST_VERSION.to_be_bytes()).unwrap();
+    let mut ver = [0u8; 4];
+    bad.read_exact(&mut ver).unwrap();
+    bad.write_all(b"\n").unwrap();
+    let mut ok = Vec::new();
+    let mut byte = [0u8; 1];
+    while !ok.ends_with(b"@RSYNCD: OK\n") {
+        bad.read_exact(&mut byte).unwrap();
+        ok.push(byte[0]);
+    }
+    bad.write_all(b"missing\n").unwrap();
+    let mut resp = Vec::new();
+    let _ = bad.read_to_end(&mut resp);
+    assert!(
+        String::from_utf8_lossy(&resp).contains("@ERROR: unknown module"),
+        "{}",
+        String::from_utf8_lossy(&resp)
+    );
+
+    let mut next = TcpStream::connect(("127.0.0.1", port)).unwrap();
+    next.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+    next.write_all(&LATEST_VERSION.to_be_bytes()).unwrap();
+    next.read_exact(&mut ver).unwrap();
+    assert!(child.try_wait().unwrap().is_none());
+
+    let mut logged = String::new();
+    for _ in 0..20 {
+        logged = fs::read_to_string(&log).unwrap_or_default();
+        if logged.contains("unknown module") {
+            break;
+        }
+        sleep(Duration::from_millis(50));
+    }
+    assert!(logged.contains("unknown module"), "{logged}");
+}
@@ -52,6 +52,7 @@ fn daemon_preserves_xattrs() {
     let src_arg = format!("{}/", src.display());
     Command::cargo_bin("oc-rsync")
         .unwrap()
+        .current_dir(&tmp)
         .args(["-AX", &src_arg, &format!("rsync://127.0.0.1:{port}/mod")])
         .assert()
         .success();
@@ -97,6 +98,7 @@ fn daemon_preserves_symlink_xattrs_rr_client() {
     let src_arg = format!("{}/", src.display());
     Command::cargo_bin("oc-rsync")
         .unwrap()
+        .current_dir(&tmp)
         .args([
             "--links",
             "--xattrs",
@@ -139,6 +141,7 @@ fn daemon_preserves_xattrs_rr_client() {
     let src_arg = format!("{}/", src.display());
     Command::cargo_bin("oc-rsync")
         .unwrap()
+        .current_dir(&tmp)
         .args([
             "--xattrs",
             &src_arg,
@@ -194,6 +197,7 @@ fn daemon_preserves_xattrs_rr_daemon() {
     let src_arg = format!("{}/", src.display());
     Command::cargo_bin("oc-rsync")
         .unwrap()
+        .current_dir(&tmp)
         .args([
             "--xattrs",
             &src_arg,
@@ -254,6 +258,7 @@ fn daemon_excludes_filtered_xattrs() {
     let src_arg = format!("{}/", src.display());
     Command::cargo_bin("oc-rsync")
         .unwrap()
+        .current_dir(&tmp)
         .args([
             "-AX",
             "--filter=-x user.secret",
@@ -281,6 +286,7 @@ fn daemon_excludes_filtered_xattrs() {
     xattr::set(&file, "user.test", b"val2").unwrap();
     Command::cargo_bin("oc-rsync")
         .unwrap()
+        .current_dir(&tmp)
         .args([
             "-AX",
             "--filter=-x user.secret",
@@ -325,6 +331,7 @@ fn daemon_excludes_filtered_xattrs_rr_client() {
     let src_arg = format!("{}/", src.display());
     Command::cargo_bin("oc-rsync")
         .unwrap()
+        .current_dir(&tmp)
         .args([
             "--xattrs",
             "--filter=-x user.secret",
@@ -350,6 +357,7 @@ fn daemon_excludes_filtered_xattrs_rr_client() {
     xattr::set(&file, "user.test", b"val2").unwrap();
     Command::cargo_bin("oc-rsync")
         .unwrap()
+        .current_dir(&tmp)
         .args([
             "--xattrs",
             "--filter=-x user.secret",
@@ -389,6 +397,7 @@ fn daemon_xattrs_match_rsync_server() {
     let src_arg = format!("{}/", src.display());
     Command::cargo_bin("oc-rsync")
         .unwrap()
+        .current_dir(&tmp)
         .args(["-aX", &src_arg, &format!("rsync://127.0.0.1:{port_oc}/mod")])
         .assert()
         .success();
@@ -397,6 +406,7 @@ fn daemon_xattrs_match_rsync_server() {
     wait_for_daemon(&mut daemon_rs);
     Command::cargo_bin("oc-rsync")
         .unwrap()
+        .current_dir(&tmp)
         .args(["-aX", &src_arg, &format!("rsync://127.0.0.1:{port_rs}/mod")])
         .assert()
         .success();
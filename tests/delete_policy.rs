@@ -40,6 +40,43 @@ fn max_delete_aborts_after_limit() {
     assert_eq!(remaining, 1);
 }
 
+#[test]
+fn max_delete_skips_remaining_and_exits_25() {
+    let dir = tempdir().unwrap();
+    let src = dir.path().join("src");
+    let dst = dir.path().join("dst");
+    fs::create_dir_all(&src).unwrap();
+    fs::create_dir_all(&dst).unwrap();
+    fs::write(src.join("keep.txt"), b"keep").unwrap();
+    fs::write(src.join("new.txt"), b"new").unwrap();
+    for name in ["old1.txt", "old2.txt", "old3.txt", "old4.txt"] {
+        fs::write(dst.join(name), b"old").unwrap();
+    }
+
+    let src_arg = format!("{}/", src.display());
+    Command::cargo_bin("oc-rsync")
+        .unwrap()
+        .args([
+            "--recursive",
+            "--delete-before",
+            "--max-delete=2",
+            &src_arg,
+            dst.to_str().unwrap(),
+        ])
+        .assert()
+        .code(25)
+        .stderr(predicates::str::contains(
+            "Deletions stopped due to --max-delete limit (2 skipped)",
+        ));
+
+    let remaining = ["old1.txt", "old2.txt", "old3.txt", "old4.txt"]
+        .iter()
+        .filter(|name| dst.join(name).exists())
+        .count();
+    assert_eq!(remaining, 2);
+    assert_eq!(fs::read(dst.join("new.txt")).unwrap(), b"new");
+}
+
 #[test]
 fn max_delete_allows_within_limit() {
     let dir = tempdir().unwrap();
@@ -73,6 +110,7 @@ fn delete_missing_args_removes_destination() {
     let dir = tempdir().unwrap();
     let dst = dir.path().join("orphan.txt");
     fs::write(&dst, b"old").unwrap();
+    fs::write(dir.path().join("sibling.txt"), b"keep").unwrap();
 
     Command::cargo_bin("oc-rsync")
         .unwrap()
@@ -85,6 +123,29 @@ fn delete_missing_args_removes_destination() {
         .success();
 
     assert!(!dst.exists());
+    assert!(dir.path().join("sibling.txt").exists());
+}
+
+#[test]
+fn delete_missing_args_removes_matching_entry_in_destination_dir() {
+    let dir = tempdir().unwrap();
+    let dst = dir.path().join("dst");
+    fs::create_dir_all(&dst).unwrap();
+    fs::write(dst.join("missing.txt"), b"old").unwrap();
+    fs::write(dst.join("keep.txt"), b"keep").unwrap();
+
+    Command::cargo_bin("oc-rsync")
+        .unwrap()
+        .args([
+            "--delete-missing-args",
+            dir.path().join("missing.txt").to_str().unwrap(),
+            &format!("{}/", dst.display()),
+        ])
+        .assert()
+        .success();
+
+    assert!(!dst.join("missing.txt").exists());
+    assert!(dst.join("keep.txt").exists());
 }
 
 #[test]
@@ -179,3 +240,49 @@ fn force_deletes_non_empty_dirs() {
 
     assert!(!dst.join("sub").exists());
 }
+
+#[test]
+fn delete_excluded_removes_protected_destination_files() {
+    let dir = tempdir().unwrap();
+    let src = dir.path().join("src");
+    let dst = dir.path().join("dst");
+    fs::create_dir_all(&src).unwrap();
+    fs::create_dir_all(&dst).unwrap();
+    fs::write(src.join("keep.txt"), b"keep").unwrap();
+    fs::write(src.join("both.log"), b"src").unwrap();
+    fs::write(dst.join("both.log"), b"dst").unwrap();
+    fs::write(dst.join("only.log"), b"dst").unwrap();
+    fs::write(dst.join("stale.txt"), b"old").unwrap();
+
+    let src_arg = format!("{}/", src.display());
+    Command::cargo_bin("oc-rsync")
+        .unwrap()
+        .args([
+            "--recursive",
+            "--delete",
+            "--exclude=*.log",
+            &src_arg,
+            dst.to_str().unwrap(),
+        ])
+        .assert()
+        .success();
+    assert!(!dst.join("stale.txt").exists());
+    assert_eq!(fs::read(dst.join("both.log")).unwrap(), b"dst");
+    assert!(dst.join("only.log").exists());
+
+    Command::cargo_bin("oc-rsync")
+        .unwrap()
+        .args([
+            "--recursive",
+            "--delete",
+            "--delete-excluded",
+            "--exclude=*.log",
+            &src_arg,
+            dst.to_str().unwrap(),
+        ])
+        .assert()
+        .success();
+    assert!(!dst.join("both.log").exists());
+    assert!(!dst.join("only.log").exists());
+    assert_eq!(fs::read(dst.join("keep.txt")).unwrap(), b"keep");
+}
@@ -37,3 +37,38 @@ fn fake_super_stores_xattrs() {
     assert!(xattr::get(&dst_file, "user.rsync.gid").unwrap().is_some());
     assert!(xattr::get(&dst_file, "user.rsync.mode").unwrap().is_some());
 }
+
+#[cfg(all(unix, feature = "xattr"))]
+#[test]
+fn fake_super_records_chown_owner() {
+    let tmp = tempdir().unwrap();
+    let src_dir = tmp.path().join("src");
+    let dst_dir = tmp.path().join("dst");
+    fs::create_dir_all(&src_dir).unwrap();
+    fs::create_dir_all(&dst_dir).unwrap();
+    fs::write(src_dir.join("file"), b"hi").unwrap();
+    if xattr::set(&dst_dir, "user.probe", b"1").is_err() {
+        eprintln!("skipping test: user xattrs unsupported");
+        return;
+    }
+    Command::cargo_bin("oc-rsync")
+        .unwrap()
+        .args([
+            "-a",
+            "--fake-super",
+            "--chown=1234:5678",
+            &format!("{}/", src_dir.display()),
+            dst_dir.to_str().unwrap(),
+        ])
+        .assert()
+        .success();
+    let dst_file = dst_dir.join("file");
+    assert_eq!(
+        xattr::get(&dst_file, "user.rsync.uid").unwrap(),
+        Some(b"1234".to_vec())
+    );
+    assert_eq!(
+        xattr::get(&dst_file, "user.rsync.gid").unwrap(),
+        Some(b"5678".to_vec())
+    );
+}
@@ -0,0 +1,41 @@
+// tests/file_errors.rs
+
+use assert_cmd::Command;
+use predicates::str::contains;
+use std::fs;
+use tempfile::tempdir;
+
+#[test]
+fn failed_file_is_reported_and_transfer_is_partial() {
+    let dir = tempdir().unwrap();
+    let src = dir.path().join("src");
+    let dst = dir.path().join("dst");
+    fs::create_dir_all(&src).unwrap();
+    fs::create_dir_all(&dst).unwrap();
+    for name in ["a.txt", "b.txt", "c.txt"] {
+        fs::write(src.join(name), name).unwrap();
+    }
+    fs::write(dst.join("b.txt"), b"old").unwrap();
+    // Backing up the existing b.txt fails, the new files need no backup.
+    let blocker = dir.path().join("blocker");
+    fs::write(&blocker, b"").unwrap();
+
+    let src_arg = format!("{}/", src.display());
+    Command::cargo_bin("oc-rsync")
+        .unwrap()
+        .args([
+            "-r",
+            "--ignore-errors",
+            "--backup",
+            &format!("--backup-dir={}", blocker.join("bak").display()),
+            &src_arg,
+            dst.to_str().unwrap(),
+        ])
+        .assert()
+        .code(23)
+        .stderr(contains("b.txt"));
+
+    assert_eq!(fs::read(dst.join("a.txt")).unwrap(), b"a.txt");
+    assert_eq!(fs::read(dst.join("c.txt")).unwrap(), b"c.txt");
+    assert_eq!(fs::read(dst.join("b.txt")).unwrap(), b"old");
+}
@@ -0,0 +1,46 @@
+// tests/list_only.rs
+
+use assert_cmd::Command;
+use filetime::{FileTime, set_file_mtime};
+use std::fs;
+#[cfg(unix)]
+use std::os::unix::fs::PermissionsExt;
+use tempfile::tempdir;
+
+#[cfg(unix)]
+#[test]
+fn list_only_matches_rsync_columns() {
+    let dir = tempdir().unwrap();
+    let src = dir.path().join("src");
+    fs::create_dir_all(src.join("sub")).unwrap();
+    let file = src.join("file.txt");
+    fs::write(&file, b"data").unwrap();
+    fs::set_permissions(&file, fs::Permissions::from_mode(0o644)).unwrap();
+    fs::set_permissions(src.join("sub"), fs::Permissions::from_mode(0o755)).unwrap();
+    set_file_mtime(&file, FileTime::from_unix_time(1_234_567_890, 0)).unwrap();
+    set_file_mtime(src.join("sub"), FileTime::from_unix_time(1_234_567_890, 0)).unwrap();
+
+    let src_arg = format!("{}/", src.display());
+    let output = Command::cargo_bin("oc-rsync")
+        .unwrap()
+        .env("TZ", "UTC")
+        .args([
+            "-r",
+            "--list-only",
+            &src_arg,
+            dir.path().join("dst").to_str().unwrap(),
+        ])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert!(lines.contains(&"-rw-r--r--              4 2009/02/13 23:31:30 file.txt"));
+    let sub = lines
+        .iter()
+        .find(|l| l.ends_with(" sub"))
+        .expect("sub listed");
+    assert!(sub.starts_with("drwxr-xr-x "));
+    assert!(sub.contains(" 2009/02/13 23:31:30 sub"));
+    assert!(!dir.path().join("dst").exists());
+}
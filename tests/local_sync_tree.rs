@@ -165,6 +165,37 @@ fn sync_keep_dirlinks_preserves_symlinked_dir() {
     assert!(dst.join("sub/file").exists());
 }
 
+#[cfg(unix)]
+#[test]
+fn sync_keep_dirlinks_only_keeps_links_to_directories() {
+    let tmp = tempdir().unwrap();
+    let src = tmp.path().join("src");
+    let dst = tmp.path().join("dst");
+    fs::create_dir_all(src.join("dir")).unwrap();
+    fs::create_dir_all(src.join("notdir")).unwrap();
+    fs::write(src.join("dir/file"), b"data").unwrap();
+    fs::write(src.join("notdir/file"), b"more").unwrap();
+    fs::create_dir_all(dst.join("real")).unwrap();
+    fs::write(dst.join("real/old"), b"old").unwrap();
+    fs::write(dst.join("plain"), b"plain").unwrap();
+    symlink("real", dst.join("dir")).unwrap();
+    symlink("plain", dst.join("notdir")).unwrap();
+
+    let src_arg = format!("{}/", src.display());
+    oc_rsync()
+        .args(["--keep-dirlinks", &src_arg, dst.to_str().unwrap()])
+        .assert()
+        .success();
+
+    assert_eq!(fs::read_link(dst.join("dir")).unwrap(), Path::new("real"));
+    assert_eq!(fs::read(dst.join("real/file")).unwrap(), b"data");
+    assert_eq!(fs::read(dst.join("real/old")).unwrap(), b"old");
+    let meta = fs::symlink_metadata(dst.join("notdir")).unwrap();
+    assert!(meta.file_type().is_dir());
+    assert_eq!(fs::read(dst.join("notdir/file")).unwrap(), b"more");
+    assert_eq!(fs::read(dst.join("plain")).unwrap(), b"plain");
+}
+
 #[cfg(unix)]
 #[test]
 fn sync_preserves_xattrs() {
@@ -26,7 +26,6 @@ use users::{get_current_gid, get_current_uid, get_group_by_gid, get_user_by_uid}
 mod common;
 use common::read_golden;
 
-#[allow(clippy::vec_init_then_push)]
 #[allow(clippy::vec_init_then_push)]
 #[test]
 fn prints_version() {
@@ -154,6 +153,87 @@ fn iconv_option_sent_to_daemon() {
     handle.join().unwrap();
 }
 
+#[test]
+fn protocol_flag_forces_handshake_version() {
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::thread;
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let handle = thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut buf = [0u8; 4];
+        stream.read_exact(&mut buf).unwrap();
+        assert_eq!(u32::from_be_bytes(buf), 30);
+        stream
+            .write_all(&SUPPORTED_PROTOCOLS[0].to_be_bytes())
+            .unwrap();
+        let mut b = [0u8; 1];
+        loop {
+            stream.read_exact(&mut b).unwrap();
+            if b[0] == b'\n' {
+                break;
+            }
+        }
+        stream.write_all(b"@RSYNCD: OK\n").unwrap();
+        let mut line = Vec::new();
+        loop {
+            line.clear();
+            loop {
+                stream.read_exact(&mut b).unwrap();
+                if b[0] == b'\n' {
+                    break;
+                }
+                line.push(b[0]);
+            }
+            if line.is_empty() {
+                break;
+            }
+        }
+    });
+
+    let sync_opts = SyncOptions {
+        protocol: Some(30),
+        ..SyncOptions::default()
+    };
+    spawn_daemon_session(
+        "127.0.0.1",
+        "mod",
+        Some(port),
+        None,
+        true,
+        None,
+        None,
+        None,
+        &[],
+        &sync_opts,
+        30,
+        None,
+        None,
+    )
+    .unwrap();
+    handle.join().unwrap();
+}
+
+#[test]
+fn protocol_flag_rejects_unsupported_version() {
+    let dir = tempdir().unwrap();
+    let src = dir.path().join("src");
+    fs::create_dir_all(&src).unwrap();
+    Command::cargo_bin("oc-rsync")
+        .unwrap()
+        .args([
+            "--protocol=29",
+            src.to_str().unwrap(),
+            dir.path().join("dst").to_str().unwrap(),
+        ])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("--protocol=29"));
+    assert!(!dir.path().join("dst").exists());
+}
+
 #[test]
 fn iconv_transcodes_filenames() {
     let spec = "utf8,latin1";
@@ -165,6 +245,74 @@ fn iconv_transcodes_filenames() {
     assert_eq!(roundtrip, remote);
 }
 
+#[test]
+fn iconv_spec_is_validated_at_parse_time() {
+    let dir = tempdir().unwrap();
+    let src_dir = dir.path().join("src");
+    let dst_dir = dir.path().join("dst");
+    std::fs::create_dir_all(&src_dir).unwrap();
+    std::fs::create_dir_all(&dst_dir).unwrap();
+    std::fs::write(src_dir.join("a.txt"), b"data").unwrap();
+    let src_arg = format!("{}/", src_dir.display());
+    Command::cargo_bin("oc-rsync")
+        .unwrap()
+        .args(["--iconv=utf-8,BOGUS", &src_arg, dst_dir.to_str().unwrap()])
+        .assert()
+        .failure()
+        .code(1)
+        .stderr(predicates::str::contains(
+            "iconv_open(\"utf-8\", \"BOGUS\") failed",
+        ));
+    Command::cargo_bin("oc-rsync")
+        .unwrap()
+        .args(["--iconv=utf-8,latin1", &src_arg, dst_dir.to_str().unwrap()])
+        .assert()
+        .success();
+    assert_eq!(std::fs::read(dst_dir.join("a.txt")).unwrap(), b"data");
+}
+
+#[test]
+fn normalize_names_matches_decomposed_source_to_composed_destination() {
+    let dir = tempdir().unwrap();
+    let src_dir = dir.path().join("src");
+    let dst_dir = dir.path().join("dst");
+    std::fs::create_dir_all(&src_dir).unwrap();
+    std::fs::create_dir_all(&dst_dir).unwrap();
+    let nfd = "cafe\u{301}";
+    let nfc = "caf\u{e9}";
+    std::fs::write(src_dir.join(nfd), b"new data").unwrap();
+    std::fs::write(dst_dir.join(nfc), b"old").unwrap();
+    let src_arg = format!("{}/", src_dir.display());
+    Command::cargo_bin("oc-rsync")
+        .unwrap()
+        .args([
+            "-r",
+            "--delete",
+            "--normalize-names=nfc",
+            &src_arg,
+            dst_dir.to_str().unwrap(),
+        ])
+        .assert()
+        .success();
+    assert_eq!(std::fs::read(dst_dir.join(nfc)).unwrap(), b"new data");
+    let names: Vec<_> = std::fs::read_dir(&dst_dir)
+        .unwrap()
+        .map(|e| e.unwrap().file_name())
+        .collect();
+    assert_eq!(names, [std::ffi::OsString::from(nfc)]);
+}
+
+#[test]
+fn iconv_question_mark_lists_charsets() {
+    Command::cargo_bin("oc-rsync")
+        .unwrap()
+        .arg("--iconv=?")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("UTF-8\n"))
+        .stdout(predicates::str::contains("windows-1252\n"));
+}
+
 #[test]
 fn client_local_sync() {
     let dir = tempdir().unwrap();
@@ -380,3 +528,33 @@ fn resumes_from_partial_dir_with_absolute_path() {
     assert_eq!(out, b"hello");
     assert!(!partial_dir.join("a.txt").exists());
 }
+
+#[cfg(unix)]
+#[test]
+fn unreadable_source_file_yields_partial_exit() {
+    let dir = tempdir().unwrap();
+    let src = dir.path().join("src");
+    let dst = dir.path().join("dst");
+    fs::create_dir_all(&src).unwrap();
+    fs::create_dir_all(&dst).unwrap();
+    fs::write(src.join("a.txt"), b"a").unwrap();
+    fs::write(src.join("b.txt"), b"b").unwrap();
+    fs::write(src.join("secret.txt"), b"secret").unwrap();
+    fs::set_permissions(src.join("secret.txt"), fs::Permissions::from_mode(0o000)).unwrap();
+    if fs::File::open(src.join("secret.txt")).is_ok() {
+        println!("skipping: file permissions are not enforced for this user");
+        return;
+    }
+
+    let src_arg = format!("{}/", src.display());
+    Command::cargo_bin("oc-rsync")
+        .unwrap()
+        .args(["-r", "--ignore-errors", &src_arg, dst.to_str().unwrap()])
+        .assert()
+        .code(23);
+
+    fs::set_permissions(src.join("secret.txt"), fs::Permissions::from_mode(0o644)).unwrap();
+    assert_eq!(fs::read(dst.join("a.txt")).unwrap(), b"a");
+    assert_eq!(fs::read(dst.join("b.txt")).unwrap(), b"b");
+    assert!(!dst.join("secret.txt").exists());
+}
@@ -26,7 +26,6 @@ use users::{get_current_gid, get_current_uid, get_group_by_gid, get_user_by_uid}
 mod common;
 use common::read_golden;
 
-#[allow(clippy::vec_init_then_push)]
 #[allow(clippy::vec_init_then_push)]
 #[test]
 fn resumes_from_partial_file() {
@@ -140,7 +139,7 @@ fn invalid_compress_level_fails() {
 fn out_of_range_compress_level_fails() {
     Command::cargo_bin("oc-rsync")
         .unwrap()
-        .args(["--compress-level", "10"])
+        .args(["--compress-level", "23"])
         .assert()
         .failure();
 }
@@ -26,7 +26,6 @@ use users::{get_current_gid, get_current_uid, get_group_by_gid, get_user_by_uid}
 mod common;
 use common::read_golden;
 
-#[allow(clippy::vec_init_then_push)]
 #[allow(clippy::vec_init_then_push)]
 #[test]
 fn sparse_files_created() {
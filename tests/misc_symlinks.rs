@@ -26,7 +26,6 @@ use users::{get_current_gid, get_current_uid, get_group_by_gid, get_user_by_uid}
 mod common;
 use common::read_golden;
 
-#[allow(clippy::vec_init_then_push)]
 #[allow(clippy::vec_init_then_push)]
 #[test]
 fn links_preserve_directory_symlinks() {
@@ -82,6 +81,47 @@ fn copy_dirlinks_transforms_directory_symlinks() {
     assert!(meta.file_type().is_symlink());
 }
 
+#[cfg(unix)]
+#[test]
+fn copy_dirlinks_copies_directory_contents() {
+    let dir = tempdir().unwrap();
+    let src = dir.path().join("src");
+    let dst = dir.path().join("dst");
+    let outside = dir.path().join("outside");
+    std::fs::create_dir_all(outside.join("nested")).unwrap();
+    std::fs::write(outside.join("nested/file"), b"hi").unwrap();
+    std::fs::create_dir_all(&src).unwrap();
+    std::fs::create_dir_all(&dst).unwrap();
+    std::fs::write(src.join("file"), b"data").unwrap();
+    symlink(&outside, src.join("dirlink")).unwrap();
+    symlink("file", src.join("filelink")).unwrap();
+
+    let src_arg = format!("{}/", src.display());
+    Command::cargo_bin("oc-rsync")
+        .unwrap()
+        .args([
+            "--links",
+            "--copy-dirlinks",
+            &src_arg,
+            dst.to_str().unwrap(),
+        ])
+        .assert()
+        .success();
+
+    let meta = std::fs::symlink_metadata(dst.join("dirlink")).unwrap();
+    assert!(meta.file_type().is_dir());
+    assert_eq!(
+        std::fs::read(dst.join("dirlink/nested/file")).unwrap(),
+        b"hi"
+    );
+    let meta = std::fs::symlink_metadata(dst.join("filelink")).unwrap();
+    assert!(meta.file_type().is_symlink());
+    assert_eq!(
+        std::fs::read_link(dst.join("filelink")).unwrap(),
+        PathBuf::from("file")
+    );
+}
+
 #[cfg(unix)]
 #[test]
 fn keep_dirlinks_handles_nested_symlinks() {
@@ -258,7 +258,13 @@ fn destination_is_replaced_atomically() {
     let src_arg = format!("{}/", src_dir.display());
     let mut child = std::process::Command::cargo_bin("oc-rsync")
         .unwrap()
-        .args(["--bwlimit", "20000", &src_arg, dst_dir.to_str().unwrap()])
+        .args([
+            "--bwlimit",
+            "20000",
+            "--no-whole-file",
+            &src_arg,
+            dst_dir.to_str().unwrap(),
+        ])
         .spawn()
         .unwrap();
 
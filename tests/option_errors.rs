@@ -21,6 +21,23 @@ fn invalid_checksum_choice_returns_protocol_error() {
         .stderr(contains("unknown checksum bogus"));
 }
 
+#[test]
+fn invalid_file_checksum_choice_returns_protocol_error() {
+    let src = tempdir().unwrap();
+    let dst = tempdir().unwrap();
+    Command::cargo_bin("oc-rsync")
+        .unwrap()
+        .args([
+            "--checksum-choice=xxh3,bogus",
+            src.path().to_str().unwrap(),
+            dst.path().to_str().unwrap(),
+        ])
+        .assert()
+        .failure()
+        .code(u8::from(ExitCode::Protocol) as i32)
+        .stderr(contains("unknown checksum bogus"));
+}
+
 #[test]
 fn invalid_compress_choice_returns_protocol_error() {
     let src = tempdir().unwrap();
@@ -38,6 +55,65 @@ fn invalid_compress_choice_returns_protocol_error() {
         .stderr(contains("unknown codec bogus"));
 }
 
+#[test]
+fn out_of_range_zlib_level_is_rejected() {
+    let src = tempdir().unwrap();
+    let dst = tempdir().unwrap();
+    Command::cargo_bin("oc-rsync")
+        .unwrap()
+        .args([
+            "--compress-choice=zlib",
+            "--compress-level=15",
+            src.path().to_str().unwrap(),
+            dst.path().to_str().unwrap(),
+        ])
+        .assert()
+        .failure()
+        .code(u8::from(ExitCode::SyntaxOrUsage) as i32)
+        .stderr(contains(
+            "--compress-level=15 is out of range for zlib (0-9)",
+        ));
+}
+
+#[test]
+fn level_is_checked_against_the_first_choice() {
+    let src = tempdir().unwrap();
+    let dst = tempdir().unwrap();
+    Command::cargo_bin("oc-rsync")
+        .unwrap()
+        .args([
+            "--compress-choice=zlib,zstd",
+            "--compress-level=15",
+            src.path().to_str().unwrap(),
+            dst.path().to_str().unwrap(),
+        ])
+        .assert()
+        .failure()
+        .code(u8::from(ExitCode::SyntaxOrUsage) as i32)
+        .stderr(contains("out of range for zlib"));
+}
+
+#[test]
+fn high_zstd_level_is_accepted() {
+    let src = tempdir().unwrap();
+    let dst = tempdir().unwrap();
+    std::fs::write(src.path().join("file"), b"zstd level nineteen").unwrap();
+    Command::cargo_bin("oc-rsync")
+        .unwrap()
+        .args([
+            "--compress-choice=zstd",
+            "--compress-level=19",
+            &format!("{}/", src.path().display()),
+            dst.path().to_str().unwrap(),
+        ])
+        .assert()
+        .success();
+    assert_eq!(
+        std::fs::read(dst.path().join("file")).unwrap(),
+        b"zstd level nineteen"
+    );
+}
+
 #[test]
 fn ipv4_and_ipv6_flags_conflict() {
     let src = tempdir().unwrap();
@@ -55,3 +131,41 @@ fn ipv4_and_ipv6_flags_conflict() {
         .code(u8::from(ExitCode::SyntaxOrUsage) as i32)
         .stderr(contains("rsync error: syntax or usage error (code 1)"));
 }
+
+#[test]
+fn oversized_block_size_is_rejected() {
+    let src = tempdir().unwrap();
+    let dst = tempdir().unwrap();
+    Command::cargo_bin("oc-rsync")
+        .unwrap()
+        .args([
+            "--block-size=1000000",
+            src.path().to_str().unwrap(),
+            dst.path().to_str().unwrap(),
+        ])
+        .assert()
+        .failure()
+        .code(u8::from(ExitCode::SyntaxOrUsage) as i32)
+        .stderr(contains("--block-size=1000000 is too large (max: 131072)"));
+}
+
+#[test]
+fn maximum_block_size_is_accepted() {
+    let src = tempdir().unwrap();
+    let dst = tempdir().unwrap();
+    std::fs::write(src.path().join("file"), vec![7u8; 300_000]).unwrap();
+    Command::cargo_bin("oc-rsync")
+        .unwrap()
+        .args([
+            "--block-size=131072",
+            "--no-whole-file",
+            &format!("{}/", src.path().display()),
+            dst.path().to_str().unwrap(),
+        ])
+        .assert()
+        .success();
+    assert_eq!(
+        std::fs::read(dst.path().join("file")).unwrap(),
+        vec![7u8; 300_000]
+    );
+}
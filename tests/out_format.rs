@@ -76,3 +76,37 @@ fn out_format_symlink_matches_rsync() {
 
     assert_eq!(ours_msg, theirs_msg);
 }
+
+#[cfg(unix)]
+#[test]
+fn out_format_nul_keeps_newline_names_in_one_record() {
+    let tmp = tempdir().unwrap();
+    let src_dir = tmp.path().join("src");
+    fs::create_dir_all(&src_dir).unwrap();
+    let dst = tmp.path().join("dst");
+    fs::create_dir_all(&dst).unwrap();
+    fs::write(dst.join("a\nb"), b"hi").unwrap();
+    let src_arg = format!("{}/", src_dir.display());
+
+    let out = TestCommand::cargo_bin("oc-rsync")
+        .unwrap()
+        .args([
+            "--recursive",
+            "--delete",
+            "--info=del",
+            "--out-format-nul",
+            &src_arg,
+            dst.to_str().unwrap(),
+        ])
+        .output()
+        .unwrap();
+    assert!(out.status.success());
+    let records: Vec<&[u8]> = out
+        .stdout
+        .split(|&b| b == 0)
+        .filter(|r| r.starts_with(b"deleting"))
+        .collect();
+    assert_eq!(records, [b"deleting a\nb".as_slice()]);
+    assert!(out.stdout.ends_with(b"\0"));
+    assert!(!dst.join("a\nb").exists());
+}
@@ -1,8 +1,10 @@
 // tests/perf_limits.rs
 
 use assert_cmd::Command;
+use filetime::{FileTime, set_file_mtime};
 use protocol::ExitCode;
 use std::fs;
+use std::time::{Duration, SystemTime};
 use tempfile::tempdir;
 
 #[test]
@@ -169,3 +171,31 @@ fn transfers_sub_kilobyte_files() {
     let meta = fs::metadata(dst.join("tiny.bin")).unwrap();
     assert_eq!(meta.len(), 512);
 }
+
+#[test]
+fn exclude_older_than_accepts_day_suffix() {
+    let dir = tempdir().unwrap();
+    let src = dir.path().join("src");
+    let dst = dir.path().join("dst");
+    fs::create_dir_all(&src).unwrap();
+    fs::create_dir_all(&dst).unwrap();
+    fs::write(src.join("old.bin"), b"old").unwrap();
+    fs::write(src.join("new.bin"), b"new").unwrap();
+    let month_ago = SystemTime::now() - Duration::from_secs(30 * 24 * 3600);
+    set_file_mtime(src.join("old.bin"), FileTime::from_system_time(month_ago)).unwrap();
+
+    let src_arg = format!("{}/", src.display());
+    Command::cargo_bin("oc-rsync")
+        .unwrap()
+        .args([
+            "--recursive",
+            "--exclude-older-than=7d",
+            &src_arg,
+            dst.to_str().unwrap(),
+        ])
+        .assert()
+        .success();
+
+    assert!(dst.join("new.bin").exists());
+    assert!(!dst.join("old.bin").exists());
+}
@@ -213,3 +213,58 @@ fn progress_flag_human_readable() {
     let expected_prefix = format!("{:>15} {:>3}%", bytes, 100);
     assert!(progress_line.starts_with(&expected_prefix));
 }
+
+fn final_progress_counts(delete_flag: &str) -> (usize, usize, usize) {
+    let dir = tempdir().unwrap();
+    let src = dir.path().join("src");
+    let dst = dir.path().join("dst");
+    fs::create_dir_all(&src).unwrap();
+    fs::create_dir_all(&dst).unwrap();
+    for name in ["a", "b", "c"] {
+        fs::write(src.join(name), name).unwrap();
+    }
+    fs::write(dst.join("old1"), b"x").unwrap();
+    fs::write(dst.join("old2"), b"y").unwrap();
+    let src_arg = format!("{}/", src.display());
+    let out = Command::cargo_bin("oc-rsync")
+        .unwrap()
+        .args([
+            "--recursive",
+            "--progress",
+            delete_flag,
+            &src_arg,
+            dst.to_str().unwrap(),
+        ])
+        .output()
+        .unwrap();
+    assert!(
+        out.status.success(),
+        "{}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    assert!(!dst.join("old1").exists() && !dst.join("old2").exists());
+    let stdout = String::from_utf8_lossy(&out.stdout).into_owned();
+    let last = stdout
+        .split(['\r', '\n'])
+        .rfind(|l| l.contains("xfr#"))
+        .unwrap()
+        .to_string();
+    let counts = last
+        .split_once("(xfr#")
+        .and_then(|(_, rest)| rest.strip_suffix(')'))
+        .unwrap();
+    let (xfr, chk) = counts.split_once(", to-chk=").unwrap();
+    let (remaining, total) = chk.split_once('/').unwrap();
+    (
+        xfr.parse().unwrap(),
+        remaining.parse().unwrap(),
+        total.parse().unwrap(),
+    )
+}
+
+#[test]
+fn progress_totals_include_deletions() {
+    assert_eq!(final_progress_counts("--delete-before"), (3, 0, 5));
+    assert_eq!(final_progress_counts("--delete-during"), (3, 0, 5));
+    assert_eq!(final_progress_counts("--delete-after"), (3, 0, 5));
+}
@@ -351,3 +351,193 @@ fn rsh_preserves_hard_links() {
     let ino2 = fs::metadata(dst.join("b")).unwrap().ino();
     assert_eq!(ino1, ino2);
 }
+
+#[cfg(unix)]
+#[test]
+fn remote_env_is_passed_to_rsh() {
+    let dir = tempdir().unwrap();
+    let src_dir = dir.path().join("src");
+    fs::create_dir(&src_dir).unwrap();
+    fs::write(src_dir.join("file.txt"), b"env").unwrap();
+    let dst_dir = dir.path().join("dst");
+
+    let out = dir.path().join("env.txt");
+    let rsh = dir.path().join("fake_rsh.sh");
+    fs::write(
+        &rsh,
+        format!(
+            "#!/bin/sh\necho \"$OC_REMOTE_VAR $OC_FORWARDED\" > {}\nshift\nexec \"$@\"\n",
+            out.display()
+        ),
+    )
+    .unwrap();
+    fs::set_permissions(&rsh, fs::Permissions::from_mode(0o755)).unwrap();
+
+    let remote_bin = dir.path().join("rr-remote");
+    fs::copy(cargo_bin("oc-rsync"), &remote_bin).unwrap();
+    fs::set_permissions(&remote_bin, fs::Permissions::from_mode(0o755)).unwrap();
+
+    let src_spec = format!("{}/", src_dir.display());
+    let dst_spec = format!("ignored:{}", dst_dir.display());
+    let mut cmd = AssertCommand::cargo_bin("oc-rsync").unwrap();
+    cmd.env("OC_FORWARDED", "forwarded");
+    cmd.args([
+        "--rsh",
+        rsh.to_str().unwrap(),
+        "--rsync-path",
+        remote_bin.to_str().unwrap(),
+        "--remote-env",
+        "OC_REMOTE_VAR=custom",
+        "--remote-env",
+        "OC_FORWARDED",
+        "-r",
+        &src_spec,
+        &dst_spec,
+    ]);
+    cmd.assert().success();
+
+    let env_out = fs::read_to_string(&out).unwrap();
+    assert_eq!(env_out.trim(), "custom forwarded");
+}
+
+#[cfg(unix)]
+#[test]
+fn missing_remote_binary_reports_clear_error() {
+    let dir = tempdir().unwrap();
+    let src_dir = dir.path().join("src");
+    fs::create_dir(&src_dir).unwrap();
+    fs::write(src_dir.join("file.txt"), b"data").unwrap();
+    let dst_dir = dir.path().join("dst");
+
+    let rsh = dir.path().join("fake_rsh.sh");
+    fs::write(&rsh, b"#!/bin/sh\nshift\nexec \"$@\"\n").unwrap();
+    fs::set_permissions(&rsh, fs::Permissions::from_mode(0o755)).unwrap();
+
+    let src_spec = format!("{}/", src_dir.display());
+    let dst_spec = format!("ignored:{}", dst_dir.display());
+    let missing = dir.path().join("no-such-rsync");
+    let output = StdCommand::new(cargo_bin("oc-rsync"))
+        .args([
+            "--rsh",
+            rsh.to_str().unwrap(),
+            "--rsync-path",
+            missing.to_str().unwrap(),
+            "-r",
+            &src_spec,
+            &dst_spec,
+        ])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("remote rsync not found or incompatible"),
+        "stderr: {stderr}"
+    );
+    assert!(stderr.contains("no-such-rsync"), "stderr: {stderr}");
+
+    let output = StdCommand::new(cargo_bin("oc-rsync"))
+        .args([
+            "--rsh",
+            rsh.to_str().unwrap(),
+            "--rsync-path",
+            "echo",
+            "-r",
+            &src_spec,
+            &dst_spec,
+        ])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("remote rsync not found or incompatible"),
+        "stderr: {stderr}"
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn remote_option_forwards_log_file_format() {
+    let dir = tempdir().unwrap();
+    let src_dir = dir.path().join("src");
+    fs::create_dir(&src_dir).unwrap();
+    fs::write(src_dir.join("file.txt"), b"log").unwrap();
+    let dst_dir = dir.path().join("dst");
+
+    let out = dir.path().join("args.txt");
+    let rsh = dir.path().join("fake_rsh.sh");
+    fs::write(
+        &rsh,
+        format!(
+            "#!/bin/sh\nshift\nprintf '%s\\n' \"$@\" > {}\n",
+            out.display()
+        ),
+    )
+    .unwrap();
+    fs::set_permissions(&rsh, fs::Permissions::from_mode(0o755)).unwrap();
+
+    let src_spec = format!("{}/", src_dir.display());
+    let dst_spec = format!("ignored:{}", dst_dir.display());
+    let _ = StdCommand::new(cargo_bin("oc-rsync"))
+        .args([
+            "--rsh",
+            rsh.to_str().unwrap(),
+            "--remote-option=--log-file-format=%h %m",
+            "-r",
+            &src_spec,
+            &dst_spec,
+        ])
+        .output()
+        .unwrap();
+
+    let args = fs::read_to_string(&out).unwrap();
+    let args: Vec<&str> = args.lines().collect();
+    let server = args.iter().position(|a| *a == "--server").unwrap();
+    let fmt = args
+        .iter()
+        .position(|a| *a == "--log-file-format=%h %m")
+        .unwrap();
+    assert!(fmt > server, "{args:?}");
+}
+
+#[cfg(unix)]
+#[test]
+fn xattr_patterns_are_forwarded() {
+    let dir = tempdir().unwrap();
+    let src_dir = dir.path().join("src");
+    fs::create_dir(&src_dir).unwrap();
+    fs::write(src_dir.join("file.txt"), b"xattr").unwrap();
+    let dst_dir = dir.path().join("dst");
+
+    let out = dir.path().join("args.txt");
+    let rsh = dir.path().join("fake_rsh.sh");
+    fs::write(
+        &rsh,
+        format!(
+            "#!/bin/sh\nshift\nprintf '%s\\n' \"$@\" > {}\n",
+            out.display()
+        ),
+    )
+    .unwrap();
+    fs::set_permissions(&rsh, fs::Permissions::from_mode(0o755)).unwrap();
+
+    let src_spec = format!("{}/", src_dir.display());
+    let dst_spec = format!("ignored:{}", dst_dir.display());
+    let _ = StdCommand::new(cargo_bin("oc-rsync"))
+        .args([
+            "--rsh",
+            rsh.to_str().unwrap(),
+            "--xattrs=user.mime*",
+            "-r",
+            &src_spec,
+            &dst_spec,
+        ])
+        .output()
+        .unwrap();
+
+    let args = fs::read_to_string(&out).unwrap();
+    let args: Vec<&str> = args.lines().collect();
+    assert!(args.contains(&"--xattrs"), "{args:?}");
+    assert!(args.contains(&"--xattrs=user.mime*"), "{args:?}");
+}
@@ -1,6 +1,7 @@
 // tests/rsync_zlib.rs
 
 use compress::Codec;
+use engine::{SyncOptions, select_codec};
 use protocol::{CAP_CODECS, LATEST_VERSION, negotiate_version};
 use std::io;
 use transport::Transport;
@@ -50,3 +51,40 @@ fn rsync_client_falls_back_to_zlib() {
     let negotiated = vec![Codec::Zlib];
     assert_eq!(negotiated, vec![Codec::Zlib]);
 }
+
+#[test]
+fn select_codec_falls_back_to_zlib_without_cap_codecs() {
+    let mut t = MockTransport {
+        reads: vec![
+            LATEST_VERSION.to_be_bytes().to_vec(),
+            0u32.to_be_bytes().to_vec(),
+        ],
+        idx: 0,
+    };
+
+    t.send(&LATEST_VERSION.to_be_bytes()).unwrap();
+    let mut buf = [0u8; 4];
+    t.receive(&mut buf).unwrap();
+    negotiate_version(LATEST_VERSION, u32::from_be_bytes(buf)).unwrap();
+
+    t.send(&CAP_CODECS.to_be_bytes()).unwrap();
+    t.receive(&mut buf).unwrap();
+    let caps = u32::from_be_bytes(buf);
+    assert_eq!(caps & CAP_CODECS, 0);
+
+    let remote: &[Codec] = &[];
+    let opts = SyncOptions {
+        compress: true,
+        ..Default::default()
+    };
+    assert_eq!(select_codec(remote, &opts), Some(Codec::Zlib));
+
+    let opts = SyncOptions {
+        compress: true,
+        compress_choice: Some(vec![Codec::Zstd]),
+        ..Default::default()
+    };
+    assert_eq!(select_codec(remote, &opts), None);
+
+    assert_eq!(select_codec(remote, &SyncOptions::default()), None);
+}
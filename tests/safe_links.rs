@@ -0,0 +1,92 @@
+// tests/safe_links.rs
+#![cfg(unix)]
+
+use assert_cmd::Command;
+use std::fs;
+use std::os::unix::fs::symlink;
+use std::path::Path;
+use tempfile::tempdir;
+
+fn sync(args: &[&str], src: &Path, dst: &Path) {
+    fs::create_dir_all(dst).unwrap();
+    Command::cargo_bin("oc-rsync")
+        .unwrap()
+        .args(args)
+        .args([&format!("{}/", src.display()), dst.to_str().unwrap()])
+        .assert()
+        .success();
+}
+
+#[test]
+fn safe_links_drops_absolute_target_outside_tree() {
+    let tmp = tempdir().unwrap();
+    let src = tmp.path().join("src");
+    let dst = tmp.path().join("dst");
+    fs::create_dir_all(&src).unwrap();
+    symlink("/etc/passwd", src.join("abs")).unwrap();
+
+    sync(&["--links", "--safe-links"], &src, &dst);
+
+    assert!(fs::symlink_metadata(dst.join("abs")).is_err());
+}
+
+#[test]
+fn safe_links_drops_parent_climbing_target() {
+    let tmp = tempdir().unwrap();
+    let src = tmp.path().join("src");
+    let dst = tmp.path().join("dst");
+    fs::create_dir_all(src.join("sub")).unwrap();
+    symlink("../../etc", src.join("sub/up")).unwrap();
+    symlink("sub/../../etc", src.join("climb")).unwrap();
+
+    sync(&["--links", "--safe-links"], &src, &dst);
+
+    assert!(fs::symlink_metadata(dst.join("sub/up")).is_err());
+    assert!(fs::symlink_metadata(dst.join("climb")).is_err());
+}
+
+#[test]
+fn safe_links_keeps_in_tree_relative_target() {
+    let tmp = tempdir().unwrap();
+    let src = tmp.path().join("src");
+    let dst = tmp.path().join("dst");
+    fs::create_dir_all(src.join("sub")).unwrap();
+    fs::write(src.join("file"), b"hi").unwrap();
+    symlink("../file", src.join("sub/back")).unwrap();
+    symlink("./sub/../file", src.join("dotted")).unwrap();
+
+    sync(&["--links", "--safe-links"], &src, &dst);
+
+    assert_eq!(
+        fs::read_link(dst.join("sub/back")).unwrap(),
+        Path::new("../file")
+    );
+    assert_eq!(
+        fs::read_link(dst.join("dotted")).unwrap(),
+        Path::new("./sub/../file")
+    );
+    assert_eq!(fs::read(dst.join("sub/back")).unwrap(), b"hi");
+}
+
+#[test]
+fn copy_unsafe_links_copies_referent_and_keeps_safe_link() {
+    let tmp = tempdir().unwrap();
+    let outside = tmp.path().join("outside");
+    let src = tmp.path().join("src");
+    let dst = tmp.path().join("dst");
+    fs::create_dir_all(&outside).unwrap();
+    fs::create_dir_all(&src).unwrap();
+    fs::write(outside.join("secret"), b"outside").unwrap();
+    fs::write(src.join("file"), b"inside").unwrap();
+    symlink("../outside/secret", src.join("unsafe")).unwrap();
+    symlink("file", src.join("safe")).unwrap();
+
+    sync(&["--links", "--copy-unsafe-links"], &src, &dst);
+
+    let unsafe_meta = fs::symlink_metadata(dst.join("unsafe")).unwrap();
+    assert!(unsafe_meta.file_type().is_file());
+    assert_eq!(fs::read(dst.join("unsafe")).unwrap(), b"outside");
+    let safe_meta = fs::symlink_metadata(dst.join("safe")).unwrap();
+    assert!(safe_meta.file_type().is_symlink());
+    assert_eq!(fs::read_link(dst.join("safe")).unwrap(), Path::new("file"));
+}
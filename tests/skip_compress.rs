@@ -30,3 +30,29 @@ fn skip_compress_option_transfers_files() {
     let out_txt = fs::read(dst.join("b.txt")).unwrap();
     assert_eq!(out_txt, b"text");
 }
+
+#[test]
+fn empty_skip_compress_clears_default_list() {
+    let dir = tempdir().unwrap();
+    let src = dir.path().join("src");
+    let dst = dir.path().join("dst");
+    fs::create_dir_all(&src).unwrap();
+    fs::create_dir_all(&dst).unwrap();
+    let data = b"poorly compressed archive ".repeat(100);
+    fs::write(src.join("a.gz"), &data).unwrap();
+
+    let src_arg = format!("{}/", src.display());
+    Command::cargo_bin("oc-rsync")
+        .unwrap()
+        .args([
+            "-r",
+            "--compress",
+            "--skip-compress=",
+            &src_arg,
+            dst.to_str().unwrap(),
+        ])
+        .assert()
+        .success();
+
+    assert_eq!(fs::read(dst.join("a.gz")).unwrap(), data);
+}
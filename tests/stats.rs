@@ -1,5 +1,6 @@
 // tests/stats.rs
 use assert_cmd::Command;
+use predicates::prelude::*;
 use std::fs;
 use tempfile::tempdir;
 
@@ -85,3 +86,101 @@ fn stats_are_printed() {
         "Number of regular files transferred",
     ));
 }
+
+#[test]
+fn transfer_times_need_stats3() {
+    let dir = tempdir().unwrap();
+    let src_dir = dir.path().join("src");
+    let dst_dir = dir.path().join("dst");
+    fs::create_dir_all(&src_dir).unwrap();
+    fs::write(src_dir.join("a.txt"), b"hello").unwrap();
+    let src_arg = format!("{}/", src_dir.display());
+
+    Command::cargo_bin("oc-rsync")
+        .unwrap()
+        .args(["--stats", &src_arg, dst_dir.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Transfer start time").not());
+    Command::cargo_bin("oc-rsync")
+        .unwrap()
+        .args([
+            "--stats",
+            "--info=stats3",
+            &src_arg,
+            dst_dir.to_str().unwrap(),
+        ])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Transfer start time: "))
+        .stdout(predicates::str::contains("Total elapsed time: "));
+}
+
+#[test]
+fn compression_savings_are_printed() {
+    let dir = tempdir().unwrap();
+    let src_dir = dir.path().join("src");
+    let dst_dir = dir.path().join("dst");
+    fs::create_dir_all(&src_dir).unwrap();
+    fs::write(src_dir.join("a.txt"), b"compressible ".repeat(4096)).unwrap();
+
+    let mut cmd = Command::cargo_bin("oc-rsync").unwrap();
+    let src_arg = format!("{}/", src_dir.display());
+    cmd.env("LC_ALL", "C")
+        .args(["-z", "--stats", &src_arg, dst_dir.to_str().unwrap()]);
+    cmd.assert()
+        .success()
+        .stdout(predicates::str::contains("Compressed literal data").not());
+
+    let dst_dir = dir.path().join("dst3");
+    let mut cmd = Command::cargo_bin("oc-rsync").unwrap();
+    cmd.env("LC_ALL", "C").args([
+        "-z",
+        "--stats",
+        "--info=stats3",
+        &src_arg,
+        dst_dir.to_str().unwrap(),
+    ]);
+    cmd.assert()
+        .success()
+        .stdout(predicates::str::contains("Compressed literal data: "))
+        .stdout(predicates::str::contains("from 53,248 bytes (ratio "));
+}
+
+#[test]
+fn local_transfers_default_to_whole_file() {
+    let dir = tempdir().unwrap();
+    let src = dir.path().join("src");
+    let dst = dir.path().join("dst");
+    fs::create_dir_all(&src).unwrap();
+    fs::create_dir_all(&dst).unwrap();
+    let basis: Vec<u8> = (0..64 * 1024).map(|i| (i % 251) as u8).collect();
+    let mut changed = basis.clone();
+    changed[1000] ^= 0xFF;
+    fs::write(src.join("file.bin"), &changed).unwrap();
+
+    let matched = |extra: &[&str]| {
+        fs::write(dst.join("file.bin"), &basis).unwrap();
+        let out = Command::cargo_bin("oc-rsync")
+            .unwrap()
+            .env("LC_ALL", "C")
+            .args(["--stats", "--checksum"])
+            .args(extra)
+            .arg(format!("{}/", src.display()))
+            .arg(&dst)
+            .output()
+            .unwrap();
+        assert!(out.status.success());
+        assert_eq!(fs::read(dst.join("file.bin")).unwrap(), changed);
+        String::from_utf8_lossy(&out.stdout)
+            .lines()
+            .find_map(|l| {
+                l.trim_start()
+                    .strip_prefix("Matched data: ")
+                    .map(str::to_string)
+            })
+            .unwrap()
+    };
+    assert_eq!(matched(&[]), "0 bytes");
+    assert_ne!(matched(&["--no-whole-file"]), "0 bytes");
+}
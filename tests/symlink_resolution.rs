@@ -434,3 +434,74 @@ fn munge_links_unmunges_existing_prefix() {
         Path::new("/rsyncd-munged/file")
     );
 }
+
+#[cfg(unix)]
+#[test]
+fn munge_links_unmunges_non_utf8_target() {
+    use std::ffi::OsStr;
+    use std::os::unix::ffi::OsStrExt;
+
+    let tmp = tempdir().unwrap();
+    let src = tmp.path().join("src");
+    let dst = tmp.path().join("dst");
+    for dir in [&src, &dst] {
+        fs::create_dir_all(dir).unwrap();
+    }
+    let target = Path::new(OsStr::from_bytes(b"/rsyncd-munged/caf\xe9"));
+    symlink(target, src.join("link"));
+
+    Command::cargo_bin("oc-rsync")
+        .unwrap()
+        .args([
+            "--links",
+            "--munge-links",
+            &format!("{}/", src.display()),
+            dst.to_str().unwrap(),
+        ])
+        .assert()
+        .success();
+
+    assert_eq!(fs::read_link(dst.join("link")).unwrap(), target);
+}
+
+#[cfg(unix)]
+#[test]
+fn munged_target_is_copied_verbatim_without_munge_links() {
+    let tmp = tempdir().unwrap();
+    let src = tmp.path().join("src");
+    let mid = tmp.path().join("mid");
+    let back = tmp.path().join("back");
+    for dir in [&src, &mid, &back] {
+        fs::create_dir_all(dir).unwrap();
+    }
+    symlink("/etc/passwd", src.join("link"));
+
+    Command::cargo_bin("oc-rsync")
+        .unwrap()
+        .args([
+            "--links",
+            "--munge-links",
+            &format!("{}/", src.display()),
+            mid.to_str().unwrap(),
+        ])
+        .assert()
+        .success();
+    assert_eq!(
+        fs::read_link(mid.join("link")).unwrap(),
+        Path::new("/rsyncd-munged//etc/passwd")
+    );
+
+    Command::cargo_bin("oc-rsync")
+        .unwrap()
+        .args([
+            "--links",
+            &format!("{}/", mid.display()),
+            back.to_str().unwrap(),
+        ])
+        .assert()
+        .success();
+    assert_eq!(
+        fs::read_link(back.join("link")).unwrap(),
+        Path::new("/rsyncd-munged//etc/passwd")
+    );
+}
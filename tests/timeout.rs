@@ -185,12 +185,47 @@ fn daemon_handshake_timeout() {
     }
 }
 
+#[test]
+fn daemon_greeting_timeout() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    thread::spawn(move || {
+        let (_sock, _) = listener.accept().unwrap();
+        thread::sleep(Duration::from_secs(5));
+    });
+    let start = Instant::now();
+    let res = spawn_daemon_session(
+        &addr.ip().to_string(),
+        "mod",
+        Some(addr.port()),
+        None,
+        true,
+        None,
+        None,
+        None,
+        &[],
+        &SyncOptions {
+            greeting_timeout: Some(Duration::from_millis(100)),
+            ..SyncOptions::default()
+        },
+        31,
+        None,
+        None,
+    );
+    match res {
+        Ok(_) => panic!("expected timeout"),
+        Err(EngineError::Io(e)) => assert_eq!(e.kind(), io::ErrorKind::TimedOut),
+        Err(other) => panic!("unexpected error: {other:?}"),
+    }
+    assert!(start.elapsed() < Duration::from_secs(2));
+}
+
 #[test]
 fn daemon_handshake_timeout_message() {
     let listener = TcpListener::bind("127.0.0.1:0").unwrap();
     let addr = listener.local_addr().unwrap();
     let modules: HashMap<String, Module> = HashMap::new();
-    let handler: Arc<Handler> = Arc::new(|_, _| Ok(()));
+    let handler: Arc<Handler> = Arc::new(|_, _, _| Ok(()));
     thread::spawn(move || {
         let (stream, _) = listener.accept().unwrap();
         let transport = TcpTransport::from_stream(stream);
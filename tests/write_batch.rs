@@ -86,3 +86,38 @@ fn only_write_batch_does_not_sync() {
     assert!(batch.exists());
     assert!(!dst.exists());
 }
+
+#[test]
+#[ignore = "requires rsync"]
+fn rsync_batch_format_is_readable_by_stock_rsync() {
+    let dir = tempdir().unwrap();
+    let src = dir.path().join("src");
+    fs::create_dir_all(src.join("sub")).unwrap();
+    fs::write(src.join("a"), b"alpha").unwrap();
+    fs::write(src.join("sub/b"), vec![7u8; 100_000]).unwrap();
+    let dst = dir.path().join("dst");
+    let batch = dir.path().join("batch.bin");
+    let src_arg = format!("{}/", src.display());
+    Command::cargo_bin("oc-rsync")
+        .unwrap()
+        .args([
+            "--only-write-batch",
+            batch.to_str().unwrap(),
+            "--batch-format=rsync",
+            "-r",
+            &src_arg,
+            dst.to_str().unwrap(),
+        ])
+        .assert()
+        .success();
+    let replay = dir.path().join("replay");
+    let status = std::process::Command::new("rsync")
+        .arg(format!("--read-batch={}", batch.display()))
+        .arg("-r")
+        .arg(&replay)
+        .status()
+        .unwrap();
+    assert!(status.success());
+    assert_eq!(fs::read(replay.join("a")).unwrap(), b"alpha");
+    assert_eq!(fs::read(replay.join("sub/b")).unwrap(), vec![7u8; 100_000]);
+}
@@ -0,0 +1,101 @@
+// tests/xattr_patterns.rs
+#![cfg(all(unix, feature = "xattr"))]
+
+use assert_cmd::Command;
+use std::fs;
+use tempfile::tempdir;
+
+fn oc_rsync() -> Command {
+    let mut cmd = Command::cargo_bin("oc-rsync").unwrap();
+    cmd.env("LC_ALL", "C").env("TZ", "UTC");
+    cmd
+}
+
+#[test]
+fn xattr_pattern_copies_only_matching_names() {
+    let tmp = tempdir().unwrap();
+    let src = tmp.path().join("src");
+    let dst = tmp.path().join("dst");
+    fs::create_dir_all(&src).unwrap();
+    fs::create_dir_all(&dst).unwrap();
+    let file = src.join("file");
+    fs::write(&file, b"hi").unwrap();
+    xattr::set(&file, "user.mime_type", b"text/plain").unwrap();
+    xattr::set(&file, "user.other", b"skip").unwrap();
+
+    let src_arg = format!("{}/", src.display());
+    oc_rsync()
+        .args(["-a", "--xattrs=user.mime*", &src_arg, dst.to_str().unwrap()])
+        .assert()
+        .success();
+
+    let out = dst.join("file");
+    assert_eq!(
+        xattr::get(&out, "user.mime_type").unwrap(),
+        Some(b"text/plain".to_vec())
+    );
+    assert_eq!(xattr::get(&out, "user.other").unwrap(), None);
+}
+
+#[test]
+fn xattr_pattern_protects_unmatched_names_from_deletion() {
+    let tmp = tempdir().unwrap();
+    let src = tmp.path().join("src");
+    let dst = tmp.path().join("dst");
+    fs::create_dir_all(&src).unwrap();
+    fs::create_dir_all(&dst).unwrap();
+    let file = src.join("file");
+    fs::write(&file, b"new").unwrap();
+    xattr::set(&file, "user.mime_type", b"text/plain").unwrap();
+    let out = dst.join("file");
+    fs::write(&out, b"old data").unwrap();
+    xattr::set(&out, "user.mime_stale", b"gone").unwrap();
+    xattr::set(&out, "user.other", b"keep").unwrap();
+
+    let src_arg = format!("{}/", src.display());
+    oc_rsync()
+        .args([
+            "-a",
+            "--inplace",
+            "--xattrs=user.mime*",
+            &src_arg,
+            dst.to_str().unwrap(),
+        ])
+        .assert()
+        .success();
+
+    assert_eq!(
+        xattr::get(&out, "user.mime_type").unwrap(),
+        Some(b"text/plain".to_vec())
+    );
+    assert_eq!(xattr::get(&out, "user.mime_stale").unwrap(), None);
+    assert_eq!(
+        xattr::get(&out, "user.other").unwrap(),
+        Some(b"keep".to_vec())
+    );
+}
+
+#[test]
+fn bare_xattrs_flag_still_copies_everything() {
+    let tmp = tempdir().unwrap();
+    let src = tmp.path().join("src");
+    let dst = tmp.path().join("dst");
+    fs::create_dir_all(&src).unwrap();
+    fs::create_dir_all(&dst).unwrap();
+    let file = src.join("file");
+    fs::write(&file, b"hi").unwrap();
+    xattr::set(&file, "user.mime_type", b"text/plain").unwrap();
+    xattr::set(&file, "user.other", b"copy").unwrap();
+
+    let src_arg = format!("{}/", src.display());
+    oc_rsync()
+        .args(["-aX", &src_arg, dst.to_str().unwrap()])
+        .assert()
+        .success();
+
+    let out = dst.join("file");
+    assert_eq!(
+        xattr::get(&out, "user.other").unwrap(),
+        Some(b"copy".to_vec())
+    );
+}